@@ -67,6 +67,8 @@ fn mcp_server_to_extension_config(mcp_server: McpServer) -> Result<ExtensionConf
             timeout: None,
             bundled: Some(false),
             available_tools: vec![],
+            auto_restart: false,
+            max_restarts: None,
         }),
         McpServer::Http(http) => Ok(ExtensionConfig::StreamableHttp {
             name: http.name,
@@ -299,6 +301,7 @@ impl GooseAcpAgent {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let provider = create(&provider_name, model_config).await?;
         let goose_mode = config
@@ -1074,6 +1077,8 @@ mod tests {
             timeout: None,
             bundled: Some(false),
             available_tools: vec![],
+            auto_restart: false,
+            max_restarts: None,
         })
     )]
     #[test_case(
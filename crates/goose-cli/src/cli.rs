@@ -13,7 +13,9 @@ use crate::commands::bench::agent_generator;
 use crate::commands::configure::{configure_telemetry_consent_dialog, handle_configure};
 use crate::commands::info::handle_info;
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_open, handle_validate};
+use crate::commands::recipe::{
+    handle_deeplink, handle_lint, handle_list, handle_open, handle_schema, handle_validate,
+};
 use crate::commands::term::{
     handle_term_info, handle_term_init, handle_term_log, handle_term_run, Shell,
 };
@@ -41,10 +43,33 @@ use tracing::warn;
 #[derive(Parser)]
 #[command(author, version, display_name = "", about, long_about = None)]
 struct Cli {
+    #[arg(
+        long = "working-dir",
+        value_name = "DIR",
+        global = true,
+        help = "Run with DIR as the working directory",
+        long_help = "Use DIR as the working directory for the whole session. Relative paths, shell commands, ignore files and the session's recorded working directory all resolve against it. The directory must already exist."
+    )]
+    working_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
 
+/// Makes `dir` the working directory for this process and every extension it starts, so
+/// nothing resolves paths against the directory goose happened to be launched from.
+fn apply_working_dir(dir: &std::path::Path) -> Result<()> {
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Working directory {} is not usable: {}", dir.display(), e))?;
+    if !dir.is_dir() {
+        anyhow::bail!("Working directory {} is not a directory", dir.display());
+    }
+    std::env::set_current_dir(&dir)?;
+    std::env::set_var("GOOSE_WORKING_DIR", &dir);
+    Ok(())
+}
+
 #[derive(Args, Debug, Clone)]
 #[group(required = false, multiple = false)]
 pub struct Identifier {
@@ -62,7 +87,7 @@ pub struct Identifier {
         alias = "id",
         value_name = "SESSION_ID",
         help = "Session ID (e.g., '20250921_143022')",
-        long_help = "Specify a session ID directly. When used with --resume, will resume this specific session if it exists."
+        long_help = "Specify a session ID directly. When used with --resume, will resume this specific session if it exists; a unique prefix of the ID or 'last' for the most recent session also works."
     )]
     pub session_id: Option<String>,
 
@@ -101,6 +126,80 @@ pub struct SessionOptions {
         long_help = "Set a limit on how many turns (iterations) the agent can take without asking for user input to continue."
     )]
     pub max_turns: Option<u32>,
+
+    #[arg(
+        long = "max-response-tokens",
+        value_name = "NUMBER",
+        help = "Maximum number of tokens the model may generate per response",
+        long_help = "Cap the length of each model response for this session, e.g. to control cost. Applies on top of any configured max tokens; background calls such as compaction are not affected.",
+        value_parser = clap::value_parser!(i32).range(1..)
+    )]
+    pub max_response_tokens: Option<i32>,
+
+    #[arg(
+        long = "transcript",
+        value_name = "FILE",
+        help = "Append a markdown transcript of the session to FILE as it runs",
+        long_help = "Write each user and assistant message to a markdown file as soon as it happens, so an up-to-date log exists even if goose crashes. Set GOOSE_TRANSCRIPT_DIR to do this for every session."
+    )]
+    pub transcript: Option<PathBuf>,
+
+    #[arg(
+        long = "system-prompt-append",
+        alias = "append-system-prompt",
+        value_name = "TEXT",
+        help = "Append text to the system prompt for this session (can be specified multiple times)",
+        long_help = "Append extra instructions (e.g. 'respond in French') to the end of the assembled system prompt, after extension and project instructions. Applies to this session only.",
+        action = clap::ArgAction::Append
+    )]
+    pub system_prompt_appends: Vec<String>,
+
+    #[arg(
+        long = "system-prompt-append-file",
+        value_name = "FILE",
+        help = "Append the contents of FILE to the system prompt for this session (can be specified multiple times)",
+        long_help = "Like --system-prompt-append, but reads the instructions from a file. They follow any --system-prompt-append text.",
+        value_parser = read_prompt_file,
+        action = clap::ArgAction::Append
+    )]
+    pub system_prompt_append_files: Vec<String>,
+
+    #[arg(
+        long = "system-prompt-prepend",
+        alias = "prepend-system-prompt",
+        value_name = "TEXT",
+        help = "Put text at the start of the system prompt for this session (can be specified multiple times)",
+        long_help = "Put standing guidance at the very start of the system prompt, ahead of goose's own instructions; extension and project instructions are still included. Applies to this session only.",
+        action = clap::ArgAction::Append
+    )]
+    pub system_prompt_prepends: Vec<String>,
+
+    #[arg(
+        long = "system-prompt-prepend-file",
+        value_name = "FILE",
+        help = "Put the contents of FILE at the start of the system prompt for this session (can be specified multiple times)",
+        long_help = "Like --system-prompt-prepend, but reads the guidance from a file. It follows any --system-prompt-prepend text.",
+        value_parser = read_prompt_file,
+        action = clap::ArgAction::Append
+    )]
+    pub system_prompt_prepend_files: Vec<String>,
+
+    #[arg(
+        long = "no-tools",
+        help = "Don't give the model any tools, for plain question and answer",
+        long_help = "Withhold every tool definition from the model so it can only reply in text. Unlike GOOSE_MODE=chat, tools are not even advertised. Toggle it during an interactive session with /chat-only."
+    )]
+    pub no_tools: bool,
+
+    #[arg(
+        long = "meta",
+        value_name = "KEY=VALUE",
+        help = "Tag the session with metadata (can be specified multiple times)",
+        long_help = "Attach key/value metadata to the session, e.g. --meta ticket=ABC-123 --meta run_id=42. Useful for correlating sessions with records in other systems.",
+        action = clap::ArgAction::Append,
+        value_parser = parse_key_val,
+    )]
+    pub metadata: Vec<(String, String)>,
 }
 
 /// Extension configuration options shared between Session and Run commands
@@ -228,15 +327,23 @@ pub struct OutputOptions {
     )]
     pub quiet: bool,
 
-    /// Output format (text, json, stream-json)
+    /// Output format (text, json, json-array, stream-json)
     #[arg(
         long = "output-format",
         value_name = "FORMAT",
-        help = "Output format (text, json, stream-json)",
+        help = "Output format (text, json, json-array, stream-json). json-array writes the same document as json, but streams messages as they arrive instead of buffering them",
         default_value = "text",
-        value_parser = clap::builder::PossibleValuesParser::new(["text", "json", "stream-json"])
+        value_parser = clap::builder::PossibleValuesParser::new(["text", "json", "json-array", "stream-json"])
     )]
     pub output_format: String,
+
+    /// Include agent-only messages in json output
+    #[arg(
+        long = "include-agent-visible",
+        help = "Include agent-only messages (summaries, continuation prompts) in json output",
+        long_help = "By default json output only contains messages shown to the user. With this flag, messages that are only visible to the agent, such as compaction summaries, are included as well."
+    )]
+    pub include_agent_visible: bool,
 }
 
 impl Default for OutputOptions {
@@ -244,6 +351,7 @@ impl Default for OutputOptions {
         Self {
             quiet: false,
             output_format: "text".to_string(),
+            include_agent_visible: false,
         }
     }
 }
@@ -300,6 +408,16 @@ pub struct RunBehavior {
     )]
     pub resume: bool,
 
+    /// Start from a conversation saved as JSON
+    #[arg(
+        long = "conversation",
+        value_name = "FILE",
+        help = "Seed the session with a conversation loaded from a JSON file",
+        long_help = "Start the session from the messages in a JSON file (a message array, or a session exported with 'goose session export --format json'), then process the new prompt. Useful for reproducing issues from an exact conversation state.",
+        conflicts_with = "resume"
+    )]
+    pub conversation: Option<PathBuf>,
+
     /// Scheduled job ID (used internally for scheduled executions)
     #[arg(
         long = "scheduled-job-id",
@@ -406,6 +524,13 @@ async fn lookup_session_id(identifier: Identifier) -> Result<String> {
     }
 }
 
+/// The contents of a file given to one of the system prompt file flags.
+fn read_prompt_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map(|text| text.trim().to_string())
+        .map_err(|e| format!("could not read {}: {}", path, e))
+}
+
 fn parse_key_val(s: &str) -> Result<(String, String), String> {
     match s.split_once('=') {
         Some((key, value)) => Ok((key.to_string(), value.to_string())),
@@ -413,6 +538,50 @@ fn parse_key_val(s: &str) -> Result<(String, String), String> {
     }
 }
 
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    #[command(about = "Print the effective value of a config key")]
+    Get {
+        #[arg(value_name = "KEY", help = "Config key, e.g. GOOSE_MODE")]
+        key: String,
+    },
+    #[command(about = "Validate and save a config value to the config file")]
+    Set {
+        #[arg(
+            value_name = "KEY",
+            help = "Config key, e.g. GOOSE_AUTO_COMPACT_THRESHOLD"
+        )]
+        key: String,
+        #[arg(value_name = "VALUE", help = "Value to store")]
+        value: String,
+        #[arg(long, help = "Allow setting keys goose doesn't know about")]
+        force: bool,
+    },
+    #[command(
+        about = "Check that the configured provider is reachable with the saved credentials"
+    )]
+    Validate {},
+}
+
+#[derive(Subcommand, Debug)]
+enum AppsCommand {
+    #[command(about = "Export all cached apps to a single JSON bundle")]
+    Export {
+        #[arg(
+            short,
+            long,
+            help = "Output file path (default: stdout)",
+            value_name = "FILE"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Import apps from a bundle created by 'goose apps export'")]
+    Import {
+        #[arg(help = "Path to the app bundle", value_name = "FILE")]
+        input: PathBuf,
+    },
+}
+
 #[derive(Subcommand)]
 enum SessionCommand {
     #[command(about = "List all available sessions")]
@@ -485,6 +654,76 @@ enum SessionCommand {
         #[arg(short = 'o', long)]
         output: Option<PathBuf>,
     },
+    #[command(
+        about = "Follow a session as messages are added",
+        long_about = "Print the last messages of a session, then keep printing new messages as they are added, like `tail -f`. Useful for watching a headless run from another terminal. Press Ctrl+C to stop."
+    )]
+    Tail {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            short = 'n',
+            long,
+            help = "Number of existing messages to print before following",
+            default_value = "10"
+        )]
+        lines: usize,
+    },
+    #[command(about = "Compare two sessions turn by turn")]
+    Diff {
+        #[arg(value_name = "SESSION_A", help = "First session ID or name")]
+        session_a: String,
+
+        #[arg(value_name = "SESSION_B", help = "Second session ID or name")]
+        session_b: String,
+
+        #[arg(long, help = "Output the diff as JSON")]
+        json: bool,
+    },
+    #[command(
+        about = "Combine two sessions into a new one",
+        long_about = "Create a new session from the conversations of two others, e.g. two forks of the same session. Turns the sessions share at the start are kept once; the remaining turns are appended (all of the first session's, then the second's) or interleaved. The result is repaired so it is a valid conversation, and both source sessions are left unchanged."
+    )]
+    Merge {
+        #[arg(value_name = "SESSION_A", help = "First session ID or name")]
+        session_a: String,
+
+        #[arg(value_name = "SESSION_B", help = "Second session ID or name")]
+        session_b: String,
+
+        #[arg(long, value_name = "NAME", help = "Name of the new merged session")]
+        into: String,
+
+        #[arg(
+            long,
+            value_name = "STRATEGY",
+            help = "How to combine the turns after the shared history",
+            default_value = "append",
+            value_parser = clap::builder::PossibleValuesParser::new(["append", "interleave"])
+        )]
+        strategy: String,
+    },
+    #[command(
+        about = "Delete old sessions according to the retention policy",
+        long_about = "Delete the oldest sessions beyond the retention policy. Limits default to GOOSE_SESSION_RETENTION_MAX_AGE_DAYS, GOOSE_SESSION_RETENTION_MAX_COUNT and GOOSE_SESSION_RETENTION_MAX_BYTES from config. Sessions with a user-provided name, and sessions updated in the last 24 hours, are never pruned."
+    )]
+    Prune {
+        #[arg(long, help = "Remove sessions not updated in this many days")]
+        max_age_days: Option<u32>,
+
+        #[arg(long, help = "Keep at most this many sessions")]
+        max_count: Option<usize>,
+
+        #[arg(long, help = "Keep at most this many bytes of session messages")]
+        max_bytes: Option<u64>,
+
+        #[arg(
+            long,
+            help = "Show which sessions would be removed without deleting them"
+        )]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -610,6 +849,14 @@ enum RecipeCommand {
         recipe_name: String,
     },
 
+    /// Report unused and undefined parameters in a recipe file
+    #[command(about = "Report unused and undefined recipe parameters")]
+    Lint {
+        /// Recipe name to get recipe file to lint
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to lint")]
+        recipe_name: String,
+    },
+
     /// Generate a deeplink for a recipe file
     #[command(about = "Generate a deeplink for a recipe")]
     Deeplink {
@@ -664,6 +911,18 @@ enum RecipeCommand {
         )]
         verbose: bool,
     },
+
+    /// Print the JSON Schema for recipe files
+    #[command(about = "Print the JSON Schema for recipe files")]
+    Schema {
+        #[arg(
+            short,
+            long,
+            help = "Output file path (default: stdout)",
+            value_name = "FILE"
+        )]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -730,6 +989,15 @@ enum Command {
         )]
         history: bool,
 
+        /// Offer to resume the last session if it crashed mid-turn
+        #[arg(
+            long = "resume-last-on-error",
+            help = "Offer to resume the last session if it ended in the middle of a turn",
+            long_help = "When starting a new session, check whether the most recent session ended abnormally (an unanswered tool call or message) and offer to resume it after repairing the conversation. Set GOOSE_RESUME_LAST_ON_ERROR=true to always do this.",
+            conflicts_with = "resume"
+        )]
+        resume_last_on_error: bool,
+
         #[command(flatten)]
         session_opts: SessionOptions,
 
@@ -777,6 +1045,20 @@ enum Command {
         command: RecipeCommand,
     },
 
+    /// Back up or restore cached MCP apps
+    #[command(about = "Back up or restore cached MCP apps")]
+    Apps {
+        #[command(subcommand)]
+        command: AppsCommand,
+    },
+
+    /// Read or write individual config values
+    #[command(about = "Get or set individual configuration values")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
     /// Manage scheduled jobs
     #[command(about = "Manage scheduled jobs", visible_alias = "sched")]
     Schedule {
@@ -956,6 +1238,8 @@ fn get_command_name(command: &Option<Command>) -> &'static str {
         Some(Command::Projects) => "projects",
         Some(Command::Run { .. }) => "run",
         Some(Command::Schedule { .. }) => "schedule",
+        Some(Command::Apps { .. }) => "apps",
+        Some(Command::Config { .. }) => "config",
         Some(Command::Update { .. }) => "update",
         Some(Command::Bench { .. }) => "bench",
         Some(Command::Recipe { .. }) => "recipe",
@@ -1040,6 +1324,84 @@ async fn handle_session_subcommand(command: SessionCommand) -> Result<()> {
             };
             crate::commands::session::handle_diagnostics(&session_id, output).await?;
         }
+        SessionCommand::Tail { identifier, lines } => {
+            let session_manager = SessionManager::instance();
+            let session_identifier = if let Some(id) = identifier {
+                lookup_session_id(id).await?
+            } else {
+                match crate::commands::session::prompt_interactive_session_selection(
+                    &session_manager,
+                )
+                .await
+                {
+                    Ok(id) => id,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return Ok(());
+                    }
+                }
+            };
+            crate::commands::session::handle_session_tail(&session_identifier, lines).await?;
+        }
+        SessionCommand::Diff {
+            session_a,
+            session_b,
+            json,
+        } => {
+            let session_a = lookup_session_id(Identifier {
+                name: Some(session_a),
+                session_id: None,
+                path: None,
+            })
+            .await?;
+            let session_b = lookup_session_id(Identifier {
+                name: Some(session_b),
+                session_id: None,
+                path: None,
+            })
+            .await?;
+            crate::commands::session::handle_session_diff(&session_a, &session_b, json).await?;
+        }
+        SessionCommand::Merge {
+            session_a,
+            session_b,
+            into,
+            strategy,
+        } => {
+            let session_a = lookup_session_id(Identifier {
+                name: Some(session_a),
+                session_id: None,
+                path: None,
+            })
+            .await?;
+            let session_b = lookup_session_id(Identifier {
+                name: Some(session_b),
+                session_id: None,
+                path: None,
+            })
+            .await?;
+            crate::commands::session::handle_session_merge(
+                &session_a,
+                &session_b,
+                into,
+                strategy.parse()?,
+            )
+            .await?;
+        }
+        SessionCommand::Prune {
+            max_age_days,
+            max_count,
+            max_bytes,
+            dry_run,
+        } => {
+            let config_policy = goose::session::RetentionPolicy::from_config();
+            let policy = goose::session::RetentionPolicy {
+                max_age_days: max_age_days.or(config_policy.max_age_days),
+                max_count: max_count.or(config_policy.max_count),
+                max_bytes: max_bytes.or(config_policy.max_bytes),
+            };
+            crate::commands::session::handle_session_prune(policy, dry_run).await?;
+        }
     }
     Ok(())
 }
@@ -1048,6 +1410,7 @@ async fn handle_interactive_session(
     identifier: Option<Identifier>,
     resume: bool,
     history: bool,
+    resume_last_on_error: bool,
     session_opts: SessionOptions,
     extension_opts: ExtensionOptions,
 ) -> Result<()> {
@@ -1055,6 +1418,28 @@ async fn handle_interactive_session(
         configure_telemetry_consent_dialog()?;
     }
 
+    let resume_last_on_error = resume_last_on_error
+        || Config::global()
+            .get_param::<bool>(crate::session::GOOSE_RESUME_LAST_ON_ERROR)
+            .unwrap_or(false);
+    let crashed_session = if resume_last_on_error && !resume && identifier.is_none() {
+        offer_crashed_session().await?
+    } else {
+        None
+    };
+    let recovering = crashed_session.is_some();
+    let (identifier, resume) = match crashed_session {
+        Some(session_id) => (
+            Some(Identifier {
+                name: None,
+                session_id: Some(session_id),
+                path: None,
+            }),
+            true,
+        ),
+        None => (identifier, resume),
+    };
+
     let session_start = std::time::Instant::now();
     let session_type = if resume { "resumed" } else { "new" };
 
@@ -1092,10 +1477,25 @@ async fn handle_interactive_session(
         debug: session_opts.debug,
         max_tool_repetitions: session_opts.max_tool_repetitions,
         max_turns: session_opts.max_turns,
+        max_response_tokens: session_opts.max_response_tokens,
+        transcript: session_opts.transcript,
+        system_prompt_appends: [
+            session_opts.system_prompt_appends,
+            session_opts.system_prompt_append_files,
+        ]
+        .concat(),
+        system_prompt_prepends: [
+            session_opts.system_prompt_prepends,
+            session_opts.system_prompt_prepend_files,
+        ]
+        .concat(),
+        no_tools: session_opts.no_tools,
+        metadata: session_opts.metadata,
         scheduled_job_id: None,
         interactive: true,
         quiet: false,
         output_format: "text".to_string(),
+        include_agent_visible: false,
     })
     .await;
 
@@ -1103,11 +1503,45 @@ async fn handle_interactive_session(
         session.render_message_history();
     }
 
+    if recovering {
+        session.recover_interrupted_turn().await?;
+    }
+
     let result = session.interactive(None).await;
     log_session_completion(&session, session_start, session_type, result.is_ok()).await;
     result
 }
 
+/// Finds the most recent user session and, if it ended mid-turn, asks whether to resume it.
+async fn offer_crashed_session() -> Result<Option<String>> {
+    let session_manager = SessionManager::instance();
+    let sessions = session_manager
+        .list_sessions_by_types(&[SessionType::User])
+        .await?;
+    let Some(last) = sessions.first() else {
+        return Ok(None);
+    };
+    let session = session_manager.get_session(&last.id, true).await?;
+    let ended_mid_turn = session
+        .conversation
+        .as_ref()
+        .is_some_and(|conversation| crate::session::ends_mid_turn(conversation.messages()));
+    if !ended_mid_turn {
+        return Ok(None);
+    }
+
+    let prompt = format!(
+        "Session '{}' ({}) ended unexpectedly. Resume it?",
+        session.name, session.id
+    );
+    match cliclack::confirm(prompt).initial_value(true).interact() {
+        Ok(true) => Ok(Some(session.id)),
+        Ok(false) => Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
 async fn log_session_completion(
     session: &crate::CliSession,
     session_start: std::time::Instant,
@@ -1294,13 +1728,32 @@ async fn handle_run_command(
         debug: session_opts.debug,
         max_tool_repetitions: session_opts.max_tool_repetitions,
         max_turns: session_opts.max_turns,
+        max_response_tokens: session_opts.max_response_tokens,
+        transcript: session_opts.transcript,
+        system_prompt_appends: [
+            session_opts.system_prompt_appends,
+            session_opts.system_prompt_append_files,
+        ]
+        .concat(),
+        system_prompt_prepends: [
+            session_opts.system_prompt_prepends,
+            session_opts.system_prompt_prepend_files,
+        ]
+        .concat(),
+        no_tools: session_opts.no_tools,
+        metadata: session_opts.metadata,
         scheduled_job_id: run_behavior.scheduled_job_id,
         interactive: run_behavior.interactive,
         quiet: output_opts.quiet,
         output_format: output_opts.output_format,
+        include_agent_visible: output_opts.include_agent_visible,
     })
     .await;
 
+    if let Some(path) = &run_behavior.conversation {
+        session.seed_conversation(path).await?;
+    }
+
     if run_behavior.interactive {
         session.interactive(input_config.contents).await
     } else if let Some(contents) = input_config.contents {
@@ -1314,7 +1767,10 @@ async fn handle_run_command(
             "Headless session started"
         );
 
-        let result = session.headless(contents).await;
+        let result = match recipe.as_ref().and_then(|r| r.timeout()) {
+            Some(timeout) => session.headless_with_timeout(contents, timeout).await,
+            None => session.headless(contents).await,
+        };
         log_session_completion(&session, session_start, session_type, result.is_ok()).await;
         result
     } else {
@@ -1365,6 +1821,7 @@ async fn handle_bench_command(cmd: BenchCommand) -> Result<()> {
 fn handle_recipe_subcommand(command: RecipeCommand) -> Result<()> {
     match command {
         RecipeCommand::Validate { recipe_name } => handle_validate(&recipe_name),
+        RecipeCommand::Lint { recipe_name } => handle_lint(&recipe_name),
         RecipeCommand::Deeplink {
             recipe_name,
             params,
@@ -1377,6 +1834,7 @@ fn handle_recipe_subcommand(command: RecipeCommand) -> Result<()> {
             params,
         } => handle_open(&recipe_name, &params),
         RecipeCommand::List { format, verbose } => handle_list(&format, verbose),
+        RecipeCommand::Schema { output } => handle_schema(output),
     }
 }
 
@@ -1402,11 +1860,23 @@ async fn handle_default_session() -> Result<()> {
         configure_telemetry_consent_dialog()?;
     }
 
-    let session_id = get_or_create_session_id(None, false, false).await?;
+    let crashed_session = if Config::global()
+        .get_param::<bool>(crate::session::GOOSE_RESUME_LAST_ON_ERROR)
+        .unwrap_or(false)
+    {
+        offer_crashed_session().await?
+    } else {
+        None
+    };
+    let recovering = crashed_session.is_some();
+    let session_id = match crashed_session {
+        Some(session_id) => Some(session_id),
+        None => get_or_create_session_id(None, false, false).await?,
+    };
 
     let mut session = build_session(SessionBuilderConfig {
         session_id,
-        resume: false,
+        resume: recovering,
         no_session: false,
         extensions: Vec::new(),
         streamable_http_extensions: Vec::new(),
@@ -1418,18 +1888,32 @@ async fn handle_default_session() -> Result<()> {
         debug: false,
         max_tool_repetitions: None,
         max_turns: None,
+        max_response_tokens: None,
+        transcript: None,
+        system_prompt_appends: Vec::new(),
+        system_prompt_prepends: Vec::new(),
+        no_tools: false,
+        metadata: Vec::new(),
         scheduled_job_id: None,
         interactive: true,
         quiet: false,
         output_format: "text".to_string(),
+        include_agent_visible: false,
     })
     .await;
+    if recovering {
+        session.recover_interrupted_turn().await?;
+    }
     session.interactive(None).await
 }
 
 pub async fn cli() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    if let Some(dir) = &cli.working_dir {
+        apply_working_dir(dir)?;
+    }
+
     if let Err(e) = crate::project_tracker::update_project_tracker(None, None) {
         warn!("Warning: Failed to update project tracker: {}", e);
     }
@@ -1459,11 +1943,19 @@ pub async fn cli() -> anyhow::Result<()> {
             identifier,
             resume,
             history,
+            resume_last_on_error,
             session_opts,
             extension_opts,
         }) => {
-            handle_interactive_session(identifier, resume, history, session_opts, extension_opts)
-                .await
+            handle_interactive_session(
+                identifier,
+                resume,
+                history,
+                resume_last_on_error,
+                session_opts,
+                extension_opts,
+            )
+            .await
         }
         Some(Command::Project {}) => {
             handle_project_default()?;
@@ -1494,6 +1986,17 @@ pub async fn cli() -> anyhow::Result<()> {
             .await
         }
         Some(Command::Schedule { command }) => handle_schedule_command(command).await,
+        Some(Command::Apps { command }) => match command {
+            AppsCommand::Export { output } => crate::commands::apps::handle_apps_export(output),
+            AppsCommand::Import { input } => crate::commands::apps::handle_apps_import(input),
+        },
+        Some(Command::Config { command }) => match command {
+            ConfigCommand::Get { key } => crate::commands::config::handle_config_get(&key),
+            ConfigCommand::Set { key, value, force } => {
+                crate::commands::config::handle_config_set(&key, &value, force)
+            }
+            ConfigCommand::Validate {} => crate::commands::config::handle_config_validate().await,
+        },
         Some(Command::Update {
             canary,
             reconfigure,
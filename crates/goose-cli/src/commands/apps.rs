@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+use goose::goose_apps::{AppBundle, McpAppCache};
+use std::fs;
+use std::path::PathBuf;
+
+pub fn handle_apps_export(output: Option<PathBuf>) -> Result<()> {
+    let cache = McpAppCache::new()?;
+    let bundle = cache.export_bundle()?;
+    let json = serde_json::to_string_pretty(&bundle)?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, json)
+                .with_context(|| format!("Failed to write app bundle to {}", path.display()))?;
+            eprintln!(
+                "Exported {} app(s) to {}",
+                bundle.apps.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+pub fn handle_apps_import(input: PathBuf) -> Result<()> {
+    let content = fs::read_to_string(&input)
+        .with_context(|| format!("Failed to read app bundle from {}", input.display()))?;
+    let bundle: AppBundle = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid app bundle: {}", input.display()))?;
+
+    let cache = McpAppCache::new()?;
+    let imported = cache.import_bundle(&bundle)?;
+    println!("Imported {} app(s) from {}", imported, input.display());
+    Ok(())
+}
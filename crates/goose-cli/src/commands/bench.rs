@@ -50,8 +50,15 @@ pub async fn agent_generator(
         interactive: false, // Benchmarking is non-interactive
         scheduled_job_id: None,
         max_turns: None,
+        max_response_tokens: None,
+        transcript: None,
+        system_prompt_appends: Vec::new(),
+        system_prompt_prepends: Vec::new(),
+        no_tools: false,
+        metadata: Vec::new(),
         quiet: false,
         output_format: "text".to_string(),
+        include_agent_visible: false,
     })
     .await;
 
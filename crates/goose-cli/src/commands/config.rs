@@ -0,0 +1,254 @@
+use anyhow::{bail, Context, Result};
+use goose::config::{Config, ConfigError, ConfigSource, GooseMode};
+use serde_json::Value;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    Text,
+    Bool,
+    Count,
+    Float,
+    /// A fraction in [0, 1]
+    Threshold,
+    Mode,
+}
+
+const KNOWN_KEYS: &[(&str, ValueKind)] = &[
+    ("GOOSE_PROVIDER", ValueKind::Text),
+    ("GOOSE_MODEL", ValueKind::Text),
+    ("GOOSE_MODE", ValueKind::Mode),
+    ("GOOSE_TEMPERATURE", ValueKind::Float),
+    ("GOOSE_MAX_TOKENS", ValueKind::Count),
+    ("GOOSE_CONTEXT_LIMIT", ValueKind::Count),
+    ("GOOSE_MAX_TURNS", ValueKind::Count),
+    ("GOOSE_MAX_ACTIVE_AGENTS", ValueKind::Count),
+    (
+        goose::agents::GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS,
+        ValueKind::Count,
+    ),
+    (
+        goose::agents::GOOSE_RETRIES_COUNT_TOWARD_MAX_TURNS,
+        ValueKind::Bool,
+    ),
+    ("GOOSE_AUTO_COMPACT_THRESHOLD", ValueKind::Threshold),
+    (
+        goose::context_mgmt::GOOSE_AUTO_COMPACT_REMAINING_TOKENS,
+        ValueKind::Count,
+    ),
+    ("GOOSE_CLI_MIN_PRIORITY", ValueKind::Threshold),
+    ("GOOSE_CLI_SHOW_COST", ValueKind::Bool),
+    (crate::session::GOOSE_CLI_THINKING, ValueKind::Text),
+    ("GOOSE_TOOLSHIM", ValueKind::Bool),
+    ("GOOSE_LEAD_MODEL", ValueKind::Text),
+    ("GOOSE_LEAD_PROVIDER", ValueKind::Text),
+    ("GOOSE_LEAD_TURNS", ValueKind::Count),
+    ("GOOSE_LEAD_FAILURE_THRESHOLD", ValueKind::Count),
+    ("GOOSE_LEAD_FALLBACK_TURNS", ValueKind::Count),
+    ("GOOSE_WORKER_CONTEXT_LIMIT", ValueKind::Count),
+    (
+        goose::context_mgmt::GOOSE_COMPACT_TARGET_TOKENS,
+        ValueKind::Count,
+    ),
+    (
+        goose::context_mgmt::GOOSE_COMPACT_KEEP_RECENT_RATIO,
+        ValueKind::Threshold,
+    ),
+    (
+        goose::context_mgmt::GOOSE_MAX_REQUEST_BYTES,
+        ValueKind::Count,
+    ),
+    (
+        goose::context_mgmt::GOOSE_MAX_CONVERSATION_MESSAGES,
+        ValueKind::Count,
+    ),
+    (
+        goose::agents::tool_result_cache::GOOSE_TOOL_RESULT_CACHE,
+        ValueKind::Bool,
+    ),
+    (
+        crate::session::GOOSE_HEADLESS_CONTEXT_RETRIES,
+        ValueKind::Count,
+    ),
+    (crate::session::GOOSE_TRANSCRIPT_DIR, ValueKind::Text),
+    (crate::session::GOOSE_RESUME_LAST_ON_ERROR, ValueKind::Bool),
+    (crate::session::GOOSE_TOKEN_COMPARE_MODELS, ValueKind::Text),
+    (
+        goose::recipe::template_recipe::GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST,
+        ValueKind::Text,
+    ),
+    (
+        goose::permission::permission_inspector::GOOSE_APPROVE_SAFE_TOOLS,
+        ValueKind::Text,
+    ),
+    (
+        goose::session::retention::GOOSE_SESSION_RETENTION_MAX_AGE_DAYS,
+        ValueKind::Count,
+    ),
+    (
+        goose::session::retention::GOOSE_SESSION_RETENTION_MAX_COUNT,
+        ValueKind::Count,
+    ),
+    (
+        goose::session::retention::GOOSE_SESSION_RETENTION_MAX_BYTES,
+        ValueKind::Count,
+    ),
+    (
+        goose::session::session_manager::GOOSE_SESSION_BATCH_WRITES,
+        ValueKind::Bool,
+    ),
+];
+
+/// Looks up a known key case-insensitively, returning its canonical spelling.
+fn known_key(key: &str) -> Option<(&'static str, ValueKind)> {
+    KNOWN_KEYS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(key))
+        .copied()
+}
+
+/// Parses `raw` according to the key's expected type, returning the value to store.
+fn parse_value(key: &str, kind: ValueKind, raw: &str) -> Result<Value> {
+    let raw = raw.trim();
+    let value = match kind {
+        ValueKind::Text => Value::String(raw.to_string()),
+        ValueKind::Bool => Value::Bool(
+            raw.parse::<bool>()
+                .map_err(|_| anyhow::anyhow!("{} must be true or false", key))?,
+        ),
+        ValueKind::Count => Value::from(
+            raw.parse::<u64>()
+                .map_err(|_| anyhow::anyhow!("{} must be a non-negative integer", key))?,
+        ),
+        ValueKind::Float => Value::from(
+            raw.parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| anyhow::anyhow!("{} must be a number", key))?,
+        ),
+        ValueKind::Threshold => Value::from(
+            raw.parse::<f64>()
+                .ok()
+                .filter(|v| (0.0..=1.0).contains(v))
+                .ok_or_else(|| anyhow::anyhow!("{} must be a number between 0 and 1", key))?,
+        ),
+        ValueKind::Mode => {
+            let mode = GooseMode::from_str(&raw.to_lowercase()).map_err(|_| {
+                anyhow::anyhow!("{} must be one of: auto, approve, smart_approve, chat", key)
+            })?;
+            serde_json::to_value(mode)?
+        }
+    };
+    Ok(value)
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn handle_config_get(key: &str) -> Result<()> {
+    let known = known_key(key);
+    let key = known.map(|(canonical, _)| canonical).unwrap_or(key);
+    let config = Config::global();
+    match config.get_param_with_source::<Value>(key) {
+        Ok((value, source)) => {
+            println!("{}", display_value(&value));
+            if source != ConfigSource::User {
+                eprintln!("(from {} config)", source);
+            }
+            Ok(())
+        }
+        Err(ConfigError::NotFound(_)) => match known {
+            Some(_) => bail!("{} is not set", key),
+            None => bail!(
+                "Unknown config key '{}'. Known keys: {}",
+                key,
+                known_key_names()
+            ),
+        },
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", key)),
+    }
+}
+
+pub fn handle_config_set(key: &str, value: &str, force: bool) -> Result<()> {
+    let known = known_key(key);
+    let parsed = match known {
+        Some((canonical, kind)) => parse_value(canonical, kind, value)?,
+        None if force => serde_yaml::from_str::<Value>(value)
+            .unwrap_or_else(|_| Value::String(value.to_string())),
+        None => bail!(
+            "Unknown config key '{}'. Use --force to set it anyway. Known keys: {}",
+            key,
+            known_key_names()
+        ),
+    };
+
+    let key = known.map(|(canonical, _)| canonical).unwrap_or(key);
+    let config = Config::global();
+    config
+        .set_param(key, &parsed)
+        .with_context(|| format!("Failed to save {}", key))?;
+    println!("{} = {}", key, display_value(&parsed));
+
+    if std::env::var(key.to_uppercase()).is_ok() {
+        eprintln!(
+            "Note: the {} environment variable is set and takes precedence over the config file",
+            key.to_uppercase()
+        );
+    }
+    Ok(())
+}
+
+pub async fn handle_config_validate() -> Result<()> {
+    goose::providers::ping_configured_provider().await?;
+    println!("Provider is reachable and credentials are valid");
+    Ok(())
+}
+
+fn known_key_names() -> String {
+    KNOWN_KEYS
+        .iter()
+        .map(|(key, _)| *key)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value_validates_known_types() {
+        assert_eq!(
+            parse_value("GOOSE_AUTO_COMPACT_THRESHOLD", ValueKind::Threshold, "0.5").unwrap(),
+            Value::from(0.5)
+        );
+        assert!(parse_value("GOOSE_AUTO_COMPACT_THRESHOLD", ValueKind::Threshold, "1.5").is_err());
+        assert!(parse_value("GOOSE_AUTO_COMPACT_THRESHOLD", ValueKind::Threshold, "high").is_err());
+
+        assert_eq!(
+            parse_value("GOOSE_MODE", ValueKind::Mode, "Smart_Approve").unwrap(),
+            Value::String("smart_approve".to_string())
+        );
+        assert!(parse_value("GOOSE_MODE", ValueKind::Mode, "yolo").is_err());
+
+        assert_eq!(
+            parse_value("GOOSE_MAX_TURNS", ValueKind::Count, "25").unwrap(),
+            Value::from(25u64)
+        );
+        assert!(parse_value("GOOSE_MAX_TURNS", ValueKind::Count, "-1").is_err());
+        assert!(parse_value("GOOSE_TOOLSHIM", ValueKind::Bool, "yes").is_err());
+    }
+
+    #[test]
+    fn test_known_key_is_case_insensitive() {
+        assert_eq!(
+            known_key("goose_mode"),
+            Some(("GOOSE_MODE", ValueKind::Mode))
+        );
+        assert_eq!(known_key("NOT_A_REAL_KEY"), None);
+    }
+}
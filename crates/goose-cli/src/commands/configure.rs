@@ -170,6 +170,7 @@ async fn handle_manual_provider_setup(config: &Config) {
             );
             set_extension(ExtensionEntry {
                 enabled: true,
+                required: false,
                 config: ExtensionConfig::default(),
             });
         }
@@ -937,6 +938,7 @@ fn configure_builtin_extension() -> anyhow::Result<()> {
 
     set_extension(ExtensionEntry {
         enabled: true,
+        required: false,
         config: ExtensionConfig::Builtin {
             name: extension.clone(),
             display_name: Some(display_name),
@@ -976,6 +978,7 @@ fn configure_stdio_extension() -> anyhow::Result<()> {
 
     set_extension(ExtensionEntry {
         enabled: true,
+        required: false,
         config: ExtensionConfig::Stdio {
             name: name.clone(),
             cmd,
@@ -986,6 +989,8 @@ fn configure_stdio_extension() -> anyhow::Result<()> {
             timeout: Some(timeout),
             bundled: None,
             available_tools: Vec::new(),
+            auto_restart: false,
+            max_restarts: None,
         },
     });
 
@@ -1019,6 +1024,7 @@ fn configure_streamable_http_extension() -> anyhow::Result<()> {
 
     set_extension(ExtensionEntry {
         enabled: true,
+        required: false,
         config: ExtensionConfig::StreamableHttp {
             name: name.clone(),
             uri,
@@ -1677,6 +1683,7 @@ pub async fn handle_openrouter_auth() -> anyhow::Result<()> {
                     if !has_developer {
                         set_extension(ExtensionEntry {
                             enabled: true,
+                            required: false,
                             config: ExtensionConfig::Builtin {
                                 name: "developer".to_string(),
                                 display_name: Some(goose::config::DEFAULT_DISPLAY_NAME.to_string()),
@@ -1753,6 +1760,7 @@ pub async fn handle_tetrate_auth() -> anyhow::Result<()> {
                     if !has_developer {
                         set_extension(ExtensionEntry {
                             enabled: true,
+                            required: false,
                             config: ExtensionConfig::Builtin {
                                 name: "developer".to_string(),
                                 display_name: Some(goose::config::DEFAULT_DISPLAY_NAME.to_string()),
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use console::style;
 use goose::config::paths::Paths;
-use goose::config::Config;
+use goose::config::{Config, ConfigSource};
 use goose::session::session_manager::{DB_NAME, SESSIONS_FOLDER};
 use serde_yaml;
 
@@ -40,12 +40,17 @@ pub fn handle_info(verbose: bool) -> Result<()> {
     let config_dir = Paths::config_dir();
     let config_yaml_file = config_dir.join(CONFIG_YAML_NAME);
 
-    let paths = [
+    let system_config_file = config.system_config_path().map(Path::to_path_buf);
+
+    let mut paths = vec![
         ("Config dir:", &config_dir),
         ("Config yaml:", &config_yaml_file),
-        ("Sessions DB (sqlite):", &sessions_db),
-        ("Logs dir:", &logs_dir),
     ];
+    if let Some(system_config_file) = &system_config_file {
+        paths.push(("System config yaml:", system_config_file));
+    }
+    paths.push(("Sessions DB (sqlite):", &sessions_db));
+    paths.push(("Logs dir:", &logs_dir));
 
     let label_padding = paths.iter().map(|(l, _)| l.len()).max().unwrap_or(0) + 4;
     let path_padding = paths
@@ -71,7 +76,7 @@ pub fn handle_info(verbose: bool) -> Result<()> {
 
     if verbose {
         println!("\n{}", style("goose Configuration:").cyan().bold());
-        let values = config.all_values()?;
+        let values = config.all_values_with_source()?;
         if values.is_empty() {
             println!("  No configuration values set");
             println!(
@@ -79,12 +84,19 @@ pub fn handle_info(verbose: bool) -> Result<()> {
                 style("goose configure").cyan()
             );
         } else {
-            let sorted_values: std::collections::BTreeMap<_, _> =
-                values.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            let sorted_values: std::collections::BTreeMap<_, _> = values.into_iter().collect();
 
-            if let Ok(yaml) = serde_yaml::to_string(&sorted_values) {
-                for line in yaml.lines() {
-                    println!("  {}", line);
+            for (key, (value, source)) in sorted_values {
+                let entry = std::collections::BTreeMap::from([(key, value)]);
+                let Ok(yaml) = serde_yaml::to_string(&entry) else {
+                    continue;
+                };
+                for (i, line) in yaml.lines().enumerate() {
+                    if i == 0 && source == ConfigSource::System {
+                        println!("  {}  {}", line, style("(system)").dim());
+                    } else {
+                        println!("  {}", line);
+                    }
                 }
             }
         }
@@ -1,4 +1,6 @@
+pub mod apps;
 pub mod bench;
+pub mod config;
 pub mod configure;
 pub mod info;
 pub mod project;
@@ -1,7 +1,10 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use console::style;
-use goose::recipe::validate_recipe::validate_recipe_template_from_file;
+use goose::recipe::validate_recipe::{lint_recipe_parameters, validate_recipe_template_from_file};
+use goose::recipe::Recipe;
 use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 
 use crate::recipes::github_recipe::RecipeSource;
 use crate::recipes::search_recipe::{list_available_recipes, load_recipe_file};
@@ -21,6 +24,35 @@ pub fn handle_validate(recipe_name: &str) -> Result<()> {
     Ok(())
 }
 
+pub fn handle_lint(recipe_name: &str) -> Result<()> {
+    let recipe_file = load_recipe_file(recipe_name)?;
+    let recipe_dir = recipe_file.parent_dir.display().to_string();
+    let lint = lint_recipe_parameters(&recipe_file.content, Some(recipe_dir))?;
+
+    if lint.is_clean() {
+        println!("{} no parameter problems found", style("✓").green().bold());
+        return Ok(());
+    }
+    for key in &lint.unused {
+        println!(
+            "{} parameter '{}' is declared but never used",
+            style("✗").red().bold(),
+            key
+        );
+    }
+    for key in &lint.undefined {
+        println!(
+            "{} '{{{{ {} }}}}' is used but not declared as a parameter",
+            style("✗").red().bold(),
+            key
+        );
+    }
+    Err(anyhow::anyhow!(
+        "found {} parameter problem(s)",
+        lint.unused.len() + lint.undefined.len()
+    ))
+}
+
 pub fn handle_deeplink(recipe_name: &str, params: &[String]) -> Result<String> {
     let params_map = parse_params(params)?;
     match generate_deeplink(recipe_name, params_map) {
@@ -150,6 +182,20 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
     Ok(())
 }
 
+pub fn handle_schema(output: Option<PathBuf>) -> Result<()> {
+    let json = serde_json::to_string_pretty(&Recipe::json_schema())?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, json)
+                .with_context(|| format!("Failed to write recipe schema to {}", path.display()))?;
+            eprintln!("Wrote recipe schema to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
 fn parse_params(params: &[String]) -> Result<HashMap<String, String>> {
     let mut params_map = HashMap::new();
     for param in params {
@@ -168,7 +214,7 @@ fn parse_params(params: &[String]) -> Result<HashMap<String, String>> {
 fn generate_deeplink(
     recipe_name: &str,
     params: HashMap<String, String>,
-) -> Result<(String, goose::recipe::Recipe)> {
+) -> Result<(String, Recipe)> {
     let recipe_file = load_recipe_file(recipe_name)?;
     // Load the recipe file first to validate it
     let recipe = validate_recipe_template_from_file(&recipe_file)?;
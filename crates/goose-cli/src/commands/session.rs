@@ -2,14 +2,23 @@ use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 
 use cliclack::{confirm, multiselect, select};
-use goose::session::{generate_diagnostics, Session, SessionManager};
+use goose::session::diff::TurnSummary;
+use goose::session::{
+    diff_sessions, generate_diagnostics, merge_conversations, MergeStrategy, RetentionPolicy,
+    Session, SessionManager, SessionType, TurnDiff,
+};
 use goose::utils::safe_truncate;
 use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
 use std::path::PathBuf;
+use std::time::Duration;
 
 const TRUNCATED_DESC_LENGTH: usize = 60;
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 async fn remove_sessions(session_manager: &SessionManager, sessions: Vec<Session>) -> Result<()> {
     println!("The following sessions will be removed:");
@@ -219,6 +228,251 @@ pub async fn handle_session_export(
     Ok(())
 }
 
+/// Prints the last `lines` messages of a session and then polls the session store,
+/// printing messages as they are appended until interrupted or the session is deleted.
+pub async fn handle_session_tail(session_id: &str, lines: usize) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let session = session_manager
+        .get_session(session_id, false)
+        .await
+        .with_context(|| format!("Session '{}' not found", session_id))?;
+    println!(
+        "{}",
+        console::style(format!(
+            "Following session {} ({}). Press Ctrl+C to stop.",
+            session.id, session.name
+        ))
+        .dim()
+    );
+
+    let existing = session_manager.get_messages_after(session_id, 0).await?;
+    let mut last_row = existing.last().map_or(0, |(row, _)| *row);
+    // `replace_conversation` rewrites every row under a new row id, and stored messages
+    // don't keep their ids, so messages already shown are recognised by their content
+    let mut seen: HashSet<u64> = existing
+        .iter()
+        .map(|(_, message)| message_key(message))
+        .collect();
+    for (_, message) in existing.iter().skip(existing.len().saturating_sub(lines)) {
+        print_tail_message(message);
+    }
+
+    loop {
+        tokio::time::sleep(TAIL_POLL_INTERVAL).await;
+        if let Err(e) = session_manager.get_session(session_id, false).await {
+            if e.to_string().contains("not found") {
+                anyhow::bail!("Session '{}' was deleted", session_id);
+            }
+            return Err(e);
+        }
+        for (row, message) in session_manager
+            .get_messages_after(session_id, last_row)
+            .await?
+        {
+            last_row = row;
+            if seen.insert(message_key(&message)) {
+                print_tail_message(&message);
+            }
+        }
+    }
+}
+
+fn message_key(message: &goose::conversation::message::Message) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(message)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+fn print_tail_message(message: &goose::conversation::message::Message) {
+    let text = message_to_markdown(message, false);
+    if text.trim().is_empty() {
+        return;
+    }
+    let role = match message.role {
+        rmcp::model::Role::User => console::style("user").cyan(),
+        rmcp::model::Role::Assistant => console::style("assistant").green(),
+    };
+    println!("{} {}\n", role.bold(), text.trim_end());
+}
+
+pub async fn handle_session_prune(policy: RetentionPolicy, dry_run: bool) -> Result<()> {
+    if policy.is_unlimited() {
+        println!("No retention limits configured; nothing to prune.");
+        return Ok(());
+    }
+
+    let session_manager = SessionManager::instance();
+    let pruned = session_manager.prune_sessions(&policy, dry_run).await?;
+
+    if pruned.is_empty() {
+        println!("No sessions exceed the retention policy.");
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!("{} {} session(s):", verb, pruned.len());
+    for session in &pruned {
+        println!(
+            "  - {} - {} - {} ({} bytes)",
+            session.id,
+            safe_truncate(&session.name, TRUNCATED_DESC_LENGTH),
+            session.updated_at,
+            session.size_bytes
+        );
+    }
+    Ok(())
+}
+
+pub async fn handle_session_diff(session_a: &str, session_b: &str, json: bool) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let a = session_manager
+        .get_session(session_a, true)
+        .await
+        .with_context(|| format!("Failed to load session '{}'", session_a))?;
+    let b = session_manager
+        .get_session(session_b, true)
+        .await
+        .with_context(|| format!("Failed to load session '{}'", session_b))?;
+
+    let diff = diff_sessions(&a, &b);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!("--- A: {} ({})", a.id, a.name);
+    println!("+++ B: {} ({})", b.id, b.name);
+
+    for turn in &diff.turns {
+        match turn {
+            TurnDiff::Identical { index, turn } => {
+                println!(
+                    "\n  turn {}: identical - {}",
+                    index + 1,
+                    safe_truncate(&turn.user_text, TRUNCATED_DESC_LENGTH)
+                );
+            }
+            TurnDiff::Diverged {
+                index,
+                a,
+                b,
+                differences,
+            } => {
+                println!("\n! turn {}: diverged", index + 1);
+                for difference in differences {
+                    println!("    * {}", difference);
+                }
+                print_turn_summary("-", a);
+                print_turn_summary("+", b);
+            }
+            TurnDiff::OnlyInA { index, turn } => {
+                println!("\n- turn {}: only in A", index + 1);
+                print_turn_summary("-", turn);
+            }
+            TurnDiff::OnlyInB { index, turn } => {
+                println!("\n+ turn {}: only in B", index + 1);
+                print_turn_summary("+", turn);
+            }
+        }
+    }
+
+    match diff.first_divergence() {
+        Some(index) => println!("\nSessions diverge at turn {}", index + 1),
+        None => println!("\nSessions are identical"),
+    }
+
+    Ok(())
+}
+
+pub async fn handle_session_merge(
+    session_a: &str,
+    session_b: &str,
+    name: String,
+    strategy: MergeStrategy,
+) -> Result<()> {
+    let session_manager = SessionManager::instance();
+    let a = session_manager
+        .get_session(session_a, true)
+        .await
+        .with_context(|| format!("Failed to load session '{}'", session_a))?;
+    let b = session_manager
+        .get_session(session_b, true)
+        .await
+        .with_context(|| format!("Failed to load session '{}'", session_b))?;
+
+    let (merged, issues) = merge_conversations(
+        &a.conversation.unwrap_or_default(),
+        &b.conversation.unwrap_or_default(),
+        strategy,
+    );
+
+    // The merged session runs in the first session's directory with its extensions and model
+    let session = session_manager
+        .create_session(a.working_dir.clone(), name, SessionType::User)
+        .await?;
+    let mut update = session_manager
+        .update(&session.id)
+        .user_provided_name(session.name.clone())
+        .extension_data(a.extension_data);
+    if let Some(provider_name) = a.provider_name {
+        update = update.provider_name(provider_name);
+    }
+    if let Some(model_config) = a.model_config {
+        update = update.model_config(model_config);
+    }
+    update.apply().await?;
+    session_manager
+        .replace_conversation(&session.id, &merged)
+        .await?;
+
+    for issue in &issues {
+        println!("  adjusted: {}", issue);
+    }
+    println!(
+        "Merged {} and {} into session {} ({} messages)",
+        a.id,
+        b.id,
+        session.id,
+        merged.len()
+    );
+    println!(
+        "Resume it with: goose session --resume --session-id {}",
+        session.id
+    );
+    Ok(())
+}
+
+fn print_turn_summary(marker: &str, turn: &TurnSummary) {
+    println!(
+        "  {} user: {}",
+        marker,
+        safe_truncate(&turn.user_text, TRUNCATED_DESC_LENGTH)
+    );
+    for call in &turn.tool_calls {
+        println!(
+            "  {} tool: {} {}",
+            marker,
+            call.name,
+            safe_truncate(&call.arguments, TRUNCATED_DESC_LENGTH)
+        );
+        if let Some(response) = &call.response {
+            println!(
+                "  {}   -> {}",
+                marker,
+                safe_truncate(response, TRUNCATED_DESC_LENGTH)
+            );
+        }
+    }
+    println!(
+        "  {} assistant: {}",
+        marker,
+        safe_truncate(&turn.assistant_text, TRUNCATED_DESC_LENGTH)
+    );
+}
+
 pub async fn handle_diagnostics(session_id: &str, output_path: Option<PathBuf>) -> Result<()> {
     println!(
         "Generating diagnostics bundle for session '{}'...",
@@ -33,7 +33,9 @@ pub fn extract_recipe_info_from_cli(
                         path: recipe_file_path.to_string_lossy().to_string(),
                         name,
                         values: None,
+                        inherit: Vec::new(),
                         sequential_when_repeated: true,
+                        timeout_seconds: None,
                         description: None,
                     };
                     all_sub_recipes.push(additional_sub_recipe);
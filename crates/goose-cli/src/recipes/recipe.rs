@@ -10,12 +10,27 @@ use goose::recipe::build_recipe::{
     apply_values_to_parameters, build_recipe_from_template, RecipeError,
 };
 use goose::recipe::validate_recipe::parse_and_validate_parameters;
-use goose::recipe::Recipe;
-
-fn create_user_prompt_callback() -> impl Fn(&str, &str) -> Result<String> {
-    |key: &str, description: &str| -> Result<String> {
-        let input_value =
-            cliclack::input(format!("Please enter {} ({})", key, description)).interact()?;
+use goose::recipe::{Recipe, RecipeParameter, RecipeParameterInputType};
+
+fn create_user_prompt_callback() -> impl Fn(&RecipeParameter) -> Result<String> {
+    |param: &RecipeParameter| -> Result<String> {
+        if let (RecipeParameterInputType::Select, Some(options)) =
+            (&param.input_type, &param.options)
+        {
+            let mut select = cliclack::select(format!(
+                "Please choose {} ({})",
+                param.key, param.description
+            ));
+            for option in options {
+                select = select.item(option.clone(), option, "");
+            }
+            return Ok(select.interact()?);
+        }
+        let input_value = cliclack::input(format!(
+            "Please enter {} ({})",
+            param.key, param.description
+        ))
+        .interact()?;
         Ok(input_value)
     }
 }
@@ -40,7 +55,7 @@ pub fn load_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<R
             }
             Ok(recipe)
         }
-        Err(RecipeError::MissingParams { parameters }) => Err(anyhow::anyhow!(
+        Err(RecipeError::MissingParams { parameters, .. }) => Err(anyhow::anyhow!(
             "Please provide the following parameters in the command line: {}",
             missing_parameters_command_line(parameters)
         )),
@@ -144,7 +159,7 @@ pub fn explain_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Resul
         &params,
         recipe_parameters,
         &recipe_dir_str,
-        None::<fn(&str, &str) -> Result<String>>,
+        None::<fn(&RecipeParameter) -> Result<String>>,
     )?;
     print_recipe_explanation(&recipe_template);
     print_required_parameters_for_template(params_for_template, missing_params);
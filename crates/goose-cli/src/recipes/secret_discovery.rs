@@ -161,6 +161,8 @@ mod tests {
                     description: "slack-mcp".to_string(),
                     bundled: None,
                     available_tools: Vec::new(),
+                    auto_restart: false,
+                    max_restarts: None,
                 },
                 ExtensionConfig::Builtin {
                     name: "builtin-ext".to_string(),
@@ -171,6 +173,7 @@ mod tests {
                     available_tools: Vec::new(),
                 },
             ]),
+            required_extensions: None,
             settings: None,
             activities: None,
             author: None,
@@ -178,6 +181,8 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         }
     }
 
@@ -214,6 +219,7 @@ mod tests {
             instructions: Some("Test instructions".to_string()),
             prompt: None,
             extensions: None,
+            required_extensions: None,
             settings: None,
             activities: None,
             author: None,
@@ -221,6 +227,8 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -257,8 +265,11 @@ mod tests {
                     description: "service-b".to_string(),
                     bundled: None,
                     available_tools: Vec::new(),
+                    auto_restart: false,
+                    max_restarts: None,
                 },
             ]),
+            required_extensions: None,
             settings: None,
             activities: None,
             author: None,
@@ -266,6 +277,8 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -310,15 +323,20 @@ mod tests {
                 name: "child-recipe".to_string(),
                 path: "path/to/child.yaml".to_string(),
                 values: None,
+                inherit: Vec::new(),
                 sequential_when_repeated: false,
+                timeout_seconds: None,
                 description: None,
             }]),
+            required_extensions: None,
             settings: None,
             activities: None,
             author: None,
             parameters: None,
             response: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
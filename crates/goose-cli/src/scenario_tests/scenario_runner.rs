@@ -250,6 +250,7 @@ where
         None,
         None,
         "text".to_string(),
+        false,
     )
     .await;
 
@@ -5,14 +5,16 @@ use goose::agents::Agent;
 use goose::config::get_enabled_extensions;
 use goose::config::resolve_extensions_for_new_session;
 use goose::config::{
-    extensions::get_extension_by_name, get_all_extensions, Config, ExtensionConfig,
+    extensions::{get_extension_by_name, name_to_key},
+    get_all_extensions, get_required_extension_names, Config, ExtensionConfig,
 };
 use goose::providers::create;
 use goose::recipe::Recipe;
 use goose::session::session_manager::SessionType;
-use goose::session::{EnabledExtensionsState, ExtensionState};
+use goose::session::{EnabledExtensionsState, ExtensionState, SessionManager};
 use rustyline::EditMode;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
 use std::process;
 use std::sync::Arc;
 use tokio::task::JoinSet;
@@ -104,6 +106,18 @@ pub struct SessionBuilderConfig {
     pub max_tool_repetitions: Option<u32>,
     /// Maximum number of turns (iterations) allowed without user input
     pub max_turns: Option<u32>,
+    /// Cap on tokens generated per model response
+    pub max_response_tokens: Option<i32>,
+    /// Markdown file to append the session transcript to
+    pub transcript: Option<PathBuf>,
+    /// Text appended to the end of the system prompt for this session only
+    pub system_prompt_appends: Vec<String>,
+    /// Text put at the start of the system prompt for this session only
+    pub system_prompt_prepends: Vec<String>,
+    /// Withhold all tools from the model
+    pub no_tools: bool,
+    /// Metadata tags to attach to the session
+    pub metadata: Vec<(String, String)>,
     /// ID of the scheduled job that triggered this session (if any)
     pub scheduled_job_id: Option<String>,
     /// Whether this session will be used interactively (affects debugging prompts)
@@ -112,6 +126,8 @@ pub struct SessionBuilderConfig {
     pub quiet: bool,
     /// Output format (text, json)
     pub output_format: String,
+    /// Include agent-only messages in json output
+    pub include_agent_visible: bool,
 }
 
 /// Manual implementation of Default to ensure proper initialization of output_format
@@ -132,10 +148,17 @@ impl Default for SessionBuilderConfig {
             debug: false,
             max_tool_repetitions: None,
             max_turns: None,
+            max_response_tokens: None,
+            transcript: None,
+            system_prompt_appends: Vec::new(),
+            system_prompt_prepends: Vec::new(),
+            no_tools: false,
+            metadata: Vec::new(),
             scheduled_job_id: None,
             interactive: false,
             quiet: false,
             output_format: "text".to_string(),
+            include_agent_visible: false,
         }
     }
 }
@@ -223,6 +246,7 @@ async fn offer_extension_debugging_help(
         None,
         None,
         "text".to_string(),
+        false,
     )
     .await;
 
@@ -245,9 +269,28 @@ async fn offer_extension_debugging_help(
     Ok(())
 }
 
+/// Required extensions named by the recipe that the session isn't going to load.
+fn unlisted_required_extensions(
+    required: &[String],
+    extensions_to_load: &[(String, ExtensionConfig)],
+) -> Vec<String> {
+    let loaded: HashSet<String> = extensions_to_load
+        .iter()
+        .map(|(_, cfg)| name_to_key(&cfg.name()))
+        .collect();
+    required
+        .iter()
+        .filter(|name| !loaded.contains(&name_to_key(name)))
+        .cloned()
+        .collect()
+}
+
+/// Starts the extensions concurrently. Failures are reported and skipped, except for
+/// extensions in `required` (keys as produced by `name_to_key`), which end the process.
 async fn load_extensions(
     agent: Agent,
     extensions_to_load: Vec<(String, ExtensionConfig)>,
+    required: &HashSet<String>,
     provider_for_debug: Arc<dyn goose::providers::base::Provider>,
     interactive: bool,
 ) -> Arc<Agent> {
@@ -295,19 +338,32 @@ async fn load_extensions(
 
     spinner.clear();
 
+    let mut failed_required = Vec::new();
     for (id, err) in offer_debug {
-        let label = extensions_to_load
+        let (label, is_required) = extensions_to_load
             .get(id)
-            .map(|e| e.0.clone())
+            .map(|(label, cfg)| (label.clone(), required.contains(&name_to_key(&cfg.name()))))
             .unwrap_or_default();
-        eprintln!(
-            "{}",
-            style(format!(
-                "Warning: Failed to start extension '{}' ({}), continuing without it",
-                label, err
-            ))
-            .yellow()
-        );
+        if is_required {
+            eprintln!(
+                "{}",
+                style(format!(
+                    "Error: Failed to start required extension '{}' ({})",
+                    label, err
+                ))
+                .red()
+            );
+            failed_required.push(label.clone());
+        } else {
+            eprintln!(
+                "{}",
+                style(format!(
+                    "Warning: Failed to start extension '{}' ({}), continuing without it",
+                    label, err
+                ))
+                .yellow()
+            );
+        }
 
         if let Err(debug_err) = offer_extension_debugging_help(
             &label,
@@ -321,6 +377,14 @@ async fn load_extensions(
         }
     }
 
+    if !failed_required.is_empty() {
+        output::render_error(&format!(
+            "Cannot start session: required extension(s) {} failed to start",
+            failed_required.join(", ")
+        ));
+        process::exit(1);
+    }
+
     agent_ptr
 }
 
@@ -363,13 +427,80 @@ fn check_missing_extensions_or_exit(saved_extensions: &[ExtensionConfig], intera
     }
 }
 
-pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
+/// Resolves a session reference to a full session id. `"last"` picks the most recently
+/// updated session; anything else must be an exact id or a prefix of exactly one id.
+/// `ids` are ordered most recently updated first, as `list_sessions` returns them.
+fn resolve_session_id(reference: &str, ids: &[String]) -> anyhow::Result<String> {
+    if reference == "last" {
+        return ids
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no previous sessions found"));
+    }
+    if ids.iter().any(|id| id == reference) {
+        return Ok(reference.to_string());
+    }
+    let candidates: Vec<&str> = ids
+        .iter()
+        .filter(|id| id.starts_with(reference))
+        .map(String::as_str)
+        .collect();
+    match candidates.as_slice() {
+        [] => anyhow::bail!("no such session exists"),
+        [id] => Ok(id.to_string()),
+        _ => anyhow::bail!(
+            "'{}' matches more than one session: {}",
+            reference,
+            candidates.join(", ")
+        ),
+    }
+}
+
+async fn resolve_resume_session_id(
+    session_manager: &SessionManager,
+    reference: &str,
+) -> anyhow::Result<String> {
+    if reference != "last" && session_manager.get_session(reference, false).await.is_ok() {
+        return Ok(reference.to_string());
+    }
+    let ids: Vec<String> = session_manager
+        .list_sessions()
+        .await?
+        .into_iter()
+        .map(|session| session.id)
+        .collect();
+    resolve_session_id(reference, &ids)
+}
+
+pub async fn build_session(mut session_config: SessionBuilderConfig) -> CliSession {
     goose::posthog::set_session_context("cli", session_config.resume);
 
     let config = Config::global();
     let agent: Agent = Agent::new();
     let session_manager = agent.config.session_manager.clone();
 
+    if session_config.resume {
+        if let Some(reference) = session_config.session_id.take() {
+            let session_id = resolve_resume_session_id(&session_manager, &reference)
+                .await
+                .unwrap_or_else(|e| {
+                    output::render_error(&format!(
+                        "Cannot resume session {} - {}",
+                        style(&reference).cyan(),
+                        e
+                    ));
+                    process::exit(1);
+                });
+            session_config.session_id = Some(session_id);
+        }
+    }
+
+    if !session_config.resume {
+        if let Err(e) = session_manager.enforce_retention_policy().await {
+            tracing::warn!("Failed to apply session retention policy: {}", e);
+        }
+    }
+
     let (saved_provider, saved_model_config) = if session_config.resume {
         if let Some(ref session_id) = session_config.session_id {
             match session_manager.get_session(session_id, false).await {
@@ -409,7 +540,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         if let Some(temp) = recipe_settings.and_then(|s| s.temperature) {
             config = config.with_temperature(Some(temp));
         }
-        config
+        config.with_max_response_tokens(session_config.max_response_tokens)
     } else {
         let temperature = recipe_settings.and_then(|s| s.temperature);
         goose::model::ModelConfig::new(&model_name)
@@ -418,6 +549,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
                 process::exit(1);
             })
             .with_temperature(temperature)
+            .with_max_response_tokens(session_config.max_response_tokens)
     };
 
     agent
@@ -486,6 +618,23 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         session_config.session_id.unwrap()
     };
 
+    if !session_config.metadata.is_empty() {
+        let mut metadata = session_manager
+            .get_session(&session_id, false)
+            .await
+            .map(|s| s.metadata)
+            .unwrap_or_default();
+        metadata.extend(session_config.metadata);
+        if let Err(e) = session_manager
+            .update(&session_id)
+            .metadata(metadata)
+            .apply()
+            .await
+        {
+            output::render_error(&format!("Failed to save session metadata: {}", e));
+        }
+    }
+
     agent
         .update_provider(new_provider, &session_id)
         .await
@@ -507,7 +656,9 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
 
         let current_workdir =
             std::env::current_dir().expect("Failed to get current working directory");
-        if current_workdir != session.working_dir {
+        // An explicit --working-dir wins over the directory the session was created in
+        let explicit_workdir = std::env::var_os("GOOSE_WORKING_DIR").is_some();
+        if current_workdir != session.working_dir && !explicit_workdir {
             if session_config.interactive {
                 let change_workdir = cliclack::confirm(format!("{} The original working directory of this session was set to {}. Your current directory is {}. Do you want to switch back to the original working directory?", style("WARNING:").yellow(), style(session.working_dir.display()).cyan(), style(current_workdir.display()).cyan()))
                         .initial_value(true)
@@ -576,9 +727,27 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         .collect();
     extensions_to_load.extend(cli_flag_extensions_to_load);
 
+    let recipe_required = recipe
+        .and_then(|r| r.required_extensions.clone())
+        .unwrap_or_default();
+    let unlisted = unlisted_required_extensions(&recipe_required, &extensions_to_load);
+    if !unlisted.is_empty() {
+        output::render_error(&format!(
+            "Recipe requires extension(s) {} but does not enable them",
+            unlisted.join(", ")
+        ));
+        process::exit(1);
+    }
+    let required: HashSet<String> = recipe_required
+        .into_iter()
+        .chain(get_required_extension_names())
+        .map(|name| name_to_key(&name))
+        .collect();
+
     let agent_ptr = load_extensions(
         agent,
         extensions_to_load,
+        &required,
         Arc::clone(&provider_for_display),
         session_config.interactive,
     )
@@ -599,7 +768,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
 
     let debug_mode = session_config.debug || config.get_param("GOOSE_DEBUG").unwrap_or(false);
 
-    let session = CliSession::new(
+    let mut session = CliSession::new(
         Arc::try_unwrap(agent_ptr).unwrap_or_else(|_| panic!("There should be no more references")),
         session_id.clone(),
         debug_mode,
@@ -608,6 +777,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         edit_mode,
         recipe.and_then(|r| r.retry.clone()),
         session_config.output_format.clone(),
+        session_config.include_agent_visible,
     )
     .await;
 
@@ -619,6 +789,30 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         tracing::warn!("Failed to save extension state: {}", e);
     }
 
+    let transcript_path = session_config.transcript.clone().or_else(|| {
+        config
+            .get_param::<String>(super::transcript::GOOSE_TRANSCRIPT_DIR)
+            .ok()
+            .map(|dir| PathBuf::from(dir).join(format!("{}.md", session_id)))
+    });
+    if let Some(path) = transcript_path {
+        match super::transcript::Transcript::open(&path, &session_id) {
+            Ok(transcript) => session.set_transcript(transcript),
+            Err(e) => {
+                output::render_error(&format!("Failed to open transcript: {:#}", e));
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(recipe) = recipe.filter(|r| r.artifacts.is_some()) {
+        session.set_recipe(recipe.clone());
+    }
+
+    if let Err(e) = session.register_frontend_tools().await {
+        output::render_error(&format!("Failed to register CLI tools: {:#}", e));
+    }
+
     // Add CLI-specific system prompt extension
     session
         .agent
@@ -629,6 +823,15 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         session.agent.extend_system_prompt(additional_prompt).await;
     }
 
+    for append in session_config.system_prompt_appends {
+        session.agent.append_system_prompt(append).await;
+    }
+    for prepend in session_config.system_prompt_prepends {
+        session.agent.prepend_system_prompt(prepend).await;
+    }
+
+    session.agent.set_tools_disabled(session_config.no_tools);
+
     // Only override system prompt if a system override exists
     let system_prompt_file: Option<String> = config.get_param("GOOSE_SYSTEM_PROMPT_FILE_PATH").ok();
     if let Some(ref path) = system_prompt_file {
@@ -654,6 +857,35 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
 mod tests {
     use super::*;
 
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_session_id_last_and_exact() {
+        let ids = ids(&["20250922_101500", "20250921_143022", "20250921_1430"]);
+        assert_eq!(resolve_session_id("last", &ids).unwrap(), "20250922_101500");
+        assert_eq!(
+            resolve_session_id("20250921_1430", &ids).unwrap(),
+            "20250921_1430"
+        );
+        assert!(resolve_session_id("last", &[]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_session_id_prefix() {
+        let ids = ids(&["20250922_101500", "20250921_143022", "20250921_090000"]);
+        assert_eq!(
+            resolve_session_id("20250922", &ids).unwrap(),
+            "20250922_101500"
+        );
+        let err = resolve_session_id("20250921", &ids)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("20250921_143022") && err.contains("20250921_090000"));
+        assert!(resolve_session_id("2024", &ids).is_err());
+    }
+
     #[test]
     fn test_session_builder_config_creation() {
         let config = SessionBuilderConfig {
@@ -670,10 +902,17 @@ mod tests {
             debug: true,
             max_tool_repetitions: Some(5),
             max_turns: None,
+            max_response_tokens: None,
+            transcript: None,
+            system_prompt_appends: Vec::new(),
+            system_prompt_prepends: Vec::new(),
+            no_tools: false,
+            metadata: Vec::new(),
             scheduled_job_id: None,
             interactive: true,
             quiet: false,
             output_format: "text".to_string(),
+            include_agent_visible: false,
         };
 
         assert_eq!(config.extensions.len(), 1);
@@ -702,11 +941,35 @@ mod tests {
         assert!(!config.debug);
         assert!(config.max_tool_repetitions.is_none());
         assert!(config.max_turns.is_none());
+        assert!(config.max_response_tokens.is_none());
+        assert!(config.system_prompt_appends.is_empty());
+        assert!(config.system_prompt_prepends.is_empty());
+        assert!(!config.no_tools);
         assert!(config.scheduled_job_id.is_none());
         assert!(!config.interactive);
         assert!(!config.quiet);
     }
 
+    #[test]
+    fn test_unlisted_required_extensions() {
+        let to_load = vec![(
+            "developer".to_string(),
+            ExtensionConfig::Builtin {
+                name: "developer".to_string(),
+                display_name: None,
+                description: String::new(),
+                timeout: None,
+                bundled: None,
+                available_tools: Vec::new(),
+            },
+        )];
+        let required = vec!["Developer".to_string(), "github".to_string()];
+        assert_eq!(
+            unlisted_required_extensions(&required, &to_load),
+            vec!["github".to_string()]
+        );
+    }
+
     #[tokio::test]
     async fn test_offer_extension_debugging_help_function_exists() {
         // This test just verifies the function compiles and can be called
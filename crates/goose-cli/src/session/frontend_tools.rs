@@ -0,0 +1,164 @@
+//! Lightweight tools declared in config and run by the CLI itself, so a script can be
+//! offered to the model without writing an MCP extension.
+
+use anyhow::{Context, Result};
+use goose::agents::extension::ExtensionConfig;
+use goose::config::Config;
+use goose::mcp_utils::ToolResult;
+use rmcp::model::{CallToolResult, Content, ErrorCode, ErrorData, JsonObject, Tool};
+use serde::Deserialize;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// A list of tools, each with a `name`, `description`, optional JSON `input_schema` and the
+/// shell `command` that runs it
+pub const GOOSE_CLI_FRONTEND_TOOLS: &str = "GOOSE_CLI_FRONTEND_TOOLS";
+
+/// Name of the extension the configured tools are registered under
+const FRONTEND_EXTENSION_NAME: &str = "cli_tools";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FrontendToolConfig {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: JsonObject,
+    /// Run with the platform shell; the call's arguments arrive as a JSON object on stdin
+    pub command: String,
+}
+
+fn empty_object_schema() -> JsonObject {
+    let mut schema = JsonObject::new();
+    schema.insert("type".to_string(), "object".into());
+    schema
+}
+
+/// The tools configured under [`GOOSE_CLI_FRONTEND_TOOLS`]; none when it is unset.
+pub fn load_frontend_tools() -> Result<Vec<FrontendToolConfig>> {
+    match Config::global().get_param::<Vec<FrontendToolConfig>>(GOOSE_CLI_FRONTEND_TOOLS) {
+        Ok(tools) => Ok(tools),
+        Err(goose::config::ConfigError::NotFound(_)) => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Invalid {}", GOOSE_CLI_FRONTEND_TOOLS)),
+    }
+}
+
+pub fn frontend_extension(tools: &[FrontendToolConfig]) -> ExtensionConfig {
+    ExtensionConfig::Frontend {
+        name: FRONTEND_EXTENSION_NAME.to_string(),
+        description: "Tools configured for the goose CLI".to_string(),
+        tools: tools
+            .iter()
+            .map(|tool| {
+                Tool::new(
+                    tool.name.clone(),
+                    tool.description.clone(),
+                    Arc::new(tool.input_schema.clone()),
+                )
+            })
+            .collect(),
+        instructions: None,
+        bundled: None,
+        available_tools: Vec::new(),
+    }
+}
+
+/// Runs `tool`'s command with `arguments` on stdin. Its stdout is the result; a non-zero exit
+/// is reported to the model as an error along with stderr.
+pub async fn run_frontend_tool(
+    tool: &FrontendToolConfig,
+    arguments: Option<&JsonObject>,
+) -> ToolResult<CallToolResult> {
+    let internal_error = |message: String| ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None);
+
+    let mut command = if cfg!(windows) {
+        let mut command = tokio::process::Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    let mut child = command
+        .arg(&tool.command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| internal_error(format!("Failed to start {}: {}", tool.name, e)))?;
+
+    let input = serde_json::to_string(&arguments.cloned().unwrap_or_default())
+        .map_err(|e| internal_error(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores its input may exit before reading it
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| internal_error(format!("Failed to run {}: {}", tool.name, e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    if output.status.success() {
+        Ok(CallToolResult::success(vec![Content::text(stdout)]))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr)
+            .trim_end()
+            .to_string();
+        Err(internal_error(format!(
+            "{} failed ({}): {}",
+            tool.name,
+            output.status,
+            if stderr.is_empty() { stdout } else { stderr }
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(command: &str) -> FrontendToolConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "echo_args",
+            "description": "Echo the arguments",
+            "command": command,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_frontend_extension_defaults_schema() {
+        let ExtensionConfig::Frontend { tools, .. } = frontend_extension(&[tool("cat")]) else {
+            panic!("expected a frontend extension");
+        };
+        assert_eq!(tools[0].name, "echo_args");
+        assert_eq!(tools[0].input_schema.get("type"), Some(&"object".into()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_frontend_tool_passes_arguments_on_stdin() {
+        let mut arguments = JsonObject::new();
+        arguments.insert("ticket".to_string(), "GOOSE-1".into());
+
+        let result = run_frontend_tool(&tool("cat"), Some(&arguments))
+            .await
+            .unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        assert_eq!(text, r#"{"ticket":"GOOSE-1"}"#);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_frontend_tool_reports_failure() {
+        let err = run_frontend_tool(&tool("echo broken >&2; exit 3"), None)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("broken"));
+    }
+}
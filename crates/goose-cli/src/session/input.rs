@@ -20,9 +20,33 @@ pub enum InputResult {
     Plan(PlanCommandOptions),
     EndPlan,
     Clear,
-    Recipe(Option<String>),
+    Recipe(RecipeCommandOptions),
     Compact,
     ToggleFullToolOutput,
+    Meta(Option<(String, String)>),
+    KillSubagent(Option<String>),
+    ImportContext(ImportContextOptions),
+    /// Model candidates as `provider/model` or `model`; empty uses the configured list
+    CompareTokens(Vec<String>),
+    /// `off`, `summary` or `full`; `None` shows the current setting
+    ThinkingVerbosity(Option<String>),
+    Verbosity(VerbosityCommand),
+    /// Withhold tools from the model; `None` toggles the current setting
+    ChatOnly(Option<bool>),
+    Pin,
+    Undo,
+    /// Branch into a new session, optionally named
+    Fork(Option<String>),
+    /// Answer the last prompt again, optionally with `provider/model` or `model` for that turn
+    RetryLast(Option<String>),
+}
+
+/// Per-session override of `GOOSE_CLI_MIN_PRIORITY` for notification formatting
+#[derive(Debug, PartialEq)]
+pub enum VerbosityCommand {
+    Show,
+    Set(f32),
+    Reset,
 }
 
 #[derive(Debug)]
@@ -32,6 +56,21 @@ pub struct PromptCommandOptions {
     pub arguments: HashMap<String, String>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ImportContextOptions {
+    pub session_id: String,
+    /// 1-based, inclusive position among the source session's agent-visible messages
+    pub start: usize,
+    /// Inclusive end position; `None` runs to the last message
+    pub end: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct RecipeCommandOptions {
+    pub filepath: Option<String>,
+    pub extract_parameters: bool,
+}
+
 #[derive(Debug)]
 pub struct PlanCommandOptions {
     pub message_text: String,
@@ -137,6 +176,17 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
     const CMD_COMPACT: &str = "/compact";
     const CMD_SUMMARIZE_DEPRECATED: &str = "/summarize";
+    const CMD_META: &str = "/meta";
+    const CMD_KILL_SUBAGENT: &str = "/kill-subagent";
+    const CMD_IMPORT_CONTEXT: &str = "/import-context";
+    const CMD_TOKENS: &str = "/tokens";
+    const CMD_THINKING: &str = "/thinking";
+    const CMD_VERBOSITY: &str = "/verbosity";
+    const CMD_CHAT_ONLY: &str = "/chat-only";
+    const CMD_PIN: &str = "/pin";
+    const CMD_UNDO: &str = "/undo";
+    const CMD_FORK: &str = "/fork";
+    const CMD_RETRY: &str = "/retry";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -199,28 +249,176 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_COMPACT => Some(InputResult::Compact),
+        s if s == CMD_PIN => Some(InputResult::Pin),
+        s if s == CMD_UNDO => Some(InputResult::Undo),
+        s if s == CMD_FORK => Some(InputResult::Fork(None)),
+        s if s.starts_with("/fork ") => {
+            let name = s.get(CMD_FORK.len()..).unwrap_or("").trim();
+            Some(InputResult::Fork(
+                (!name.is_empty()).then(|| name.to_string()),
+            ))
+        }
+        s if s == CMD_RETRY => Some(InputResult::RetryLast(None)),
+        s if s.starts_with("/retry ") => Some(InputResult::RetryLast(Some(
+            s.get(CMD_RETRY.len()..).unwrap_or("").trim().to_string(),
+        ))),
         s if s == CMD_SUMMARIZE_DEPRECATED => {
             println!("{}", console::style("⚠️  Note: /summarize has been renamed to /compact and will be removed in a future release.").yellow());
             Some(InputResult::Compact)
         }
         "/r" => Some(InputResult::ToggleFullToolOutput),
+        s if s == CMD_META => Some(InputResult::Meta(None)),
+        s if s.starts_with("/meta ") => parse_meta_command(s.get(CMD_META.len()..).unwrap_or("")),
+        s if s == CMD_KILL_SUBAGENT => Some(InputResult::KillSubagent(None)),
+        s if s.starts_with("/kill-subagent ") => Some(InputResult::KillSubagent(Some(
+            s.get(CMD_KILL_SUBAGENT.len()..)
+                .unwrap_or("")
+                .trim()
+                .to_string(),
+        ))),
+        s if s == CMD_IMPORT_CONTEXT || s.starts_with("/import-context ") => {
+            parse_import_context_command(s.get(CMD_IMPORT_CONTEXT.len()..).unwrap_or(""))
+        }
+        s if s == CMD_TOKENS || s.starts_with("/tokens ") => {
+            parse_tokens_command(s.get(CMD_TOKENS.len()..).unwrap_or(""))
+        }
+        s if s == CMD_THINKING || s.starts_with("/thinking ") => {
+            parse_thinking_command(s.get(CMD_THINKING.len()..).unwrap_or(""))
+        }
+        s if s == CMD_VERBOSITY || s.starts_with("/verbosity ") => {
+            parse_verbosity_command(s.get(CMD_VERBOSITY.len()..).unwrap_or(""))
+        }
+        s if s == CMD_CHAT_ONLY || s.starts_with("/chat-only ") => {
+            parse_chat_only_command(s.get(CMD_CHAT_ONLY.len()..).unwrap_or(""))
+        }
         _ => None,
     }
 }
 
-fn parse_recipe_command(s: &str) -> Option<InputResult> {
-    const CMD_RECIPE: &str = "/recipe";
+fn parse_import_context_command(args: &str) -> Option<InputResult> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let parsed = match parts.as_slice() {
+        [session_id, range] => {
+            parse_message_range(range).map(|(start, end)| ImportContextOptions {
+                session_id: session_id.to_string(),
+                start,
+                end,
+            })
+        }
+        _ => None,
+    };
 
-    if s == CMD_RECIPE {
-        // No filepath provided, use default
-        return Some(InputResult::Recipe(None));
+    match parsed {
+        Some(options) => Some(InputResult::ImportContext(options)),
+        None => {
+            println!(
+                "{}",
+                console::style(
+                    "Usage: /import-context <session-id> <start>[-[end]] (e.g. 3-7, 5 or 4-)"
+                )
+                .red()
+            );
+            Some(InputResult::Retry)
+        }
+    }
+}
+
+fn parse_tokens_command(args: &str) -> Option<InputResult> {
+    let mut parts = args.split_whitespace();
+    match parts.next() {
+        Some("compare") => Some(InputResult::CompareTokens(
+            parts.map(str::to_string).collect(),
+        )),
+        _ => {
+            println!(
+                "{}",
+                console::style("Usage: /tokens compare [provider/model ...]").red()
+            );
+            Some(InputResult::Retry)
+        }
+    }
+}
+
+fn parse_thinking_command(args: &str) -> Option<InputResult> {
+    let level = args.trim().to_lowercase();
+    if level.is_empty() {
+        return Some(InputResult::ThinkingVerbosity(None));
+    }
+    if ["off", "summary", "full"].contains(&level.as_str()) {
+        Some(InputResult::ThinkingVerbosity(Some(level)))
+    } else {
+        println!(
+            "{}",
+            console::style("Usage: /thinking [off|summary|full]").red()
+        );
+        Some(InputResult::Retry)
+    }
+}
+
+fn parse_chat_only_command(args: &str) -> Option<InputResult> {
+    match args.trim().to_lowercase().as_str() {
+        "" => Some(InputResult::ChatOnly(None)),
+        "on" => Some(InputResult::ChatOnly(Some(true))),
+        "off" => Some(InputResult::ChatOnly(Some(false))),
+        _ => {
+            println!("{}", console::style("Usage: /chat-only [on|off]").red());
+            Some(InputResult::Retry)
+        }
+    }
+}
+
+fn parse_verbosity_command(args: &str) -> Option<InputResult> {
+    let command = match args.trim() {
+        "" => Some(VerbosityCommand::Show),
+        "reset" => Some(VerbosityCommand::Reset),
+        value => value
+            .parse::<f32>()
+            .ok()
+            .filter(|priority| (0.0..=1.0).contains(priority))
+            .map(VerbosityCommand::Set),
+    };
+    match command {
+        Some(command) => Some(InputResult::Verbosity(command)),
+        None => {
+            println!(
+                "{}",
+                console::style("Usage: /verbosity [0.0-1.0|reset]").red()
+            );
+            Some(InputResult::Retry)
+        }
     }
+}
+
+/// Parses `N`, `N-M` or `N-` into 1-based inclusive bounds.
+fn parse_message_range(range: &str) -> Option<(usize, Option<usize>)> {
+    let (start, end) = match range.split_once('-') {
+        Some((start, "")) => (start.parse().ok()?, None),
+        Some((start, end)) => (start.parse().ok()?, Some(end.parse().ok()?)),
+        None => {
+            let single = range.parse().ok()?;
+            (single, Some(single))
+        }
+    };
+    let valid = start >= 1 && end.is_none_or(|end| end >= start);
+    valid.then_some((start, end))
+}
+
+fn parse_recipe_command(s: &str) -> Option<InputResult> {
+    const CMD_RECIPE: &str = "/recipe";
+    const PARAMS_FLAG: &str = "--params";
 
-    // Extract the filepath from the command
-    let filepath = s.get(CMD_RECIPE.len()..).unwrap_or("").trim();
+    let args = s.get(CMD_RECIPE.len()..).unwrap_or("").trim();
+    let (extract_parameters, filepath) = match args.strip_prefix(PARAMS_FLAG) {
+        Some(rest) => (true, rest.trim()),
+        None => (false, args),
+    };
 
     if filepath.is_empty() {
-        return Some(InputResult::Recipe(None));
+        // No filepath provided, use default
+        return Some(InputResult::Recipe(RecipeCommandOptions {
+            filepath: None,
+            extract_parameters,
+        }));
     }
 
     // Validate that the filepath ends with .yaml
@@ -230,7 +428,26 @@ fn parse_recipe_command(s: &str) -> Option<InputResult> {
     }
 
     // Return the filepath for validation in the handler
-    Some(InputResult::Recipe(Some(filepath.to_string())))
+    Some(InputResult::Recipe(RecipeCommandOptions {
+        filepath: Some(filepath.to_string()),
+        extract_parameters,
+    }))
+}
+
+fn parse_meta_command(args: &str) -> Option<InputResult> {
+    match args.trim().split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Some(InputResult::Meta(Some((
+            key.trim().to_string(),
+            value.trim().to_string(),
+        )))),
+        _ => {
+            println!(
+                "{}",
+                console::style("Usage: /meta key=value (leave value empty to remove a key)").red()
+            );
+            Some(InputResult::Retry)
+        }
+    }
 }
 
 fn parse_prompts_command(args: &str) -> Option<InputResult> {
@@ -328,11 +545,23 @@ fn print_help() {
                         The model is used based on $GOOSE_PLANNER_PROVIDER and $GOOSE_PLANNER_MODEL environment variables.
                         If no model is set, the default model is used.
 /endplan - Exit plan mode and return to 'normal' goose mode.
-/recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
+/recipe [--params] [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
+                       With --params, URLs, paths and recurring names are turned into recipe parameters.
 /compact - Compact the current conversation to reduce context length while preserving key information.
+/pin - Pin the last message so compaction keeps it word for word instead of summarizing it
 /? or /help - Display this help message
 /clear - Clears the current chat history
+/undo - Remove your last message and everything after it, including any tool calls
+/fork [name] - Continue in a copy of this session, leaving the original as it is so it can be resumed later
+/retry [model] - Answer your last message again, optionally with another model (model or provider/model) for just that turn
+/meta [key=value] - Show session metadata, or set a key (an empty value removes it)
+/kill-subagent [id] - Cancel a running subagent, or list running subagents if no id is given
+/import-context <session-id> <range> - Copy agent-visible messages (e.g. 3-7, 5 or 4-) from another session into this one
+/tokens compare [provider/model ...] - Count this conversation's tokens under each model (defaults to GOOSE_TOKEN_COMPARE_MODELS)
+/thinking [off|summary|full] - Show or set how much model reasoning is displayed (saved as GOOSE_CLI_THINKING)
+/verbosity [0.0-1.0|reset] - Show or override GOOSE_CLI_MIN_PRIORITY for this session's notifications (lower shows more detail)
+/chat-only [on|off] - Toggle withholding all tools from the model so it only answers in text
 
 Navigation:
 Ctrl+C - Clear current line if text is entered, otherwise exit the session
@@ -549,24 +778,235 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_import_context_command() {
+        if let Some(InputResult::ImportContext(options)) =
+            handle_slash_command("/import-context 20250101_3 2-4")
+        {
+            assert_eq!(
+                options,
+                ImportContextOptions {
+                    session_id: "20250101_3".to_string(),
+                    start: 2,
+                    end: Some(4),
+                }
+            );
+        } else {
+            panic!("Expected ImportContext");
+        }
+        assert_eq!(parse_message_range("5"), Some((5, Some(5))));
+        assert_eq!(parse_message_range("4-"), Some((4, None)));
+        assert_eq!(parse_message_range("0-2"), None);
+        assert_eq!(parse_message_range("7-3"), None);
+        assert!(matches!(
+            handle_slash_command("/import-context 20250101_3"),
+            Some(InputResult::Retry)
+        ));
+    }
+
+    #[test]
+    fn test_tokens_command() {
+        if let Some(InputResult::CompareTokens(models)) =
+            handle_slash_command("/tokens compare openai/gpt-4o claude-haiku-4-5")
+        {
+            assert_eq!(models, vec!["openai/gpt-4o", "claude-haiku-4-5"]);
+        } else {
+            panic!("Expected CompareTokens");
+        }
+        assert!(matches!(
+            handle_slash_command("/tokens compare"),
+            Some(InputResult::CompareTokens(models)) if models.is_empty()
+        ));
+        assert!(matches!(
+            handle_slash_command("/tokens"),
+            Some(InputResult::Retry)
+        ));
+    }
+
+    #[test]
+    fn test_thinking_command() {
+        assert!(matches!(
+            handle_slash_command("/thinking"),
+            Some(InputResult::ThinkingVerbosity(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/thinking Summary"),
+            Some(InputResult::ThinkingVerbosity(Some(level))) if level == "summary"
+        ));
+        assert!(matches!(
+            handle_slash_command("/thinking loud"),
+            Some(InputResult::Retry)
+        ));
+    }
+
+    #[test]
+    fn test_fork_command() {
+        assert!(matches!(
+            handle_slash_command("/fork"),
+            Some(InputResult::Fork(None))
+        ));
+        match handle_slash_command("/fork  try streaming ") {
+            Some(InputResult::Fork(Some(name))) => assert_eq!(name, "try streaming"),
+            _ => panic!("Expected Fork with a name"),
+        }
+        assert!(matches!(
+            handle_slash_command("/fork "),
+            Some(InputResult::Fork(None))
+        ));
+        assert!(handle_slash_command("/forks").is_none());
+    }
+
+    #[test]
+    fn test_retry_command() {
+        assert!(matches!(
+            handle_slash_command("/retry"),
+            Some(InputResult::RetryLast(None))
+        ));
+        match handle_slash_command("/retry openai/gpt-4o") {
+            Some(InputResult::RetryLast(Some(model))) => assert_eq!(model, "openai/gpt-4o"),
+            _ => panic!("Expected RetryLast with a model"),
+        }
+        assert!(handle_slash_command("/retrying").is_none());
+    }
+
+    #[test]
+    fn test_undo_command() {
+        assert!(matches!(
+            handle_slash_command("/undo"),
+            Some(InputResult::Undo)
+        ));
+        assert!(handle_slash_command("/undo 2").is_none());
+    }
+
+    #[test]
+    fn test_pin_command() {
+        assert!(matches!(
+            handle_slash_command("/pin"),
+            Some(InputResult::Pin)
+        ));
+        assert!(handle_slash_command("/pinned").is_none());
+    }
+
+    #[test]
+    fn test_chat_only_command() {
+        assert!(matches!(
+            handle_slash_command("/chat-only"),
+            Some(InputResult::ChatOnly(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/chat-only ON"),
+            Some(InputResult::ChatOnly(Some(true)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/chat-only off"),
+            Some(InputResult::ChatOnly(Some(false)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/chat-only maybe"),
+            Some(InputResult::Retry)
+        ));
+    }
+
+    #[test]
+    fn test_verbosity_command() {
+        assert!(matches!(
+            handle_slash_command("/verbosity"),
+            Some(InputResult::Verbosity(VerbosityCommand::Show))
+        ));
+        assert!(matches!(
+            handle_slash_command("/verbosity 0.1"),
+            Some(InputResult::Verbosity(VerbosityCommand::Set(p))) if p == 0.1
+        ));
+        assert!(matches!(
+            handle_slash_command("/verbosity reset"),
+            Some(InputResult::Verbosity(VerbosityCommand::Reset))
+        ));
+        assert!(matches!(
+            handle_slash_command("/verbosity 2"),
+            Some(InputResult::Retry)
+        ));
+    }
+
+    #[test]
+    fn test_kill_subagent_command() {
+        assert!(matches!(
+            handle_slash_command("/kill-subagent"),
+            Some(InputResult::KillSubagent(None))
+        ));
+        if let Some(InputResult::KillSubagent(Some(id))) =
+            handle_slash_command("/kill-subagent 20250101_3 ")
+        {
+            assert_eq!(id, "20250101_3");
+        } else {
+            panic!("Expected KillSubagent with id");
+        }
+    }
+
+    #[test]
+    fn test_meta_command() {
+        assert!(matches!(
+            handle_slash_command("/meta"),
+            Some(InputResult::Meta(None))
+        ));
+
+        if let Some(InputResult::Meta(Some((key, value)))) =
+            handle_slash_command("/meta ticket = ABC-123")
+        {
+            assert_eq!(key, "ticket");
+            assert_eq!(value, "ABC-123");
+        } else {
+            panic!("Expected Meta with entry");
+        }
+
+        if let Some(InputResult::Meta(Some((key, value)))) = handle_slash_command("/meta ticket=") {
+            assert_eq!(key, "ticket");
+            assert!(value.is_empty());
+        } else {
+            panic!("Expected Meta with empty value");
+        }
+
+        assert!(matches!(
+            handle_slash_command("/meta ticket"),
+            Some(InputResult::Retry)
+        ));
+    }
+
     #[test]
     fn test_recipe_command() {
         // Test recipe with no filepath
-        if let Some(InputResult::Recipe(filepath)) = handle_slash_command("/recipe") {
-            assert!(filepath.is_none());
+        if let Some(InputResult::Recipe(opts)) = handle_slash_command("/recipe") {
+            assert!(opts.filepath.is_none());
+            assert!(!opts.extract_parameters);
         } else {
             panic!("Expected Recipe");
         }
 
         // Test recipe with filepath
-        if let Some(InputResult::Recipe(filepath)) =
-            handle_slash_command("/recipe /path/to/file.yaml")
+        if let Some(InputResult::Recipe(opts)) = handle_slash_command("/recipe /path/to/file.yaml")
         {
-            assert_eq!(filepath, Some("/path/to/file.yaml".to_string()));
+            assert_eq!(opts.filepath, Some("/path/to/file.yaml".to_string()));
+            assert!(!opts.extract_parameters);
         } else {
             panic!("Expected recipe with filepath");
         }
 
+        // Test recipe with parameter extraction
+        if let Some(InputResult::Recipe(opts)) =
+            handle_slash_command("/recipe --params /path/to/file.yaml")
+        {
+            assert_eq!(opts.filepath, Some("/path/to/file.yaml".to_string()));
+            assert!(opts.extract_parameters);
+        } else {
+            panic!("Expected recipe with parameter extraction");
+        }
+
+        if let Some(InputResult::Recipe(opts)) = handle_slash_command("/recipe --params") {
+            assert!(opts.filepath.is_none());
+            assert!(opts.extract_parameters);
+        } else {
+            panic!("Expected recipe with parameter extraction");
+        }
+
         // Test recipe with invalid extension
         let result = handle_slash_command("/recipe /path/to/file.txt");
         assert!(matches!(result, Some(InputResult::Retry)));
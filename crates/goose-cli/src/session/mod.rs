@@ -2,17 +2,21 @@ mod builder;
 mod completion;
 mod elicitation;
 mod export;
+mod frontend_tools;
 mod input;
 mod output;
 mod prompt;
 mod task_execution_display;
 mod thinking;
+mod transcript;
 
 use crate::session::task_execution_display::{
     format_task_execution_notification, TASK_EXECUTION_NOTIFICATION_TYPE,
 };
-use goose::conversation::Conversation;
-use std::io::Write;
+use goose::conversation::{
+    effective_role, fix_conversation, remove_orphaned_tool_calls, Conversation,
+};
+use std::io::{IsTerminal, Write};
 use std::str::FromStr;
 use tokio::signal::ctrl_c;
 use tokio_util::task::AbortOnDropHandle;
@@ -24,15 +28,23 @@ use goose::agents::AgentEvent;
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::permission::Permission;
 use goose::permission::PermissionConfirmation;
-use goose::providers::base::Provider;
+use goose::providers::base::{effective_context_limit, Provider};
+use goose::providers::errors::ProviderError;
 use goose::utils::safe_truncate;
+pub use output::GOOSE_CLI_THINKING;
+pub use transcript::GOOSE_TRANSCRIPT_DIR;
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
 use goose::agents::extension::{Envs, ExtensionConfig, PLATFORM_EXTENSIONS};
 use goose::agents::types::RetryConfig;
-use goose::agents::{Agent, SessionConfig, COMPACT_TRIGGERS};
+use goose::agents::{Agent, SessionConfig};
+use goose::config::permission::PermissionLevel;
 use goose::config::{Config, GooseMode};
+use goose::context_mgmt::{compact_messages, compact_messages_with_guidance, CompactionStrategy};
+use goose::mcp_utils::categorize_tool_error;
+use goose::recipe::parameter_extraction::extract_recipe_parameters;
+use goose::recipe::Recipe;
 use input::InputResult;
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
@@ -40,17 +52,28 @@ use rmcp::model::{ErrorCode, ErrorData};
 
 use goose::config::paths::Paths;
 use goose::conversation::message::{ActionRequiredData, Message, MessageContent};
+use goose::token_counter::create_token_counter;
 use rustyline::EditMode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio;
 use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
+/// How many times a headless run compacts and retries a turn that overflowed the context
+pub const GOOSE_HEADLESS_CONTEXT_RETRIES: &str = "GOOSE_HEADLESS_CONTEXT_RETRIES";
+const DEFAULT_HEADLESS_CONTEXT_RETRIES: usize = 1;
+
+/// Comma-separated `provider/model` candidates for `/tokens compare`
+pub const GOOSE_TOKEN_COMPARE_MODELS: &str = "GOOSE_TOKEN_COMPARE_MODELS";
+
+/// Offer to resume the last session on startup when it ended mid-turn
+pub const GOOSE_RESUME_LAST_ON_ERROR: &str = "GOOSE_RESUME_LAST_ON_ERROR";
+
 #[derive(Serialize, Deserialize, Debug)]
 struct JsonOutput {
     messages: Vec<Message>,
@@ -60,7 +83,70 @@ struct JsonOutput {
 #[derive(Serialize, Deserialize, Debug)]
 struct JsonMetadata {
     total_tokens: Option<i32>,
+    /// `completed`, `interrupted`, `cancelled`, `error` or `missing_artifacts`
     status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    artifacts: Vec<ArtifactReport>,
+}
+
+impl JsonMetadata {
+    /// A completed run that left expected artifacts missing is reported as `missing_artifacts`.
+    fn new(
+        total_tokens: Option<i32>,
+        outcome: &RunOutcome,
+        artifacts: Vec<ArtifactReport>,
+    ) -> Self {
+        let status = if *outcome == RunOutcome::Completed
+            && !artifacts.iter().all(|artifact| artifact.exists)
+        {
+            "missing_artifacts"
+        } else {
+            outcome.status()
+        };
+        Self {
+            total_tokens,
+            status: status.to_string(),
+            error: outcome.error(),
+            artifacts,
+        }
+    }
+}
+
+/// How a run of `process_agent_response` ended
+#[derive(Debug, Clone, PartialEq)]
+enum RunOutcome {
+    Completed,
+    /// Stopped by Ctrl+C or a deadline
+    Interrupted,
+    /// The user declined a tool call or information request
+    Cancelled,
+    Error(String),
+}
+
+impl RunOutcome {
+    fn status(&self) -> &'static str {
+        match self {
+            RunOutcome::Completed => "completed",
+            RunOutcome::Interrupted => "interrupted",
+            RunOutcome::Cancelled => "cancelled",
+            RunOutcome::Error(_) => "error",
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        match self {
+            RunOutcome::Error(message) => Some(message.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ArtifactReport {
+    path: String,
+    exists: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -69,6 +155,11 @@ enum StreamEvent {
     Message {
         message: Message,
     },
+    /// Text of the assistant message being streamed, sent as it arrives. The complete
+    /// message follows as a `Message` event once it is finished.
+    MessageDelta {
+        text: String,
+    },
     Notification {
         extension_id: String,
         #[serde(flatten)]
@@ -83,6 +174,9 @@ enum StreamEvent {
     },
     Complete {
         total_tokens: Option<i32>,
+        status: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
     },
 }
 
@@ -163,6 +257,11 @@ pub struct CliSession {
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
     output_format: String,
+    include_agent_visible: bool,
+    transcript: Option<transcript::Transcript>,
+    recipe: Option<Recipe>, // the recipe being run, whose declared artifacts are checked at the end
+    min_priority: Option<f32>, // overrides GOOSE_CLI_MIN_PRIORITY for this session
+    frontend_tools: HashMap<String, frontend_tools::FrontendToolConfig>,
 }
 
 // Cache structure for completion data
@@ -170,6 +269,8 @@ struct CompletionCache {
     prompts: HashMap<String, Vec<String>>,
     prompt_info: HashMap<String, output::PromptInfo>,
     last_updated: Instant,
+    /// Set when extensions change; the cache is rebuilt before the next prompt
+    stale: bool,
 }
 
 impl CompletionCache {
@@ -178,6 +279,7 @@ impl CompletionCache {
             prompts: HashMap::new(),
             prompt_info: HashMap::new(),
             last_updated: Instant::now(),
+            stale: false,
         }
     }
 }
@@ -227,6 +329,7 @@ impl CliSession {
         edit_mode: Option<EditMode>,
         retry_config: Option<RetryConfig>,
         output_format: String,
+        include_agent_visible: bool,
     ) -> Self {
         let messages = agent
             .config
@@ -248,6 +351,153 @@ impl CliSession {
             edit_mode,
             retry_config,
             output_format,
+            include_agent_visible,
+            transcript: None,
+            recipe: None,
+            min_priority: None,
+            frontend_tools: HashMap::new(),
+        }
+    }
+
+    pub fn set_transcript(&mut self, transcript: transcript::Transcript) {
+        self.transcript = Some(transcript);
+    }
+
+    pub fn set_recipe(&mut self, recipe: Recipe) {
+        self.recipe = Some(recipe);
+    }
+
+    /// Offers the tools configured in `GOOSE_CLI_FRONTEND_TOOLS` to the model; the CLI runs
+    /// them itself when they are called.
+    pub async fn register_frontend_tools(&mut self) -> Result<()> {
+        let tools = frontend_tools::load_frontend_tools()?;
+        if tools.is_empty() {
+            return Ok(());
+        }
+        self.agent
+            .add_extension(frontend_tools::frontend_extension(&tools))
+            .await?;
+        self.frontend_tools = tools
+            .into_iter()
+            .map(|tool| (tool.name.clone(), tool))
+            .collect();
+        Ok(())
+    }
+
+    /// Frontend tools bypass the agent's permission inspectors, so the CLI applies the same
+    /// rules itself: saved per-tool permissions first, then a prompt unless in auto mode.
+    fn frontend_tool_permission(&self, tool_name: &str) -> Result<Permission> {
+        let permission_manager = &self.agent.config.permission_manager;
+        match permission_manager.get_user_permission(tool_name) {
+            Some(PermissionLevel::AlwaysAllow) => return Ok(Permission::AllowOnce),
+            Some(PermissionLevel::NeverAllow) => return Ok(Permission::DenyOnce),
+            _ => {}
+        }
+        if self.agent.config.goose_mode == GooseMode::Auto {
+            return Ok(Permission::AllowOnce);
+        }
+        let permission = prompt_tool_confirmation(&None)?;
+        match permission {
+            Permission::AlwaysAllow => {
+                permission_manager.update_user_permission(tool_name, PermissionLevel::AlwaysAllow)
+            }
+            Permission::AlwaysDeny => {
+                permission_manager.update_user_permission(tool_name, PermissionLevel::NeverAllow)
+            }
+            _ => {}
+        }
+        Ok(permission)
+    }
+
+    /// Answers tool call `id` as cancelled; the caller ends the turn.
+    fn cancel_tool_call(&mut self, id: String) {
+        output::render_text(
+            "Tool call cancelled. Returning to chat...",
+            Some(Color::Yellow),
+            true,
+        );
+        let mut response_message = Message::user();
+        response_message.content.push(MessageContent::tool_response(
+            id,
+            Err(ErrorData {
+                code: ErrorCode::INVALID_REQUEST,
+                message: std::borrow::Cow::from("Tool call cancelled by user"),
+                data: None,
+            }),
+        ));
+        self.messages.push(response_message);
+    }
+
+    /// Artifacts count as produced only when written during this session.
+    async fn artifact_reports(&self) -> Vec<ArtifactReport> {
+        let Some(recipe) = &self.recipe else {
+            return Vec::new();
+        };
+        let missing = match self
+            .agent
+            .config
+            .session_manager
+            .get_session(&self.session_id, false)
+            .await
+        {
+            Ok(session) => {
+                recipe.missing_artifacts(&session.working_dir, Some(session.created_at.into()))
+            }
+            Err(e) => {
+                warn!("Failed to load session to check artifacts: {}", e);
+                recipe.missing_artifacts(&std::env::current_dir().unwrap_or_default(), None)
+            }
+        };
+        recipe
+            .artifacts
+            .iter()
+            .flatten()
+            .map(|path| ArtifactReport {
+                path: path.clone(),
+                exists: !missing.contains(path),
+            })
+            .collect()
+    }
+
+    /// Fails the run if any artifact declared by the recipe wasn't produced.
+    async fn verify_artifacts(&self) -> Result<()> {
+        let missing: Vec<String> = self
+            .artifact_reports()
+            .await
+            .into_iter()
+            .filter(|artifact| !artifact.exists)
+            .map(|artifact| artifact.path)
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Recipe did not produce declared artifact(s): {}",
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    fn record_transcript(&mut self, message: &Message) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            if let Err(e) = transcript.record(message) {
+                warn!(
+                    "Failed to write transcript {}: {}",
+                    transcript.path().display(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn flush_transcript(&mut self) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            if let Err(e) = transcript.flush_pending() {
+                warn!(
+                    "Failed to write transcript {}: {}",
+                    transcript.path().display(),
+                    e
+                );
+            }
         }
     }
 
@@ -286,6 +536,8 @@ impl CliSession {
             timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: None,
             available_tools: Vec::new(),
+            auto_restart: false,
+            max_restarts: None,
         })
     }
 
@@ -425,8 +677,14 @@ impl CliSession {
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
         if let Some(prompt) = prompt {
             let msg = Message::user().with_text(&prompt);
-            self.process_message(msg, CancellationToken::default())
-                .await?;
+            match self
+                .process_message(msg, CancellationToken::default())
+                .await
+            {
+                // The agent has already explained why the turn stopped
+                Err(e) if is_context_length_exceeded(&e) => {}
+                result => result?,
+            }
         }
 
         self.update_completion_cache().await?;
@@ -439,6 +697,9 @@ impl CliSession {
         loop {
             self.display_context_usage().await?;
 
+            if self.completion_cache.read().unwrap().stale {
+                self.update_completion_cache().await?;
+            }
             let input = input::get_input(&mut editor)?;
             if matches!(input, InputResult::Exit) {
                 break;
@@ -538,14 +799,65 @@ impl CliSession {
                 history.save(editor);
                 self.handle_prompt_command(opts).await?;
             }
-            InputResult::Recipe(filepath_opt) => {
+            InputResult::Recipe(opts) => {
                 history.save(editor);
-                self.handle_recipe(filepath_opt).await;
+                self.handle_recipe(opts.filepath, opts.extract_parameters)
+                    .await;
             }
             InputResult::Compact => {
                 history.save(editor);
                 self.handle_compact().await?;
             }
+            InputResult::Pin => {
+                history.save(editor);
+                self.handle_pin().await?;
+            }
+            InputResult::Undo => {
+                history.save(editor);
+                self.handle_undo().await?;
+            }
+            InputResult::Fork(name) => {
+                history.save(editor);
+                self.handle_fork(name).await?;
+            }
+            InputResult::RetryLast(model) => {
+                history.save(editor);
+                self.handle_retry(model).await?;
+            }
+            InputResult::KillSubagent(subagent_id) => {
+                history.save(editor);
+                self.handle_kill_subagent(subagent_id);
+            }
+            InputResult::Meta(entry) => {
+                history.save(editor);
+                if let Err(e) = self.handle_meta(entry).await {
+                    output::render_error(&format!("Failed to update session metadata: {}", e));
+                }
+            }
+            InputResult::ImportContext(options) => {
+                history.save(editor);
+                if let Err(e) = self.handle_import_context(options).await {
+                    output::render_error(&format!("Failed to import context: {}", e));
+                }
+            }
+            InputResult::CompareTokens(models) => {
+                history.save(editor);
+                if let Err(e) = self.handle_compare_tokens(models).await {
+                    output::render_error(&format!("Failed to compare token counts: {}", e));
+                }
+            }
+            InputResult::ThinkingVerbosity(level) => {
+                history.save(editor);
+                self.handle_thinking_verbosity(level.as_deref());
+            }
+            InputResult::Verbosity(command) => {
+                history.save(editor);
+                self.handle_verbosity(command);
+            }
+            InputResult::ChatOnly(enabled) => {
+                history.save(editor);
+                self.handle_chat_only(enabled);
+            }
         }
         Ok(())
     }
@@ -559,6 +871,12 @@ impl CliSession {
         match self.run_mode {
             RunMode::Normal => {
                 history.save(editor);
+                if is_repeated_user_message(self.messages.messages(), content)
+                    && !confirm_repeated_message()?
+                {
+                    output::render_text("Ignored duplicate message.", Some(Color::Yellow), true);
+                    return Ok(());
+                }
                 self.push_message(Message::user().with_text(content));
 
                 if let Err(e) = crate::project_tracker::update_project_tracker(
@@ -656,6 +974,72 @@ impl CliSession {
         }
     }
 
+    /// Notification priority threshold: the session override, else `GOOSE_CLI_MIN_PRIORITY`
+    fn min_priority(&self) -> f32 {
+        self.min_priority.unwrap_or_else(|| {
+            Config::global()
+                .get_param::<f32>("GOOSE_CLI_MIN_PRIORITY")
+                .unwrap_or(0.5)
+        })
+    }
+
+    fn handle_verbosity(&mut self, command: input::VerbosityCommand) {
+        match command {
+            input::VerbosityCommand::Show => {}
+            input::VerbosityCommand::Set(priority) => self.min_priority = Some(priority),
+            input::VerbosityCommand::Reset => self.min_priority = None,
+        }
+        let source = if self.min_priority.is_some() {
+            "this session"
+        } else {
+            "GOOSE_CLI_MIN_PRIORITY"
+        };
+        println!(
+            "{}",
+            console::style(format!(
+                "Notification min priority: {} (from {})",
+                self.min_priority(),
+                source
+            ))
+            .dim()
+        );
+    }
+
+    fn handle_thinking_verbosity(&self, level: Option<&str>) {
+        let verbosity = match level.and_then(output::ThinkingVerbosity::from_config_str) {
+            Some(verbosity) => {
+                output::set_thinking_verbosity(verbosity);
+                verbosity
+            }
+            None => output::get_thinking_verbosity(),
+        };
+        let description = match verbosity {
+            output::ThinkingVerbosity::Off => "model reasoning is hidden",
+            output::ThinkingVerbosity::Summary => "the start of model reasoning is shown, dimmed",
+            output::ThinkingVerbosity::Full => "model reasoning is shown in full",
+        };
+        println!(
+            "{}",
+            console::style(format!(
+                "Thinking display: {} ({})",
+                verbosity.as_config_str(),
+                description
+            ))
+            .dim()
+        );
+    }
+
+    fn handle_chat_only(&self, enabled: Option<bool>) {
+        let enabled = enabled.unwrap_or(!self.agent.tools_disabled());
+        self.agent.set_tools_disabled(enabled);
+        let status = if enabled {
+            "Chat-only mode on: no tools are sent to the model"
+        } else {
+            "Chat-only mode off: tools are available again"
+        };
+        println!("{}", console::style(status).dim());
+    }
+
     fn handle_goose_mode(&self, mode: &str) -> Result<()> {
         let config = Config::global();
         let mode = match GooseMode::from_str(&mode.to_lowercase()) {
@@ -688,6 +1072,187 @@ impl CliSession {
         self.plan_with_reasoner_model(plan_messages, reasoner).await
     }
 
+    async fn handle_pin(&mut self) -> Result<()> {
+        let (pinned, message) = match pin_last_message(&self.messages) {
+            Ok(result) => result,
+            Err(reason) => {
+                output::render_error(reason);
+                return Ok(());
+            }
+        };
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &pinned)
+            .await?;
+        self.messages = pinned;
+
+        let text = message.as_concat_text();
+        let preview: String = text.chars().take(80).collect();
+        let ellipsis = if text.chars().count() > 80 { "..." } else { "" };
+        println!(
+            "{}",
+            console::style(format!("Pinned: {}{}", preview, ellipsis)).dim()
+        );
+        Ok(())
+    }
+
+    async fn handle_undo(&mut self) -> Result<()> {
+        let Some((remaining, prompt, removed)) = retry_last_exchange(&self.messages) else {
+            output::render_error("There is no message to undo");
+            return Ok(());
+        };
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &remaining)
+            .await?;
+        self.messages = remaining;
+
+        let prompt = removed[0].as_concat_text();
+        let preview: String = prompt.chars().take(80).collect();
+        let ellipsis = if prompt.chars().count() > 80 {
+            "..."
+        } else {
+            ""
+        };
+        println!(
+            "{}",
+            console::style(format!(
+                "Removed {} message(s), starting from: {}{}",
+                removed.len(),
+                preview,
+                ellipsis
+            ))
+            .dim()
+        );
+        if let Some(last) = self.messages.iter().rev().find(|msg| msg.is_user_visible()) {
+            output::render_message(last, self.debug);
+        }
+        Ok(())
+    }
+
+    /// Drops the reply to the last prompt and asks for it again, with `model` standing in for
+    /// the session's model for this one turn.
+    async fn handle_retry(&mut self, model: Option<String>) -> Result<()> {
+        let Some((remaining, prompt, removed)) = retry_last_exchange(&self.messages) else {
+            output::render_error("There is no message to retry");
+            return Ok(());
+        };
+
+        let original = self.agent.provider().await?;
+        let retry_provider = match model {
+            Some(spec) => {
+                let (provider_name, model) = parse_model_candidate(&spec, original.get_name());
+                let provider = match goose::model::ModelConfig::new(&model) {
+                    Ok(model_config) => {
+                        goose::providers::create(&provider_name, model_config).await
+                    }
+                    Err(e) => Err(e.into()),
+                };
+                match provider {
+                    Ok(provider) => Some(provider),
+                    Err(e) => {
+                        output::render_error(&format!("Cannot retry with {}: {}", spec, e));
+                        return Ok(());
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // The agent stores the prompt again when it replies, so only the history before it is saved
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &remaining)
+            .await?;
+        self.messages = remaining;
+        self.messages.push(prompt);
+
+        let model_name = retry_provider
+            .as_ref()
+            .unwrap_or(&original)
+            .get_model_config()
+            .model_name;
+        println!(
+            "{}",
+            console::style(format!(
+                "Retrying with {} after removing {} message(s)",
+                model_name, removed
+            ))
+            .dim()
+        );
+
+        if let Some(provider) = &retry_provider {
+            self.agent
+                .update_provider(provider.clone(), &self.session_id)
+                .await?;
+        }
+        output::show_thinking();
+        let result = self
+            .process_agent_response(true, CancellationToken::default())
+            .await;
+        output::hide_thinking();
+        if retry_provider.is_some() {
+            self.agent
+                .update_provider(original, &self.session_id)
+                .await?;
+        }
+        result
+    }
+
+    async fn handle_fork(&mut self, name: Option<String>) -> Result<()> {
+        let session_manager = &self.agent.config.session_manager;
+        let original = session_manager.get_session(&self.session_id, false).await?;
+        let name = name.unwrap_or_else(|| format!("{} (fork)", original.name));
+        let fork = session_manager
+            .create_session(original.working_dir.clone(), name, original.session_type)
+            .await?;
+
+        let mut update = session_manager
+            .update(&fork.id)
+            .extension_data(original.extension_data)
+            .recipe(original.recipe)
+            .user_recipe_values(original.user_recipe_values)
+            .metadata(original.metadata)
+            .total_tokens(original.total_tokens)
+            .input_tokens(original.input_tokens)
+            .output_tokens(original.output_tokens)
+            .accumulated_total_tokens(original.accumulated_total_tokens)
+            .accumulated_input_tokens(original.accumulated_input_tokens)
+            .accumulated_output_tokens(original.accumulated_output_tokens);
+        if let Some(provider_name) = original.provider_name {
+            update = update.provider_name(provider_name);
+        }
+        if let Some(model_config) = original.model_config {
+            update = update.model_config(model_config);
+        }
+        update.apply().await?;
+        session_manager
+            .replace_conversation(&fork.id, &self.messages)
+            .await?;
+
+        let original_id = std::mem::replace(&mut self.session_id, fork.id.clone());
+        println!(
+            "{}",
+            console::style(format!(
+                "Forked session {} into new session {}, which is now active",
+                original_id, fork.id
+            ))
+            .green()
+        );
+        println!(
+            "{}",
+            console::style(format!(
+                "Resume the original with: goose session --resume --session-id {}",
+                original_id
+            ))
+            .dim()
+        );
+        Ok(())
+    }
+
     async fn handle_clear(&mut self) -> Result<()> {
         if let Err(e) = self
             .agent
@@ -724,7 +1289,7 @@ impl CliSession {
         Ok(())
     }
 
-    async fn handle_recipe(&mut self, filepath_opt: Option<String>) {
+    async fn handle_recipe(&mut self, filepath_opt: Option<String>, extract_parameters: bool) {
         println!("{}", console::style("Generating Recipe").green());
 
         output::show_thinking();
@@ -732,7 +1297,10 @@ impl CliSession {
         output::hide_thinking();
 
         match recipe {
-            Ok(recipe) => {
+            Ok(mut recipe) => {
+                if extract_parameters {
+                    extract_recipe_parameters(&mut recipe, &self.messages);
+                }
                 let filepath_str = filepath_opt.as_deref().unwrap_or("recipe.yaml");
                 match self.save_recipe(&recipe, filepath_str) {
                     Ok(path) => println!(
@@ -765,27 +1333,242 @@ impl CliSession {
             }
         };
 
-        if should_summarize {
-            self.push_message(Message::user().with_text(COMPACT_TRIGGERS[0]));
+        if !should_summarize {
+            println!("{}", console::style("Compaction cancelled.").yellow());
+            return Ok(());
+        }
+
+        let provider = self.agent.provider().await?;
+        let mut guidance = None;
+        loop {
             output::show_thinking();
-            self.process_agent_response(true, CancellationToken::default())
-                .await?;
+            let result = compact_messages_with_guidance(
+                provider.as_ref(),
+                &self.messages,
+                true,
+                CompactionStrategy::FullSummary,
+                guidance,
+            )
+            .await;
             output::hide_thinking();
+            let (compacted, _usage, stats) = result.context("Failed to compact conversation")?;
+
+            println!("{}", console::style("Proposed summary:").bold());
+            println!("{}\n", compaction_summary(&compacted).unwrap_or_default());
+
+            match prompt_compaction_choice()? {
+                CompactionChoice::Keep => {
+                    self.agent
+                        .config
+                        .session_manager
+                        .replace_conversation(&self.session_id, &compacted)
+                        .await?;
+                    self.messages = compacted;
+                    println!(
+                        "{}",
+                        console::style(format!("Compaction complete: {}.", stats)).green()
+                    );
+                    return Ok(());
+                }
+                CompactionChoice::Regenerate => guidance = Some(REGENERATE_SUMMARY_GUIDANCE),
+                CompactionChoice::Cancel => {
+                    println!("{}", console::style("Compaction cancelled.").yellow());
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn handle_kill_subagent(&self, subagent_id: Option<String>) {
+        match subagent_id {
+            Some(id) => {
+                if self.agent.cancel_subagent(&id) {
+                    println!(
+                        "{}",
+                        console::style(format!("Cancelled subagent {}", id)).yellow()
+                    );
+                } else {
+                    output::render_error(&format!("No running subagent with id {}", id));
+                }
+            }
+            None => {
+                let running = self.agent.running_subagents();
+                if running.is_empty() {
+                    println!("{}", console::style("No subagents are running.").dim());
+                } else {
+                    println!("Running subagents:");
+                    for id in running {
+                        println!("  {}", console::style(id).cyan());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_meta(&mut self, entry: Option<(String, String)>) -> Result<()> {
+        let session_manager = &self.agent.config.session_manager;
+        let mut metadata = session_manager
+            .get_session(&self.session_id, false)
+            .await?
+            .metadata;
+
+        if let Some((key, value)) = entry {
+            if value.is_empty() {
+                metadata.remove(&key);
+            } else {
+                metadata.insert(key, value);
+            }
+            session_manager
+                .update(&self.session_id)
+                .metadata(metadata.clone())
+                .apply()
+                .await?;
+        }
+
+        if metadata.is_empty() {
+            println!("{}", console::style("No session metadata set.").dim());
         } else {
-            println!("{}", console::style("Compaction cancelled.").yellow());
+            let mut entries: Vec<_> = metadata.iter().collect();
+            entries.sort();
+            for (key, value) in entries {
+                println!("{} = {}", console::style(key).cyan(), value);
+            }
         }
         Ok(())
     }
 
-    async fn plan_with_reasoner_model(
-        &mut self,
-        plan_messages: Conversation,
-        reasoner: Arc<dyn Provider>,
-    ) -> Result<(), anyhow::Error> {
-        let plan_prompt = self.agent.get_plan_prompt().await?;
-        output::show_thinking();
-        let (plan_response, _usage) = reasoner
-            .complete(&plan_prompt, plan_messages.messages(), &[])
+    async fn handle_compare_tokens(&mut self, models: Vec<String>) -> Result<()> {
+        let current = self.agent.provider().await?;
+        let current_provider = current.get_name().to_string();
+        let current_model = current.get_model_config().model_name;
+
+        let models = if models.is_empty() {
+            Config::global()
+                .get_param::<String>(GOOSE_TOKEN_COMPARE_MODELS)
+                .map(|raw| raw.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default()
+        } else {
+            models
+        };
+        let mut candidates = vec![(current_provider.clone(), current_model)];
+        for spec in models.iter().filter(|spec| !spec.is_empty()) {
+            let candidate = parse_model_candidate(spec, &current_provider);
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+        if candidates.len() == 1 {
+            output::render_text(
+                &format!(
+                    "Only the current model is listed. Pass models to compare, e.g. /tokens compare openai/gpt-4o, or set {}.",
+                    GOOSE_TOKEN_COMPARE_MODELS
+                ),
+                Some(Color::Yellow),
+                true,
+            );
+        }
+
+        // Count the request as the agent would send it, system prompt included
+        let working_dir = self
+            .agent
+            .config
+            .session_manager
+            .get_session(&self.session_id, false)
+            .await?
+            .working_dir;
+        let (tools, _toolshim_tools, system_prompt) = self
+            .agent
+            .prepare_tools_and_prompt(&self.session_id, &working_dir)
+            .await?;
+        let messages = self.messages.agent_visible_messages();
+        let estimate = create_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .count_chat_tokens(&system_prompt, &messages, &tools);
+
+        let mut rows = Vec::new();
+        for (index, (provider_name, model)) in candidates.into_iter().enumerate() {
+            let provider = if index == 0 {
+                Ok(current.clone())
+            } else {
+                match goose::model::ModelConfig::new(&model) {
+                    Ok(model_config) => {
+                        goose::providers::create(&provider_name, model_config).await
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            };
+            let count = match provider {
+                Ok(provider) => match provider
+                    .count_tokens(
+                        &provider.get_model_config(),
+                        &system_prompt,
+                        &messages,
+                        &tools,
+                    )
+                    .await
+                {
+                    Ok(Some(count)) => output::TokenCount::Exact(count),
+                    Ok(None) => output::TokenCount::Estimated(estimate),
+                    Err(e) => output::TokenCount::Failed(e.to_string()),
+                },
+                Err(e) => output::TokenCount::Failed(e.to_string()),
+            };
+            rows.push((format!("{}/{}", provider_name, model), count));
+        }
+
+        output::render_token_comparison(&rows);
+        Ok(())
+    }
+
+    async fn handle_import_context(&mut self, options: input::ImportContextOptions) -> Result<()> {
+        let session_manager = &self.agent.config.session_manager;
+        let source = session_manager
+            .get_session(&options.session_id, true)
+            .await
+            .with_context(|| format!("Session '{}' not found", options.session_id))?;
+        let available = source
+            .conversation
+            .map(|conversation| conversation.agent_visible_messages())
+            .unwrap_or_default();
+        let selected = select_message_range(&available, options.start, options.end)?;
+        let selected_count = selected.len();
+
+        let mut combined = self.messages.clone();
+        combined.extend(selected);
+        let (fixed, issues) = fix_conversation(combined);
+        if !issues.is_empty() {
+            output::render_text(
+                &format!("Adjusted imported messages: {}", issues.join("; ")),
+                Some(Color::Yellow),
+                true,
+            );
+        }
+
+        session_manager
+            .replace_conversation(&self.session_id, &fixed)
+            .await?;
+        self.messages = fixed;
+        println!(
+            "{}",
+            console::style(format!(
+                "Imported {} message(s) from session {}",
+                selected_count, options.session_id
+            ))
+            .green()
+        );
+        Ok(())
+    }
+
+    async fn plan_with_reasoner_model(
+        &mut self,
+        plan_messages: Conversation,
+        reasoner: Arc<dyn Provider>,
+    ) -> Result<(), anyhow::Error> {
+        let plan_prompt = self.agent.get_plan_prompt().await?;
+        output::show_thinking();
+        let (plan_response, _usage) = reasoner
+            .complete(&plan_prompt, plan_messages.messages(), &[])
             .await?;
         output::render_message(&plan_response, self.debug);
         output::hide_thinking();
@@ -854,29 +1637,125 @@ impl CliSession {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
+        self.headless_with_cancellation(prompt, CancellationToken::default())
+            .await?;
+        self.verify_artifacts().await
+    }
+
+    /// Like `headless`, but the run is cancelled and fails once `timeout` has elapsed.
+    pub async fn headless_with_timeout(&mut self, prompt: String, timeout: Duration) -> Result<()> {
+        let deadline_token = CancellationToken::new();
+        let timer = {
+            let deadline_token = deadline_token.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                deadline_token.cancel();
+            })
+        };
+        let _timer = AbortOnDropHandle::new(timer);
+
+        self.headless_with_cancellation(prompt, deadline_token.clone())
+            .await?;
+        if deadline_token.is_cancelled() {
+            anyhow::bail!("Recipe exceeded its deadline of {}s", timeout.as_secs());
+        }
+        self.verify_artifacts().await
+    }
+
+    async fn headless_with_cancellation(
+        &mut self,
+        prompt: String,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
         let message = Message::user().with_text(&prompt);
-        self.process_message(message, CancellationToken::default())
+        let max_retries = Config::global()
+            .get_param::<usize>(GOOSE_HEADLESS_CONTEXT_RETRIES)
+            .unwrap_or(DEFAULT_HEADLESS_CONTEXT_RETRIES);
+
+        let mut retries = 0;
+        loop {
+            let history = self.messages.clone();
+            match self
+                .process_message(message.clone(), cancel_token.clone())
+                .await
+            {
+                Err(e) if is_context_length_exceeded(&e) && retries < max_retries => {
+                    retries += 1;
+                    eprintln!(
+                        "{}",
+                        console::style(format!(
+                            "Context limit exceeded, compacting and retrying ({}/{})",
+                            retries, max_retries
+                        ))
+                        .yellow()
+                    );
+                    self.compact_history(history).await?;
+                }
+                Err(e) if is_context_length_exceeded(&e) => {
+                    handle_agent_error(&e, self.output_format == "stream-json");
+                    return Err(e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Replaces the session's conversation with a compacted version of `history`.
+    async fn compact_history(&mut self, history: Conversation) -> Result<()> {
+        let provider = self.agent.provider().await?;
+        let (compacted, _usage, _stats) = compact_messages(
+            provider.as_ref(),
+            &history,
+            true,
+            CompactionStrategy::FullSummary,
+        )
+        .await
+        .context("Failed to compact conversation")?;
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &compacted)
             .await?;
+        self.messages = compacted;
         Ok(())
     }
 
+    /// Runs one agent turn. The `agent_turn` span is a trace root, so provider calls, tool
+    /// executions and compaction inside the turn are exported as its children over OTLP.
+    #[tracing::instrument(
+        name = "agent_turn",
+        parent = None,
+        skip_all,
+        fields(session.id = %self.session_id, interactive)
+    )]
     async fn process_agent_response(
         &mut self,
         interactive: bool,
         cancel_token: CancellationToken,
     ) -> Result<()> {
-        let is_json_mode = self.output_format == "json";
+        let is_json_array_mode = self.output_format == "json-array";
+        let is_json_mode = self.output_format == "json" || is_json_array_mode;
         let is_stream_json_mode = self.output_format == "stream-json";
 
+        let mut json_array = if is_json_array_mode {
+            let mut writer = JsonArrayWriter::begin()?;
+            for message in self.messages.iter() {
+                if self.include_agent_visible || message.is_user_visible() {
+                    writer.push(message)?;
+                }
+            }
+            Some(writer)
+        } else {
+            None
+        };
+
         let session_config = SessionConfig {
             id: self.session_id.clone(),
             schedule_id: self.scheduled_job_id.clone(),
             max_turns: self.max_turns,
             retry_config: self.retry_config.clone(),
         };
-        let user_message = self
-            .messages
-            .last()
+        let user_message = trailing_user_message(&self.messages)
             .ok_or_else(|| anyhow::anyhow!("No user message"))?;
 
         let cancel_token_interrupt = cancel_token.clone();
@@ -897,6 +1776,9 @@ impl CliSession {
             .await?;
 
         let mut progress_bars = output::McpSpinners::new();
+        let mut markdown = output::MarkdownStream::default();
+        let mut stream_json = StreamJsonMessages::default();
+        let mut outcome = RunOutcome::Completed;
         let cancel_token_clone = cancel_token.clone();
 
         use futures::StreamExt;
@@ -906,20 +1788,12 @@ impl CliSession {
                     match result {
                         Some(Ok(AgentEvent::Message(message))) => {
                             if let Some((id, security_prompt)) = find_tool_confirmation(&message) {
+                                markdown.finish();
                                 let permission = prompt_tool_confirmation(&security_prompt)?;
 
                                 if permission == Permission::Cancel {
-                                    output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
-                                    let mut response_message = Message::user();
-                                    response_message.content.push(MessageContent::tool_response(
-                                        id,
-                                        Err(ErrorData {
-                                            code: ErrorCode::INVALID_REQUEST,
-                                            message: std::borrow::Cow::from("Tool call cancelled by user"),
-                                            data: None,
-                                        }),
-                                    ));
-                                    self.messages.push(response_message);
+                                    self.cancel_tool_call(id);
+                                    outcome = RunOutcome::Cancelled;
                                     cancel_token_clone.cancel();
                                     drop(stream);
                                     break;
@@ -928,7 +1802,38 @@ impl CliSession {
                                     principal_type: PrincipalType::Tool,
                                     permission,
                                 }).await;
+                            } else if let Some((id, tool_call)) = find_frontend_tool_request(&message) {
+                                // The tool request itself was already shown; this asks the CLI to run it
+                                let result = match self.frontend_tools.get(tool_call.name.as_ref()) {
+                                    Some(tool) => {
+                                        markdown.finish();
+                                        match self.frontend_tool_permission(&tool.name)? {
+                                            Permission::Cancel => {
+                                                self.cancel_tool_call(id);
+                                                outcome = RunOutcome::Cancelled;
+                                                cancel_token_clone.cancel();
+                                                drop(stream);
+                                                break;
+                                            }
+                                            Permission::DenyOnce | Permission::AlwaysDeny => Err(ErrorData::new(
+                                                ErrorCode::INVALID_REQUEST,
+                                                format!("The user declined to run {}", tool.name),
+                                                None,
+                                            )),
+                                            Permission::AllowOnce | Permission::AlwaysAllow => {
+                                                frontend_tools::run_frontend_tool(tool, tool_call.arguments.as_ref()).await
+                                            }
+                                        }
+                                    }
+                                    None => Err(ErrorData::new(
+                                        ErrorCode::INVALID_REQUEST,
+                                        format!("Tool {} is not handled by the CLI", tool_call.name),
+                                        None,
+                                    )),
+                                };
+                                self.agent.handle_tool_result(id, result).await;
                             } else if let Some((elicitation_id, elicitation_message, schema)) = find_elicitation_request(&message) {
+                                markdown.finish();
                                 output::hide_thinking();
                                 let _ = progress_bars.hide();
 
@@ -949,12 +1854,15 @@ impl CliSession {
                                     }
                                     Ok(None) => {
                                         output::render_text("Information request cancelled.", Some(Color::Yellow), true);
+                                        outcome = RunOutcome::Cancelled;
                                         cancel_token_clone.cancel();
                                         drop(stream);
                                         break;
                                     }
                                     Err(e) => {
-                                        output::render_error(&format!("Failed to collect input: {}", e));
+                                        let message = format!("Failed to collect input: {}", e);
+                                        output::render_error(&message);
+                                        outcome = RunOutcome::Error(message);
                                         cancel_token_clone.cancel();
                                         drop(stream);
                                         break;
@@ -962,19 +1870,29 @@ impl CliSession {
                                 }
                             } else {
                                 log_tool_metrics(&message, &self.messages);
+                                self.record_transcript(&message);
                                 self.messages.push(message.clone());
 
                                 if interactive { output::hide_thinking() };
                                 let _ = progress_bars.hide();
 
                                 if is_stream_json_mode {
-                                    emit_stream_event(&StreamEvent::Message { message: message.clone() });
+                                    for event in stream_json.push(message.clone()) {
+                                        emit_stream_event(&event);
+                                    }
+                                } else if let Some(writer) = json_array.as_mut() {
+                                    if self.include_agent_visible || message.is_user_visible() {
+                                        writer.push(&message)?;
+                                    }
                                 } else if !is_json_mode {
-                                    output::render_message(&message, self.debug);
+                                    output::render_message_streaming(&message, self.debug, &mut markdown);
                                 }
                             }
                         }
                         Some(Ok(AgentEvent::McpNotification((extension_id, notification)))) => {
+                            if let Some(event) = stream_json.flush() {
+                                emit_stream_event(&event);
+                            }
                             handle_mcp_notification(
                                 &extension_id,
                                 &notification,
@@ -983,12 +1901,32 @@ impl CliSession {
                                 interactive,
                                 is_json_mode,
                                 self.debug,
+                                self.min_priority(),
                             );
                         }
                         Some(Ok(AgentEvent::HistoryReplaced(updated_conversation))) => {
                             self.messages = updated_conversation;
                         }
+                        Some(Ok(AgentEvent::ContextLengthExceeded)) => {
+                            let error = anyhow::Error::new(ProviderError::ContextLengthExceeded(
+                                "still too long after compacting".to_string(),
+                            ));
+                            // Let `headless` compact and retry the turn. A json-array document
+                            // has already been started, so that mode reports the failure instead.
+                            if !interactive && !is_json_array_mode {
+                                markdown.finish();
+                                if let Some(event) = stream_json.flush() {
+                                    emit_stream_event(&event);
+                                }
+                                drop(stream);
+                                return Err(error);
+                            }
+                            outcome = RunOutcome::Error(error.to_string());
+                        }
                         Some(Ok(AgentEvent::ModelChange { model, mode })) => {
+                            if let Some(event) = stream_json.flush() {
+                                emit_stream_event(&event);
+                            }
                             if is_stream_json_mode {
                                 emit_stream_event(&StreamEvent::ModelChange { model: model.clone(), mode: mode.clone() });
                             } else if self.debug {
@@ -996,7 +1934,11 @@ impl CliSession {
                             }
                         }
                         Some(Err(e)) => {
-                            handle_agent_error(&e, is_stream_json_mode);
+                            markdown.finish();
+                            if let Some(event) = stream_json.flush() {
+                                emit_stream_event(&event);
+                            }
+                            outcome = RunOutcome::Error(handle_agent_error(&e, is_stream_json_mode));
                             cancel_token_clone.cancel();
                             drop(stream);
                             if let Err(e) = self.handle_interrupted_messages(false).await {
@@ -1015,6 +1957,7 @@ impl CliSession {
                     }
                 }
                 _ = cancel_token_clone.cancelled() => {
+                    outcome = RunOutcome::Interrupted;
                     drop(stream);
                     if let Err(e) = self.handle_interrupted_messages(true).await {
                         eprintln!("Error handling interruption: {}", e);
@@ -1023,28 +1966,23 @@ impl CliSession {
                 }
             }
         }
-
-        if is_json_mode {
-            let metadata = match self
-                .agent
-                .config
-                .session_manager
-                .get_session(&self.session_id, false)
-                .await
-            {
-                Ok(session) => JsonMetadata {
-                    total_tokens: session.total_tokens,
-                    status: "completed".to_string(),
-                },
-                Err(_) => JsonMetadata {
-                    total_tokens: None,
-                    status: "completed".to_string(),
-                },
-            };
-            let json_output = JsonOutput {
-                messages: self.messages.messages().to_vec(),
-                metadata,
+        markdown.finish();
+        if let Some(event) = stream_json.flush() {
+            emit_stream_event(&event);
+        }
+        self.flush_transcript();
+
+        if let Some(writer) = json_array {
+            let metadata = self.json_metadata(&outcome).await;
+            writer.finish(&metadata)?;
+        } else if is_json_mode {
+            let metadata = self.json_metadata(&outcome).await;
+            let messages = if self.include_agent_visible {
+                self.messages.messages().to_vec()
+            } else {
+                self.messages.user_visible_messages()
             };
+            let json_output = JsonOutput { messages, metadata };
             println!("{}", serde_json::to_string_pretty(&json_output)?);
         } else if is_stream_json_mode {
             let total_tokens = self
@@ -1055,14 +1993,37 @@ impl CliSession {
                 .await
                 .ok()
                 .and_then(|s| s.total_tokens);
-            emit_stream_event(&StreamEvent::Complete { total_tokens });
+            emit_stream_event(&StreamEvent::Complete {
+                total_tokens,
+                status: outcome.status().to_string(),
+                error: outcome.error(),
+            });
         } else {
             println!();
         }
 
+        // Scripts reading machine-readable output also get a failing exit code
+        if let RunOutcome::Error(message) = &outcome {
+            if !interactive && (is_json_mode || is_stream_json_mode) {
+                anyhow::bail!("Agent run failed: {}", message);
+            }
+        }
         Ok(())
     }
 
+    async fn json_metadata(&self, outcome: &RunOutcome) -> JsonMetadata {
+        let total_tokens = self
+            .agent
+            .config
+            .session_manager
+            .get_session(&self.session_id, false)
+            .await
+            .ok()
+            .and_then(|session| session.total_tokens);
+        let artifacts = self.artifact_reports().await;
+        JsonMetadata::new(total_tokens, outcome, artifacts)
+    }
+
     async fn handle_interrupted_messages(&mut self, interrupt: bool) -> Result<()> {
         // First, get any tool requests from the last message if it exists
         let tool_requests = self
@@ -1150,35 +2111,99 @@ impl CliSession {
         Ok(())
     }
 
+    /// Replaces this session's conversation with one loaded from a JSON file, repaired with
+    /// `fix_conversation`, so a run can start from an exact conversation state.
+    pub async fn seed_conversation(&mut self, path: &std::path::Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read conversation file {}", path.display()))?;
+        let conversation = parse_conversation_json(&contents)
+            .with_context(|| format!("Invalid conversation file {}", path.display()))?;
+        let (fixed, issues) = fix_conversation(conversation);
+        if !issues.is_empty() {
+            eprintln!(
+                "{}",
+                console::style(format!(
+                    "Adjusted seeded conversation: {}",
+                    issues.join("; ")
+                ))
+                .yellow()
+            );
+        }
+
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &fixed)
+            .await?;
+        self.messages = fixed;
+        Ok(())
+    }
+
+    /// Cleans up a conversation that a crash left mid-turn so it can be continued, and saves
+    /// the result so the agent sees the same valid history.
+    pub async fn recover_interrupted_turn(&mut self) -> Result<()> {
+        if !ends_mid_turn(self.messages.messages()) {
+            return Ok(());
+        }
+        self.handle_interrupted_messages(false).await?;
+        self.agent
+            .config
+            .session_manager
+            .replace_conversation(&self.session_id, &self.messages)
+            .await
+    }
+
     /// Update the completion cache with fresh data
-    /// This should be called before the interactive session starts
+    /// This should be called before the interactive session starts. Prompts are read from
+    /// the copy saved on disk when the same extensions at the same versions are loaded and it
+    /// is younger than `COMPLETION_CACHE_TTL`, and only queried from the extensions otherwise.
     pub async fn update_completion_cache(&mut self) -> Result<()> {
-        // Get fresh data
-        let prompts = self.agent.list_extension_prompts().await;
+        let key =
+            completion_cache_key(&self.agent.extension_manager.get_extension_versions().await);
+        let cache_path = Paths::in_state_dir(COMPLETION_CACHE_FILE);
+        let prompt_infos = match load_completion_cache(&cache_path, &key, COMPLETION_CACHE_TTL) {
+            Some(prompt_infos) => prompt_infos,
+            None => {
+                let prompt_infos: Vec<output::PromptInfo> = self
+                    .agent
+                    .list_extension_prompts()
+                    .await
+                    .into_iter()
+                    .flat_map(|(extension, prompt_list)| {
+                        prompt_list
+                            .into_iter()
+                            .map(move |prompt| output::PromptInfo {
+                                name: prompt.name,
+                                description: prompt.description,
+                                arguments: prompt.arguments,
+                                extension: Some(extension.clone()),
+                            })
+                    })
+                    .collect();
+                if let Err(e) = save_completion_cache(&cache_path, &key, &prompt_infos) {
+                    warn!("Failed to save completion cache: {}", e);
+                }
+                prompt_infos
+            }
+        };
 
         // Update the cache with write lock
         let mut cache = self.completion_cache.write().unwrap();
         cache.prompts.clear();
         cache.prompt_info.clear();
 
-        for (extension, prompt_list) in prompts {
-            let names: Vec<String> = prompt_list.iter().map(|p| p.name.clone()).collect();
-            cache.prompts.insert(extension.clone(), names);
-
-            for prompt in prompt_list {
-                cache.prompt_info.insert(
-                    prompt.name.clone(),
-                    output::PromptInfo {
-                        name: prompt.name.clone(),
-                        description: prompt.description.clone(),
-                        arguments: prompt.arguments.clone(),
-                        extension: Some(extension.clone()),
-                    },
-                );
-            }
+        for info in prompt_infos {
+            let extension = info.extension.clone().unwrap_or_default();
+            cache
+                .prompts
+                .entry(extension)
+                .or_default()
+                .push(info.name.clone());
+            cache.prompt_info.insert(info.name.clone(), info);
         }
 
         cache.last_updated = Instant::now();
+        cache.stale = false;
         Ok(())
     }
 
@@ -1189,6 +2214,7 @@ impl CliSession {
         cache.prompts.clear();
         cache.prompt_info.clear();
         cache.last_updated = Instant::now();
+        cache.stale = true;
     }
 
     pub fn message_history(&self) -> Conversation {
@@ -1238,7 +2264,7 @@ impl CliSession {
     pub async fn display_context_usage(&self) -> Result<()> {
         let provider = self.agent.provider().await?;
         let model_config = provider.get_model_config();
-        let context_limit = model_config.context_limit();
+        let context_limit = effective_context_limit(provider.as_ref()).await;
 
         let config = Config::global();
         let show_cost = config
@@ -1294,52 +2320,53 @@ impl CliSession {
 
             match self.get_prompt(&opts.name, arguments).await {
                 Ok(messages) => {
-                    let start_len = self.messages.len();
-                    let mut valid = true;
-                    let num_messages = messages.len();
-                    for (i, prompt_message) in messages.into_iter().enumerate() {
-                        let msg = Message::from(prompt_message);
-                        // ensure we get a User - Assistant - User type pattern
-                        let expected_role = if i % 2 == 0 {
-                            rmcp::model::Role::User
-                        } else {
-                            rmcp::model::Role::Assistant
-                        };
+                    let prompt_messages: Vec<Message> =
+                        messages.into_iter().map(Message::from).collect();
+                    if !prompt_messages
+                        .iter()
+                        .any(|msg| msg.role == rmcp::model::Role::User)
+                    {
+                        output::render_error(&format!(
+                            "Prompt '{}' returned no user messages to send",
+                            opts.name
+                        ));
+                        return Ok(());
+                    }
 
-                        if msg.role != expected_role {
-                            output::render_error(&format!(
-                                "Expected {:?} message at position {}, but found {:?}",
-                                expected_role, i, msg.role
-                            ));
-                            valid = false;
-                            // get rid of everything we added to messages
-                            self.messages.truncate(start_len);
-                            break;
-                        }
+                    // Prompts don't always alternate roles strictly, so repair what we
+                    // can instead of rejecting the whole prompt
+                    let (fixed, issues) =
+                        fix_conversation(Conversation::new_unvalidated(prompt_messages));
+                    if !issues.is_empty() {
+                        output::render_text(
+                            &format!("Adjusted prompt messages: {}", issues.join("; ")),
+                            Some(Color::Yellow),
+                            true,
+                        );
+                    }
 
+                    let start_len = self.messages.len();
+                    let num_messages = fixed.len();
+                    for msg in fixed.messages().iter().cloned() {
                         if msg.role == rmcp::model::Role::User {
                             output::render_message(&msg, self.debug);
                         }
                         self.push_message(msg);
                     }
 
-                    if valid {
-                        if num_messages > 1 {
-                            for i in 0..(num_messages - 1) {
-                                let msg = &self.messages.messages()[start_len + i];
-                                self.agent
-                                    .config
-                                    .session_manager
-                                    .add_message(&self.session_id, msg)
-                                    .await?;
-                            }
+                    if num_messages > 1 {
+                        let session_manager = &self.agent.config.session_manager;
+                        for i in 0..(num_messages - 1) {
+                            let msg = &self.messages.messages()[start_len + i];
+                            session_manager.queue_message(&self.session_id, msg).await;
                         }
-
-                        output::show_thinking();
-                        self.process_agent_response(true, CancellationToken::default())
-                            .await?;
-                        output::hide_thinking();
+                        session_manager.flush_messages(&self.session_id).await?;
                     }
+
+                    output::show_thinking();
+                    self.process_agent_response(true, CancellationToken::default())
+                        .await?;
+                    output::hide_thinking();
                 }
                 Err(e) => output::render_error(&e.to_string()),
             }
@@ -1392,16 +2419,333 @@ impl CliSession {
     }
 
     fn push_message(&mut self, message: Message) {
+        self.record_transcript(&message);
         self.messages.push(message);
     }
 }
 
+const REGENERATE_SUMMARY_GUIDANCE: &str = "The user rejected a previous summary of this conversation as inadequate. \
+Write a more thorough one that keeps concrete details such as file paths, commands, decisions and unfinished tasks.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CompactionChoice {
+    Keep,
+    Regenerate,
+    Cancel,
+}
+
+fn prompt_compaction_choice() -> Result<CompactionChoice> {
+    let choice = cliclack::select("Use this summary?")
+        .item(
+            CompactionChoice::Keep,
+            "Keep",
+            "Replace the conversation history with this summary",
+        )
+        .item(
+            CompactionChoice::Regenerate,
+            "Regenerate",
+            "Ask for a more thorough summary",
+        )
+        .item(
+            CompactionChoice::Cancel,
+            "Cancel",
+            "Leave the conversation unchanged",
+        )
+        .interact();
+    match choice {
+        Ok(choice) => Ok(choice),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(CompactionChoice::Cancel),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The summary text of a freshly compacted conversation: the first message only the agent sees.
+fn compaction_summary(conversation: &Conversation) -> Option<String> {
+    conversation
+        .messages()
+        .iter()
+        .find(|msg| msg.metadata.agent_visible && !msg.metadata.user_visible)
+        .map(|msg| msg.as_concat_text())
+}
+
+/// The message the agent should reply to: the trailing one, when the user (or a tool
+/// response sent on their behalf) has the last word. Falling back to an earlier prompt
+/// would repeat a turn the agent already answered.
+fn trailing_user_message(conversation: &Conversation) -> Option<&Message> {
+    conversation
+        .last()
+        .filter(|message| message.role == rmcp::model::Role::User)
+}
+
+/// Drops the last prompt the user typed along with every reply and tool call that followed it.
+/// Tool responses also carry the user role, so the cut is made at the last user-visible user
+/// message without any, which never leaves a tool request separated from its response.
+fn undo_last_exchange(conversation: &Conversation) -> Option<(Conversation, Vec<Message>)> {
+    let messages = conversation.messages();
+    let cut = messages.iter().rposition(|msg| {
+        msg.role == rmcp::model::Role::User
+            && msg.is_user_visible()
+            && !msg
+                .content
+                .iter()
+                .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+    })?;
+    let removed = messages[cut..].to_vec();
+    let (remaining, _) = remove_orphaned_tool_calls(Conversation::new_unvalidated(
+        messages[..cut].iter().cloned(),
+    ));
+    Some((remaining, removed))
+}
+
+/// Splits the conversation for `/retry` into the history to keep, the prompt to send again and
+/// the number of messages that answered it. Earlier prompts that never got a reply are folded
+/// into the retried one, so the history it is sent after still ends with the agent's turn.
+fn retry_last_exchange(conversation: &Conversation) -> Option<(Conversation, Message, usize)> {
+    let (mut history, removed) = undo_last_exchange(conversation)?;
+    let mut prompt = removed.first()?.clone();
+    while history
+        .last()
+        .is_some_and(|message| effective_role(message) == "user")
+    {
+        let mut earlier = history.pop()?;
+        earlier.content.extend(prompt.content);
+        prompt.content = earlier.content;
+    }
+    Some((history, prompt, removed.len() - 1))
+}
+
+const COMPLETION_CACHE_FILE: &str = "completion_cache.json";
+
+/// Extensions without a version string keep the same key across upgrades, so saved
+/// completions are also refreshed once they are this old.
+const COMPLETION_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Prompt completions saved between sessions, valid only for the extension set in `key`
+#[derive(Serialize, Deserialize)]
+struct StoredCompletionCache {
+    key: String,
+    /// Seconds since the Unix epoch; caches written before this was recorded are stale
+    #[serde(default)]
+    saved_at: u64,
+    prompts: Vec<output::PromptInfo>,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Identifies a set of loaded extensions by their names and versions.
+fn completion_cache_key(extension_versions: &[(String, String)]) -> String {
+    extension_versions
+        .iter()
+        .map(|(name, version)| format!("{}@{}", name, version))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn load_completion_cache(
+    path: &std::path::Path,
+    key: &str,
+    max_age: Duration,
+) -> Option<Vec<output::PromptInfo>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let stored: StoredCompletionCache = serde_json::from_str(&contents).ok()?;
+    let fresh = unix_now().saturating_sub(stored.saved_at) < max_age.as_secs();
+    (stored.key == key && fresh).then_some(stored.prompts)
+}
+
+fn save_completion_cache(
+    path: &std::path::Path,
+    key: &str,
+    prompts: &[output::PromptInfo],
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let stored = StoredCompletionCache {
+        key: key.to_string(),
+        saved_at: unix_now(),
+        prompts: prompts.to_vec(),
+    };
+    std::fs::write(path, serde_json::to_string(&stored)?)?;
+    Ok(())
+}
+
+/// Pins the most recent message that both the user and the agent can see, returning the
+/// updated conversation and the pinned message.
+fn pin_last_message(conversation: &Conversation) -> Result<(Conversation, Message), &'static str> {
+    let mut messages = conversation.messages().clone();
+    let message = messages
+        .iter_mut()
+        .rev()
+        .find(|msg| msg.is_agent_visible() && msg.is_user_visible())
+        .ok_or("There is no message to pin")?;
+    if message.is_pinned() {
+        return Err("The last message is already pinned");
+    }
+    // Pinning one half of a tool call would separate it from the other half during compaction
+    let has_tool_content = message.content.iter().any(|c| {
+        matches!(
+            c,
+            MessageContent::ToolRequest(_) | MessageContent::ToolResponse(_)
+        )
+    });
+    if has_tool_content {
+        return Err("The last message contains tool calls and can't be pinned");
+    }
+
+    message.metadata = message.metadata.with_pinned(true);
+    let pinned = message.clone();
+    Ok((Conversation::new_unvalidated(messages), pinned))
+}
+
+/// Holds the assistant message that is still streaming in stream-json mode, so its text
+/// can go out as `MessageDelta` events and the whole message once it is complete.
+#[derive(Default)]
+struct StreamJsonMessages {
+    pending: Option<Message>,
+}
+
+impl StreamJsonMessages {
+    fn push(&mut self, message: Message) -> Vec<StreamEvent> {
+        let continues = self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.id.is_some() && pending.id == message.id);
+        let mut events: Vec<StreamEvent> = if continues {
+            Vec::new()
+        } else {
+            self.flush().into_iter().collect()
+        };
+
+        if message.role != rmcp::model::Role::Assistant || message.id.is_none() {
+            events.push(StreamEvent::Message { message });
+            return events;
+        }
+
+        events.extend(
+            message
+                .content
+                .iter()
+                .filter_map(|content| content.as_text())
+                .filter(|text| !text.is_empty())
+                .map(|text| StreamEvent::MessageDelta {
+                    text: text.to_string(),
+                }),
+        );
+        match self.pending.as_mut() {
+            Some(pending) if continues => append_chunk(pending, message),
+            _ => self.pending = Some(message),
+        }
+        events
+    }
+
+    /// The completed message, if one is still being held.
+    fn flush(&mut self) -> Option<StreamEvent> {
+        self.pending
+            .take()
+            .map(|message| StreamEvent::Message { message })
+    }
+}
+
+/// Same merge as `Conversation::push`, so a streamed message matches the session.
+fn append_chunk(pending: &mut Message, chunk: Message) {
+    match (pending.content.last_mut(), chunk.content.as_slice()) {
+        (Some(MessageContent::Text(last)), [MessageContent::Text(new)]) => {
+            last.text.push_str(&new.text);
+        }
+        _ => pending.content.extend(chunk.content),
+    }
+}
+
 fn emit_stream_event(event: &StreamEvent) {
     if let Ok(json) = serde_json::to_string(event) {
         println!("{}", json);
     }
 }
 
+/// Writes the same document as `JsonOutput`, but one message at a time so large
+/// sessions never have to be held in a single serialized string. Streamed chunks are
+/// merged by message id before they are written. Dropping the writer without calling
+/// `finish` still closes the document, leaving out only the metadata.
+struct JsonArrayWriter<W: Write = std::io::Stdout> {
+    out: W,
+    wrote_any: bool,
+    pending: Option<Message>,
+    finished: bool,
+}
+
+impl JsonArrayWriter {
+    fn begin() -> Result<Self> {
+        Self::begin_with(std::io::stdout())
+    }
+}
+
+impl<W: Write> JsonArrayWriter<W> {
+    fn begin_with(mut out: W) -> Result<Self> {
+        write!(out, "{{\"messages\":[")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            wrote_any: false,
+            pending: None,
+            finished: false,
+        })
+    }
+
+    fn push(&mut self, message: &Message) -> Result<()> {
+        match self.pending.as_mut() {
+            Some(pending) if pending.id.is_some() && pending.id == message.id => {
+                append_chunk(pending, message.clone());
+                Ok(())
+            }
+            _ => {
+                let completed = self.pending.replace(message.clone());
+                self.write_message(completed)
+            }
+        }
+    }
+
+    fn write_message(&mut self, message: Option<Message>) -> Result<()> {
+        let Some(message) = message else {
+            return Ok(());
+        };
+        if self.wrote_any {
+            write!(self.out, ",")?;
+        }
+        writeln!(self.out)?;
+        serde_json::to_writer(&mut self.out, &message)?;
+        self.out.flush()?;
+        self.wrote_any = true;
+        Ok(())
+    }
+
+    fn finish(mut self, metadata: &JsonMetadata) -> Result<()> {
+        self.finished = true;
+        let pending = self.pending.take();
+        self.write_message(pending)?;
+        write!(self.out, "\n],\"metadata\":")?;
+        serde_json::to_writer(&mut self.out, metadata)?;
+        writeln!(self.out, "}}")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for JsonArrayWriter<W> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let pending = self.pending.take();
+        let _ = self.write_message(pending);
+        let _ = writeln!(self.out, "\n]}}");
+        let _ = self.out.flush();
+    }
+}
+
 /// Prompt user for tool call confirmation, returns the Permission selected
 fn prompt_tool_confirmation(security_prompt: &Option<String>) -> Result<Permission> {
     output::hide_thinking();
@@ -1464,6 +2808,20 @@ fn find_tool_confirmation(message: &Message) -> Option<(String, Option<String>)>
     })
 }
 
+/// Extract a request for the CLI to run one of its configured tools from a message
+fn find_frontend_tool_request(
+    message: &Message,
+) -> Option<(String, rmcp::model::CallToolRequestParam)> {
+    message.content.iter().find_map(|content| match content {
+        MessageContent::FrontendToolRequest(request) => request
+            .tool_call
+            .as_ref()
+            .ok()
+            .map(|tool_call| (request.id.clone(), tool_call.clone())),
+        _ => None,
+    })
+}
+
 /// Extract elicitation request from a message
 fn find_elicitation_request(message: &Message) -> Option<(String, String, Value)> {
     message.content.iter().find_map(|content| {
@@ -1482,6 +2840,7 @@ fn find_elicitation_request(message: &Message) -> Option<(String, String, Value)
 }
 
 /// Handle MCP notification event (logging or progress)
+#[allow(clippy::too_many_arguments)]
 fn handle_mcp_notification(
     extension_id: &str,
     notification: &ServerNotification,
@@ -1490,11 +2849,12 @@ fn handle_mcp_notification(
     interactive: bool,
     is_json_mode: bool,
     debug: bool,
+    min_priority: f32,
 ) {
     match notification {
         ServerNotification::LoggingMessageNotification(log_notif) => {
             let (formatted, subagent_id, notif_type) =
-                format_logging_notification(&log_notif.params.data, debug);
+                format_logging_notification(&log_notif.params.data, debug, min_priority);
 
             if is_stream_json_mode {
                 emit_stream_event(&StreamEvent::Notification {
@@ -1541,6 +2901,7 @@ fn handle_mcp_notification(
 fn format_logging_notification(
     data: &Value,
     debug: bool,
+    min_priority: f32,
 ) -> (String, Option<String>, Option<String>) {
     match data {
         Value::String(s) => (s.clone(), None, None),
@@ -1560,12 +2921,6 @@ fn format_logging_notification(
                         format!("💭 {}", msg)
                     }
                     Some("response_generated") => {
-                        let config = Config::global();
-                        let min_priority = config
-                            .get_param::<f32>("GOOSE_CLI_MIN_PRIORITY")
-                            .ok()
-                            .unwrap_or(0.5);
-
                         if min_priority > 0.1 && !debug {
                             if let Some(response_content) = msg.strip_prefix("Responded: ") {
                                 format!("🤖 Responded: {}", safe_truncate(response_content, 100))
@@ -1628,7 +2983,11 @@ fn display_log_notification(
                 let _ = progress_bars.hide();
             }
             if !is_json_mode {
-                println!("{}", formatted_message);
+                if std::io::stdout().is_terminal() {
+                    println!("{}", formatted_message);
+                } else {
+                    println!("{}", console::strip_ansi_codes(formatted_message));
+                }
             }
         }
     } else if output::is_showing_thinking() {
@@ -1669,23 +3028,107 @@ fn log_tool_metrics(message: &Message, messages: &Conversation) {
                 })
                 .unwrap_or_else(|| "unknown".to_string().into());
 
-            let result_status = if tool_response.tool_result.is_ok() {
-                "success"
-            } else {
-                "error"
+            let (result_status, error_category) = match &tool_response.tool_result {
+                Ok(_) => ("success", "none"),
+                Err(error) => ("error", categorize_tool_error(error).as_str()),
             };
             tracing::info!(
                 counter.goose.tool_completions = 1,
                 tool_name = %tool_name,
                 result = %result_status,
+                error_category = %error_category,
                 "Tool call completed"
             );
         }
     }
 }
 
+/// Accepts a bare message array or a session exported with `goose session export --format json`.
+fn parse_conversation_json(contents: &str) -> Result<Conversation> {
+    let value: Value = serde_json::from_str(contents)?;
+    let value = match value {
+        Value::Object(mut session) if session.contains_key("conversation") => {
+            session.remove("conversation").unwrap_or_default()
+        }
+        value => value,
+    };
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Picks messages by 1-based inclusive position, with `end: None` meaning the last message.
+fn select_message_range(
+    messages: &[Message],
+    start: usize,
+    end: Option<usize>,
+) -> Result<Vec<Message>> {
+    let end = end.unwrap_or(messages.len());
+    if start == 0 || start > end || end > messages.len() {
+        anyhow::bail!(
+            "Range {}-{} is out of bounds; the session has {} agent-visible message(s)",
+            start,
+            end,
+            messages.len()
+        );
+    }
+    Ok(messages[start - 1..end].to_vec())
+}
+
+/// Splits `provider/model` at the first slash; a bare model name keeps the current provider.
+fn parse_model_candidate(spec: &str, current_provider: &str) -> (String, String) {
+    match spec.split_once('/') {
+        Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+            (provider.to_string(), model.to_string())
+        }
+        _ => (current_provider.to_string(), spec.to_string()),
+    }
+}
+
+/// True when the conversation stops inside a turn: an assistant tool request with no
+/// response, or a user message the model never answered.
+pub fn ends_mid_turn(messages: &[Message]) -> bool {
+    messages.last().is_some_and(|last| {
+        last.role == rmcp::model::Role::User
+            || last
+                .content
+                .iter()
+                .any(|content| matches!(content, MessageContent::ToolRequest(_)))
+    })
+}
+
+/// True when `content` is exactly the text of the last message the user typed.
+fn is_repeated_user_message(messages: &[Message], content: &str) -> bool {
+    messages
+        .iter()
+        .rev()
+        .find(|message| {
+            message.role == rmcp::model::Role::User
+                && message.is_user_visible()
+                && !message.is_tool_response()
+        })
+        .is_some_and(|message| message.as_concat_text() == content)
+}
+
+fn confirm_repeated_message() -> Result<bool> {
+    match cliclack::confirm("You just sent this exact message. Send it again?")
+        .initial_value(false)
+        .interact()
+    {
+        Ok(choice) => Ok(choice),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_context_length_exceeded(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<ProviderError>(),
+        Some(ProviderError::ContextLengthExceeded(_))
+    )
+}
+
 /// Handle and display an agent error
-fn handle_agent_error(e: &anyhow::Error, is_stream_json_mode: bool) {
+/// Reports an error from the agent stream and returns its message.
+fn handle_agent_error(e: &anyhow::Error, is_stream_json_mode: bool) -> String {
     let error_msg = e.to_string();
 
     if is_stream_json_mode {
@@ -1694,28 +3137,10 @@ fn handle_agent_error(e: &anyhow::Error, is_stream_json_mode: bool) {
         });
     }
 
-    if e.downcast_ref::<goose::providers::errors::ProviderError>()
-        .map(|provider_error| {
-            matches!(
-                provider_error,
-                goose::providers::errors::ProviderError::ContextLengthExceeded(_)
-            )
-        })
-        .unwrap_or(false)
-    {
-        if !is_stream_json_mode {
-            output::render_text(
-                "Compaction requested. Should have happened in the agent!",
-                Some(Color::Yellow),
-                true,
-            );
-        }
-        warn!("Compaction requested. Should have happened in the agent!");
-    }
-
     if !is_stream_json_mode {
         eprintln!("Error: {}", error_msg);
     }
+    error_msg
 }
 
 async fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -1769,6 +3194,374 @@ mod tests {
     use super::*;
     use std::time::Duration;
 
+    #[test]
+    fn test_completion_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join(COMPLETION_CACHE_FILE);
+        let key = completion_cache_key(&[
+            ("developer".to_string(), "1.2.0".to_string()),
+            ("memory".to_string(), String::new()),
+        ]);
+        assert_eq!(key, "developer@1.2.0,memory@");
+
+        let prompts = vec![output::PromptInfo {
+            name: "review".to_string(),
+            description: Some("Review a change".to_string()),
+            arguments: None,
+            extension: Some("developer".to_string()),
+        }];
+        assert!(load_completion_cache(&path, &key, COMPLETION_CACHE_TTL).is_none());
+        save_completion_cache(&path, &key, &prompts).unwrap();
+
+        let loaded = load_completion_cache(&path, &key, COMPLETION_CACHE_TTL).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "review");
+        assert_eq!(loaded[0].extension.as_deref(), Some("developer"));
+
+        // A different extension set misses the saved cache
+        assert!(load_completion_cache(&path, "developer@1.3.0", COMPLETION_CACHE_TTL).is_none());
+        // An expired cache is queried again even when the key matches
+        assert!(load_completion_cache(&path, &key, Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_json_metadata_reports_outcome() {
+        let metadata = |outcome: RunOutcome| {
+            serde_json::to_value(JsonMetadata::new(Some(10), &outcome, Vec::new())).unwrap()
+        };
+
+        let completed = metadata(RunOutcome::Completed);
+        assert_eq!(completed["status"], "completed");
+        assert!(completed.get("error").is_none());
+        assert_eq!(metadata(RunOutcome::Interrupted)["status"], "interrupted");
+        assert_eq!(metadata(RunOutcome::Cancelled)["status"], "cancelled");
+
+        let failed = metadata(RunOutcome::Error("rate limited".to_string()));
+        assert_eq!(failed["status"], "error");
+        assert_eq!(failed["error"], "rate limited");
+
+        let artifact = |exists| ArtifactReport {
+            path: "report.md".to_string(),
+            exists,
+        };
+        let missing = JsonMetadata::new(None, &RunOutcome::Completed, vec![artifact(false)]);
+        assert_eq!(missing.status, "missing_artifacts");
+        let present = JsonMetadata::new(None, &RunOutcome::Completed, vec![artifact(true)]);
+        assert_eq!(present.status, "completed");
+        let interrupted = JsonMetadata::new(None, &RunOutcome::Interrupted, vec![artifact(false)]);
+        assert_eq!(interrupted.status, "interrupted");
+    }
+
+    #[test]
+    fn test_stream_json_message_deltas() {
+        let describe = |events: Vec<StreamEvent>| -> Vec<String> {
+            events
+                .into_iter()
+                .map(|event| match event {
+                    StreamEvent::MessageDelta { text } => format!("delta:{}", text),
+                    StreamEvent::Message { message } => {
+                        format!("message:{}", message.as_concat_text())
+                    }
+                    other => format!("{:?}", other),
+                })
+                .collect()
+        };
+        let mut stream = StreamJsonMessages::default();
+
+        assert_eq!(
+            describe(stream.push(Message::assistant().with_text("Hel").with_id("m1"))),
+            vec!["delta:Hel"]
+        );
+        assert_eq!(
+            describe(stream.push(Message::assistant().with_text("lo").with_id("m1"))),
+            vec!["delta:lo"]
+        );
+        // A new message completes the previous one
+        assert_eq!(
+            describe(stream.push(Message::assistant().with_text("Next").with_id("m2"))),
+            vec!["message:Hello", "delta:Next"]
+        );
+        // Messages that aren't streamed go out whole
+        assert_eq!(
+            describe(stream.push(Message::user().with_text("tool output"))),
+            vec!["message:Next", "message:tool output"]
+        );
+        assert!(stream.flush().is_none());
+
+        stream.push(Message::assistant().with_text("Done").with_id("m3"));
+        assert_eq!(
+            describe(stream.flush().into_iter().collect()),
+            vec!["message:Done"]
+        );
+    }
+
+    #[test]
+    fn test_json_array_writer_merges_chunks_and_closes_on_drop() {
+        let mut out = Vec::new();
+        {
+            let mut writer = JsonArrayWriter::begin_with(&mut out).unwrap();
+            writer.push(&Message::user().with_text("Hi")).unwrap();
+            writer
+                .push(&Message::assistant().with_text("Hel").with_id("m1"))
+                .unwrap();
+            writer
+                .push(&Message::assistant().with_text("lo").with_id("m1"))
+                .unwrap();
+            // Dropped without `finish`, as when a turn fails part way through
+        }
+
+        let document: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let messages = document["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["content"][0]["text"], "Hello");
+    }
+
+    #[test]
+    fn test_compaction_summary() {
+        use goose::conversation::message::MessageMetadata;
+
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user()
+                .with_text("original question")
+                .with_metadata(MessageMetadata::user_only()),
+            Message::user()
+                .with_text("summary of the work so far")
+                .with_metadata(MessageMetadata::agent_only()),
+            Message::assistant()
+                .with_text("continue")
+                .with_metadata(MessageMetadata::agent_only()),
+        ]);
+        assert_eq!(
+            compaction_summary(&conversation).as_deref(),
+            Some("summary of the work so far")
+        );
+        assert_eq!(compaction_summary(&Conversation::empty()), None);
+    }
+
+    #[test]
+    fn test_undo_last_exchange() {
+        let tool_request = Message::assistant().with_tool_request(
+            "call_1",
+            Ok(rmcp::model::CallToolRequestParam {
+                task: None,
+                name: "shell".into(),
+                arguments: None,
+            }),
+        );
+        let tool_response = Message::user().with_tool_response(
+            "call_1",
+            Ok(rmcp::model::CallToolResult::success(vec![
+                rmcp::model::Content::text("ok"),
+            ])),
+        );
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("first"),
+            Message::assistant().with_text("first reply"),
+            Message::user().with_text("run ls"),
+            tool_request,
+            tool_response,
+            Message::assistant().with_text("done"),
+        ]);
+
+        let (remaining, removed) = undo_last_exchange(&conversation).unwrap();
+        assert_eq!(removed.len(), 4);
+        assert_eq!(removed[0].as_concat_text(), "run ls");
+        assert_eq!(remaining.messages().len(), 2);
+        assert_eq!(remaining.messages()[1].as_concat_text(), "first reply");
+
+        let (remaining, removed) = undo_last_exchange(&remaining).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(remaining.is_empty());
+        assert!(undo_last_exchange(&remaining).is_none());
+    }
+
+    #[test]
+    fn test_retry_last_exchange_alternates_roles() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("first"),
+            Message::assistant().with_text("first reply"),
+            Message::user().with_text("interrupted"),
+            Message::user().with_text("second"),
+            Message::assistant().with_text("second reply"),
+        ]);
+
+        let (history, prompt, removed) = retry_last_exchange(&conversation).unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.messages()[1].as_concat_text(), "first reply");
+        assert_eq!(prompt.as_concat_text(), "interrupted\nsecond");
+
+        let mut retried = history;
+        retried.push(prompt);
+        let roles: Vec<_> = retried.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(
+            roles,
+            vec![
+                rmcp::model::Role::User,
+                rmcp::model::Role::Assistant,
+                rmcp::model::Role::User
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trailing_user_message_with_tool_response_tail() {
+        let tool_response = Message::user().with_tool_response(
+            "call_1",
+            Ok(rmcp::model::CallToolResult::success(vec![
+                rmcp::model::Content::text("ok"),
+            ])),
+        );
+        let mut conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("run ls"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(rmcp::model::CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: None,
+                }),
+            ),
+            tool_response.clone(),
+        ]);
+
+        // The tool response is replied to, not the earlier prompt
+        assert_eq!(trailing_user_message(&conversation), Some(&tool_response));
+
+        conversation.push(Message::assistant().with_text("done"));
+        assert!(trailing_user_message(&conversation).is_none());
+    }
+
+    #[test]
+    fn test_pin_last_message() {
+        use goose::conversation::message::MessageMetadata;
+
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("always use tabs"),
+            Message::assistant().with_text("noted"),
+            Message::user()
+                .with_text("summary")
+                .with_metadata(MessageMetadata::agent_only()),
+        ]);
+        let (pinned, message) = pin_last_message(&conversation).unwrap();
+        assert_eq!(message.as_concat_text(), "noted");
+        assert!(pinned.messages()[1].is_pinned());
+        assert!(!pinned.messages()[0].is_pinned());
+        assert_eq!(
+            pin_last_message(&pinned).unwrap_err(),
+            "The last message is already pinned"
+        );
+        assert!(pin_last_message(&Conversation::empty()).is_err());
+
+        let tool_call = Conversation::new_unvalidated(vec![Message::assistant()
+            .with_tool_request(
+                "call_1",
+                Ok(rmcp::model::CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: None,
+                }),
+            )]);
+        assert!(pin_last_message(&tool_call).is_err());
+    }
+
+    #[test]
+    fn test_select_message_range() {
+        let messages: Vec<Message> = (1..=4)
+            .map(|i| Message::user().with_text(format!("message {}", i)))
+            .collect();
+
+        let selected = select_message_range(&messages, 2, Some(3)).unwrap();
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].as_concat_text(), "message 2");
+
+        assert_eq!(select_message_range(&messages, 3, None).unwrap().len(), 2);
+        assert!(select_message_range(&messages, 3, Some(5)).is_err());
+        assert!(select_message_range(&messages, 5, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_conversation_json() {
+        let messages = vec![
+            Message::user().with_text("hello"),
+            Message::assistant().with_text("hi there"),
+        ];
+        let array = serde_json::to_string(&messages).unwrap();
+        assert_eq!(parse_conversation_json(&array).unwrap().messages().len(), 2);
+
+        let exported = serde_json::json!({
+            "id": "20250101_1",
+            "conversation": messages,
+        })
+        .to_string();
+        let conversation = parse_conversation_json(&exported).unwrap();
+        assert_eq!(conversation.messages()[1].as_concat_text(), "hi there");
+
+        assert!(parse_conversation_json("{\"id\": 1}").is_err());
+    }
+
+    #[test]
+    fn test_parse_model_candidate() {
+        assert_eq!(
+            parse_model_candidate("openrouter/anthropic/claude-sonnet-4", "openai"),
+            (
+                "openrouter".to_string(),
+                "anthropic/claude-sonnet-4".to_string()
+            )
+        );
+        assert_eq!(
+            parse_model_candidate("gpt-4o-mini", "openai"),
+            ("openai".to_string(), "gpt-4o-mini".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ends_mid_turn() {
+        let question = Message::user().with_text("list the files");
+        let answer = Message::assistant().with_text("Here they are.");
+        let tool_request = Message::assistant().with_tool_request(
+            "req_1",
+            Ok(rmcp::model::CallToolRequestParam {
+                task: None,
+                name: "developer__shell".into(),
+                arguments: None,
+            }),
+        );
+
+        assert!(!ends_mid_turn(&[]));
+        assert!(!ends_mid_turn(&[question.clone(), answer]));
+        assert!(ends_mid_turn(std::slice::from_ref(&question)));
+        assert!(ends_mid_turn(&[question, tool_request]));
+    }
+
+    #[test]
+    fn test_is_repeated_user_message() {
+        let messages = vec![
+            Message::user().with_text("run the tests"),
+            Message::assistant().with_text("All tests pass."),
+        ];
+        assert!(is_repeated_user_message(&messages, "run the tests"));
+        assert!(!is_repeated_user_message(&messages, "run the tests again"));
+        assert!(!is_repeated_user_message(&[], "run the tests"));
+
+        let mut messages = messages;
+        messages.push(Message::user().with_text("now lint"));
+        assert!(!is_repeated_user_message(&messages, "run the tests"));
+    }
+
+    #[test]
+    fn test_is_context_length_exceeded() {
+        let overflow = anyhow::Error::new(ProviderError::ContextLengthExceeded("too long".into()));
+        assert!(is_context_length_exceeded(&overflow));
+
+        let other = anyhow::Error::new(ProviderError::RateLimitExceeded {
+            details: "slow down".into(),
+            retry_delay: None,
+        });
+        assert!(!is_context_length_exceeded(&other));
+        assert!(!is_context_length_exceeded(&anyhow::anyhow!("boom")));
+    }
+
     #[test]
     fn test_format_elapsed_time_under_60_seconds() {
         // Test sub-second duration
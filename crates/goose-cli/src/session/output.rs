@@ -64,6 +64,67 @@ thread_local! {
             )
     );
     static SHOW_FULL_TOOL_OUTPUT: RefCell<bool> = const { RefCell::new(false) };
+    static THINKING_VERBOSITY: RefCell<ThinkingVerbosity> =
+        RefCell::new(ThinkingVerbosity::from_config());
+}
+
+pub const GOOSE_CLI_THINKING: &str = "GOOSE_CLI_THINKING";
+const THINKING_SUMMARY_CHARS: usize = 300;
+
+/// How much of a reasoning model's thinking is rendered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThinkingVerbosity {
+    /// Not rendered at all
+    Off,
+    /// The start of the reasoning on one dimmed line
+    Summary,
+    /// The whole reasoning, rendered as markdown
+    Full,
+}
+
+impl ThinkingVerbosity {
+    pub fn from_config_str(val: &str) -> Option<Self> {
+        match val.trim().to_lowercase().as_str() {
+            "off" => Some(ThinkingVerbosity::Off),
+            "summary" => Some(ThinkingVerbosity::Summary),
+            "full" => Some(ThinkingVerbosity::Full),
+            _ => None,
+        }
+    }
+
+    pub fn as_config_str(&self) -> &'static str {
+        match self {
+            ThinkingVerbosity::Off => "off",
+            ThinkingVerbosity::Summary => "summary",
+            ThinkingVerbosity::Full => "full",
+        }
+    }
+
+    /// Reads GOOSE_CLI_THINKING, falling back to the older GOOSE_CLI_SHOW_THINKING switch.
+    fn from_config() -> Self {
+        Config::global()
+            .get_param::<String>(GOOSE_CLI_THINKING)
+            .ok()
+            .and_then(|val| Self::from_config_str(&val))
+            .unwrap_or_else(|| {
+                if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok() {
+                    ThinkingVerbosity::Full
+                } else {
+                    ThinkingVerbosity::Off
+                }
+            })
+    }
+}
+
+pub fn set_thinking_verbosity(verbosity: ThinkingVerbosity) {
+    THINKING_VERBOSITY.with(|v| *v.borrow_mut() = verbosity);
+    if let Err(e) = Config::global().set_param(GOOSE_CLI_THINKING, verbosity.as_config_str()) {
+        eprintln!("Failed to save thinking setting to config: {}", e);
+    }
+}
+
+pub fn get_thinking_verbosity() -> ThinkingVerbosity {
+    THINKING_VERBOSITY.with(|v| *v.borrow())
 }
 
 pub fn set_theme(theme: Theme) {
@@ -135,7 +196,7 @@ impl ThinkingIndicator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PromptInfo {
     pub name: String,
     pub description: Option<String>,
@@ -177,58 +238,175 @@ pub fn set_thinking_message(s: &String) {
 pub fn render_message(message: &Message, debug: bool) {
     let theme = get_theme();
 
+    for content in &message.content {
+        render_content(content, theme, debug);
+    }
+
+    let _ = std::io::stdout().flush();
+}
+
+/// Like `render_message`, but text is fed through `markdown` so fragments of a streamed
+/// response are rendered on line and code block boundaries.
+pub fn render_message_streaming(message: &Message, debug: bool, markdown: &mut MarkdownStream) {
+    let theme = get_theme();
+
     for content in &message.content {
         match content {
-            MessageContent::ActionRequired(action) => match &action.data {
-                ActionRequiredData::ToolConfirmation { tool_name, .. } => {
-                    println!("action_required(tool_confirmation): {}", tool_name)
-                }
-                ActionRequiredData::Elicitation { message, .. } => {
-                    println!("action_required(elicitation): {}", message)
-                }
-                ActionRequiredData::ElicitationResponse { id, .. } => {
-                    println!("action_required(elicitation_response): {}", id)
-                }
-            },
-            MessageContent::Text(text) => print_markdown(&text.text, theme),
-            MessageContent::ToolRequest(req) => render_tool_request(req, theme, debug),
-            MessageContent::ToolResponse(resp) => render_tool_response(resp, theme, debug),
-            MessageContent::Image(image) => {
-                println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+            MessageContent::Text(text) => markdown.push(&text.text),
+            other => {
+                markdown.finish();
+                render_content(other, theme, debug);
             }
-            MessageContent::Thinking(thinking) => {
-                if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok()
-                    && std::io::stdout().is_terminal()
-                {
-                    println!("\n{}", style("Thinking:").dim().italic());
-                    print_markdown(&thinking.thinking, theme);
-                }
+        }
+    }
+
+    let _ = std::io::stdout().flush();
+}
+
+/// Buffers streamed markdown so it is only highlighted in complete pieces. Whole lines
+/// are rendered as they arrive, while a fenced code block is held back until its
+/// closing fence so it is highlighted as one block instead of disconnected fragments.
+#[derive(Default)]
+pub struct MarkdownStream {
+    buffer: String,
+    /// Length of the buffer prefix whose lines have already been scanned for fences
+    scanned: usize,
+    /// Fence character of the currently open code block, if any
+    open_fence: Option<char>,
+}
+
+impl MarkdownStream {
+    pub fn push(&mut self, text: &str) {
+        self.buffer.push_str(text);
+        let ready = self.take_ready();
+        if !ready.is_empty() {
+            print_markdown(&ready, get_theme());
+        }
+    }
+
+    /// Renders whatever is left, including a partial line or an unterminated code block.
+    pub fn finish(&mut self) {
+        if !self.buffer.is_empty() {
+            print_markdown(&self.buffer, get_theme());
+        }
+        *self = Self::default();
+    }
+
+    /// Removes and returns the complete lines at the front of the buffer that aren't
+    /// inside an open code block.
+    fn take_ready(&mut self) -> String {
+        let mut ready_len = if self.open_fence.is_none() {
+            self.scanned
+        } else {
+            0
+        };
+        while let Some((line, _)) = self
+            .buffer
+            .get(self.scanned..)
+            .and_then(|rest| rest.split_once('\n'))
+        {
+            self.scanned += line.len() + 1;
+            self.open_fence = match (self.open_fence, fence_char(line)) {
+                (None, Some(fence)) => Some(fence),
+                (Some(open), Some(fence)) if open == fence && is_closing_fence(line) => None,
+                (open, _) => open,
+            };
+            if self.open_fence.is_none() {
+                ready_len = self.scanned;
+            }
+        }
+        self.scanned -= ready_len;
+        self.buffer.drain(..ready_len).collect()
+    }
+}
+
+fn fence_char(line: &str) -> Option<char> {
+    let trimmed = line.trim_start();
+    ["```", "~~~"]
+        .iter()
+        .find(|fence| trimmed.starts_with(*fence))
+        .and_then(|fence| fence.chars().next())
+}
+
+fn is_closing_fence(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.chars().all(|c| c == '`') || trimmed.chars().all(|c| c == '~')
+}
+
+fn render_content(content: &MessageContent, theme: Theme, debug: bool) {
+    match content {
+        MessageContent::ActionRequired(action) => match &action.data {
+            ActionRequiredData::ToolConfirmation { tool_name, .. } => {
+                println!("action_required(tool_confirmation): {}", tool_name)
+            }
+            ActionRequiredData::Elicitation { message, .. } => {
+                println!("action_required(elicitation): {}", message)
             }
-            MessageContent::RedactedThinking(_) => {
-                // For redacted thinking, print thinking was redacted
-                println!("\n{}", style("Thinking:").dim().italic());
-                print_markdown("Thinking was redacted", theme);
+            ActionRequiredData::ElicitationResponse { id, .. } => {
+                println!("action_required(elicitation_response): {}", id)
+            }
+        },
+        MessageContent::Text(text) => print_markdown(&text.text, theme),
+        MessageContent::ToolRequest(req) => render_tool_request(req, theme, debug),
+        MessageContent::ToolResponse(resp) => render_tool_response(resp, theme, debug),
+        MessageContent::Image(image) => {
+            println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
+        }
+        MessageContent::Thinking(thinking) => {
+            if std::io::stdout().is_terminal() {
+                render_thinking(&thinking.thinking, get_thinking_verbosity(), theme);
             }
-            MessageContent::SystemNotification(notification) => {
-                use goose::conversation::message::SystemNotificationType;
+        }
+        MessageContent::RedactedThinking(_) => {
+            // For redacted thinking, print thinking was redacted
+            println!("\n{}", style("Thinking:").dim().italic());
+            print_markdown("Thinking was redacted", theme);
+        }
+        MessageContent::SystemNotification(notification) => {
+            use goose::conversation::message::SystemNotificationType;
 
-                match notification.notification_type {
-                    SystemNotificationType::ThinkingMessage => {
-                        show_thinking();
-                        set_thinking_message(&notification.msg);
-                    }
-                    SystemNotificationType::InlineMessage => {
-                        println!("\n{}", style(&notification.msg).yellow());
-                    }
+            match notification.notification_type {
+                SystemNotificationType::ThinkingMessage => {
+                    show_thinking();
+                    set_thinking_message(&notification.msg);
+                }
+                SystemNotificationType::InlineMessage => {
+                    println!("\n{}", style(&notification.msg).yellow());
                 }
             }
-            _ => {
-                println!("WARNING: Message content type could not be rendered");
+        }
+        _ => {
+            println!("WARNING: Message content type could not be rendered");
+        }
+    }
+}
+
+fn render_thinking(thinking: &str, verbosity: ThinkingVerbosity, theme: Theme) {
+    match verbosity {
+        ThinkingVerbosity::Off => {}
+        ThinkingVerbosity::Summary => {
+            if let Some(summary) = summarize_thinking(thinking) {
+                println!(
+                    "\n{} {}",
+                    style("Thinking:").dim().italic(),
+                    style(summary).dim()
+                );
             }
         }
+        ThinkingVerbosity::Full => {
+            println!("\n{}", style("Thinking:").dim().italic());
+            print_markdown(thinking, theme);
+        }
     }
+}
 
-    let _ = std::io::stdout().flush();
+/// Collapses reasoning onto one line and keeps only its opening.
+fn summarize_thinking(thinking: &str) -> Option<String> {
+    let collapsed = thinking.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    Some(safe_truncate(&collapsed, THINKING_SUMMARY_CHARS))
 }
 
 pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
@@ -333,6 +511,58 @@ pub fn render_error(message: &str) {
     println!("\n  {} {}\n", style("error:").red().bold(), message);
 }
 
+pub enum TokenCount {
+    /// Counted by the provider with the model's tokenizer
+    Exact(usize),
+    /// The provider can't count tokens; this is the local tokenizer's estimate
+    Estimated(usize),
+    Failed(String),
+}
+
+impl TokenCount {
+    fn value(&self) -> Option<usize> {
+        match self {
+            TokenCount::Exact(count) | TokenCount::Estimated(count) => Some(*count),
+            TokenCount::Failed(_) => None,
+        }
+    }
+}
+
+/// Shows each model's token count, relative to the first (current) model.
+pub fn render_token_comparison(rows: &[(String, TokenCount)]) {
+    let baseline = rows.first().and_then(|(_, count)| count.value());
+    let width = rows.iter().map(|(model, _)| model.len()).max().unwrap_or(0);
+
+    println!();
+    for (index, (model, count)) in rows.iter().enumerate() {
+        let label = format!("{:<width$}", model);
+        let label = if index == 0 {
+            style(label).cyan().bold()
+        } else {
+            style(label).cyan()
+        };
+        let detail = match count {
+            TokenCount::Failed(error) => style(format!("failed: {}", error)).red().to_string(),
+            _ => {
+                let value = count.value().unwrap_or_default();
+                let mut detail = format!("{:>9} tokens", value);
+                if let (Some(baseline), true) = (baseline, index > 0) {
+                    if baseline > 0 {
+                        let change = (value as f64 - baseline as f64) / baseline as f64 * 100.0;
+                        detail.push_str(&format!("  {:+.1}%", change));
+                    }
+                }
+                if matches!(count, TokenCount::Estimated(_)) {
+                    detail.push_str(&style("  (local estimate)").dim().to_string());
+                }
+                detail
+            }
+        };
+        println!("  {}  {}", label, detail);
+    }
+    println!();
+}
+
 pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
     println!();
     for (extension, prompts) in prompts {
@@ -962,6 +1192,29 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn test_thinking_verbosity_parsing() {
+        assert_eq!(
+            ThinkingVerbosity::from_config_str(" Summary "),
+            Some(ThinkingVerbosity::Summary)
+        );
+        assert_eq!(ThinkingVerbosity::from_config_str("verbose"), None);
+        assert_eq!(ThinkingVerbosity::Full.as_config_str(), "full");
+    }
+
+    #[test]
+    fn test_summarize_thinking() {
+        assert_eq!(summarize_thinking("  \n "), None);
+        assert_eq!(
+            summarize_thinking("First,\n\n  check the   input.").unwrap(),
+            "First, check the input."
+        );
+        let long = "word ".repeat(200);
+        let summary = summarize_thinking(&long).unwrap();
+        assert_eq!(summary.chars().count(), THINKING_SUMMARY_CHARS);
+        assert!(summary.ends_with("..."));
+    }
+
     #[test]
     fn test_short_paths_unchanged() {
         assert_eq!(shorten_path("/usr/bin", false), "/usr/bin");
@@ -1004,6 +1257,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_markdown_stream_holds_back_open_code_blocks() {
+        let mut stream = MarkdownStream::default();
+        let mut push = |text: &str| {
+            stream.buffer.push_str(text);
+            stream.take_ready()
+        };
+
+        assert_eq!(push("Here is a fi"), "");
+        assert_eq!(push("x:\n- one\n- tw"), "Here is a fix:\n- one\n");
+        assert_eq!(push("o\n```rust\nfn main() {\n"), "- two\n");
+        assert_eq!(push("    println!(\"```\");\n}\n"), "");
+        assert_eq!(
+            push("```\nDone"),
+            "```rust\nfn main() {\n    println!(\"```\");\n}\n```\n"
+        );
+        assert_eq!(stream.buffer, "Done");
+        assert!(stream.open_fence.is_none());
+    }
+
     #[test]
     fn test_toggle_full_tool_output() {
         let initial = get_show_full_tool_output();
@@ -0,0 +1,144 @@
+use super::export::message_to_markdown;
+use anyhow::{Context, Result};
+use goose::conversation::message::{Message, MessageContent};
+use rmcp::model::Role;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// When set, every session writes a markdown transcript to `<dir>/<session id>.md`
+pub const GOOSE_TRANSCRIPT_DIR: &str = "GOOSE_TRANSCRIPT_DIR";
+
+/// Appends user-visible messages to a markdown file as the session runs, flushing after
+/// each one so the log stays current even if the process dies.
+pub struct Transcript {
+    file: File,
+    path: PathBuf,
+    /// Streamed assistant text is collected per message id and written once complete
+    pending: Option<Message>,
+}
+
+impl Transcript {
+    pub fn open(path: &Path, session_id: &str) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open transcript {}", path.display()))?;
+        if is_new {
+            writeln!(file, "# Session {}\n", session_id)?;
+            file.flush()?;
+        }
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            pending: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&mut self, message: &Message) -> Result<()> {
+        if !message.is_user_visible() {
+            return Ok(());
+        }
+
+        if let Some(pending) = self.pending.as_mut() {
+            if is_streamed_text(message) && pending.id == message.id {
+                pending.content.extend(message.content.iter().cloned());
+                return Ok(());
+            }
+        }
+        self.flush_pending()?;
+
+        if is_streamed_text(message) {
+            self.pending = Some(message.clone());
+            Ok(())
+        } else {
+            self.write(message)
+        }
+    }
+
+    /// Writes out any assistant text still being streamed, e.g. at the end of a turn.
+    pub fn flush_pending(&mut self) -> Result<()> {
+        match self.pending.take() {
+            Some(message) => self.write(&message),
+            None => Ok(()),
+        }
+    }
+
+    fn write(&mut self, message: &Message) -> Result<()> {
+        let body = message_to_markdown(&merge_text(message), false);
+        if body.trim().is_empty() {
+            return Ok(());
+        }
+        let heading = match message.role {
+            Role::User => "### User:",
+            Role::Assistant => "### Assistant:",
+        };
+        write!(self.file, "{}\n{}\n\n---\n\n", heading, body.trim_end())?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Assistant text with an id arrives in fragments when the provider streams.
+fn is_streamed_text(message: &Message) -> bool {
+    message.role == Role::Assistant
+        && message.id.is_some()
+        && message
+            .content
+            .iter()
+            .all(|content| matches!(content, MessageContent::Text(_)))
+}
+
+/// Joins adjacent text fragments so they render as one paragraph run.
+fn merge_text(message: &Message) -> Message {
+    let mut merged = message.clone();
+    merged.content.clear();
+    for content in &message.content {
+        match (merged.content.last_mut(), content) {
+            (Some(MessageContent::Text(last)), MessageContent::Text(text)) => {
+                last.raw.text.push_str(&text.text);
+            }
+            _ => merged.content.push(content.clone()),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_merges_streamed_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("session.md");
+        let mut transcript = Transcript::open(&path, "20250101_1").unwrap();
+
+        transcript
+            .record(&Message::user().with_text("What is 2 + 2?"))
+            .unwrap();
+        for chunk in ["The answer", " is 4."] {
+            transcript
+                .record(&Message::assistant().with_id("msg_1").with_text(chunk))
+                .unwrap();
+        }
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(on_disk.contains("### User:\nWhat is 2 + 2?"));
+        assert!(!on_disk.contains("The answer"));
+
+        transcript.flush_pending().unwrap();
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(on_disk.starts_with("# Session 20250101_1"));
+        assert!(on_disk.contains("### Assistant:\nThe answer is 4."));
+    }
+}
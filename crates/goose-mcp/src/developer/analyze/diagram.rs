@@ -0,0 +1,78 @@
+//! Call graph diagrams for focused analysis, selected with `output_format: "mermaid"` or `"dot"`.
+
+use crate::developer::analyze::types::FocusedAnalysisData;
+
+/// Distinct symbols and caller -> callee edges of the focus symbol's call chains,
+/// in the order they are first seen. The focus symbol is always the first node.
+struct CallEdges {
+    nodes: Vec<String>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl CallEdges {
+    fn collect(focus_data: &FocusedAnalysisData) -> Self {
+        let mut graph = Self {
+            nodes: vec![focus_data.focus_symbol.to_string()],
+            edges: Vec::new(),
+        };
+        let steps = focus_data
+            .incoming_chains
+            .iter()
+            .chain(focus_data.outgoing_chains)
+            .flat_map(|chain| chain.path.iter());
+        for (_, _, from, to) in steps {
+            let edge = (graph.node(from), graph.node(to));
+            if !graph.edges.contains(&edge) {
+                graph.edges.push(edge);
+            }
+        }
+        graph
+    }
+
+    fn node(&mut self, name: &str) -> usize {
+        match self.nodes.iter().position(|node| node == name) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(name.to_string());
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+/// A Mermaid flowchart in a fenced block, ready to paste into markdown
+pub fn mermaid(focus_data: &FocusedAnalysisData) -> String {
+    let graph = CallEdges::collect(focus_data);
+    let mut lines = vec!["```mermaid".to_string(), "flowchart LR".to_string()];
+    for (index, name) in graph.nodes.iter().enumerate() {
+        lines.push(format!(
+            "    n{}[\"{}\"]",
+            index,
+            name.replace('"', "#quot;")
+        ));
+    }
+    for (from, to) in &graph.edges {
+        lines.push(format!("    n{} --> n{}", from, to));
+    }
+    lines.push("    classDef focus stroke-width:3px".to_string());
+    lines.push("    class n0 focus".to_string());
+    lines.push("```".to_string());
+    lines.join("\n")
+}
+
+/// A Graphviz DOT digraph
+pub fn dot(focus_data: &FocusedAnalysisData) -> String {
+    let graph = CallEdges::collect(focus_data);
+    let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut lines = vec!["digraph calls {".to_string(), "    rankdir=LR;".to_string()];
+    lines.push(format!("    {} [style=bold];", quote(&graph.nodes[0])));
+    for (from, to) in &graph.edges {
+        lines.push(format!(
+            "    {} -> {};",
+            quote(&graph.nodes[*from]),
+            quote(&graph.nodes[*to])
+        ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
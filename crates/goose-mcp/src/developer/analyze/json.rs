@@ -0,0 +1,110 @@
+//! Structured JSON output for the analyze tool, selected with `output_format: "json"`.
+
+use crate::developer::analyze::types::{
+    AnalysisMode, AnalysisResult, CallChain, EntryType, FocusedAnalysisData,
+};
+use crate::developer::lang;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn relative(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// A single file analyzed in structure or semantic mode
+pub fn file_analysis(path: &Path, result: &AnalysisResult, mode: &AnalysisMode) -> Value {
+    json!({
+        "mode": mode.as_str(),
+        "path": path.to_string_lossy(),
+        "language": lang::get_language_identifier(path),
+        "analysis": result,
+    })
+}
+
+/// Per-file counts for a directory analyzed in structure mode
+pub fn directory_structure(
+    base_path: &Path,
+    results: &[(PathBuf, EntryType)],
+    max_depth: u32,
+) -> Value {
+    let mut files: Vec<(&PathBuf, &AnalysisResult)> = results
+        .iter()
+        .filter_map(|(path, entry)| match entry {
+            EntryType::File(result) => Some((path, result)),
+            _ => None,
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(b.0));
+
+    let entries: Vec<Value> = files
+        .iter()
+        .map(|(path, result)| {
+            json!({
+                "path": relative(base_path, path),
+                "language": lang::get_language_identifier(path),
+                "line_count": result.line_count,
+                "function_count": result.function_count,
+                "class_count": result.class_count,
+                "import_count": result.import_count,
+                "main_line": result.main_line,
+            })
+        })
+        .collect();
+
+    json!({
+        "mode": AnalysisMode::Structure.as_str(),
+        "path": base_path.to_string_lossy(),
+        "max_depth": max_depth,
+        "summary": {
+            "files": files.len(),
+            "lines": files.iter().map(|(_, r)| r.line_count).sum::<usize>(),
+            "functions": files.iter().map(|(_, r)| r.function_count).sum::<usize>(),
+            "classes": files.iter().map(|(_, r)| r.class_count).sum::<usize>(),
+        },
+        "files": entries,
+    })
+}
+
+fn chains(chains: &[CallChain]) -> Vec<Value> {
+    chains
+        .iter()
+        .map(|chain| {
+            Value::Array(
+                chain
+                    .path
+                    .iter()
+                    .map(|(file, line, from, to)| {
+                        json!({
+                            "file": file.to_string_lossy(),
+                            "line": line,
+                            "from": from,
+                            "to": to,
+                        })
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Definitions and call chains of the focus symbol
+pub fn focused(focus_data: &FocusedAnalysisData) -> Value {
+    let definitions: Vec<Value> = focus_data
+        .definitions
+        .iter()
+        .map(|(file, line)| json!({ "file": file.to_string_lossy(), "line": line }))
+        .collect();
+
+    json!({
+        "mode": AnalysisMode::Focused.as_str(),
+        "symbol": focus_data.focus_symbol,
+        "follow_depth": focus_data.follow_depth,
+        "files_analyzed": focus_data.files_analyzed.len(),
+        "definitions": definitions,
+        "incoming_chains": chains(focus_data.incoming_chains),
+        "outgoing_chains": chains(focus_data.outgoing_chains),
+    })
+}
@@ -1,6 +1,8 @@
 pub mod cache;
+pub mod diagram;
 pub mod formatter;
 pub mod graph;
+pub mod json;
 pub mod languages;
 pub mod parser;
 pub mod traversal;
@@ -10,7 +12,7 @@ pub mod types;
 mod tests;
 
 use ignore::gitignore::Gitignore;
-use rmcp::model::{CallToolResult, ErrorCode, ErrorData};
+use rmcp::model::{CallToolResult, Content, ErrorCode, ErrorData};
 use std::path::{Path, PathBuf};
 
 use crate::developer::lang;
@@ -20,7 +22,9 @@ use self::formatter::Formatter;
 use self::graph::CallGraph;
 use self::parser::{ElementExtractor, ParserManager};
 use self::traversal::FileTraverser;
-use self::types::{AnalysisMode, AnalysisResult, AnalyzeParams, FocusedAnalysisData};
+use self::types::{
+    AnalysisMode, AnalysisResult, AnalyzeParams, CallChain, EntryType, FocusedAnalysisData,
+};
 
 /// Helper to safely lock a mutex with poison recovery
 /// The recovery function is called on the mutex contents if the lock was poisoned
@@ -69,7 +73,10 @@ impl CodeAnalyzer {
     ) -> Result<CallToolResult, ErrorData> {
         tracing::info!("Starting analysis of {:?} with params {:?}", path, params);
 
-        let traverser = FileTraverser::new(ignore_patterns);
+        let output_format = OutputFormat::parse(params.output_format.as_deref())?;
+
+        let traverser = FileTraverser::new(ignore_patterns)
+            .with_depth_overrides(&path, &params.depth_overrides);
 
         traverser.validate_path(&path)?;
 
@@ -77,6 +84,14 @@ impl CodeAnalyzer {
 
         tracing::debug!("Using analysis mode: {:?}", mode);
 
+        match output_format {
+            OutputFormat::Text => {}
+            OutputFormat::Json => return self.analyze_json(&path, &params, &traverser, mode),
+            OutputFormat::Mermaid | OutputFormat::Dot => {
+                return self.analyze_diagram(&path, &params, &traverser, mode, output_format)
+            }
+        }
+
         let mut output = match mode {
             AnalysisMode::Focused => self.analyze_focused(&path, &params, &traverser)?,
             AnalysisMode::Semantic => {
@@ -104,6 +119,82 @@ impl CodeAnalyzer {
             }
         }
 
+        if let Some(warning) = Self::large_output_warning(&output, &path, &params) {
+            return Ok(CallToolResult::success(vec![Content::text(warning)]));
+        }
+
+        tracing::info!("Analysis complete");
+        Ok(CallToolResult::success(Formatter::format_results(output)))
+    }
+
+    /// Builds the structured form of the analysis for `output_format: "json"`.
+    fn analyze_json(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+        mode: AnalysisMode,
+    ) -> Result<CallToolResult, ErrorData> {
+        let value = match mode {
+            AnalysisMode::Focused => {
+                let trace = self.trace_focus(path, params, traverser)?;
+                json::focused(&trace.data(params))
+            }
+            _ if path.is_file() => {
+                let result = self.analyze_file(path, &mode, params)?;
+                json::file_analysis(path, &result, &mode)
+            }
+            _ => {
+                let results = self.collect_directory(path, params, traverser, &mode)?;
+                json::directory_structure(path, &results, params.max_depth)
+            }
+        };
+
+        let pretty = serde_json::to_string_pretty(&value)
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        if let Some(warning) = Self::large_output_warning(&pretty, path, params) {
+            return Ok(CallToolResult::success(vec![Content::text(warning)]));
+        }
+
+        tracing::info!("Analysis complete");
+        Ok(CallToolResult::success(vec![Content::json(value)?]))
+    }
+
+    /// Renders the focus symbol's call graph for `output_format: "mermaid"` or `"dot"`.
+    fn analyze_diagram(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+        mode: AnalysisMode,
+        output_format: OutputFormat,
+    ) -> Result<CallToolResult, ErrorData> {
+        if mode != AnalysisMode::Focused {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "output_format '{}' renders a call graph and requires the 'focus' parameter",
+                    params.output_format.as_deref().unwrap_or_default()
+                ),
+                None,
+            ));
+        }
+
+        let trace = self.trace_focus(path, params, traverser)?;
+        let output = match output_format {
+            OutputFormat::Mermaid => diagram::mermaid(&trace.data(params)),
+            _ => diagram::dot(&trace.data(params)),
+        };
+        if let Some(warning) = Self::large_output_warning(&output, path, params) {
+            return Ok(CallToolResult::success(vec![Content::text(warning)]));
+        }
+
+        tracing::info!("Analysis complete");
+        Ok(CallToolResult::success(Formatter::format_results(output)))
+    }
+
+    /// The warning returned instead of `output` when it is too long and `force` isn't set
+    fn large_output_warning(output: &str, path: &Path, params: &AnalyzeParams) -> Option<String> {
         const OUTPUT_LIMIT: usize = 1000;
         if !params.force {
             let line_count = output.lines().count();
@@ -128,14 +219,10 @@ impl CodeAnalyzer {
                         String::new()
                     }
                 );
-                return Ok(CallToolResult::success(vec![rmcp::model::Content::text(
-                    warning,
-                )]));
+                return Some(warning);
             }
         }
-
-        tracing::info!("Analysis complete");
-        Ok(CallToolResult::success(Formatter::format_results(output)))
+        None
     }
 
     fn determine_mode(&self, params: &AnalyzeParams, path: &Path) -> AnalysisMode {
@@ -239,11 +326,7 @@ impl CodeAnalyzer {
     ) -> Result<String, ErrorData> {
         tracing::debug!("Analyzing directory {:?} in {:?} mode", path, mode);
 
-        let mode = *mode;
-
-        let results = traverser.collect_directory_results(path, params.max_depth, |file_path| {
-            self.analyze_file(file_path, &mode, params)
-        })?;
+        let results = self.collect_directory(path, params, traverser, mode)?;
 
         Ok(Formatter::format_directory_structure(
             path,
@@ -252,12 +335,44 @@ impl CodeAnalyzer {
         ))
     }
 
+    fn collect_directory(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+        mode: &AnalysisMode,
+    ) -> Result<Vec<(PathBuf, EntryType)>, ErrorData> {
+        let mode = *mode;
+        traverser.collect_directory_results(path, params.max_depth, |file_path| {
+            self.analyze_file(file_path, &mode, params)
+        })
+    }
+
     fn analyze_focused(
         &self,
         path: &Path,
         params: &AnalyzeParams,
         traverser: &FileTraverser<'_>,
     ) -> Result<String, ErrorData> {
+        let trace = self.trace_focus(path, params, traverser)?;
+        let mut output = Formatter::format_focused_output(&trace.data(params));
+
+        if path.is_file() {
+            let hint = "NOTE: Focus mode works best with directory paths. \
+                        Use a parent directory in the path for cross-file analysis.\n\n";
+            output = format!("{}{}", hint, output);
+        }
+
+        Ok(output)
+    }
+
+    /// Finds the definitions and call chains of the focus symbol.
+    fn trace_focus(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+    ) -> Result<FocusTrace, ErrorData> {
         let focus_symbol = params.focus.as_ref().ok_or_else(|| {
             ErrorData::new(
                 ErrorCode::INVALID_PARAMS,
@@ -310,23 +425,60 @@ impl CodeAnalyzer {
             .cloned()
             .unwrap_or_default();
 
-        let focus_data = FocusedAnalysisData {
-            focus_symbol,
-            follow_depth: params.follow_depth,
-            files_analyzed: &files_to_analyze,
-            definitions: &definitions,
-            incoming_chains: &incoming_chains,
-            outgoing_chains: &outgoing_chains,
-        };
+        Ok(FocusTrace {
+            files_analyzed: files_to_analyze,
+            definitions,
+            incoming_chains,
+            outgoing_chains,
+        })
+    }
+}
 
-        let mut output = Formatter::format_focused_output(&focus_data);
+/// How the analysis is rendered, from `AnalyzeParams::output_format`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Mermaid,
+    Dot,
+}
 
-        if path.is_file() {
-            let hint = "NOTE: Focus mode works best with directory paths. \
-                        Use a parent directory in the path for cross-file analysis.\n\n";
-            output = format!("{}{}", hint, output);
+impl OutputFormat {
+    fn parse(format: Option<&str>) -> Result<Self, ErrorData> {
+        match format {
+            None | Some("text") => Ok(Self::Text),
+            Some("json") => Ok(Self::Json),
+            Some("mermaid") => Ok(Self::Mermaid),
+            Some("dot") => Ok(Self::Dot),
+            Some(other) => Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Unsupported output_format '{}'. Use \"text\", \"json\", \"mermaid\" or \"dot\"",
+                    other
+                ),
+                None,
+            )),
         }
+    }
+}
 
-        Ok(output)
+/// Results of tracing the focus symbol through the call graph
+struct FocusTrace {
+    files_analyzed: Vec<PathBuf>,
+    definitions: Vec<(PathBuf, usize)>,
+    incoming_chains: Vec<CallChain>,
+    outgoing_chains: Vec<CallChain>,
+}
+
+impl FocusTrace {
+    fn data<'a>(&'a self, params: &'a AnalyzeParams) -> FocusedAnalysisData<'a> {
+        FocusedAnalysisData {
+            focus_symbol: params.focus.as_deref().unwrap_or_default(),
+            follow_depth: params.follow_depth,
+            files_analyzed: &self.files_analyzed,
+            definitions: &self.definitions,
+            incoming_chains: &self.incoming_chains,
+            outgoing_chains: &self.outgoing_chains,
+        }
     }
 }
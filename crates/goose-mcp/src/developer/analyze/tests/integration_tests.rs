@@ -2,6 +2,7 @@
 
 use crate::developer::analyze::tests::fixtures::create_test_gitignore;
 use crate::developer::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use std::collections::HashMap;
 use std::fs;
 use tempfile::TempDir;
 
@@ -17,8 +18,10 @@ fn test_analyze_python_file() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -46,8 +49,10 @@ fn test_analyze_directory() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -83,8 +88,10 @@ fn test_focused_analysis() {
         focus: Some("helper".to_string()),
         follow_depth: 1,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -112,8 +119,10 @@ fn test_analyze_with_cache() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -144,8 +153,10 @@ fn test_analyze_unsupported_file() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -163,8 +174,10 @@ fn test_analyze_nonexistent_path() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -188,8 +201,10 @@ fn test_focused_without_symbol() {
         focus: Some("nonexistent_symbol".to_string()),
         follow_depth: 1,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -226,8 +241,10 @@ fn test_nested_directory_analysis() {
         focus: None,
         follow_depth: 2,
         max_depth: 3, // Increase max_depth to ensure we reach nested files
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false,
+        output_format: None,
     };
 
     let ignore = create_test_gitignore();
@@ -242,3 +259,125 @@ fn test_nested_directory_analysis() {
         assert!(text_content.text.contains("src"));
     }
 }
+
+#[test]
+fn test_analyze_json_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+    fs::write(
+        dir_path.join("app.py"),
+        "def main():\n    helper()\n\ndef helper():\n    pass",
+    )
+    .unwrap();
+    fs::write(dir_path.join("lib.rs"), "fn util() {}").unwrap();
+
+    let analyzer = CodeAnalyzer::new();
+    let ignore = create_test_gitignore();
+    let params = |path: &std::path::Path, focus: Option<&str>, format: &str| AnalyzeParams {
+        path: path.to_string_lossy().to_string(),
+        focus: focus.map(str::to_string),
+        follow_depth: 1,
+        max_depth: 3,
+        depth_overrides: HashMap::new(),
+        ast_recursion_limit: None,
+        force: false,
+        output_format: Some(format.to_string()),
+    };
+    let as_json = |result: rmcp::model::CallToolResult| -> serde_json::Value {
+        serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+    };
+
+    let result = analyzer
+        .analyze(
+            params(dir_path, None, "json"),
+            dir_path.to_path_buf(),
+            &ignore,
+        )
+        .unwrap();
+    let value = as_json(result);
+    assert_eq!(value["mode"], "structure");
+    assert_eq!(value["summary"]["files"], 2);
+    assert_eq!(value["files"][0]["path"], "app.py");
+    assert_eq!(value["files"][0]["function_count"], 2);
+
+    let file_path = dir_path.join("app.py");
+    let result = analyzer
+        .analyze(params(&file_path, None, "json"), file_path.clone(), &ignore)
+        .unwrap();
+    let value = as_json(result);
+    assert_eq!(value["mode"], "semantic");
+    assert_eq!(value["analysis"]["functions"][1]["name"], "helper");
+
+    let result = analyzer
+        .analyze(
+            params(dir_path, Some("helper"), "json"),
+            dir_path.to_path_buf(),
+            &ignore,
+        )
+        .unwrap();
+    let value = as_json(result);
+    assert_eq!(value["symbol"], "helper");
+    assert_eq!(value["definitions"][0]["line"], 4);
+    assert_eq!(value["incoming_chains"][0][0]["from"], "main");
+
+    let err = analyzer
+        .analyze(
+            params(dir_path, None, "yaml"),
+            dir_path.to_path_buf(),
+            &ignore,
+        )
+        .unwrap_err();
+    assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+}
+
+#[test]
+fn test_analyze_call_graph_diagrams() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+    fs::write(
+        dir_path.join("app.py"),
+        "def main():\n    helper()\n\ndef helper():\n    log()\n\ndef log():\n    pass",
+    )
+    .unwrap();
+
+    let analyzer = CodeAnalyzer::new();
+    let ignore = create_test_gitignore();
+    let analyze = |focus: Option<&str>, format: &str| {
+        analyzer.analyze(
+            AnalyzeParams {
+                path: dir_path.to_string_lossy().to_string(),
+                focus: focus.map(str::to_string),
+                follow_depth: 2,
+                max_depth: 3,
+                depth_overrides: HashMap::new(),
+                ast_recursion_limit: None,
+                force: false,
+                output_format: Some(format.to_string()),
+            },
+            dir_path.to_path_buf(),
+            &ignore,
+        )
+    };
+    let text =
+        |result: rmcp::model::CallToolResult| result.content[0].as_text().unwrap().text.clone();
+
+    let mermaid = text(analyze(Some("helper"), "mermaid").unwrap());
+    assert!(
+        mermaid.starts_with("```mermaid\nflowchart LR\n"),
+        "{}",
+        mermaid
+    );
+    assert!(mermaid.contains("    n0[\"helper\"]"), "{}", mermaid);
+    assert!(mermaid.contains("    n1 --> n0"), "{}", mermaid);
+    assert!(mermaid.contains("    n0 --> n2"), "{}", mermaid);
+    assert!(mermaid.contains("class n0 focus"), "{}", mermaid);
+
+    let dot = text(analyze(Some("helper"), "dot").unwrap());
+    assert!(dot.starts_with("digraph calls {"), "{}", dot);
+    assert!(dot.contains("    \"main\" -> \"helper\";"), "{}", dot);
+    assert!(dot.contains("    \"helper\" -> \"log\";"), "{}", dot);
+
+    let err = analyze(None, "mermaid").unwrap_err();
+    assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+    assert!(err.message.contains("requires the 'focus' parameter"));
+}
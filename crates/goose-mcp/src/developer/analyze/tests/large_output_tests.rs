@@ -1,5 +1,6 @@
 use super::fixtures::create_test_gitignore;
 use crate::developer::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use std::collections::HashMap;
 use std::fs;
 use tempfile::TempDir;
 
@@ -34,8 +35,10 @@ fn test_large_output_warning() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false, // Should trigger warning
+        output_format: None,
     };
 
     let result = analyzer
@@ -83,8 +86,10 @@ fn test_force_flag_bypasses_warning() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: true, // Should bypass warning
+        output_format: None,
     };
 
     let result = analyzer
@@ -121,8 +126,10 @@ fn test_small_output_no_warning() {
         focus: None,
         follow_depth: 2,
         max_depth: 3,
+        depth_overrides: HashMap::new(),
         ast_recursion_limit: None,
         force: false, // Shouldn't matter for small output
+        output_format: None,
     };
 
     let result = analyzer
@@ -3,6 +3,7 @@
 use crate::developer::analyze::tests::fixtures::create_test_gitignore;
 use crate::developer::analyze::traversal::FileTraverser;
 use ignore::gitignore::Gitignore;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
@@ -188,3 +189,32 @@ fn test_gitignore_patterns() {
     assert!(files.iter().any(|p| p.ends_with("main.py")));
     assert!(!files.iter().any(|p| p.ends_with(".log")));
 }
+
+#[test]
+fn test_depth_overrides() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+
+    let deep = dir_path.join("src").join("a").join("b");
+    fs::create_dir_all(&deep).unwrap();
+    fs::write(deep.join("deep.rs"), "").unwrap();
+
+    let vendored = dir_path.join("vendor").join("lib");
+    fs::create_dir_all(&vendored).unwrap();
+    fs::write(dir_path.join("vendor").join("top.rs"), "").unwrap();
+    fs::write(vendored.join("nested.rs"), "").unwrap();
+
+    let ignore = Gitignore::empty();
+    let overrides = HashMap::from([("vendor".to_string(), 1), ("./src/a/b".to_string(), 1)]);
+    let traverser = FileTraverser::new(&ignore).with_depth_overrides(dir_path, &overrides);
+
+    // A global depth of 1 would stop at src/, but the override for src/a/b is still reached
+    let files = traverser.collect_files_for_focused(dir_path, 1).unwrap();
+    assert!(files.iter().any(|p| p.ends_with("deep.rs")));
+    assert!(files.iter().any(|p| p.ends_with("top.rs")));
+
+    // vendor/ is skimmed even when the rest of the tree is unlimited
+    let files = traverser.collect_files_for_focused(dir_path, 0).unwrap();
+    assert!(files.iter().any(|p| p.ends_with("deep.rs")));
+    assert!(!files.iter().any(|p| p.ends_with("nested.rs")));
+}
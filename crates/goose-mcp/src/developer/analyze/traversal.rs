@@ -1,7 +1,8 @@
 use ignore::gitignore::Gitignore;
 use rayon::prelude::*;
 use rmcp::model::{ErrorCode, ErrorData};
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 
 use crate::developer::analyze::types::{AnalysisResult, EntryType};
 use crate::developer::lang;
@@ -9,12 +10,55 @@ use crate::developer::lang;
 /// Handles file system traversal with ignore patterns
 pub struct FileTraverser<'a> {
     ignore_patterns: &'a Gitignore,
+    /// Directories that use their own depth limit, counted from that directory
+    depth_overrides: Vec<(PathBuf, u32)>,
 }
 
 impl<'a> FileTraverser<'a> {
     /// Create a new file traverser with the given ignore patterns
     pub fn new(ignore_patterns: &'a Gitignore) -> Self {
-        Self { ignore_patterns }
+        Self {
+            ignore_patterns,
+            depth_overrides: Vec::new(),
+        }
+    }
+
+    /// Use a different max depth below specific directories. Relative paths are resolved
+    /// against `root`; a depth of 0 means unlimited, as with the global max depth.
+    pub fn with_depth_overrides(mut self, root: &Path, overrides: &HashMap<String, u32>) -> Self {
+        self.depth_overrides = overrides
+            .iter()
+            .map(|(dir, depth)| {
+                let dir = Path::new(dir);
+                let dir = if dir.is_absolute() {
+                    dir.to_path_buf()
+                } else {
+                    root.join(dir)
+                };
+                (normalize(&dir), *depth)
+            })
+            .collect();
+        self
+    }
+
+    fn depth_override(&self, path: &Path) -> Option<u32> {
+        let path = normalize(path);
+        self.depth_overrides
+            .iter()
+            .find(|(dir, _)| *dir == path)
+            .map(|(_, depth)| *depth)
+    }
+
+    fn leads_to_override_or_is(&self, path: &Path) -> bool {
+        self.depth_override(path).is_some() || self.leads_to_override(path)
+    }
+
+    /// True when an overridden directory lies somewhere below `path`
+    fn leads_to_override(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.depth_overrides
+            .iter()
+            .any(|(dir, _)| dir != &path && dir.starts_with(&path))
     }
 
     /// Check if a path should be ignored
@@ -93,9 +137,16 @@ impl<'a> FileTraverser<'a> {
             return Ok(files);
         }
 
+        // An overridden directory restarts the depth count with its own limit
+        let (current_depth, max_depth) = match self.depth_override(path) {
+            Some(depth) => (0, depth),
+            None => (current_depth, max_depth),
+        };
+
         // max_depth of 0 means unlimited depth
         // current_depth starts at 0, max_depth is the number of directory levels to traverse
-        if max_depth > 0 && current_depth >= max_depth {
+        let depth_exhausted = max_depth > 0 && current_depth >= max_depth;
+        if depth_exhausted && !self.leads_to_override(path) {
             tracing::trace!("Reached max depth {} at {:?}", max_depth, path);
             return Ok(files);
         }
@@ -125,6 +176,16 @@ impl<'a> FileTraverser<'a> {
                 continue;
             }
 
+            if depth_exhausted {
+                // Past the limit we only walk towards directories with their own depth
+                if entry_path.is_dir() && self.leads_to_override_or_is(&entry_path) {
+                    let mut sub_files =
+                        self.collect_files_recursive(&entry_path, current_depth + 1, max_depth)?;
+                    files.append(&mut sub_files);
+                }
+                continue;
+            }
+
             if entry_path.is_file() {
                 // Only include supported file types
                 let lang = lang::get_language_identifier(&entry_path);
@@ -169,3 +230,18 @@ impl<'a> FileTraverser<'a> {
         results
     }
 }
+
+/// Lexically removes `.` and `..` so user-supplied override paths compare equal to walked paths
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -1,5 +1,6 @@
 use rmcp::schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -16,6 +17,10 @@ pub struct AnalyzeParams {
     #[serde(default = "default_max_depth")]
     pub max_depth: u32,
 
+    /// Per-directory recursion limits, e.g. {"vendor": 1}. Paths are relative to `path`; unlisted directories use max_depth
+    #[serde(default)]
+    pub depth_overrides: HashMap<String, u32>,
+
     /// Maximum depth for recursive AST traversal (prevents stack overflow in deeply nested code)
     #[serde(default)]
     pub ast_recursion_limit: Option<usize>,
@@ -23,6 +28,12 @@ pub struct AnalyzeParams {
     /// Allow large outputs without warning (default: false)
     #[serde(default)]
     pub force: bool,
+
+    /// "text" (default) for a readable summary, "json" for a structured representation of
+    /// the files, symbols and call chains, or "mermaid"/"dot" to draw the call graph of the
+    /// focus symbol as a Mermaid flowchart or Graphviz digraph
+    #[serde(default)]
+    pub output_format: Option<String>,
 }
 
 fn default_follow_depth() -> u32 {
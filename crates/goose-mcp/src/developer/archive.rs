@@ -0,0 +1,362 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use rmcp::model::{ErrorCode, ErrorData};
+use rmcp::schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Entries listed before the listing is cut short
+const MAX_LISTED_ENTRIES: usize = 1_000;
+
+/// Entries larger than this are not read into context
+const MAX_ENTRY_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Parameters for the read_archive tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReadArchiveParams {
+    /// Path to a .zip, .tar, .tar.gz/.tgz or .gz file
+    pub path: String,
+
+    /// Optional: name of the entry to read, as shown in the listing. Lists the entries when omitted.
+    pub entry: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    Gz,
+}
+
+impl ArchiveKind {
+    fn detect(path: &Path) -> Result<Self, ErrorData> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else if name.ends_with(".tar") {
+            Ok(Self::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if name.ends_with(".gz") {
+            Ok(Self::Gz)
+        } else {
+            Err(invalid_params(format!(
+                "'{}' is not a supported archive. Supported formats are .zip, .tar, .tar.gz, .tgz and .gz",
+                path.display()
+            )))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn internal_error(path: &Path, e: impl std::fmt::Display) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!("Failed to read archive '{}': {}", path.display(), e),
+        None,
+    )
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, ErrorData> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| internal_error(path, e))
+}
+
+/// The name a plain `.gz` file decompresses to
+fn gz_entry_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Uncompressed size of a plain `.gz` file. Only decompresses up to [`MAX_ENTRY_BYTES`]; past
+/// that it falls back to the size recorded in the gzip trailer, which wraps at 4 GiB.
+fn gz_entry_size(path: &Path) -> Result<u64, ErrorData> {
+    let size = std::io::copy(
+        &mut GzDecoder::new(open(path)?).take(MAX_ENTRY_BYTES + 1),
+        &mut std::io::sink(),
+    )
+    .map_err(|e| internal_error(path, e))?;
+    if size <= MAX_ENTRY_BYTES {
+        return Ok(size);
+    }
+
+    let mut file = open(path)?;
+    let mut trailer = [0u8; 4];
+    file.seek(SeekFrom::End(-4))
+        .and_then(|_| file.read_exact(&mut trailer))
+        .map_err(|e| internal_error(path, e))?;
+    Ok(u64::from(u32::from_le_bytes(trailer)).max(size))
+}
+
+fn tar_archive(path: &Path, kind: ArchiveKind) -> Result<tar::Archive<Box<dyn Read>>, ErrorData> {
+    let file = open(path)?;
+    let reader: Box<dyn Read> = if kind == ArchiveKind::TarGz {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// Lists the entries of the archive at `path`.
+pub fn list_entries(path: &Path) -> Result<Vec<ArchiveEntry>, ErrorData> {
+    let mut entries = Vec::new();
+    match ArchiveKind::detect(path)? {
+        ArchiveKind::Zip => {
+            let mut archive =
+                zip::ZipArchive::new(open(path)?).map_err(|e| internal_error(path, e))?;
+            for index in 0..archive.len() {
+                let entry = archive
+                    .by_index(index)
+                    .map_err(|e| internal_error(path, e))?;
+                entries.push(ArchiveEntry {
+                    name: entry.name().to_string(),
+                    size: entry.size(),
+                    is_dir: entry.is_dir(),
+                });
+            }
+        }
+        kind @ (ArchiveKind::Tar | ArchiveKind::TarGz) => {
+            let mut archive = tar_archive(path, kind)?;
+            for entry in archive.entries().map_err(|e| internal_error(path, e))? {
+                let entry = entry.map_err(|e| internal_error(path, e))?;
+                let name = entry.path().map_err(|e| internal_error(path, e))?;
+                entries.push(ArchiveEntry {
+                    name: name.to_string_lossy().to_string(),
+                    size: entry.size(),
+                    is_dir: entry.header().entry_type().is_dir(),
+                });
+            }
+        }
+        ArchiveKind::Gz => {
+            entries.push(ArchiveEntry {
+                name: gz_entry_name(path),
+                size: gz_entry_size(path)?,
+                is_dir: false,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Reads one entry of the archive at `path` as text, without extracting anything to disk.
+pub fn read_entry(path: &Path, name: &str) -> Result<String, ErrorData> {
+    let too_large = || {
+        invalid_params(format!(
+            "Entry '{}' is larger than {}MB and cannot be read into context",
+            name,
+            MAX_ENTRY_BYTES / (1024 * 1024)
+        ))
+    };
+    let not_found = || {
+        invalid_params(format!(
+            "No entry named '{}' in '{}'. Call read_archive without an entry to list them.",
+            name,
+            path.display()
+        ))
+    };
+
+    let mut bytes = Vec::new();
+    match ArchiveKind::detect(path)? {
+        ArchiveKind::Zip => {
+            let mut archive =
+                zip::ZipArchive::new(open(path)?).map_err(|e| internal_error(path, e))?;
+            let entry = match archive.by_name(name) {
+                Ok(entry) => entry,
+                Err(zip::result::ZipError::FileNotFound) => return Err(not_found()),
+                Err(e) => return Err(internal_error(path, e)),
+            };
+            if entry.size() > MAX_ENTRY_BYTES {
+                return Err(too_large());
+            }
+            entry
+                .take(MAX_ENTRY_BYTES)
+                .read_to_end(&mut bytes)
+                .map_err(|e| internal_error(path, e))?;
+        }
+        kind @ (ArchiveKind::Tar | ArchiveKind::TarGz) => {
+            let mut archive = tar_archive(path, kind)?;
+            let mut found = false;
+            for entry in archive.entries().map_err(|e| internal_error(path, e))? {
+                let entry = entry.map_err(|e| internal_error(path, e))?;
+                let entry_name = entry.path().map_err(|e| internal_error(path, e))?;
+                if entry_name.to_string_lossy() != name {
+                    continue;
+                }
+                if entry.size() > MAX_ENTRY_BYTES {
+                    return Err(too_large());
+                }
+                entry
+                    .take(MAX_ENTRY_BYTES)
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| internal_error(path, e))?;
+                found = true;
+                break;
+            }
+            if !found {
+                return Err(not_found());
+            }
+        }
+        ArchiveKind::Gz => {
+            if name != gz_entry_name(path) {
+                return Err(not_found());
+            }
+            GzDecoder::new(open(path)?)
+                .take(MAX_ENTRY_BYTES + 1)
+                .read_to_end(&mut bytes)
+                .map_err(|e| internal_error(path, e))?;
+            if bytes.len() as u64 > MAX_ENTRY_BYTES {
+                return Err(too_large());
+            }
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| {
+        invalid_params(format!(
+            "Entry '{}' is not a text file and cannot be read into context",
+            name
+        ))
+    })
+}
+
+/// Formats a listing of `entries` for the model.
+pub fn describe_entries(path: &Path, entries: &[ArchiveEntry]) -> String {
+    let mut text = format!("{} ({} entries):\n", path.display(), entries.len());
+    for entry in entries.iter().take(MAX_LISTED_ENTRIES) {
+        if entry.is_dir {
+            text.push_str(&format!("{}\n", entry.name));
+        } else {
+            text.push_str(&format!("{} ({} bytes)\n", entry.name, entry.size));
+        }
+    }
+    if entries.len() > MAX_LISTED_ENTRIES {
+        text.push_str(&format!(
+            "... {} more entries not shown\n",
+            entries.len() - MAX_LISTED_ENTRIES
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_zip_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::FileOptions::default();
+        writer.add_directory("docs/", options).unwrap();
+        writer.start_file("docs/readme.txt", options).unwrap();
+        writer.write_all(b"hello from zip").unwrap();
+        writer.start_file("logo.bin", options).unwrap();
+        writer.write_all(&[0xff, 0xfe, 0x00]).unwrap();
+        writer.finish().unwrap();
+
+        let entries = list_entries(&path).unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            vec!["docs/", "docs/readme.txt", "logo.bin"]
+        );
+        assert!(entries[0].is_dir);
+        assert_eq!(
+            read_entry(&path, "docs/readme.txt").unwrap(),
+            "hello from zip"
+        );
+        assert!(read_entry(&path, "missing.txt")
+            .unwrap_err()
+            .message
+            .contains("No entry named"));
+        assert!(read_entry(&path, "logo.bin")
+            .unwrap_err()
+            .message
+            .contains("not a text file"));
+    }
+
+    #[test]
+    fn test_tar_gz_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let contents = b"version = 1\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "release/config.toml", &contents[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let entries = list_entries(&path).unwrap();
+        assert_eq!(
+            entries,
+            vec![ArchiveEntry {
+                name: "release/config.toml".to_string(),
+                size: contents.len() as u64,
+                is_dir: false,
+            }]
+        );
+        assert_eq!(
+            read_entry(&path, "release/config.toml").unwrap(),
+            "version = 1\n"
+        );
+    }
+
+    #[test]
+    fn test_gz_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.log.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"started\n").unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(list_entries(&path).unwrap()[0].name, "server.log");
+        assert_eq!(read_entry(&path, "server.log").unwrap(), "started\n");
+        assert!(list_entries(&dir.path().join("notes.txt")).is_err());
+    }
+
+    #[test]
+    fn test_large_gz_file_size_comes_from_trailer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.sql.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let size = MAX_ENTRY_BYTES as usize * 3;
+        encoder.write_all(&vec![b'x'; size]).unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(list_entries(&path).unwrap()[0].size, size as u64);
+        assert!(read_entry(&path, "dump.sql").is_err());
+    }
+}
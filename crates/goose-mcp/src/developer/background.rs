@@ -0,0 +1,211 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Child;
+use tokio_stream::{wrappers::SplitStream, StreamExt as _};
+use tokio_util::sync::CancellationToken;
+
+use super::shell::{kill_process_group, strip_ansi_codes};
+
+/// Unread output kept per job; older output is dropped once this is exceeded.
+const MAX_PENDING_CHARS: usize = 400_000;
+
+/// How long an exited job waits for the rest of its output. A child that outlives the
+/// job can hold the pipes open, so this is bounded.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct JobState {
+    /// Output produced since the last poll
+    pending: String,
+    /// Bytes dropped from `pending` because nobody polled in time
+    dropped: usize,
+    /// Set once the process has exited or been killed
+    status: Option<String>,
+}
+
+impl JobState {
+    fn push(&mut self, text: &str) {
+        self.pending.push_str(text);
+        if self.pending.len() > MAX_PENDING_CHARS {
+            let mut cut = self.pending.len() - MAX_PENDING_CHARS;
+            while !self.pending.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.pending.drain(..cut);
+            self.dropped += cut;
+        }
+    }
+}
+
+/// A shell command registered in `running_processes`, keyed by request or job id.
+#[derive(Clone)]
+pub struct TrackedProcess {
+    pub command: String,
+    pub token: CancellationToken,
+    pub started: Instant,
+}
+
+impl TrackedProcess {
+    pub fn new(command: &str, token: CancellationToken) -> Self {
+        Self {
+            command: command.to_string(),
+            token,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn describe(&self, id: &str) -> String {
+        format!(
+            "{} (running for {}): {}",
+            id,
+            format_elapsed(self.started.elapsed()),
+            self.command
+        )
+    }
+
+    /// Like `describe`, but for a job whose final status has not been polled yet.
+    pub fn describe_finished(&self, id: &str, status: &str) -> String {
+        format!("{} ({}): {}", id, status, self.command)
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
+
+/// Output collected from a background job since it was last polled.
+pub struct JobPoll {
+    pub output: String,
+    pub dropped: usize,
+    /// `None` while the job is still running
+    pub status: Option<String>,
+}
+
+/// A shell command started with `shell_background`. The child is owned by a task
+/// that buffers its output and kills it when the job's token is cancelled.
+#[derive(Clone)]
+pub struct BackgroundJob {
+    pub command: String,
+    state: Arc<Mutex<JobState>>,
+}
+
+impl BackgroundJob {
+    pub fn spawn(command: String, mut child: Child, token: CancellationToken) -> Self {
+        let state = Arc::new(Mutex::new(JobState::default()));
+
+        let stdout = child
+            .stdout
+            .take()
+            .map(|out| SplitStream::new(BufReader::new(out).split(b'\n')).map(|line| line.ok()));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|err| SplitStream::new(BufReader::new(err).split(b'\n')).map(|line| line.ok()));
+        let reader_state = state.clone();
+        let reader = tokio::spawn(async move {
+            let (Some(stdout), Some(stderr)) = (stdout, stderr) else {
+                return;
+            };
+            let mut merged = stdout.merge(stderr);
+            while let Some(Some(mut line)) = merged.next().await {
+                line.push(b'\n');
+                let line = String::from_utf8_lossy(&line);
+                if let Ok(mut state) = reader_state.lock() {
+                    state.push(&strip_ansi_codes(&line));
+                }
+            }
+        });
+
+        let waiter_state = state.clone();
+        tokio::spawn(async move {
+            let pid = child.id();
+            let status = tokio::select! {
+                status = child.wait() => match status {
+                    Ok(status) => match status.code() {
+                        Some(code) => format!("exited with code {}", code),
+                        None => "terminated by a signal".to_string(),
+                    },
+                    Err(e) => format!("failed: {}", e),
+                },
+                _ = token.cancelled() => {
+                    if let Err(e) = kill_process_group(&mut child, pid).await {
+                        tracing::error!("Failed to kill background job: {}", e);
+                    }
+                    "killed".to_string()
+                }
+            };
+            // Publish the status only after the remaining output is buffered, so the poll
+            // that reports the exit also returns the last lines
+            let _ = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, reader).await;
+            if let Ok(mut state) = waiter_state.lock() {
+                state.status = Some(status);
+            }
+        });
+
+        Self { command, state }
+    }
+
+    /// Takes the output produced since the previous poll.
+    pub fn poll(&self) -> JobPoll {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        JobPoll {
+            output: std::mem::take(&mut state.pending),
+            dropped: std::mem::take(&mut state.dropped),
+            status: state.status.clone(),
+        }
+    }
+
+    /// How the job ended, without consuming its pending output. `None` while running.
+    pub fn status(&self) -> Option<String> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.status.clone()
+    }
+}
+
+impl JobPoll {
+    pub fn describe(&self, job_id: &str, command: &str) -> String {
+        let status = self.status.as_deref().unwrap_or("running");
+        let mut text = format!("Job {} ({}): {}\n\n", job_id, command, status);
+        if self.dropped > 0 {
+            text.push_str(&format!(
+                "[{} bytes of earlier output were dropped]\n",
+                self.dropped
+            ));
+        }
+        if self.output.is_empty() {
+            text.push_str("(no new output)");
+        } else {
+            text.push_str(&self.output);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(4_500)), "4s");
+        assert_eq!(format_elapsed(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_elapsed(Duration::from_secs(3_725)), "1h 02m 05s");
+    }
+
+    #[test]
+    fn test_job_state_drops_oldest_output() {
+        let mut state = JobState::default();
+        state.push(&"a".repeat(MAX_PENDING_CHARS));
+        state.push("bcd");
+        assert_eq!(state.pending.len(), MAX_PENDING_CHARS);
+        assert_eq!(state.dropped, 3);
+        assert!(state.pending.ends_with("abcd"));
+    }
+}
@@ -0,0 +1,81 @@
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+use super::shell::{expand_path, working_dir};
+
+/// Comma-separated files or directories the developer extension may modify without asking.
+/// While it is set, writes anywhere else need explicit approval.
+pub const GOOSE_CHANGELIST: &str = "GOOSE_CHANGELIST";
+
+/// The files a scoped task is expected to touch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changelist {
+    paths: Vec<PathBuf>,
+}
+
+impl Changelist {
+    /// Relative entries are resolved against `base`. Entries may be files or directories.
+    pub fn new<I, S>(entries: I, base: &Path) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let paths = entries
+            .into_iter()
+            .map(|entry| normalize(&base.join(expand_path(entry.as_ref()))))
+            .collect();
+        Self { paths }
+    }
+
+    /// Reads `GOOSE_CHANGELIST`, resolving relative entries against the working directory.
+    pub fn from_env() -> Option<Self> {
+        let raw = env::var(GOOSE_CHANGELIST).ok()?;
+        let entries: Vec<&str> = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        (!entries.is_empty()).then(|| Self::new(entries, &working_dir()))
+    }
+
+    /// Whether `path` is a listed file or lies under a listed directory.
+    pub fn contains(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.paths.iter().any(|entry| path.starts_with(entry))
+    }
+}
+
+/// Drops `.` and folds `..` without touching the filesystem, since new files don't exist yet.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_files_and_directories() {
+        let base = Path::new("/repo");
+        let changelist = Changelist::new(["src/lib.rs", "./docs/", "/tmp/notes.md"], base);
+
+        assert!(changelist.contains(Path::new("/repo/src/lib.rs")));
+        assert!(changelist.contains(Path::new("/repo/src/../src/./lib.rs")));
+        assert!(changelist.contains(Path::new("/repo/docs/guide/intro.md")));
+        assert!(changelist.contains(Path::new("/tmp/notes.md")));
+
+        assert!(!changelist.contains(Path::new("/repo/src/main.rs")));
+        assert!(!changelist.contains(Path::new("/repo/src/lib.rs.bak")));
+        assert!(!changelist.contains(Path::new("/repo/docs/../Cargo.toml")));
+    }
+}
@@ -0,0 +1,37 @@
+/// Reads an on/off setting from the environment: "1", "true" or "yes" turn it on and "0",
+/// "false" or "no" turn it off, ignoring case and surrounding whitespace. Anything else,
+/// including an unset variable, gives `default`.
+pub fn env_flag(name: &str, default: bool) -> bool {
+    match std::env::var(name) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "1" | "true" | "yes" => true,
+            "0" | "false" | "no" => false,
+            _ => default,
+        },
+        Err(_) => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAG: &str = "GOOSE_TEST_ENV_FLAG";
+
+    #[test]
+    fn test_env_flag() {
+        for (value, default, expected) in [
+            (None, false, false),
+            (None, true, true),
+            (Some(" Yes "), false, true),
+            (Some("1"), false, true),
+            (Some("FALSE"), true, false),
+            (Some("no"), true, false),
+            (Some("maybe"), false, false),
+            (Some("maybe"), true, true),
+        ] {
+            let enabled = temp_env::with_var(FLAG, value, || env_flag(FLAG, default));
+            assert_eq!(enabled, expected, "{:?} with default {}", value, default);
+        }
+    }
+}
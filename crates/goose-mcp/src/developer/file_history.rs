@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use etcetera::AppStrategy;
+use serde::{Deserialize, Serialize};
+
+use super::env_flag::env_flag;
+
+/// Enables saving text editor undo history to disk so `undo_edit` works across restarts.
+pub const GOOSE_PERSIST_FILE_HISTORY: &str = "GOOSE_PERSIST_FILE_HISTORY";
+
+/// Versions kept on disk per file; older ones are discarded.
+const MAX_PERSISTED_VERSIONS: usize = 20;
+
+pub fn persist_file_history_enabled() -> bool {
+    env_flag(GOOSE_PERSIST_FILE_HISTORY, false)
+}
+
+/// The `file_history` directory under the goose config dir
+pub fn default_dir() -> Option<PathBuf> {
+    etcetera::choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.config_dir().join("file_history"))
+        .ok()
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredHistory {
+    path: PathBuf,
+    versions: Vec<String>,
+}
+
+/// On-disk copy of the editor's undo history, one JSON file per edited path.
+#[derive(Clone, Debug)]
+pub struct FileHistoryStore {
+    dir: PathBuf,
+}
+
+impl FileHistoryStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// File names are a hash of the absolute path, which is also stored inside the file
+    /// so collisions are detected rather than restoring the wrong history.
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in path.to_string_lossy().bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        self.dir.join(format!("{:016x}.json", hash))
+    }
+
+    fn read(file: &Path) -> Option<StoredHistory> {
+        let content = std::fs::read_to_string(file).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn load(&self, path: &Path) -> Vec<String> {
+        Self::read(&self.entry_path(path))
+            .filter(|stored| stored.path == path)
+            .map(|stored| stored.versions)
+            .unwrap_or_default()
+    }
+
+    /// Loads every stored history, skipping unreadable files.
+    pub fn load_all(&self) -> HashMap<PathBuf, Vec<String>> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return HashMap::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| Self::read(&entry.path()))
+            .map(|stored| (stored.path, stored.versions))
+            .collect()
+    }
+
+    /// Saves the most recent versions of `path`, or removes its entry when there are none.
+    pub fn save(&self, path: &Path, versions: &[String]) -> std::io::Result<()> {
+        let file = self.entry_path(path);
+        if versions.is_empty() {
+            return match std::fs::remove_file(&file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+
+        let start = versions.len().saturating_sub(MAX_PERSISTED_VERSIONS);
+        let stored = StoredHistory {
+            path: path.to_path_buf(),
+            versions: versions[start..].to_vec(),
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(file, serde_json::to_string(&stored)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_history_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileHistoryStore::new(dir.path().join("file_history"));
+        let path = Path::new("/work/src/main.rs");
+        assert!(store.load(path).is_empty());
+
+        let versions: Vec<String> = (0..MAX_PERSISTED_VERSIONS + 5)
+            .map(|i| format!("version {}", i))
+            .collect();
+        store.save(path, &versions).unwrap();
+        let loaded = store.load(path);
+        assert_eq!(loaded.len(), MAX_PERSISTED_VERSIONS);
+        assert_eq!(loaded.last(), versions.last());
+        assert_eq!(store.load_all().get(path), Some(&loaded));
+        assert!(store.load(Path::new("/work/src/lib.rs")).is_empty());
+
+        store.save(path, &[]).unwrap();
+        assert!(store.load(path).is_empty());
+        assert!(store.load_all().is_empty());
+    }
+}
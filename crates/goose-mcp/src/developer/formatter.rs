@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use super::env_flag::env_flag;
+
+/// Enables formatting files after `write` and `str_replace` edits.
+pub const GOOSE_AUTO_FORMAT: &str = "GOOSE_AUTO_FORMAT";
+
+/// Overrides or extends the built-in formatters, e.g. `py=ruff format,ts=biome format --write`.
+/// The file path is appended to the command.
+pub const GOOSE_FORMATTERS: &str = "GOOSE_FORMATTERS";
+
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn auto_format_enabled() -> bool {
+    env_flag(GOOSE_AUTO_FORMAT, false)
+}
+
+/// Built-in formatter command for a file extension
+fn default_formatter(extension: &str) -> Option<&'static [&'static str]> {
+    match extension {
+        "rs" => Some(&["rustfmt", "--edition", "2021"]),
+        "py" | "pyi" => Some(&["black", "--quiet"]),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "json" | "css" | "scss" | "less" | "html"
+        | "vue" | "yaml" | "yml" | "md" | "graphql" => Some(&["prettier", "--write"]),
+        _ => None,
+    }
+}
+
+/// Parses `GOOSE_FORMATTERS` entries of the form `ext=command args`
+fn configured_formatter(config: &str, extension: &str) -> Option<Vec<String>> {
+    config
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(ext, _)| ext.trim().trim_start_matches('.') == extension)
+        .map(|(_, command)| command.split_whitespace().map(str::to_string).collect())
+        .filter(|command: &Vec<String>| !command.is_empty())
+}
+
+/// Prefers a project-local prettier from the nearest `node_modules/.bin`.
+fn resolve_program(program: &str, file: &Path) -> Option<PathBuf> {
+    if program == "prettier" {
+        let local = file
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("node_modules/.bin/prettier"))
+            .find(|candidate| candidate.is_file());
+        if local.is_some() {
+            return local;
+        }
+    }
+    which::which(program).ok()
+}
+
+/// The formatter command to run on `file`, if one is configured or installed.
+fn formatter_for(file: &Path) -> Option<Vec<String>> {
+    let extension = file.extension()?.to_str()?.to_lowercase();
+    let mut command = std::env::var(GOOSE_FORMATTERS)
+        .ok()
+        .and_then(|config| configured_formatter(&config, &extension))
+        .or_else(|| {
+            default_formatter(&extension)
+                .map(|command| command.iter().map(|s| s.to_string()).collect())
+        })?;
+    let program = resolve_program(&command[0], file)?;
+    command[0] = program.to_string_lossy().to_string();
+    Some(command)
+}
+
+/// Runs the formatter for `file` in place. Returns a note for the model when the file
+/// was reformatted or the formatter failed, and `None` when there was nothing to report.
+pub async fn format_file(file: &Path) -> Option<String> {
+    run_formatter(file, formatter_for(file)?).await
+}
+
+async fn run_formatter(file: &Path, command: Vec<String>) -> Option<String> {
+    let name = Path::new(&command[0])
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| command[0].clone());
+    let before = std::fs::read(file).ok()?;
+
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]).arg(file).kill_on_drop(true);
+    if let Some(dir) = file.parent() {
+        cmd.current_dir(dir);
+    }
+
+    let output = match tokio::time::timeout(FORMAT_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Some(format!("Auto-format with {} failed to start: {}", name, e)),
+        Err(_) => {
+            return Some(format!(
+                "Auto-format with {} timed out after {}s",
+                name,
+                FORMAT_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    if !output.status.success() {
+        return Some(format!(
+            "Auto-format with {} failed, the file was left as written: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let after = std::fs::read(file).ok()?;
+    (before != after).then(|| {
+        format!(
+            "Reformatted {} with {}. View the file before making further edits to it.",
+            file.display(),
+            name
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_formatter() {
+        let config = "py=ruff format, .ts=biome format --write";
+        assert_eq!(
+            configured_formatter(config, "py"),
+            Some(vec!["ruff".to_string(), "format".to_string()])
+        );
+        assert_eq!(
+            configured_formatter(config, "ts"),
+            Some(vec![
+                "biome".to_string(),
+                "format".to_string(),
+                "--write".to_string()
+            ])
+        );
+        assert_eq!(configured_formatter(config, "rs"), None);
+        assert_eq!(configured_formatter("go=", "go"), None);
+    }
+
+    #[test]
+    fn test_default_formatter() {
+        assert_eq!(default_formatter("rs").unwrap()[0], "rustfmt");
+        assert_eq!(default_formatter("tsx").unwrap()[0], "prettier");
+        assert_eq!(default_formatter("py").unwrap()[0], "black");
+        assert!(default_formatter("txt").is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_formatter_reports_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "foo\n").unwrap();
+        let sed = || {
+            vec![
+                "sed".to_string(),
+                "-i.bak".to_string(),
+                "s/foo/bar/".to_string(),
+            ]
+        };
+
+        let note = run_formatter(&file, sed()).await.unwrap();
+        assert!(note.starts_with("Reformatted"));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "bar\n");
+        // Already formatted, so nothing to report
+        assert_eq!(run_formatter(&file, sed()).await, None);
+
+        let note = run_formatter(&file, vec!["false".to_string()])
+            .await
+            .unwrap();
+        assert!(note.contains("failed"));
+    }
+
+    #[test]
+    fn test_prefers_local_prettier() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("node_modules/.bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("prettier"), "").unwrap();
+        let file = dir.path().join("src/index.ts");
+
+        assert_eq!(
+            resolve_program("prettier", &file),
+            Some(bin.join("prettier"))
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+
+use rmcp::model::{ErrorCode, ErrorData};
+use tokio::process::Command;
+
+/// Checkpoints live under their own ref namespace so they never show up as
+/// branches or stashes and don't disturb the user's HEAD or index.
+const CHECKPOINT_REF_PREFIX: &str = "refs/goose/checkpoints/";
+
+fn tool_error(message: impl Into<String>) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message.into(), None)
+}
+
+struct GitRepo {
+    root: PathBuf,
+    /// Scratch index so snapshots and restores leave the real index untouched
+    scratch_index: tempfile::TempPath,
+}
+
+impl GitRepo {
+    async fn discover(dir: &Path) -> Result<Self, ErrorData> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(dir)
+            .output()
+            .await
+            .map_err(|e| tool_error(format!("Failed to run git: {}", e)))?;
+        if !output.status.success() {
+            return Err(tool_error(format!(
+                "'{}' is not inside a git repository, so checkpoints are unavailable",
+                dir.display()
+            )));
+        }
+
+        let root = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        let scratch_index = tempfile::NamedTempFile::new()
+            .map_err(|e| tool_error(format!("Failed to create scratch index: {}", e)))?
+            .into_temp_path();
+        // git refuses to read an empty file as an index, so start from nothing
+        let _ = std::fs::remove_file(&scratch_index);
+        Ok(Self {
+            root,
+            scratch_index,
+        })
+    }
+
+    async fn git(&self, args: &[&str]) -> Result<String, ErrorData> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.root)
+            .env("GIT_INDEX_FILE", &self.scratch_index)
+            .output()
+            .await
+            .map_err(|e| tool_error(format!("Failed to run git: {}", e)))?;
+        if !output.status.success() {
+            return Err(tool_error(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Writes the current working tree (tracked and untracked, minus gitignored files
+    /// and those `is_ignored` matches) as a tree object.
+    async fn snapshot_tree(
+        &self,
+        is_ignored: &(dyn Fn(&Path) -> bool + Sync),
+    ) -> Result<String, ErrorData> {
+        let _ = std::fs::remove_file(&self.scratch_index);
+        self.git(&["add", "-A", "."]).await?;
+        self.unstage_ignored(is_ignored).await?;
+        self.git(&["write-tree"]).await
+    }
+
+    /// Drops the paths `is_ignored` matches from the scratch index, so they are neither
+    /// recorded in a checkpoint nor written over when one is restored.
+    async fn unstage_ignored(
+        &self,
+        is_ignored: &(dyn Fn(&Path) -> bool + Sync),
+    ) -> Result<(), ErrorData> {
+        let staged = self.git(&["ls-files", "-z"]).await?;
+        let ignored: Vec<&str> = staged
+            .split('\0')
+            .filter(|path| !path.is_empty() && is_ignored(&self.root.join(path)))
+            .collect();
+        if ignored.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["update-index", "--force-remove", "--"];
+        args.extend(ignored);
+        self.git(&args).await.map(|_| ())
+    }
+
+    async fn checkpoint_names(&self) -> Result<Vec<String>, ErrorData> {
+        let refs = self
+            .git(&["for-each-ref", "--format=%(refname)", CHECKPOINT_REF_PREFIX])
+            .await?;
+        Ok(refs
+            .lines()
+            .filter_map(|r| r.strip_prefix(CHECKPOINT_REF_PREFIX))
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), ErrorData> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(tool_error(format!(
+            "Invalid checkpoint name '{}': use letters, digits, '-', '_' or '.'",
+            name
+        )))
+    }
+}
+
+/// Records the working tree of the repository containing `dir` as a checkpoint
+/// commit under `refs/goose/checkpoints/<name>`, leaving out the paths `is_ignored`
+/// matches. Returns the commit id.
+pub async fn create_checkpoint(
+    dir: &Path,
+    name: &str,
+    is_ignored: impl Fn(&Path) -> bool + Sync,
+) -> Result<String, ErrorData> {
+    validate_name(name)?;
+    let repo = GitRepo::discover(dir).await?;
+    let tree = repo.snapshot_tree(&is_ignored).await?;
+
+    let message = format!("goose checkpoint: {}", name);
+    // Checkpoints are internal bookkeeping, so don't depend on the user having a
+    // git identity configured
+    let mut args = vec![
+        "-c",
+        "user.name=goose",
+        "-c",
+        "user.email=goose@localhost",
+        "commit-tree",
+        tree.as_str(),
+        "-m",
+        message.as_str(),
+    ];
+    let head = repo
+        .git(&["rev-parse", "--verify", "--quiet", "HEAD"])
+        .await
+        .ok();
+    if let Some(head) = head.as_deref() {
+        args.extend(["-p", head]);
+    }
+    let commit = repo.git(&args).await?;
+
+    let ref_name = format!("{}{}", CHECKPOINT_REF_PREFIX, name);
+    repo.git(&["update-ref", &ref_name, &commit]).await?;
+    Ok(commit)
+}
+
+/// Rolls the working tree back to a checkpoint: files are restored to their
+/// checkpointed contents and files created since are removed. HEAD, the index and
+/// the paths `is_ignored` matches are left alone. Returns the paths that were removed.
+pub async fn restore_checkpoint(
+    dir: &Path,
+    name: &str,
+    is_ignored: impl Fn(&Path) -> bool + Sync,
+) -> Result<Vec<String>, ErrorData> {
+    validate_name(name)?;
+    let repo = GitRepo::discover(dir).await?;
+    let ref_name = format!("{}{}", CHECKPOINT_REF_PREFIX, name);
+
+    if repo
+        .git(&["rev-parse", "--verify", "--quiet", &ref_name])
+        .await
+        .is_err()
+    {
+        let available = repo.checkpoint_names().await.unwrap_or_default();
+        let hint = if available.is_empty() {
+            "No checkpoints exist yet".to_string()
+        } else {
+            format!("Available checkpoints: {}", available.join(", "))
+        };
+        return Err(tool_error(format!(
+            "Checkpoint '{}' does not exist. {}",
+            name, hint
+        )));
+    }
+
+    let current_tree = repo.snapshot_tree(&is_ignored).await?;
+    let checkpoint_tree = format!("{}^{{tree}}", ref_name);
+    let added = repo
+        .git(&[
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--diff-filter=A",
+            &checkpoint_tree,
+            &current_tree,
+        ])
+        .await?;
+
+    let _ = std::fs::remove_file(&repo.scratch_index);
+    repo.git(&["read-tree", &ref_name]).await?;
+    // A checkpoint taken before a path was ignored may still hold it
+    repo.unstage_ignored(&is_ignored).await?;
+    repo.git(&["checkout-index", "--all", "--force"]).await?;
+
+    let removed: Vec<String> = added
+        .lines()
+        .filter(|path| !is_ignored(&repo.root.join(path)))
+        .map(str::to_string)
+        .collect();
+    for path in &removed {
+        let _ = std::fs::remove_file(repo.root.join(path));
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    async fn init_repo() -> Option<tempfile::TempDir> {
+        let dir = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(dir.path())
+            .status()
+            .await
+            .ok()?;
+        status.success().then_some(dir)
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_and_restore_round_trip() {
+        let Some(dir) = init_repo().await else {
+            return;
+        };
+        let path = dir.path();
+        fs::write(path.join("main.rs"), "fn main() {}").unwrap();
+
+        create_checkpoint(path, "before-edit", |_| false)
+            .await
+            .unwrap();
+
+        fs::write(path.join("main.rs"), "broken").unwrap();
+        fs::write(path.join("scratch.txt"), "temp").unwrap();
+
+        let removed = restore_checkpoint(path, "before-edit", |_| false)
+            .await
+            .unwrap();
+        assert_eq!(removed, vec!["scratch.txt".to_string()]);
+        assert_eq!(
+            fs::read_to_string(path.join("main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(!path.join("scratch.txt").exists());
+
+        let err = restore_checkpoint(path, "missing", |_| false)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("before-edit"));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_leave_ignored_files_alone() {
+        let Some(dir) = init_repo().await else {
+            return;
+        };
+        let path = dir.path();
+        let is_ignored = |p: &Path| p.extension().is_some_and(|ext| ext == "env");
+        fs::write(path.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(path.join("app.env"), "TOKEN=old").unwrap();
+
+        create_checkpoint(path, "before-edit", is_ignored)
+            .await
+            .unwrap();
+
+        fs::write(path.join("main.rs"), "broken").unwrap();
+        fs::write(path.join("app.env"), "TOKEN=new").unwrap();
+        fs::write(path.join("local.env"), "DEBUG=1").unwrap();
+
+        let removed = restore_checkpoint(path, "before-edit", is_ignored)
+            .await
+            .unwrap();
+        assert!(removed.is_empty());
+        assert_eq!(
+            fs::read_to_string(path.join("main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(
+            fs::read_to_string(path.join("app.env")).unwrap(),
+            "TOKEN=new"
+        );
+        assert!(path.join("local.env").exists());
+    }
+
+    #[tokio::test]
+    async fn test_errors_outside_git_repo_and_on_bad_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = create_checkpoint(dir.path(), "ok", |_| false)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("not inside a git repository"));
+
+        let err = create_checkpoint(dir.path(), "../escape", |_| false)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("Invalid checkpoint name"));
+    }
+}
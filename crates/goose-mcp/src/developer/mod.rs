@@ -1,7 +1,17 @@
 pub mod analyze;
+mod archive;
+mod background;
+pub mod changelist;
 mod editor_models;
+mod env_flag;
+mod file_history;
+mod formatter;
+mod git_checkpoint;
 mod lang;
+mod ocr;
 pub mod paths;
+mod redact;
+mod search;
 mod shell;
 mod text_editor;
 
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use tokio::process::Command;
+use xcap::image::{DynamicImage, ImageFormat};
+
+const OCR_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Recognizes text in `image` with the `tesseract` CLI. Returns the reason extraction was
+/// skipped when OCR isn't available or fails.
+pub async fn extract_text(image: &DynamicImage) -> Result<String, String> {
+    let tesseract = which::which("tesseract")
+        .map_err(|_| "Text extraction was skipped: tesseract is not installed.".to_string())?;
+
+    // Decoded full-resolution copy, so OCR sees more detail than the downscaled image
+    let file = tempfile::Builder::new()
+        .suffix(".png")
+        .tempfile()
+        .map_err(|e| format!("Text extraction was skipped: {}", e))?;
+    image
+        .save_with_format(file.path(), ImageFormat::Png)
+        .map_err(|e| format!("Text extraction was skipped: {}", e))?;
+
+    let mut command = Command::new(tesseract);
+    command.arg(file.path()).arg("stdout").kill_on_drop(true);
+    let output = match tokio::time::timeout(OCR_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("Text extraction failed to start: {}", e)),
+        Err(_) => {
+            return Err(format!(
+                "Text extraction timed out after {}s",
+                OCR_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    if !output.status.success() {
+        return Err(format!(
+            "Text extraction failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(clean_ocr_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Trims trailing whitespace and collapses the runs of blank lines tesseract emits.
+fn clean_ocr_output(raw: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in raw.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_ocr_output() {
+        assert_eq!(
+            clean_ocr_output("\n\nFile  Edit \n\n\n\nBuild succeeded\n\u{c}\n"),
+            "File  Edit\n\nBuild succeeded"
+        );
+        assert_eq!(clean_ocr_output(" \n\n"), "");
+    }
+}
@@ -2,19 +2,21 @@ use anyhow::anyhow;
 use base64::Engine;
 use etcetera::AppStrategy;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
 use include_dir::{include_dir, Dir};
 use indoc::{formatdoc, indoc};
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, CancelledNotificationParam, Content, ErrorCode, ErrorData,
-        GetPromptRequestParam, GetPromptResult, Implementation, ListPromptsResult, LoggingLevel,
+        CallToolResult, CancelledNotificationParam, Content, CreateElicitationRequestParam,
+        ElicitationAction, ElicitationSchema, ErrorCode, ErrorData, GetPromptRequestParam,
+        GetPromptResult, Implementation, ListPromptsResult, LoggingLevel,
         LoggingMessageNotificationParam, PaginatedRequestParam, Prompt, PromptArgument,
         PromptMessage, PromptMessageRole, Role, ServerCapabilities, ServerInfo,
     },
     schemars::JsonSchema,
     service::{NotificationContext, RequestContext},
-    tool, tool_handler, tool_router, RoleServer, ServerHandler,
+    tool, tool_handler, tool_router, Peer, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -24,7 +26,11 @@ use std::{
     future::Future,
     io::Cursor,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 use xcap::{Monitor, Window};
 
@@ -38,10 +44,26 @@ use tokio_util::sync::CancellationToken;
 use crate::developer::{paths::get_shell_path_dirs, shell::ShellConfig};
 
 use super::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use super::archive::{self, ReadArchiveParams};
+use super::background::{BackgroundJob, TrackedProcess};
+use super::changelist::Changelist;
 use super::editor_models::{create_editor_model, EditorModel};
-use super::shell::{configure_shell_command, expand_path, is_absolute_path, kill_process_group};
+use super::env_flag::env_flag;
+use super::file_history::{self, FileHistoryStore};
+use super::formatter;
+use super::git_checkpoint;
+use super::ocr;
+use super::redact::{
+    diff_introduces_placeholder, introduces_placeholder, SecretRedactor, REDACTED,
+};
+use super::search::{search_files, SearchParams};
+use super::shell::{
+    configure_shell_command, expand_path, is_absolute_path, kill_process_group,
+    shell_color_enabled, shell_timeout_from_env, strip_ansi_codes, working_dir, OutputLimits,
+};
 use super::text_editor::{
-    text_editor_insert, text_editor_replace, text_editor_undo, text_editor_view, text_editor_write,
+    diff_target_paths, text_editor_insert, text_editor_replace, text_editor_undo, text_editor_view,
+    text_editor_write,
 };
 
 /// Parameters for the screen_capture tool
@@ -54,10 +76,110 @@ pub struct ScreenCaptureParams {
     /// Optional: the exact title of the window to capture.
     /// Use the list_windows tool to find the available windows.
     pub window_title: Option<String>,
+
+    /// Optional: left edge of a region to crop to, in pixels of the captured image
+    pub x: Option<u32>,
+
+    /// Optional: top edge of a region to crop to, in pixels of the captured image
+    pub y: Option<u32>,
+
+    /// Optional: width of the region to crop to
+    pub width: Option<u32>,
+
+    /// Optional: height of the region to crop to
+    pub height: Option<u32>,
+}
+
+impl ScreenCaptureParams {
+    /// The crop rectangle as `(x, y, width, height)`, checked against the captured image size.
+    fn crop_region(
+        &self,
+        image_width: u32,
+        image_height: u32,
+    ) -> Result<Option<(u32, u32, u32, u32)>, ErrorData> {
+        let (x, y, width, height) = match (self.x, self.y, self.width, self.height) {
+            (None, None, None, None) => return Ok(None),
+            (Some(x), Some(y), Some(width), Some(height)) => (x, y, width, height),
+            _ => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "A crop region needs all of x, y, width and height".to_string(),
+                    None,
+                ))
+            }
+        };
+        if width == 0 || height == 0 {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "The crop width and height must be greater than 0".to_string(),
+                None,
+            ));
+        }
+        let fits = x
+            .checked_add(width)
+            .is_some_and(|right| right <= image_width)
+            && y.checked_add(height)
+                .is_some_and(|bottom| bottom <= image_height);
+        if !fits {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Crop region {}x{} at ({}, {}) is outside the {}x{} captured image",
+                    width, height, x, y, image_width, image_height
+                ),
+                None,
+            ));
+        }
+        Ok(Some((x, y, width, height)))
+    }
+}
+
+/// A window as reported by list_windows. Fields the platform doesn't expose are omitted.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WindowInfo {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+impl WindowInfo {
+    fn from_window(window: &Window) -> Option<Self> {
+        Some(Self {
+            title: window.title().ok()?,
+            app_name: window.app_name().ok().filter(|name| !name.is_empty()),
+            x: window.x().ok(),
+            y: window.y().ok(),
+            width: window.width().ok(),
+            height: window.height().ok(),
+        })
+    }
+
+    /// One line summary, e.g. `README.md - Zed [Zed] at (0, 25) 1440x875`
+    fn summary(&self) -> String {
+        let mut line = self.title.clone();
+        if let Some(app_name) = &self.app_name {
+            line.push_str(&format!(" [{}]", app_name));
+        }
+        if let (Some(x), Some(y)) = (self.x, self.y) {
+            line.push_str(&format!(" at ({}, {})", x, y));
+        }
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            line.push_str(&format!(" {}x{}", width, height));
+        }
+        line
+    }
 }
 
 /// Parameters for the text_editor tool
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct TextEditorParams {
     /// Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`.
     pub path: String,
@@ -75,6 +197,9 @@ pub struct TextEditorParams {
     /// This parameter only applies when viewing files, not directories.
     pub view_range: Option<Vec<i64>>,
 
+    /// How many levels deep to list when `path` is a directory. Defaults to 2.
+    pub max_depth: Option<u64>,
+
     /// The content to write to the file. Required for `write` command.
     pub file_text: Option<String>,
 
@@ -86,13 +211,91 @@ pub struct TextEditorParams {
 
     /// The line number after which to insert text (0 for beginning). Required for `insert` command.
     pub insert_line: Option<i64>,
+
+    /// Replace every occurrence of `old_str` instead of requiring a unique match. Only applies to `str_replace`.
+    #[serde(default)]
+    pub replace_all: Option<bool>,
+
+    /// Check that every hunk of `diff` would apply and report the result without writing anything.
+    /// Only applies to `str_replace` with `diff`.
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+
+    /// Access a path even though it matches `.gooseignore`. Only set this when the user has
+    /// asked for that specific file; the user is asked to approve the access.
+    #[serde(default)]
+    pub allow_ignored: Option<bool>,
 }
 
 /// Parameters for the shell tool
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ShellParams {
     /// The command string to execute in the shell
     pub command: String,
+
+    /// Optional: lines to keep from the start of long output, in addition to the tail. Defaults to 0.
+    pub head_lines: Option<usize>,
+
+    /// Optional: lines to keep from the end of long output. Defaults to 100.
+    pub tail_lines: Option<usize>,
+
+    /// Optional: maximum number of characters of output before the command fails. Defaults to 400000.
+    pub max_chars: Option<usize>,
+
+    /// Optional: directory to run the command in. Defaults to the session working directory.
+    pub cwd: Option<String>,
+
+    /// Optional: run even if the command references paths matching `.gooseignore`. Only set this
+    /// when the user has asked for that specific file; the user is asked to approve the access.
+    /// Defaults to false.
+    pub allow_ignored: Option<bool>,
+
+    /// Optional: seconds to wait before the command is killed and reported as timed out.
+    /// Defaults to the configured shell timeout, if any.
+    pub timeout_secs: Option<u64>,
+}
+
+impl ShellParams {
+    fn output_limits(&self) -> OutputLimits {
+        let defaults = OutputLimits::default();
+        OutputLimits {
+            head_lines: self.head_lines.unwrap_or(defaults.head_lines),
+            tail_lines: self.tail_lines.unwrap_or(defaults.tail_lines),
+            max_chars: self.max_chars.unwrap_or(defaults.max_chars),
+        }
+    }
+}
+
+/// Parameters for the shell_background tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ShellBackgroundParams {
+    /// The command string to run in the background
+    pub command: String,
+
+    /// Optional: directory to run the command in. Defaults to the session working directory.
+    pub cwd: Option<String>,
+}
+
+/// Parameters for the shell_poll and shell_kill tools
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ShellJobParams {
+    /// The job id returned by shell_background
+    pub job_id: String,
+}
+
+/// Parameters for the git_checkpoint tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitCheckpointParams {
+    /// Optional name for the checkpoint (letters, digits, '-', '_' or '.').
+    /// Defaults to a timestamp. Reusing a name replaces that checkpoint.
+    pub name: Option<String>,
+}
+
+/// Parameters for the git_restore tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitRestoreParams {
+    /// Name of the checkpoint to roll back to, as returned by git_checkpoint
+    pub name: String,
 }
 
 /// Parameters for the image_processor tool
@@ -100,6 +303,9 @@ pub struct ShellParams {
 pub struct ImageProcessorParams {
     /// Absolute path to the image file to process
     pub path: String,
+
+    /// Optional: also return the text recognized in the image (OCR). Defaults to false.
+    pub extract_text: Option<bool>,
 }
 
 /// Template structure for prompt definitions
@@ -175,16 +381,23 @@ fn load_prompt_files() -> HashMap<String, Prompt> {
 pub struct DeveloperServer {
     tool_router: ToolRouter<Self>,
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
+    file_history_store: Option<FileHistoryStore>,
     ignore_patterns: Gitignore,
     editor_model: Option<EditorModel>,
     prompts: HashMap<String, Prompt>,
     code_analyzer: CodeAnalyzer,
     #[cfg(test)]
-    pub running_processes: Arc<RwLock<HashMap<String, CancellationToken>>>,
+    pub running_processes: Arc<RwLock<HashMap<String, TrackedProcess>>>,
     #[cfg(not(test))]
-    running_processes: Arc<RwLock<HashMap<String, CancellationToken>>>,
+    running_processes: Arc<RwLock<HashMap<String, TrackedProcess>>>,
     bash_env_file: Option<PathBuf>,
     extend_path_with_shell: bool,
+    auto_format: bool,
+    shell_color: bool,
+    shell_timeout: Option<Duration>,
+    changelist: Option<Changelist>,
+    background_jobs: Arc<RwLock<HashMap<String, BackgroundJob>>>,
+    next_job_id: Arc<AtomicUsize>,
 }
 
 #[tool_handler(router = self.tool_router)]
@@ -192,7 +405,7 @@ impl ServerHandler for DeveloperServer {
     #[allow(clippy::too_many_lines)]
     fn get_info(&self) -> ServerInfo {
         // Get base instructions and working directory
-        let cwd = std::env::current_dir().expect("should have a current working dir");
+        let cwd = working_dir();
         let os = std::env::consts::OS;
         let in_container = Self::is_definitely_container();
 
@@ -259,7 +472,7 @@ impl ServerHandler for DeveloperServer {
 
                 Perform text editing operations on files.
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file, or a tree of a directory (set `max_depth`, default 2).
                 - `write`: Create or overwrite a file with the given content
                 - `str_replace`: Replace text in one or more files.
                 - `insert`: Insert text at a specific line location in the file.
@@ -274,9 +487,11 @@ impl ServerHandler for DeveloperServer {
                 To use the str_replace command to edit multiple files, use the `diff` parameter with a unified diff.
                 To use the str_replace command to edit one file, you must specify both `old_str` and `new_str` - the `old_str` needs to exactly match one
                 unique section of the original file, including any whitespace. Make sure to include enough context that the match is not
-                ambiguous. The entire original string will be replaced with `new_str`
+                ambiguous. The entire original string will be replaced with `new_str`. Set `replace_all` to true to instead replace
+                every occurrence of `old_str`, e.g. to rename a variable throughout a file.
 
                 When possible, batch file edits together by using a multi-file unified `diff` within a single str_replace tool call.
+                Set `dry_run` to true alongside a `diff` to check which hunks would apply, with line numbers, without changing any files.
 
                 {}
 
@@ -289,7 +504,7 @@ impl ServerHandler for DeveloperServer {
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file, or a tree of a directory (set `max_depth`, default 2).
                 - `write`: Create or overwrite a file with the given content
                 - `str_replace`: Replace text in one or more files.
                 - `insert`: Insert text at a specific line location in the file.
@@ -301,9 +516,11 @@ impl ServerHandler for DeveloperServer {
                 To use the str_replace command to edit multiple files, use the `diff` parameter with a unified diff.
                 To use the str_replace command to edit one file, you must specify both `old_str` and `new_str` - the `old_str` needs to exactly match one
                 unique section of the original file, including any whitespace. Make sure to include enough context that the match is not
-                ambiguous. The entire original string will be replaced with `new_str`
+                ambiguous. The entire original string will be replaced with `new_str`. Set `replace_all` to true to instead replace
+                every occurrence of `old_str`, e.g. to rename a variable throughout a file.
 
                 When possible, batch file edits together by using a multi-file unified `diff` within a single str_replace tool call.
+                Set `dry_run` to true alongside a `diff` to check which hunks would apply, with line numbers, without changing any files.
 
                 To use the insert command, you must specify both `insert_line` (the line number after which to insert, 0 for beginning, -1 for end)
                 and `new_str` (the text to insert).
@@ -322,10 +539,13 @@ impl ServerHandler for DeveloperServer {
             of if the command succeeded or failed.
 
             Avoid commands that produce a large amount of output, and consider piping those outputs to files.
+            Long output is truncated to its last 100 lines. Set `tail_lines` and `head_lines` to see more of
+            it, e.g. the start and end of a build log, and `max_chars` to raise the 400000 character limit.
 
             **Important**: Each shell command runs in its own process. Things like directory changes or
             sourcing files do not persist between tool calls. So you may need to repeat them each time by
-            stringing together commands.
+            stringing together commands. To run a command in another directory, set `cwd` rather
+            than prefixing it with `cd`.
 
             If fetching web content, consider adding Accept: text/markdown header
         "#};
@@ -352,10 +572,12 @@ impl ServerHandler for DeveloperServer {
         "#};
 
         let unix_specific = indoc! {r#"
-            If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
-            this tool does not run indefinitely.
+            If you need to run a long lived command such as a dev server, start it with `shell_background`
+            so that this tool does not run indefinitely, then check on it with `shell_poll`.
+            `list_processes` shows every command that is still running and `kill_all_processes` stops them all.
 
-            **Important**: Use ripgrep - `rg` - exclusively when you need to locate a file or a code reference,
+            **Important**: To find code, prefer the `search` tool, which returns structured matches.
+            Otherwise use ripgrep - `rg` - exclusively when you need to locate a file or a code reference,
             other solutions may produce too large output because of hidden files! For example *do not* use `find` or `ls -r`
               - List files by name: `rg --files | rg <filename>`
               - List files that contain a regex: `rg '<regex>' -l`
@@ -524,8 +746,8 @@ impl ServerHandler for DeveloperServer {
             let request_id = notification.request_id.to_string();
             let processes = self.running_processes.read().await;
 
-            if let Some(token) = processes.get(&request_id) {
-                token.cancel();
+            if let Some(process) = processes.get(&request_id) {
+                process.token.cancel();
                 tracing::debug!("Found process for request {}, cancelling token", request_id);
             } else {
                 tracing::warn!("No process found for request ID: {}", request_id);
@@ -540,19 +762,101 @@ impl Default for DeveloperServer {
     }
 }
 
+/// Set to false to skip the built-in `.env`/secrets rules used when no `.gooseignore` exists.
+const GOOSE_IGNORE_DEFAULTS: &str = "GOOSE_IGNORE_DEFAULTS";
+
+fn ignore_defaults_enabled() -> bool {
+    env_flag(GOOSE_IGNORE_DEFAULTS, true)
+}
+
+/// Limits how many parent directories are searched for `.gooseignore` files.
+/// Without it the search stops at the enclosing git repository root.
+const GOOSE_IGNORE_SEARCH_DEPTH: &str = "GOOSE_IGNORE_SEARCH_DEPTH";
+
+/// Directories whose `.gooseignore` files apply to `cwd`, closest first. Walks up to
+/// the enclosing git repository root, or `GOOSE_IGNORE_SEARCH_DEPTH` parents when set.
+/// Outside a repository with no depth configured, only `cwd` itself is used.
+fn gooseignore_search_dirs(cwd: &Path) -> Vec<PathBuf> {
+    let max_depth = std::env::var(GOOSE_IGNORE_SEARCH_DEPTH)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok());
+
+    let mut dirs = Vec::new();
+    for (depth, dir) in cwd.ancestors().enumerate() {
+        if max_depth.is_some_and(|max| depth > max) {
+            return dirs;
+        }
+        dirs.push(dir.to_path_buf());
+        if dir.join(".git").exists() {
+            return dirs;
+        }
+    }
+
+    if max_depth.is_some() {
+        dirs
+    } else {
+        vec![cwd.to_path_buf()]
+    }
+}
+
+/// Rewrites a line from an ancestor's `.gooseignore` so it applies relative to `cwd`,
+/// where `prefix` is the path from that ancestor down to `cwd`. Anchored patterns that
+/// point outside `cwd` can never match and are dropped.
+fn rebase_ignore_line(line: &str, prefix: &Path) -> Option<String> {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    if prefix.as_os_str().is_empty() {
+        return Some(trimmed.to_string());
+    }
+
+    let (negation, pattern) = match trimmed.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", trimmed),
+    };
+    let dir_suffix = if pattern.ends_with('/') { "/" } else { "" };
+    let body = pattern.trim_end_matches('/');
+
+    // Unanchored patterns match at any depth, so they apply unchanged
+    if !body.contains('/') || body.starts_with("**/") {
+        return Some(trimmed.to_string());
+    }
+
+    let prefix = prefix
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    body.trim_start_matches('/')
+        .strip_prefix(&prefix)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .map(|rest| format!("{}/{}{}", negation, rest, dir_suffix))
+}
+
 #[tool_router(router = tool_router)]
 impl DeveloperServer {
     pub fn new() -> Self {
         // Build ignore patterns (simplified version for this tool)
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = working_dir();
         let ignore_patterns = Self::build_ignore_patterns(&cwd);
 
         // Initialize editor model for AI-powered code editing
         let editor_model = create_editor_model();
 
+        let file_history_store = file_history::persist_file_history_enabled()
+            .then(file_history::default_dir)
+            .flatten()
+            .map(FileHistoryStore::new);
+        let file_history = file_history_store
+            .as_ref()
+            .map(FileHistoryStore::load_all)
+            .unwrap_or_default();
+
         Self {
             tool_router: Self::tool_router(),
-            file_history: Arc::new(Mutex::new(HashMap::new())),
+            file_history: Arc::new(Mutex::new(file_history)),
+            file_history_store,
             ignore_patterns,
             editor_model,
             prompts: load_prompt_files(),
@@ -560,6 +864,12 @@ impl DeveloperServer {
             running_processes: Arc::new(RwLock::new(HashMap::new())),
             extend_path_with_shell: false,
             bash_env_file: None,
+            auto_format: formatter::auto_format_enabled(),
+            shell_color: shell_color_enabled(),
+            shell_timeout: shell_timeout_from_env(),
+            changelist: Changelist::from_env(),
+            background_jobs: Arc::new(RwLock::new(HashMap::new())),
+            next_job_id: Arc::new(AtomicUsize::new(1)),
         }
     }
 
@@ -573,12 +883,160 @@ impl DeveloperServer {
         self
     }
 
+    /// Run the project's formatter (rustfmt, prettier, black, ...) on files after
+    /// `write` and `str_replace` edits. Defaults to `GOOSE_AUTO_FORMAT`.
+    pub fn auto_format(mut self, value: bool) -> Self {
+        self.auto_format = value;
+        self
+    }
+
+    /// Force colored output from shell commands and stream it with ANSI codes intact.
+    /// Defaults to `GOOSE_SHELL_COLOR`.
+    pub fn shell_color(mut self, value: bool) -> Self {
+        self.shell_color = value;
+        self
+    }
+
+    /// Kill shell commands that run longer than this unless the call sets its own
+    /// `timeout_secs`. Defaults to `GOOSE_SHELL_TIMEOUT`.
+    pub fn shell_timeout(mut self, value: Option<Duration>) -> Self {
+        self.shell_timeout = value;
+        self
+    }
+
+    /// Let `text_editor` modify files in the changelist freely while asking the user before
+    /// it writes anywhere else. Defaults to `GOOSE_CHANGELIST`.
+    pub fn changelist(mut self, value: Option<Changelist>) -> Self {
+        self.changelist = value;
+        self
+    }
+
+    /// Persist text editor undo history in `dir` so `undo_edit` survives restarts, loading
+    /// any history already there. Defaults to the config dir when `GOOSE_PERSIST_FILE_HISTORY`
+    /// is set.
+    pub fn file_history_dir(mut self, dir: Option<PathBuf>) -> Self {
+        let store = dir.map(FileHistoryStore::new);
+        *self.file_history.lock().unwrap() = store
+            .as_ref()
+            .map(FileHistoryStore::load_all)
+            .unwrap_or_default();
+        self.file_history_store = store;
+        self
+    }
+
+    /// Asks the user to approve a `text_editor` command that would modify a file outside
+    /// the changelist, failing the call unless they accept.
+    async fn confirm_write_outside_changelist(
+        &self,
+        params: &TextEditorParams,
+        peer: &Peer<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let Some(changelist) = &self.changelist else {
+            return Ok(());
+        };
+        let modifies = match params.command.as_str() {
+            "write" | "insert" | "undo_edit" => true,
+            "str_replace" => !params.dry_run.unwrap_or(false),
+            _ => false,
+        };
+        if !modifies {
+            return Ok(());
+        }
+        let path = self.resolve_path(&params.path)?;
+        // A diff patches every file it names, relative to the directory of `path`
+        let targets = match (params.command.as_str(), &params.diff) {
+            ("str_replace", Some(diff)) => diff_target_paths(&path, diff)?,
+            _ => vec![path],
+        };
+        let outside: Vec<String> = targets
+            .iter()
+            .filter(|target| !changelist.contains(target))
+            .map(|target| format!("'{}'", target.display()))
+            .collect();
+        if outside.is_empty() {
+            return Ok(());
+        }
+        let outside = outside.join(", ");
+
+        let refused = |reason: &str| {
+            ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!(
+                    "{} is outside the changelist and the write was not approved: {}",
+                    outside, reason
+                ),
+                None,
+            )
+        };
+        let approved = ask_approval(
+            peer,
+            format!(
+                "{} wants to modify {}, which is outside the changelist.",
+                params.command, outside
+            ),
+            "Allow this write",
+        )
+        .await
+        .map_err(|e| refused(&e.to_string()))?;
+        if approved {
+            Ok(())
+        } else {
+            Err(refused("declined by the user"))
+        }
+    }
+
+    /// Writes the undo history of `path` to disk when persistence is on.
+    fn persist_file_history(&self, path: &Path) {
+        let Some(store) = &self.file_history_store else {
+            return;
+        };
+        let versions = self
+            .file_history
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .unwrap_or_default();
+        if let Err(e) = store.save(path, &versions) {
+            tracing::warn!(
+                "Failed to persist edit history for {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Fills an empty in-memory undo history for `path` from disk, e.g. after another
+    /// process edited the file.
+    fn restore_file_history(&self, path: &Path) {
+        let Some(store) = &self.file_history_store else {
+            return;
+        };
+        let mut history = self.file_history.lock().unwrap();
+        if history.get(path).is_none_or(|versions| versions.is_empty()) {
+            let versions = store.load(path);
+            if !versions.is_empty() {
+                history.insert(path.to_path_buf(), versions);
+            }
+        }
+    }
+
+    /// Formats an edited file when auto-format is on, noting any change in the result.
+    async fn format_after_edit(&self, path: &Path, mut content: Vec<Content>) -> Vec<Content> {
+        if self.auto_format && path.is_file() {
+            if let Some(note) = formatter::format_file(path).await {
+                content.push(Content::text(note));
+            }
+        }
+        content
+    }
+
     /// List all available windows that can be used with screen_capture.
-    /// Returns a list of window titles that can be used with the window_title parameter
-    /// of the screen_capture tool.
+    /// Returns each window's title, owning application, position and size. The title
+    /// can be used with the window_title parameter of the screen_capture tool.
     #[tool(
         name = "list_windows",
-        description = "List all available window titles that can be used with screen_capture. Returns a list of window titles that can be used with the window_title parameter of the screen_capture tool."
+        description = "List all available windows that can be used with screen_capture. Returns each window's title, owning application, position (x, y) and size (width x height), as text and as JSON. The title can be used with the window_title parameter of the screen_capture tool."
     )]
     pub async fn list_windows(&self) -> Result<CallToolResult, ErrorData> {
         let windows = Window::all().map_err(|_| {
@@ -589,16 +1047,17 @@ impl DeveloperServer {
             )
         })?;
 
-        let window_titles: Vec<String> =
-            windows.into_iter().filter_map(|w| w.title().ok()).collect();
+        let windows: Vec<WindowInfo> = windows.iter().filter_map(WindowInfo::from_window).collect();
 
-        let content_text = format!("Available windows:\n{}", window_titles.join("\n"));
+        let summaries: Vec<String> = windows.iter().map(WindowInfo::summary).collect();
+        let content_text = format!("Available windows:\n{}", summaries.join("\n"));
 
         Ok(CallToolResult::success(vec![
             Content::text(content_text.clone()).with_audience(vec![Role::Assistant]),
             Content::text(content_text)
                 .with_audience(vec![Role::User])
                 .with_priority(0.0),
+            Content::json(serde_json::json!({ "windows": windows }))?,
         ]))
     }
 
@@ -608,9 +1067,10 @@ impl DeveloperServer {
     /// 2. A specific window by its title using the window_title parameter
     ///
     /// Only one of display or window_title should be specified.
+    /// Set x, y, width and height to crop to a region, e.g. a single UI element.
     #[tool(
         name = "screen_capture",
-        description = "Capture a screenshot of a specified display or window. You can capture either: 1. A full display (monitor) using the display parameter 2. A specific window by its title using the window_title parameter. Only one of display or window_title should be specified."
+        description = "Capture a screenshot of a specified display or window. You can capture either: 1. A full display (monitor) using the display parameter 2. A specific window by its title using the window_title parameter. Only one of display or window_title should be specified. Set x, y, width and height (in pixels of the full capture) to crop to a region; crops up to 768px wide are returned at full resolution."
     )]
     pub async fn screen_capture(
         &self,
@@ -679,6 +1139,10 @@ impl DeveloperServer {
             })?
         };
 
+        if let Some((x, y, width, height)) = params.crop_region(image.width(), image.height())? {
+            image = xcap::image::imageops::crop_imm(&image, x, y, width, height).to_image();
+        }
+
         // Resize the image to a reasonable width while maintaining aspect ratio
         let max_width = 768;
         if image.width() > max_width {
@@ -722,10 +1186,35 @@ impl DeveloperServer {
     /// - `str_replace`: Replace old_str with new_str in the file.
     /// - `insert`: Insert text at a specific line location in the file.
     /// - `undo_edit`: Undo the last edit made to a file.
+    ///
+    /// With a changelist set, the user is asked before a file outside it is modified.
     #[tool(
         name = "text_editor",
         description = "Perform text editing operations on files. Commands: view (show file content), write (create/overwrite file), str_replace (edit file), insert (insert at line), undo_edit (undo last change)."
     )]
+    pub async fn changelist_text_editor(
+        &self,
+        params: Parameters<TextEditorParams>,
+        peer: Peer<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut params = params;
+        self.confirm_write_outside_changelist(&params.0, &peer)
+            .await?;
+        if params.0.allow_ignored.unwrap_or(false) {
+            let path = self.resolve_path(&params.0.path)?;
+            if let Some(rule) = self.ignore_reason(&path) {
+                let restriction = format!(
+                    "Access to '{}' is restricted by .gooseignore rule {}",
+                    path.display(),
+                    rule
+                );
+                params.0.allow_ignored = Some(confirm_ignored_access(&restriction, &peer).await);
+            }
+        }
+        self.text_editor(params).await
+    }
+
+    /// Runs a `text_editor` command without consulting the changelist.
     pub async fn text_editor(
         &self,
         params: Parameters<TextEditorParams>,
@@ -734,18 +1223,27 @@ impl DeveloperServer {
         let path = self.resolve_path(&params.path)?;
 
         // Check if file is ignored before proceeding with any text editor operation
-        if self.is_ignored(&path) {
+        if let Some(rule) = self.blocking_ignore_rule(&path, params.allow_ignored.unwrap_or(false))
+        {
             return Err(ErrorData::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!(
-                    "Access to '{}' is restricted by .gooseignore",
-                    path.display()
+                    "Access to '{}' is restricted by .gooseignore rule {}",
+                    path.display(),
+                    rule
                 ),
                 None,
             ));
         }
 
-        match params.command.as_str() {
+        let redactor = SecretRedactor::from_env();
+        if redactor.is_some() {
+            reject_redacted_placeholder(&path, &params)?;
+        }
+        // View redacts the file itself; other commands echo snippets of the edited file
+        let redact_output = params.command != "view";
+
+        let result = match params.command.as_str() {
             "view" => {
                 let view_range = params.view_range.as_ref().and_then(|vr| {
                     if vr.len() == 2 {
@@ -754,7 +1252,11 @@ impl DeveloperServer {
                         None
                     }
                 });
-                let content = text_editor_view(&path, view_range).await?;
+                let max_depth = params
+                    .max_depth
+                    .map_or(DEFAULT_VIEW_DEPTH, |depth| depth as usize);
+                let content =
+                    text_editor_view(&path, view_range, max_depth, &self.ignore_patterns).await?;
                 Ok(CallToolResult::success(content))
             }
             "write" => {
@@ -766,21 +1268,36 @@ impl DeveloperServer {
                     )
                 })?;
                 let content = text_editor_write(&path, &file_text).await?;
+                let content = self.format_after_edit(&path, content).await;
                 Ok(CallToolResult::success(content))
             }
             "str_replace" => {
+                let dry_run = params.dry_run.unwrap_or(false);
                 // Check if diff parameter is provided
                 if let Some(ref diff) = params.diff {
+                    // A diff can patch several files, each with its own undo history and
+                    // formatting
+                    let targets = if dry_run {
+                        Vec::new()
+                    } else {
+                        diff_target_paths(&path, diff)?
+                    };
                     // When diff is provided, old_str and new_str are not required
-                    let content = text_editor_replace(
+                    let mut content = text_editor_replace(
                         &path,
                         "", // old_str not used with diff
                         "", // new_str not used with diff
                         Some(diff),
+                        false,
+                        dry_run,
                         &self.editor_model,
                         &self.file_history,
                     )
                     .await?;
+                    for target in &targets {
+                        self.persist_file_history(target);
+                        content = self.format_after_edit(target, content).await;
+                    }
                     Ok(CallToolResult::success(content))
                 } else {
                     // Traditional str_replace with old_str and new_str
@@ -803,10 +1320,16 @@ impl DeveloperServer {
                         &old_str,
                         &new_str,
                         None,
+                        params.replace_all.unwrap_or(false),
+                        dry_run,
                         &self.editor_model,
                         &self.file_history,
                     )
                     .await?;
+                    if !dry_run {
+                        self.persist_file_history(&path);
+                    }
+                    let content = self.format_after_edit(&path, content).await;
                     Ok(CallToolResult::success(content))
                 }
             }
@@ -828,10 +1351,13 @@ impl DeveloperServer {
                 let content =
                     text_editor_insert(&path, insert_line as i64, &new_str, &self.file_history)
                         .await?;
+                self.persist_file_history(&path);
                 Ok(CallToolResult::success(content))
             }
             "undo_edit" => {
+                self.restore_file_history(&path);
                 let content = text_editor_undo(&path, &self.file_history).await?;
+                self.persist_file_history(&path);
                 Ok(CallToolResult::success(content))
             }
             _ => Err(ErrorData::new(
@@ -839,7 +1365,13 @@ impl DeveloperServer {
                 format!("Unknown command '{}'", params.command),
                 None,
             )),
+        };
+
+        let mut result = result?;
+        if let Some(redactor) = redactor.filter(|_| redact_output) {
+            redactor.redact_contents(&mut result.content);
         }
+        Ok(result)
     }
 
     /// Execute a command in the shell.
@@ -849,11 +1381,11 @@ impl DeveloperServer {
     /// of if the command succeeded or failed.
     ///
     /// Avoid commands that produce a large amount of output, and consider piping those outputs to files.
-    /// If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
+    /// If you need to run a long lived command, start it with shell_background so that
     /// this tool does not run indefinitely.
     #[tool(
         name = "shell",
-        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that this tool does not run indefinitely."
+        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, start it with shell_background so that this tool does not run indefinitely."
     )]
     pub async fn shell(
         &self,
@@ -862,23 +1394,59 @@ impl DeveloperServer {
     ) -> Result<CallToolResult, ErrorData> {
         let params = params.0;
         let command = &params.command;
+        let limits = params.output_limits();
         let peer = context.peer;
         let request_id = context.id;
 
-        // Validate the shell command
-        self.validate_shell_command(command)?;
+        // Validate the shell command. Paths restricted by .gooseignore are only let through
+        // once the user approves the override.
+        let mut allow_ignored = false;
+        if params.allow_ignored.unwrap_or(false) {
+            let restricted = self
+                .validate_shell_command(command, false)
+                .err()
+                .or_else(|| {
+                    let cwd = params.cwd.as_deref()?;
+                    self.resolve_shell_cwd(cwd, false).err()
+                })
+                .filter(|e| e.code == ErrorCode::INTERNAL_ERROR);
+            if let Some(restricted) = restricted {
+                allow_ignored = confirm_ignored_access(&restricted.message, &peer).await;
+            }
+        }
+        self.validate_shell_command(command, allow_ignored)?;
+        let working_dir = params
+            .cwd
+            .as_deref()
+            .map(|cwd| self.resolve_shell_cwd(cwd, allow_ignored))
+            .transpose()?;
+
+        let timeout = match params.timeout_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => self.shell_timeout,
+        };
 
         let cancellation_token = CancellationToken::new();
         // Track the process using the request ID
         {
             let mut processes = self.running_processes.write().await;
             let request_id_str = request_id.to_string();
-            processes.insert(request_id_str.clone(), cancellation_token.clone());
+            processes.insert(
+                request_id_str.clone(),
+                TrackedProcess::new(command, cancellation_token.clone()),
+            );
         }
 
         // Execute the command and capture output
         let output_result = self
-            .execute_shell_command(command, &peer, cancellation_token.clone())
+            .execute_shell_command(
+                command,
+                working_dir.as_deref(),
+                &peer,
+                cancellation_token.clone(),
+                timeout,
+            )
             .await;
 
         // Clean up the process from tracking
@@ -894,27 +1462,211 @@ impl DeveloperServer {
             }
         }
 
-        let output_str = output_result?;
+        let (output_str, exit_code) = output_result?;
 
         // Validate output size
-        self.validate_shell_output_size(command, &output_str)?;
+        self.validate_shell_output_size(command, &output_str, limits.max_chars)?;
 
         // Process and format the output
-        let (final_output, user_output) = self.process_shell_output(&output_str)?;
+        let (final_output, user_output) = self.process_shell_output(&output_str, &limits)?;
+        // Providers only send text content to the model, so failures are spelled out there too
+        let final_output = match exit_code {
+            Some(0) => final_output,
+            Some(code) => format!("{}\n\nCommand exited with code {}", final_output, code),
+            None => format!("{}\n\nCommand was killed by a signal", final_output),
+        };
 
-        Ok(CallToolResult::success(vec![
+        let mut result = CallToolResult::success(vec![
             Content::text(final_output).with_audience(vec![Role::Assistant]),
             Content::text(user_output)
                 .with_audience(vec![Role::User])
                 .with_priority(0.0),
-        ]))
+        ]);
+        // Null when the process was killed by a signal
+        result.structured_content = Some(serde_json::json!({ "exit_code": exit_code }));
+        Ok(result)
+    }
+
+    /// Start a long-running shell command without waiting for it to finish.
+    #[tool(
+        name = "shell_background",
+        description = "Start a long-running shell command (dev servers, watchers, long builds) in the background and return a job id immediately. Use shell_poll with the job id to read its output and status, and shell_kill to stop it. Prefer this over backgrounding with `&`, whose output is lost."
+    )]
+    pub async fn shell_background(
+        &self,
+        params: Parameters<ShellBackgroundParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        self.validate_shell_command(&params.command, false)?;
+        let working_dir = params
+            .cwd
+            .as_deref()
+            .map(|cwd| self.resolve_shell_cwd(cwd, false))
+            .transpose()?;
+
+        let child = self
+            .build_shell_command(&params.command, working_dir.as_deref())
+            .await
+            .spawn()
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        let job_id = format!("job-{}", self.next_job_id.fetch_add(1, Ordering::Relaxed));
+        let cancellation_token = CancellationToken::new();
+        let job = BackgroundJob::spawn(params.command.clone(), child, cancellation_token.clone());
+        self.running_processes.write().await.insert(
+            job_id.clone(),
+            TrackedProcess::new(&params.command, cancellation_token),
+        );
+        self.background_jobs
+            .write()
+            .await
+            .insert(job_id.clone(), job);
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Started job {} running `{}`. Use shell_poll with job_id '{}' to read its output.",
+            job_id, params.command, job_id
+        ))]))
+    }
+
+    /// Read the output a background job produced since it was last polled.
+    #[tool(
+        name = "shell_poll",
+        description = "Read the output a background job started with shell_background has produced since the last poll, and whether it is still running. Finished jobs are forgotten once their final output has been read."
+    )]
+    pub async fn shell_poll(
+        &self,
+        params: Parameters<ShellJobParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let job_id = params.0.job_id;
+        let job = self.background_job(&job_id).await?;
+        let poll = job.poll();
+        if poll.status.is_some() {
+            self.forget_background_job(&job_id).await;
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            poll.describe(&job_id, &job.command),
+        )]))
+    }
+
+    /// Stop a background job and everything it spawned.
+    #[tool(
+        name = "shell_kill",
+        description = "Stop a background job started with shell_background, including any processes it spawned, and return its remaining output."
+    )]
+    pub async fn shell_kill(
+        &self,
+        params: Parameters<ShellJobParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let job_id = params.0.job_id;
+        let job = self.background_job(&job_id).await?;
+        if let Some(process) = self.running_processes.read().await.get(&job_id) {
+            process.token.cancel();
+        }
+        self.forget_background_job(&job_id).await;
+
+        let mut poll = job.poll();
+        poll.status.get_or_insert_with(|| "killed".to_string());
+        Ok(CallToolResult::success(vec![Content::text(
+            poll.describe(&job_id, &job.command),
+        )]))
+    }
+
+    /// List the tracked shell commands, with the exit status of finished background jobs.
+    #[tool(
+        name = "list_processes",
+        description = "List the tracked shell commands, both foreground shell calls and background jobs, with their request or job id, command and how long they have been running. Background jobs that have exited but were not polled yet are shown with their exit status."
+    )]
+    pub async fn list_processes(&self) -> Result<CallToolResult, ErrorData> {
+        let processes = self.running_processes.read().await;
+        if processes.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No shell processes are running.",
+            )]));
+        }
+
+        // Background jobs stay tracked after exiting until their final output is polled
+        let jobs = self.background_jobs.read().await;
+        let mut ids: Vec<&String> = processes.keys().collect();
+        ids.sort();
+        let lines: Vec<String> = ids
+            .into_iter()
+            .map(|id| {
+                let process = &processes[id];
+                match jobs.get(id).and_then(BackgroundJob::status) {
+                    Some(status) => format!("- {}", process.describe_finished(id, &status)),
+                    None => format!("- {}", process.describe(id)),
+                }
+            })
+            .collect();
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Shell processes:\n{}",
+            lines.join("\n")
+        ))]))
+    }
+
+    /// Stop every tracked shell command.
+    #[tool(
+        name = "kill_all_processes",
+        description = "Stop every shell command that is still running, both foreground shell calls and background jobs, including any processes they spawned. Use list_processes first to see what will be stopped."
+    )]
+    pub async fn kill_all_processes(&self) -> Result<CallToolResult, ErrorData> {
+        let mut stopped: Vec<String> = {
+            let processes = self.running_processes.read().await;
+            processes
+                .iter()
+                .map(|(id, process)| {
+                    // Cancelling kills the process group wherever the command is awaited
+                    process.token.cancel();
+                    format!("- {}", process.describe(id))
+                })
+                .collect()
+        };
+        stopped.sort();
+
+        // Foreground entries are removed by their shell call once it returns
+        let job_ids: Vec<String> = self.background_jobs.read().await.keys().cloned().collect();
+        for job_id in job_ids {
+            self.forget_background_job(&job_id).await;
+        }
+
+        if stopped.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No shell processes are running.",
+            )]));
+        }
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Stopped {} shell process(es):\n{}",
+            stopped.len(),
+            stopped.join("\n")
+        ))]))
+    }
+
+    async fn background_job(&self, job_id: &str) -> Result<BackgroundJob, ErrorData> {
+        self.background_jobs
+            .read()
+            .await
+            .get(job_id)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("No background job with id '{}'", job_id),
+                    None,
+                )
+            })
+    }
+
+    async fn forget_background_job(&self, job_id: &str) {
+        self.background_jobs.write().await.remove(job_id);
+        self.running_processes.write().await.remove(job_id);
     }
 
     /// Validate a shell command before execution.
     ///
     /// Checks for empty commands and ensures the command doesn't attempt to access
     /// files that are restricted by ignore patterns.
-    fn validate_shell_command(&self, command: &str) -> Result<(), ErrorData> {
+    fn validate_shell_command(&self, command: &str, allow_ignored: bool) -> Result<(), ErrorData> {
         // Check for empty commands
         if command.trim().is_empty() {
             return Err(ErrorData::new(
@@ -939,12 +1691,12 @@ impl DeveloperServer {
                 continue;
             }
 
-            if self.is_ignored(path) {
+            if let Some(rule) = self.blocking_ignore_rule(path, allow_ignored) {
                 return Err(ErrorData::new(
                     ErrorCode::INTERNAL_ERROR,
                     format!(
-                        "The command attempts to access '{}' which is restricted by .gooseignore",
-                        arg
+                        "The command attempts to access '{}' which is restricted by .gooseignore rule {}",
+                        arg, rule
                     ),
                     None,
                 ));
@@ -954,24 +1706,46 @@ impl DeveloperServer {
         Ok(())
     }
 
-    /// Execute a shell command and return the combined output.
-    ///
-    /// Streams output in real-time to the client using logging notifications.
-    async fn execute_shell_command(
+    /// Resolve the `cwd` parameter of a shell call to an existing, non-ignored directory.
+    fn resolve_shell_cwd(&self, cwd: &str, allow_ignored: bool) -> Result<PathBuf, ErrorData> {
+        let dir = self.resolve_path(cwd)?;
+        if !dir.is_dir() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("The working directory '{}' does not exist", dir.display()),
+                None,
+            ));
+        }
+        if let Some(rule) = self.blocking_ignore_rule(&dir, allow_ignored) {
+            return Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "The working directory '{}' is restricted by .gooseignore rule {}",
+                    dir.display(),
+                    rule
+                ),
+                None,
+            ));
+        }
+        Ok(dir)
+    }
+
+    /// Build the process for a shell command, with the configured environment applied.
+    /// `working_dir` overrides the session's working directory for this command.
+    async fn build_shell_command(
         &self,
         command: &str,
-        peer: &rmcp::service::Peer<RoleServer>,
-        cancellation_token: CancellationToken,
-    ) -> Result<String, ErrorData> {
+        working_dir: Option<&Path>,
+    ) -> tokio::process::Command {
         let mut shell_config = ShellConfig::default();
         let shell_name = std::path::Path::new(&shell_config.executable)
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("bash");
 
-        let working_dir = std::env::var("GOOSE_WORKING_DIR")
-            .ok()
-            .map(std::path::PathBuf::from);
+        let working_dir = working_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(super::shell::working_dir);
 
         if let Some(ref env_file) = self.bash_env_file {
             if shell_name == "bash" {
@@ -982,7 +1756,11 @@ impl DeveloperServer {
             }
         }
 
-        let mut command = configure_shell_command(&shell_config, command, working_dir.as_deref());
+        if self.shell_color {
+            shell_config.force_color();
+        }
+
+        let mut command = configure_shell_command(&shell_config, command, Some(&working_dir));
 
         if self.extend_path_with_shell {
             if let Err(e) = get_shell_path_dirs()
@@ -994,6 +1772,22 @@ impl DeveloperServer {
             }
         }
 
+        command
+    }
+
+    /// Execute a shell command and return the combined output and its exit code.
+    ///
+    /// Streams output in real-time to the client using logging notifications.
+    async fn execute_shell_command(
+        &self,
+        command: &str,
+        working_dir: Option<&Path>,
+        peer: &rmcp::service::Peer<RoleServer>,
+        cancellation_token: CancellationToken,
+        timeout: Option<Duration>,
+    ) -> Result<(String, Option<i32>), ErrorData> {
+        let mut command = self.build_shell_command(command, working_dir).await;
+
         let mut child = command
             .spawn()
             .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
@@ -1010,13 +1804,14 @@ impl DeveloperServer {
             child.stdout.take().unwrap(),
             child.stderr.take().unwrap(),
             peer.clone(),
+            self.shell_color,
         );
 
         tokio::select! {
             output_result = output_task => {
                 // Wait for the process to complete
-                let _exit_status = child.wait().await.map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
-                output_result
+                let exit_status = child.wait().await.map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                output_result.map(|output| (output, exit_status.code()))
             }
             _ = cancellation_token.cancelled() => {
                 tracing::info!("Cancellation token triggered! Attempting to kill process and all child processes");
@@ -1037,6 +1832,27 @@ impl DeveloperServer {
                     None,
                 ))
             }
+            _ = async {
+                match timeout {
+                    Some(timeout) => tokio::time::sleep(timeout).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                tracing::info!("Shell command timed out, killing process and all child processes");
+
+                if let Err(e) = kill_process_group(&mut child, pid).await {
+                    tracing::error!("Failed to kill shell process and child processes: {}", e);
+                }
+
+                Err(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!(
+                        "Shell command timed out after {} seconds and was killed",
+                        timeout.map(|t| t.as_secs()).unwrap_or_default()
+                    ),
+                    None,
+                ))
+            }
         }
     }
 
@@ -1048,6 +1864,7 @@ impl DeveloperServer {
         stdout: tokio::process::ChildStdout,
         stderr: tokio::process::ChildStderr,
         peer: rmcp::service::Peer<RoleServer>,
+        preserve_ansi: bool,
     ) -> Result<String, ErrorData> {
         let stdout = BufReader::new(stdout);
         let stderr = BufReader::new(stderr);
@@ -1068,7 +1885,12 @@ impl DeveloperServer {
                 // Convert to UTF-8 to avoid corrupted output
                 let line_str = String::from_utf8_lossy(&line);
 
-                combined_output.push_str(&line_str);
+                // Colors are only for the live view, keep them out of the transcript
+                if preserve_ansi {
+                    combined_output.push_str(&strip_ansi_codes(&line_str));
+                } else {
+                    combined_output.push_str(&line_str);
+                }
 
                 // Stream each line back to the client in real-time
                 let trimmed_line = line_str.trim();
@@ -1107,18 +1929,22 @@ impl DeveloperServer {
     }
 
     /// Validate that shell output doesn't exceed size limits.
-    fn validate_shell_output_size(&self, command: &str, output: &str) -> Result<(), ErrorData> {
-        const MAX_CHAR_COUNT: usize = 400_000; // 400KB
+    fn validate_shell_output_size(
+        &self,
+        command: &str,
+        output: &str,
+        max_chars: usize,
+    ) -> Result<(), ErrorData> {
         let char_count = output.chars().count();
 
-        if char_count > MAX_CHAR_COUNT {
+        if char_count > max_chars {
             return Err(ErrorData::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!(
                     "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
                     command,
                     char_count,
-                    MAX_CHAR_COUNT
+                    max_chars
                 ),
                 None,
             ));
@@ -1138,9 +1964,10 @@ impl DeveloperServer {
     /// analyze(path="file.py") -> semantic analysis
     /// analyze(path="src/") -> structure overview down to max_depth subdirs
     /// analyze(path="src/", focus="main") -> track main() across files in src/ down to max_depth subdirs
+    /// analyze(path=".", max_depth=5, depth_overrides={"vendor": 1}) -> deep overview that only skims vendor/
     #[tool(
         name = "analyze",
-        description = "Analyze code structure in 3 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). Typical flow: directory → files → symbols. Functions called >3x show •N."
+        description = "Analyze code structure in 3 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). Typical flow: directory → files → symbols. Functions called >3x show •N. Set output_format=\"json\" for structured output, or \"mermaid\"/\"dot\" with focus for a call graph diagram."
     )]
     pub async fn analyze(
         &self,
@@ -1152,6 +1979,147 @@ impl DeveloperServer {
             .analyze(params, path, &self.ignore_patterns)
     }
 
+    /// Search file contents with a regular expression and return structured matches.
+    #[tool(
+        name = "search",
+        description = "Search file contents for a regex and return a JSON list of matches with file, line_number, column and matched_line. Respects .gitignore and .gooseignore, skips binary files and stops after 500 matches. Optionally restrict to a path and to glob patterns like [\"*.rs\", \"!tests/**\"]. Prefer this over running rg or grep in the shell."
+    )]
+    pub async fn search(
+        &self,
+        params: Parameters<SearchParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let root = self.resolve_path(params.path.as_deref().unwrap_or("."))?;
+        if !root.exists() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("The path '{}' does not exist", root.display()),
+                None,
+            ));
+        }
+        if let Some(rule) = self.ignore_reason(&root) {
+            return Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "Access to '{}' is restricted by .gooseignore rule {}",
+                    root.display(),
+                    rule
+                ),
+                None,
+            ));
+        }
+
+        let ignore_patterns = self.ignore_patterns.clone();
+        let mut results =
+            tokio::task::spawn_blocking(move || search_files(&root, &params, &ignore_patterns))
+                .await
+                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))??;
+        if let Some(redactor) = SecretRedactor::from_env() {
+            for found in &mut results.matches {
+                found.matched_line = redactor.redact(&found.matched_line).0;
+            }
+        }
+        results.into_tool_result()
+    }
+
+    /// List the entries of an archive or read one of them without extracting it.
+    #[tool(
+        name = "read_archive",
+        description = "List the entries of a .zip, .tar, .tar.gz/.tgz or .gz file, or read the text of a single entry by name, without unpacking anything to disk. Prefer this over running unzip or tar in the shell."
+    )]
+    pub async fn read_archive(
+        &self,
+        params: Parameters<ReadArchiveParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let path = self.resolve_path(&params.path)?;
+        if let Some(rule) = self.ignore_reason(&path) {
+            return Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "Access to '{}' is restricted by .gooseignore rule {}",
+                    path.display(),
+                    rule
+                ),
+                None,
+            ));
+        }
+        if !path.is_file() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("The path '{}' does not exist", path.display()),
+                None,
+            ));
+        }
+
+        let text = tokio::task::spawn_blocking(move || match params.entry {
+            Some(entry) => archive::read_entry(&path, &entry),
+            None => archive::list_entries(&path)
+                .map(|entries| archive::describe_entries(&path, &entries)),
+        })
+        .await
+        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))??;
+
+        let text = match SecretRedactor::from_env() {
+            Some(redactor) => redactor.redact(&text).0,
+            None => text,
+        };
+        Ok(CallToolResult::success(vec![
+            Content::text(text).with_audience(vec![Role::Assistant])
+        ]))
+    }
+
+    /// Snapshot the working tree of the current git repository so it can be
+    /// rolled back later with git_restore.
+    #[tool(
+        name = "git_checkpoint",
+        description = "Snapshot the current git working tree (tracked and untracked files, excluding gitignored and .gooseignore'd ones) as a named checkpoint before making risky changes. Does not modify HEAD, branches, the index or stashes. Use git_restore to roll back."
+    )]
+    pub async fn git_checkpoint(
+        &self,
+        params: Parameters<GitCheckpointParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let name = params.0.name.unwrap_or_else(|| {
+            chrono::Local::now()
+                .format("checkpoint-%Y%m%d-%H%M%S")
+                .to_string()
+        });
+        let cwd = working_dir();
+        let commit =
+            git_checkpoint::create_checkpoint(&cwd, &name, |path| self.is_ignored(path)).await?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Created checkpoint '{}' ({}). Use git_restore with name '{}' to roll back.",
+            name,
+            commit.get(..12).unwrap_or(&commit),
+            name
+        ))]))
+    }
+
+    /// Roll the working tree back to a checkpoint created with git_checkpoint.
+    #[tool(
+        name = "git_restore",
+        description = "Roll the git working tree back to a checkpoint created with git_checkpoint. Restores file contents and deletes files created since the checkpoint; gitignored and .gooseignore'd files, HEAD and the index are left untouched."
+    )]
+    pub async fn git_restore(
+        &self,
+        params: Parameters<GitRestoreParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let name = params.0.name;
+        let cwd = working_dir();
+        let removed =
+            git_checkpoint::restore_checkpoint(&cwd, &name, |path| self.is_ignored(path)).await?;
+
+        let mut message = format!("Restored the working tree to checkpoint '{}'.", name);
+        if !removed.is_empty() {
+            message.push_str(&format!(
+                "\nRemoved files created since the checkpoint:\n{}",
+                removed.join("\n")
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(message)]))
+    }
+
     /// Process an image file from disk.
     ///
     /// The image will be:
@@ -1160,9 +2128,10 @@ impl DeveloperServer {
     /// 3. Returned as base64 encoded data
     ///
     /// This allows processing image files for use in the conversation.
+    /// With extract_text, the text recognized in the image is returned as well.
     #[tool(
         name = "image_processor",
-        description = "Process an image file from disk. Resizes if needed, converts to PNG, and returns as base64 data."
+        description = "Process an image file from disk. Resizes if needed, converts to PNG, and returns as base64 data. Set extract_text to also get the text in the image via OCR, which is more reliable than reading small fonts from the image."
     )]
     pub async fn image_processor(
         &self,
@@ -1181,12 +2150,13 @@ impl DeveloperServer {
         };
 
         // Check if file is ignored before proceeding
-        if self.is_ignored(&path) {
+        if let Some(rule) = self.ignore_reason(&path) {
             return Err(ErrorData::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!(
-                    "Access to '{}' is restricted by .gooseignore",
-                    path.display()
+                    "Access to '{}' is restricted by .gooseignore rule {}",
+                    path.display(),
+                    rule
                 ),
                 None,
             ));
@@ -1225,14 +2195,14 @@ impl DeveloperServer {
             ));
         }
 
-        // Open and decode the image
-        let image = xcap::image::open(&path).map_err(|e| {
-            ErrorData::new(
-                ErrorCode::INTERNAL_ERROR,
-                format!("Failed to open image file: {}", e),
-                None,
-            )
-        })?;
+        let image = open_image(&path)?;
+
+        // OCR the full-resolution image before it is downscaled
+        let extracted_text = if params.extract_text.unwrap_or(false) {
+            Some(ocr::extract_text(&image).await)
+        } else {
+            None
+        };
 
         // Resize if necessary (same logic as screen_capture)
         let mut processed_image = image;
@@ -1262,19 +2232,31 @@ impl DeveloperServer {
 
         let data = base64::prelude::BASE64_STANDARD.encode(bytes);
 
-        Ok(CallToolResult::success(vec![
+        let mut content = vec![
             Content::text(format!(
                 "Successfully processed image from {}",
                 path.display()
             ))
             .with_audience(vec![Role::Assistant]),
             Content::image(data, "image/png").with_priority(0.0),
-        ]))
+        ];
+        let extracted_text = match extracted_text {
+            Some(Ok(text)) if text.is_empty() => {
+                Some("No text was found in the image.".to_string())
+            }
+            Some(Ok(text)) => Some(format!("Text extracted from the image:\n{}", text)),
+            Some(Err(note)) => Some(note),
+            None => None,
+        };
+        if let Some(text) = extracted_text {
+            content.push(Content::text(text).with_audience(vec![Role::Assistant]));
+        }
+        Ok(CallToolResult::success(content))
     }
 
     // Helper method to resolve and validate file paths
     fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ErrorData> {
-        let cwd = std::env::current_dir().expect("should have a current working dir");
+        let cwd = working_dir();
         let expanded = expand_path(path_str);
         let path = Path::new(&expanded);
 
@@ -1282,20 +2264,18 @@ impl DeveloperServer {
         if is_absolute_path(&expanded) {
             Ok(path.to_path_buf())
         } else {
-            // For relative paths, resolve them relative to the current working directory
+            // For relative paths, resolve them relative to the session's working directory
             Ok(cwd.join(path))
         }
     }
 
     fn build_ignore_patterns(cwd: &PathBuf) -> Gitignore {
         let mut builder = GitignoreBuilder::new(cwd);
-        let local_ignore_path = cwd.join(".gooseignore");
 
         let global_ignore_path = etcetera::choose_app_strategy(crate::APP_STRATEGY.clone())
             .map(|strategy| strategy.config_dir().join(".gooseignore"))
             .ok();
 
-        let has_local_ignore = local_ignore_path.is_file();
         let has_global_ignore = global_ignore_path
             .as_ref()
             .map(|p| p.is_file())
@@ -1305,11 +2285,24 @@ impl DeveloperServer {
             let _ = builder.add(global_ignore_path.as_ref().unwrap());
         }
 
-        if has_local_ignore {
-            let _ = builder.add(&local_ignore_path);
+        // Like git, rules in directories closer to cwd take precedence, so add the
+        // farthest ancestor first
+        let mut has_local_ignore = false;
+        for dir in gooseignore_search_dirs(cwd).iter().rev() {
+            let ignore_path = dir.join(".gooseignore");
+            let Ok(content) = std::fs::read_to_string(&ignore_path) else {
+                continue;
+            };
+            has_local_ignore = true;
+            let prefix = cwd.strip_prefix(dir).unwrap_or(Path::new(""));
+            for line in content.lines() {
+                if let Some(line) = rebase_ignore_line(line, prefix) {
+                    let _ = builder.add_line(Some(ignore_path.clone()), &line);
+                }
+            }
         }
 
-        if !has_local_ignore && !has_global_ignore {
+        if !has_local_ignore && !has_global_ignore && ignore_defaults_enabled() {
             let _ = builder.add_line(None, "**/.env");
             let _ = builder.add_line(None, "**/.env.*");
             let _ = builder.add_line(None, "**/secrets.*");
@@ -1318,9 +2311,36 @@ impl DeveloperServer {
         builder.build().expect("Failed to build ignore patterns")
     }
 
+    /// The rule that makes `path` ignored and where it came from, e.g.
+    /// `'*.key' from /repo/.gooseignore`, or `None` if the path isn't ignored.
+    fn ignore_reason(&self, path: &Path) -> Option<String> {
+        match self.ignore_patterns.matched(path, false) {
+            Match::Ignore(glob) => Some(match glob.from() {
+                Some(source) => format!("'{}' from {}", glob.original(), source.display()),
+                None => format!("'{}' (built-in default)", glob.original()),
+            }),
+            _ => None,
+        }
+    }
+
     // Helper method to check if a path should be ignored
     fn is_ignored(&self, path: &Path) -> bool {
-        self.ignore_patterns.matched(path, false).is_ignore()
+        self.ignore_reason(path).is_some()
+    }
+
+    /// Like `ignore_reason`, but lets an ignored path through when the user approved the
+    /// call's `allow_ignored`, logging the access.
+    fn blocking_ignore_rule(&self, path: &Path, allow_ignored: bool) -> Option<String> {
+        let reason = self.ignore_reason(path)?;
+        if allow_ignored {
+            tracing::warn!(
+                "Allowing access to '{}', which is restricted by .gooseignore rule {}",
+                path.display(),
+                reason
+            );
+            return None;
+        }
+        Some(reason)
     }
 
     // Only returns true when 100% certain (checks /proc/1/cgroup for container markers)
@@ -1393,14 +2413,37 @@ impl DeveloperServer {
     }
 
     // shell output can be large, this will help manage that
-    fn process_shell_output(&self, output_str: &str) -> Result<(String, String), ErrorData> {
+    fn process_shell_output(
+        &self,
+        output_str: &str,
+        limits: &OutputLimits,
+    ) -> Result<(String, String), ErrorData> {
         let lines: Vec<&str> = output_str.lines().collect();
         let line_count = lines.len();
+        let truncated = line_count > limits.head_lines + limits.tail_lines;
 
-        let start = lines.len().saturating_sub(100);
-        let last_100_lines_str = lines[start..].join("\n");
+        let head = &lines[..limits.head_lines.min(line_count)];
+        let tail = &lines[line_count.saturating_sub(limits.tail_lines)..];
+        let shown_lines_str = if limits.head_lines > 0 && truncated {
+            format!(
+                "{}\n... ({} lines omitted) ...\n{}",
+                head.join("\n"),
+                line_count - limits.head_lines - limits.tail_lines,
+                tail.join("\n")
+            )
+        } else {
+            tail.join("\n")
+        };
+        let shown_description = if limits.head_lines > 0 {
+            format!(
+                "the first {} and last {} lines",
+                limits.head_lines, limits.tail_lines
+            )
+        } else {
+            format!("the last {} lines", limits.tail_lines)
+        };
 
-        let final_output = if line_count > 100 {
+        let final_output = if truncated {
             let tmp_file = tempfile::NamedTempFile::new().map_err(|e| {
                 ErrorData::new(
                     ErrorCode::INTERNAL_ERROR,
@@ -1426,19 +2469,20 @@ impl DeveloperServer {
             })?;
 
             format!(
-                "private note: output was {} lines and we are only showing the most recent lines, remainder of lines in {} do not show tmp file to user, that file can be searched if extra context needed to fulfill request. truncated output: \n{}",
+                "private note: output was {} lines and we are only showing {}, remainder of lines in {} do not show tmp file to user, that file can be searched if extra context needed to fulfill request. truncated output: \n{}",
                 line_count,
+                shown_description,
                 path.display(),
-                last_100_lines_str
+                shown_lines_str
             )
         } else {
             output_str.to_string()
         };
 
-        let user_output = if line_count > 100 {
+        let user_output = if truncated {
             format!(
-                "NOTE: Output was {} lines, showing only the last 100 lines.\n\n{}",
-                line_count, last_100_lines_str
+                "NOTE: Output was {} lines, showing only {}.\n\n{}",
+                line_count, shown_description, shown_lines_str
             )
         } else {
             output_str.to_string()
@@ -1448,6 +2492,102 @@ impl DeveloperServer {
     }
 }
 
+/// Refuses edits that would write the [`REDACTED`] placeholder over a secret the model was
+/// only shown in redacted form.
+/// Asks the user a yes-or-no question through an elicitation, returning whether they approved.
+async fn ask_approval(
+    peer: &Peer<RoleServer>,
+    message: String,
+    description: &'static str,
+) -> Result<bool, rmcp::ServiceError> {
+    let request = CreateElicitationRequestParam {
+        message,
+        requested_schema: ElicitationSchema::builder()
+            .required_bool_with("approve", |schema| schema.description(description))
+            .build_unchecked(),
+    };
+    let result = peer.create_elicitation(request).await?;
+    Ok(result.action == ElicitationAction::Accept
+        && result
+            .content
+            .as_ref()
+            .and_then(|content| content.get("approve"))
+            .and_then(|approve| approve.as_bool())
+            .unwrap_or(false))
+}
+
+/// Asks the user whether a call that set `allow_ignored` may get past `restriction`. When the
+/// question can't be asked, the path stays restricted.
+async fn confirm_ignored_access(restriction: &str, peer: &Peer<RoleServer>) -> bool {
+    let message = format!("{}. The agent asked to access it anyway.", restriction);
+    match ask_approval(peer, message, "Allow this access").await {
+        Ok(approved) => approved,
+        Err(e) => {
+            tracing::warn!("Could not ask the user to approve ignored access: {}", e);
+            false
+        }
+    }
+}
+
+fn reject_redacted_placeholder(path: &Path, params: &TextEditorParams) -> Result<(), ErrorData> {
+    let introduces = match params.command.as_str() {
+        "write" => {
+            let existing = std::fs::read_to_string(path).unwrap_or_default();
+            introduces_placeholder(&existing, params.file_text.as_deref().unwrap_or_default())
+        }
+        "str_replace" => match params.diff.as_deref() {
+            Some(diff) => diff_introduces_placeholder(diff),
+            None => introduces_placeholder(
+                params.old_str.as_deref().unwrap_or_default(),
+                params.new_str.as_deref().unwrap_or_default(),
+            ),
+        },
+        "insert" => introduces_placeholder("", params.new_str.as_deref().unwrap_or_default()),
+        _ => false,
+    };
+    if !introduces {
+        return Ok(());
+    }
+    Err(ErrorData::new(
+        ErrorCode::INVALID_PARAMS,
+        format!(
+            "The edit would write {} into '{}'. It stands for a secret that was hidden when the \
+             file was read; leave those lines out of the edit instead of copying the placeholder.",
+            REDACTED,
+            path.display()
+        ),
+        None,
+    ))
+}
+
+/// Decodes the image at `path`, detecting the format from its contents. Formats whose
+/// decoder isn't compiled in (AVIF needs `dav1d`) are reported by name.
+fn open_image(path: &Path) -> Result<image::DynamicImage, ErrorData> {
+    let internal_error = |message: String| ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None);
+
+    let reader = image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| internal_error(format!("Failed to open image file: {}", e)))?;
+    let Some(format) = reader.format() else {
+        return Err(internal_error(format!(
+            "'{}' is not in a recognized image format",
+            path.display()
+        )));
+    };
+    let format_name = format!("{:?}", format).to_uppercase();
+    if !format.reading_enabled() {
+        return Err(internal_error(format!(
+            "'{}' is a {} image, but {} decoding is not available in this build",
+            path.display(),
+            format_name,
+            format_name
+        )));
+    }
+    reader
+        .decode()
+        .map_err(|e| internal_error(format!("Failed to decode {} image: {}", format_name, e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1522,6 +2662,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "".to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1542,6 +2683,85 @@ mod tests {
         });
     }
 
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_timeout_kills_command() {
+        run_shell_test(|| async {
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+
+            let start = std::time::Instant::now();
+            let result = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "sleep 30".to_string(),
+                        timeout_secs: Some(1),
+                        ..Default::default()
+                    }),
+                    RequestContext {
+                        ct: Default::default(),
+                        id: NumberOrString::Number(1),
+                        meta: Default::default(),
+                        extensions: Default::default(),
+                        peer: peer.clone(),
+                    },
+                )
+                .await;
+
+            let err = result.expect_err("command should time out");
+            assert!(err.message.contains("timed out after 1 seconds"));
+            assert!(start.elapsed() < Duration::from_secs(10));
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_returns_exit_code() {
+        run_shell_test(|| async {
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+
+            for (command, expected) in [("true", 0), ("echo failing; exit 3", 3)] {
+                let result = server
+                    .shell(
+                        Parameters(ShellParams {
+                            command: command.to_string(),
+                            ..Default::default()
+                        }),
+                        RequestContext {
+                            ct: Default::default(),
+                            id: NumberOrString::Number(1),
+                            meta: Default::default(),
+                            extensions: Default::default(),
+                            peer: peer.clone(),
+                        },
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(
+                    result.structured_content,
+                    Some(serde_json::json!({ "exit_code": expected }))
+                );
+                let assistant_text = &result.content[0].as_text().unwrap().text;
+                assert_eq!(
+                    assistant_text.contains("Command exited with code 3"),
+                    expected == 3,
+                    "{}",
+                    assistant_text
+                );
+            }
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
     #[test]
     #[serial]
     #[cfg(windows)]
@@ -1557,6 +2777,7 @@ mod tests {
             // Test PowerShell command
             let shell_params = Parameters(ShellParams {
                 command: "Get-ChildItem".to_string(),
+                ..Default::default()
             });
 
             let result = server
@@ -1610,6 +2831,7 @@ mod tests {
                 new_str: None,
                 insert_line: None,
                 diff: None,
+                ..Default::default()
             });
 
             let result = server.text_editor(view_params).await;
@@ -1637,6 +2859,7 @@ mod tests {
                 new_str: None,
                 insert_line: None,
                 diff: None,
+                ..Default::default()
             });
 
             let result = server.text_editor(view_params).await;
@@ -1668,6 +2891,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -1682,6 +2906,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let view_result = server.text_editor(view_params).await.unwrap();
@@ -1720,6 +2945,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -1734,6 +2960,7 @@ mod tests {
             new_str: Some("Rust".to_string()),
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let replace_result = server.text_editor(replace_params).await.unwrap();
@@ -1759,6 +2986,152 @@ mod tests {
         assert!(content.contains("Hello, Rust!"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_str_replace_all() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.py");
+        let file_path_str = file_path.to_str().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        fs::write(&file_path, "count = 0\ncount += 1\nprint(count)\n").unwrap();
+
+        let server = create_test_server();
+        let replace = |replace_all| {
+            Parameters(TextEditorParams {
+                path: file_path_str.to_string(),
+                command: "str_replace".to_string(),
+                view_range: None,
+                file_text: None,
+                old_str: Some("count".to_string()),
+                new_str: Some("total".to_string()),
+                insert_line: None,
+                diff: None,
+                replace_all,
+                ..Default::default()
+            })
+        };
+
+        // Without replace_all an ambiguous match is still an error
+        assert!(server.text_editor(replace(None)).await.is_err());
+        assert!(server.text_editor(replace(Some(false))).await.is_err());
+
+        let result = server.text_editor(replace(Some(true))).await.unwrap();
+        let assistant_content = result
+            .content
+            .iter()
+            .find(|c| {
+                c.audience()
+                    .is_some_and(|roles| roles.contains(&Role::Assistant))
+            })
+            .unwrap()
+            .as_text()
+            .unwrap();
+        assert!(assistant_content.text.contains("replaced 3 occurrences"));
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "total = 0\ntotal += 1\nprint(total)\n"
+        );
+
+        let undo_params = Parameters(TextEditorParams {
+            path: file_path_str.to_string(),
+            command: "undo_edit".to_string(),
+            view_range: None,
+            file_text: None,
+            old_str: None,
+            new_str: None,
+            insert_line: None,
+            diff: None,
+            ..Default::default()
+        });
+        server.text_editor(undo_params).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "count = 0\ncount += 1\nprint(count)\n"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_undo_edit_persisted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let file_path = temp_dir.path().join("notes.txt");
+        fs::write(&file_path, "Original content").unwrap();
+        let history_dir = temp_dir.path().join("file_history");
+        let params = |command: &str, old_str: Option<&str>, new_str: Option<&str>| {
+            Parameters(TextEditorParams {
+                path: file_path.to_str().unwrap().to_string(),
+                command: command.to_string(),
+                view_range: None,
+                file_text: None,
+                old_str: old_str.map(str::to_string),
+                new_str: new_str.map(str::to_string),
+                insert_line: None,
+                diff: None,
+                ..Default::default()
+            })
+        };
+
+        let server = create_test_server().file_history_dir(Some(history_dir.clone()));
+        server
+            .text_editor(params("str_replace", Some("Original"), Some("Modified")))
+            .await
+            .unwrap();
+        drop(server);
+
+        // A new server, as after a restart, can still undo the edit
+        let server = create_test_server().file_history_dir(Some(history_dir));
+        server
+            .text_editor(params("undo_edit", None, None))
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "Original content");
+        assert!(server
+            .text_editor(params("undo_edit", None, None))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_persists_history_of_every_file_in_a_diff() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        fs::write(&first, "old\n").unwrap();
+        fs::write(&second, "old\n").unwrap();
+        let history_dir = temp_dir.path().join("file_history");
+
+        let server = create_test_server().file_history_dir(Some(history_dir.clone()));
+        let diff = "--- a/first.txt\n+++ b/first.txt\n@@ -1 +1 @@\n-old\n+new\n\
+                    --- a/second.txt\n+++ b/second.txt\n@@ -1 +1 @@\n-old\n+new\n";
+        server
+            .text_editor(Parameters(TextEditorParams {
+                path: first.to_str().unwrap().to_string(),
+                command: "str_replace".to_string(),
+                diff: Some(diff.to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        drop(server);
+
+        // After a restart, both files can still be undone
+        let server = create_test_server().file_history_dir(Some(history_dir));
+        for path in [&first, &second] {
+            server
+                .text_editor(Parameters(TextEditorParams {
+                    path: path.to_str().unwrap().to_string(),
+                    command: "undo_edit".to_string(),
+                    ..Default::default()
+                }))
+                .await
+                .unwrap();
+            assert_eq!(fs::read_to_string(path).unwrap(), "old\n");
+        }
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_undo_edit() {
@@ -1779,6 +3152,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -1793,6 +3167,7 @@ mod tests {
             new_str: Some("Modified".to_string()),
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(replace_params).await.unwrap();
@@ -1811,6 +3186,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let undo_result = server.text_editor(undo_params).await.unwrap();
@@ -1829,6 +3205,57 @@ mod tests {
         assert!(undo_content.text.contains("Undid the last edit"));
     }
 
+    #[test]
+    fn test_gooseignore_merges_ancestor_files_up_to_repo_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("repo");
+        let package = root.join("packages").join("app");
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(&package).unwrap();
+        fs::write(temp_dir.path().join(".gooseignore"), "*.txt").unwrap();
+        fs::write(
+            root.join(".gooseignore"),
+            "*.secret\n/packages/app/build\n/packages/other/dist\n",
+        )
+        .unwrap();
+        fs::write(package.join(".gooseignore"), "local.key\n!keep.secret").unwrap();
+
+        assert_eq!(
+            gooseignore_search_dirs(&package),
+            vec![package.clone(), root.join("packages"), root.clone()]
+        );
+
+        let patterns = DeveloperServer::build_ignore_patterns(&package);
+        let ignored = |p: &str| patterns.matched(package.join(p), false).is_ignore();
+        assert!(ignored("token.secret"));
+        assert!(ignored("nested/token.secret"));
+        assert!(ignored("build"));
+        assert!(ignored("local.key"));
+        assert!(!ignored("keep.secret"));
+        assert!(!ignored("src/build"));
+        assert!(!ignored("notes.txt"));
+    }
+
+    #[test]
+    fn test_rebase_ignore_line() {
+        let prefix = Path::new("packages/app");
+        assert_eq!(rebase_ignore_line("*.log", prefix), Some("*.log".into()));
+        assert_eq!(
+            rebase_ignore_line("/packages/app/dist/", prefix),
+            Some("/dist/".into())
+        );
+        assert_eq!(
+            rebase_ignore_line("!packages/app/keep", prefix),
+            Some("!/keep".into())
+        );
+        assert_eq!(rebase_ignore_line("/packages/other/dist", prefix), None);
+        assert_eq!(rebase_ignore_line("# comment", prefix), None);
+        assert_eq!(
+            rebase_ignore_line("/dist", Path::new("")),
+            Some("/dist".into())
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_goose_ignore_basic_patterns() {
@@ -1891,6 +3318,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(write_params).await;
@@ -1898,7 +3326,18 @@ mod tests {
             result.is_err(),
             "Should not be able to write to ignored file"
         );
-        assert_eq!(result.unwrap_err().code, ErrorCode::INTERNAL_ERROR);
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::INTERNAL_ERROR);
+        // The error names the rule and the file it came from
+        let rule_source = temp_dir.path().join(".gooseignore");
+        assert!(
+            err.message.ends_with(&format!(
+                "restricted by .gooseignore rule 'secret.txt' from {}",
+                rule_source.display()
+            )),
+            "{}",
+            err.message
+        );
 
         // Try to write to a non-ignored file
         let allowed_path = temp_dir.path().join("allowed.txt");
@@ -1911,6 +3350,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(write_params).await;
@@ -1918,6 +3358,22 @@ mod tests {
             result.is_ok(),
             "Should be able to write to non-ignored file"
         );
+
+        // An explicit allow_ignored lets a single call through
+        let write_params = Parameters(TextEditorParams {
+            path: secret_path.to_str().unwrap().to_string(),
+            command: "write".to_string(),
+            view_range: None,
+            file_text: Some("test content".to_string()),
+            old_str: None,
+            new_str: None,
+            insert_line: None,
+            diff: None,
+            allow_ignored: Some(true),
+            ..Default::default()
+        });
+        server.text_editor(write_params).await.unwrap();
+        assert_eq!(fs::read_to_string(&secret_path).unwrap(), "test content");
     }
 
     #[test]
@@ -1940,6 +3396,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", secret_file_path.to_str().unwrap()),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1962,6 +3419,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", allowed_file_path.to_str().unwrap()),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1983,6 +3441,145 @@ mod tests {
         });
     }
 
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_cwd() {
+        run_shell_test(|| async {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::env::set_current_dir(&temp_dir).unwrap();
+            fs::write(".gooseignore", "private").unwrap();
+            fs::create_dir_all(temp_dir.path().join("sub")).unwrap();
+            fs::create_dir_all(temp_dir.path().join("private")).unwrap();
+
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+            let run = |cwd: &str| {
+                server.shell(
+                    Parameters(ShellParams {
+                        command: "pwd".to_string(),
+                        cwd: Some(cwd.to_string()),
+                        ..Default::default()
+                    }),
+                    RequestContext {
+                        ct: Default::default(),
+                        id: NumberOrString::Number(1),
+                        meta: Default::default(),
+                        extensions: Default::default(),
+                        peer: peer.clone(),
+                    },
+                )
+            };
+
+            let result = run("sub").await.unwrap();
+            let output = result.content[0].as_text().unwrap().text.clone();
+            assert!(
+                output.trim_end().ends_with("sub"),
+                "unexpected output: {}",
+                output
+            );
+
+            let err = run("missing").await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+            let err = run("private").await.unwrap_err();
+            assert!(err.message.contains(".gooseignore"));
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
+    #[tokio::test]
+    #[serial]
+    #[cfg(unix)]
+    async fn test_shell_background_jobs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let server = create_test_server();
+        let start = |command: &str| {
+            server.shell_background(Parameters(ShellBackgroundParams {
+                command: command.to_string(),
+                cwd: None,
+            }))
+        };
+        let job = |job_id: &str| {
+            Parameters(ShellJobParams {
+                job_id: job_id.to_string(),
+            })
+        };
+        let text = |result: CallToolResult| result.content[0].as_text().unwrap().text.clone();
+
+        let started = text(start("echo hello").await.unwrap());
+        assert!(started.contains("job-1"));
+        let mut output = String::new();
+        for _ in 0..50 {
+            output.push_str(&text(server.shell_poll(job("job-1")).await.unwrap()));
+            if output.contains("exited") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(output.contains("exited with code 0"), "{}", output);
+        assert!(output.contains("hello"), "{}", output);
+        // Finished jobs are forgotten after their final poll
+        assert!(server.shell_poll(job("job-1")).await.is_err());
+
+        start("sleep 30").await.unwrap();
+        assert!(text(server.shell_poll(job("job-2")).await.unwrap()).contains("running"));
+        let killed = text(server.shell_kill(job("job-2")).await.unwrap());
+        assert!(killed.contains("killed"));
+        assert!(!server.running_processes.read().await.contains_key("job-2"));
+        assert_eq!(
+            text(server.list_processes().await.unwrap()),
+            "No shell processes are running."
+        );
+
+        start("sleep 30").await.unwrap();
+        let listed = text(server.list_processes().await.unwrap());
+        assert!(
+            listed.contains("job-3 (running for 0s): sleep 30"),
+            "{}",
+            listed
+        );
+        server.shell_kill(job("job-3")).await.unwrap();
+
+        start("true").await.unwrap();
+        let mut listed = String::new();
+        for _ in 0..50 {
+            listed = text(server.list_processes().await.unwrap());
+            if !listed.contains("running") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(
+            listed.contains("job-4 (exited with code 0): true"),
+            "{}",
+            listed
+        );
+        server.shell_poll(job("job-4")).await.unwrap();
+
+        start("sleep 30").await.unwrap();
+        start("sleep 31").await.unwrap();
+        let stopped = text(server.kill_all_processes().await.unwrap());
+        assert!(
+            stopped.starts_with("Stopped 2 shell process(es)"),
+            "{}",
+            stopped
+        );
+        assert!(stopped.contains("sleep 31"), "{}", stopped);
+        assert!(server.shell_poll(job("job-5")).await.is_err());
+        assert_eq!(
+            text(server.list_processes().await.unwrap()),
+            "No shell processes are running."
+        );
+        assert_eq!(
+            text(server.kill_all_processes().await.unwrap()),
+            "No shell processes are running."
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_descriptions() {
@@ -2028,6 +3625,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2042,6 +3640,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let view_result = server.text_editor(view_params).await.unwrap();
@@ -2089,6 +3688,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2103,6 +3703,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let view_result = server.text_editor(view_params).await.unwrap();
@@ -2149,6 +3750,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2163,6 +3765,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2193,6 +3796,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2207,6 +3811,7 @@ mod tests {
             new_str: Some("Line 1".to_string()),
             insert_line: Some(0),
             diff: None,
+            ..Default::default()
         });
 
         let insert_result = server.text_editor(insert_params).await.unwrap();
@@ -2250,6 +3855,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2264,6 +3870,7 @@ mod tests {
             new_str: Some("Line 3".to_string()),
             insert_line: Some(2),
             diff: None,
+            ..Default::default()
         });
 
         let insert_result = server.text_editor(insert_params).await.unwrap();
@@ -2312,6 +3919,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2326,6 +3934,7 @@ mod tests {
             new_str: Some("Line 4".to_string()),
             insert_line: Some(3),
             diff: None,
+            ..Default::default()
         });
 
         let insert_result = server.text_editor(insert_params).await.unwrap();
@@ -2369,6 +3978,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2383,6 +3993,7 @@ mod tests {
             new_str: Some("Line 4".to_string()),
             insert_line: Some(-1),
             diff: None,
+            ..Default::default()
         });
 
         let insert_result = server.text_editor(insert_params).await.unwrap();
@@ -2426,6 +4037,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2440,6 +4052,7 @@ mod tests {
             new_str: Some("Line 11".to_string()),
             insert_line: Some(10),
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(insert_params).await;
@@ -2470,6 +4083,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2484,6 +4098,7 @@ mod tests {
             new_str: None, // Missing required parameter
             insert_line: Some(1),
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(insert_params).await;
@@ -2502,6 +4117,7 @@ mod tests {
             new_str: Some("New text".to_string()),
             insert_line: None, // Missing required parameter
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(insert_params).await;
@@ -2532,6 +4148,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2546,6 +4163,7 @@ mod tests {
             new_str: Some("Inserted Line".to_string()),
             insert_line: Some(1),
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(insert_params).await.unwrap();
@@ -2560,6 +4178,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let undo_result = server.text_editor(undo_params).await.unwrap();
@@ -2599,6 +4218,7 @@ mod tests {
             new_str: Some("New line".to_string()),
             insert_line: Some(0),
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(insert_params).await;
@@ -2634,6 +4254,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2648,6 +4269,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2673,6 +4295,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2705,6 +4328,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2736,6 +4360,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2750,6 +4375,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2797,6 +4423,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         server.text_editor(write_params).await.unwrap();
@@ -2811,6 +4438,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(view_params).await;
@@ -2833,6 +4461,110 @@ mod tests {
         assert!(text.text.contains("100: Line 100"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_redacts_secrets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("settings.yaml");
+        std::env::set_current_dir(&temp_dir).unwrap();
+        fs::write(&file_path, "name: demo\napi_key: 'abc123def456ghi'\n").unwrap();
+
+        let server = create_test_server();
+        let view_params = Parameters(TextEditorParams {
+            path: file_path.to_str().unwrap().to_string(),
+            command: "view".to_string(),
+            view_range: None,
+            file_text: None,
+            old_str: None,
+            new_str: None,
+            insert_line: None,
+            diff: None,
+            ..Default::default()
+        });
+        let result = server.text_editor(view_params).await.unwrap();
+
+        let texts: Vec<String> = result
+            .content
+            .iter()
+            .map(|c| match &c.raw {
+                rmcp::model::RawContent::Text(text) => text.text.clone(),
+                rmcp::model::RawContent::Resource(resource) => match &resource.resource {
+                    rmcp::model::ResourceContents::TextResourceContents { text, .. } => {
+                        text.clone()
+                    }
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            })
+            .collect();
+        assert!(texts.iter().all(|text| !text.contains("abc123def456ghi")));
+        assert!(texts
+            .iter()
+            .any(|text| text.contains("api_key: '[REDACTED]'")));
+        assert!(texts.iter().any(|text| text.contains("1 secret value(s)")));
+        assert!(fs::read_to_string(&file_path)
+            .unwrap()
+            .contains("abc123def456ghi"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_edits_and_search_keep_secrets_redacted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("settings.yaml");
+        std::env::set_current_dir(&temp_dir).unwrap();
+        fs::write(&file_path, "name: demo\napi_key: 'abc123def456ghi'\n").unwrap();
+
+        let server = create_test_server();
+        let edit = |command: &str, file_text: Option<&str>, old_str: Option<&str>| {
+            Parameters(TextEditorParams {
+                path: file_path.to_str().unwrap().to_string(),
+                command: command.to_string(),
+                view_range: None,
+                file_text: file_text.map(str::to_string),
+                old_str: old_str.map(str::to_string),
+                new_str: old_str.map(|_| "name: app".to_string()),
+                insert_line: None,
+                diff: None,
+                ..Default::default()
+            })
+        };
+
+        let result = server
+            .text_editor(edit("str_replace", None, Some("name: demo")))
+            .await
+            .unwrap();
+        let output = serde_json::to_string(&result.content).unwrap();
+        assert!(!output.contains("abc123def456ghi"));
+
+        // Writing back what the model was shown would replace the real secret
+        let err = server
+            .text_editor(edit(
+                "write",
+                Some("name: app\napi_key: '[REDACTED]'\n"),
+                None,
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("[REDACTED]"));
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "name: app\napi_key: 'abc123def456ghi'\n"
+        );
+
+        let result = server
+            .search(Parameters(SearchParams {
+                pattern: "api_key".to_string(),
+                path: Some(temp_dir.path().to_str().unwrap().to_string()),
+                glob: None,
+                case_insensitive: None,
+            }))
+            .await
+            .unwrap();
+        let matches = result.structured_content.unwrap()["matches"].clone();
+        assert_eq!(matches[0]["matched_line"], "api_key: '[REDACTED]'");
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_view_directory() {
@@ -2864,6 +4596,7 @@ mod tests {
                 new_str: None,
                 insert_line: None,
                 diff: None,
+                ..Default::default()
             }))
             .await;
 
@@ -2877,19 +4610,66 @@ mod tests {
 
         // Check that it identifies as a directory
         assert!(output.contains("is a directory"));
-        assert!(output.contains("Contents:"));
+        assert!(output.contains("Contents (depth 2):"));
 
         // Check directories are listed with trailing slash
-        assert!(output.contains("Directories:"));
-        assert!(output.contains("another_dir/"));
-        assert!(output.contains("subdir1/"));
-        assert!(output.contains("subdir2/"));
+        assert!(output.contains("  another_dir/\n"));
+        assert!(output.contains("  subdir1/\n"));
+        assert!(output.contains("  subdir2/\n"));
+
+        // Check files are listed with their sizes
+        assert!(output.contains("  file1.txt (8B)"));
+        assert!(output.contains("  file2.rs (8B)"));
+        assert!(output.contains("  README.md (8B)"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_directory_tree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        std::env::set_current_dir(temp_path).unwrap();
+
+        fs::create_dir_all(temp_path.join("src/nested/deeper")).unwrap();
+        fs::write(temp_path.join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(temp_path.join("src/nested/mod.rs"), "").unwrap();
+        fs::write(temp_path.join("src/nested/deeper/hidden.rs"), "").unwrap();
+        fs::write(temp_path.join("secret.key"), "hunter2").unwrap();
+        fs::write(temp_path.join(".gooseignore"), "*.key\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(temp_path.join("src"), temp_path.join("loop")).unwrap();
+
+        let server = create_test_server();
+        let view = |max_depth| {
+            server.text_editor(Parameters(TextEditorParams {
+                command: "view".to_string(),
+                path: temp_path.to_str().unwrap().to_string(),
+                view_range: None,
+                max_depth,
+                file_text: None,
+                old_str: None,
+                new_str: None,
+                insert_line: None,
+                diff: None,
+                ..Default::default()
+            }))
+        };
 
-        // Check files are listed
-        assert!(output.contains("Files:"));
-        assert!(output.contains("file1.txt"));
-        assert!(output.contains("file2.rs"));
-        assert!(output.contains("README.md"));
+        let result = view(None).await.unwrap();
+        let output = &result.content[0].as_text().unwrap().text;
+        assert!(output.contains("  src/\n"));
+        assert!(output.contains("    lib.rs (12B)"));
+        assert!(output.contains("    nested/\n"));
+        assert!(!output.contains("mod.rs"));
+        assert!(!output.contains("secret.key"));
+        #[cfg(unix)]
+        assert!(output.contains("  loop@\n"));
+
+        let result = view(Some(3)).await.unwrap();
+        let output = &result.content[0].as_text().unwrap().text;
+        assert!(output.contains("      mod.rs (0B)"));
+        assert!(output.contains("      deeper/\n"));
+        assert!(!output.contains("hidden.rs"));
     }
 
     #[tokio::test]
@@ -2927,6 +4707,7 @@ mod tests {
                 new_str: None,
                 insert_line: None,
                 diff: None,
+                ..Default::default()
             }))
             .await;
 
@@ -2944,10 +4725,48 @@ mod tests {
 
         // Count the actual number of items shown (should be 50)
         let dir_count = output.matches("/\n").count(); // directories end with /
-        let file_count = output.matches(".txt\n").count(); // only counting .txt files for simplicity
+        let file_count = output.matches(".txt (").count(); // only counting .txt files for simplicity
         assert!(dir_count + file_count <= 50);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_view_directory_stops_descending_at_limit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        std::env::set_current_dir(temp_path).unwrap();
+
+        // Each directory takes two lines (itself and its file), so the listing is full
+        // after 25 of them and the other 35 are counted without being walked, so their
+        // files are left out of the count
+        for i in 0..60 {
+            let dir = temp_path.join(format!("dir{:02}", i));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("inner.txt"), "").unwrap();
+        }
+
+        let server = create_test_server();
+        let result = server
+            .text_editor(Parameters(TextEditorParams {
+                command: "view".to_string(),
+                path: temp_path.to_str().unwrap().to_string(),
+                view_range: None,
+                file_text: None,
+                old_str: None,
+                new_str: None,
+                insert_line: None,
+                diff: None,
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+        let output = &result.content[0].as_text().unwrap().text;
+        assert!(output.contains("  dir24/\n    inner.txt (0B)\n"));
+        assert!(!output.contains("dir25/"));
+        assert!(output.contains("... and at least 35 more items (showing first 50 items)"));
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_view_empty_directory() {
@@ -2969,6 +4788,7 @@ mod tests {
                 new_str: None,
                 insert_line: None,
                 diff: None,
+                ..Default::default()
             }))
             .await;
 
@@ -3006,6 +4826,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3099,6 +4920,126 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_window_info_summary() {
+        let mut window = WindowInfo {
+            title: "README.md - Zed".to_string(),
+            app_name: Some("Zed".to_string()),
+            x: Some(0),
+            y: Some(25),
+            width: Some(1440),
+            height: Some(875),
+        };
+        assert_eq!(
+            window.summary(),
+            "README.md - Zed [Zed] at (0, 25) 1440x875"
+        );
+        assert_eq!(
+            serde_json::to_value(&window).unwrap(),
+            serde_json::json!({
+                "title": "README.md - Zed",
+                "app_name": "Zed",
+                "x": 0,
+                "y": 25,
+                "width": 1440,
+                "height": 875
+            })
+        );
+
+        window.app_name = None;
+        window.x = None;
+        assert_eq!(window.summary(), "README.md - Zed 1440x875");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    #[serial]
+    async fn test_auto_format_formats_every_file_in_a_diff() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        fs::write(&first, "old\n").unwrap();
+        fs::write(&second, "old\n").unwrap();
+        let server = create_test_server().auto_format(true);
+
+        std::env::set_var(formatter::GOOSE_FORMATTERS, "txt=sed -i.bak s/foo/bar/");
+        let diff = "--- a/first.txt\n+++ b/first.txt\n@@ -1 +1 @@\n-old\n+foo\n\
+                    --- a/second.txt\n+++ b/second.txt\n@@ -1 +1 @@\n-old\n+foo\n";
+        let result = server
+            .text_editor(Parameters(TextEditorParams {
+                path: first.to_str().unwrap().to_string(),
+                command: "str_replace".to_string(),
+                diff: Some(diff.to_string()),
+                ..Default::default()
+            }))
+            .await;
+        std::env::remove_var(formatter::GOOSE_FORMATTERS);
+
+        result.unwrap();
+        assert_eq!(fs::read_to_string(&first).unwrap(), "bar\n");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "bar\n");
+    }
+
+    #[test]
+    fn test_open_image_formats() {
+        let dir = TempDir::new().unwrap();
+        let image = xcap::image::DynamicImage::new_rgba8(4, 3);
+
+        let webp = dir.path().join("picture.png");
+        image
+            .save_with_format(&webp, xcap::image::ImageFormat::WebP)
+            .unwrap();
+        let decoded = open_image(&webp).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 3));
+
+        let avif = dir.path().join("picture.avif");
+        fs::write(&avif, b"\x00\x00\x00\x1cftypavif\x00\x00\x00\x00avifmif1").unwrap();
+        let err = open_image(&avif).unwrap_err();
+        assert!(err.message.contains("AVIF"), "{}", err.message);
+
+        let text = dir.path().join("notes.png");
+        fs::write(&text, "not an image").unwrap();
+        let err = open_image(&text).unwrap_err();
+        assert!(err.message.contains("not in a recognized image format"));
+    }
+
+    #[test]
+    fn test_screen_capture_crop_region() {
+        let params = |x, y, width, height| ScreenCaptureParams {
+            display: None,
+            window_title: None,
+            x,
+            y,
+            width,
+            height,
+        };
+
+        assert_eq!(
+            params(None, None, None, None).crop_region(800, 600),
+            Ok(None)
+        );
+        assert_eq!(
+            params(Some(10), Some(20), Some(100), Some(50)).crop_region(800, 600),
+            Ok(Some((10, 20, 100, 50)))
+        );
+        assert_eq!(
+            params(Some(0), Some(0), Some(800), Some(600)).crop_region(800, 600),
+            Ok(Some((0, 0, 800, 600)))
+        );
+
+        let err = params(Some(700), Some(0), Some(200), Some(50))
+            .crop_region(800, 600)
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("outside the 800x600"));
+        assert!(params(Some(10), None, Some(100), Some(50))
+            .crop_region(800, 600)
+            .is_err());
+        assert!(params(Some(0), Some(0), Some(0), Some(50))
+            .crop_region(800, 600)
+            .is_err());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_process_shell_output_short() {
@@ -3109,13 +5050,53 @@ mod tests {
 
         // Test with short output (< 100 lines)
         let short_output = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
-        let result = server.process_shell_output(short_output).unwrap();
+        let result = server
+            .process_shell_output(short_output, &OutputLimits::default())
+            .unwrap();
 
         // Both outputs should be the same for short outputs
         assert_eq!(result.0, short_output);
         assert_eq!(result.1, short_output);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_process_shell_output_head_and_tail() {
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        let server = create_test_server();
+        let output = (1..=20)
+            .map(|i| format!("Line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let limits = OutputLimits {
+            head_lines: 2,
+            tail_lines: 3,
+            ..OutputLimits::default()
+        };
+
+        let (_, user_output) = server.process_shell_output(&output, &limits).unwrap();
+        assert_eq!(
+            user_output,
+            "NOTE: Output was 20 lines, showing only the first 2 and last 3 lines.\n\n\
+             Line 1\nLine 2\n... (15 lines omitted) ...\nLine 18\nLine 19\nLine 20"
+        );
+
+        // Output that fits within head + tail is returned unchanged
+        let limits = OutputLimits {
+            head_lines: 10,
+            tail_lines: 10,
+            ..OutputLimits::default()
+        };
+        let (final_output, _) = server.process_shell_output(&output, &limits).unwrap();
+        assert_eq!(final_output, output);
+
+        assert!(server
+            .validate_shell_output_size("cat", &output, 10)
+            .is_err());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_process_shell_output_empty() {
@@ -3126,7 +5107,9 @@ mod tests {
 
         // Test with empty output
         let empty_output = "";
-        let result = server.process_shell_output(empty_output).unwrap();
+        let result = server
+            .process_shell_output(empty_output, &OutputLimits::default())
+            .unwrap();
 
         // Both outputs should be empty
         assert_eq!(result.0, "");
@@ -3155,6 +5138,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3170,7 +5154,9 @@ mod tests {
 
             // Test the output processing logic that would be used by shell method
             let output_without_newline = "Content without newline";
-            let result = server.process_shell_output(output_without_newline).unwrap();
+            let result = server
+                .process_shell_output(output_without_newline, &OutputLimits::default())
+                .unwrap();
 
             // The output should contain the content even without a trailing newline
             assert!(
@@ -3204,7 +5190,7 @@ mod tests {
         // Test output truncation logic with content without trailing newlines
         let content_without_newline = "Content without newline";
         let result = server
-            .process_shell_output(content_without_newline)
+            .process_shell_output(content_without_newline, &OutputLimits::default())
             .unwrap();
 
         assert_eq!(result.0, content_without_newline);
@@ -3216,13 +5202,17 @@ mod tests {
 
         // Test with content that has trailing newlines
         let content_with_newline = "Content with newline\n";
-        let result = server.process_shell_output(content_with_newline).unwrap();
+        let result = server
+            .process_shell_output(content_with_newline, &OutputLimits::default())
+            .unwrap();
         assert_eq!(result.0, content_with_newline);
         assert_eq!(result.1, content_with_newline);
 
         // Test empty output handling
         let empty_output = "";
-        let result = server.process_shell_output(empty_output).unwrap();
+        let result = server
+            .process_shell_output(empty_output, &OutputLimits::default())
+            .unwrap();
         assert_eq!(result.0, "");
         assert_eq!(result.1, "");
     }
@@ -3253,6 +5243,16 @@ mod tests {
             !server.is_ignored(Path::new("normal.txt")),
             "normal.txt should not be ignored"
         );
+        assert_eq!(
+            server.ignore_reason(Path::new(".env")).as_deref(),
+            Some("'**/.env' (built-in default)")
+        );
+
+        let server = temp_env::with_var(GOOSE_IGNORE_DEFAULTS, Some("false"), create_test_server);
+        assert!(
+            !server.is_ignored(Path::new(".env")),
+            ".env should not be ignored when defaults are disabled"
+        );
     }
 
     #[test]
@@ -3283,6 +5283,24 @@ mod tests {
         assert_eq!(resolved, expected);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_resolve_path_prefers_goose_working_dir() {
+        let cwd = tempfile::tempdir().unwrap();
+        let session_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&cwd).unwrap();
+        std::env::set_var("GOOSE_WORKING_DIR", session_dir.path());
+
+        let server = create_test_server();
+        let resolved = server.resolve_path("subdir/test.txt");
+        std::env::remove_var("GOOSE_WORKING_DIR");
+
+        assert_eq!(
+            resolved.unwrap(),
+            session_dir.path().join("subdir/test.txt")
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_with_absolute_path() {
@@ -3302,6 +5320,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(write_params).await;
@@ -3329,6 +5348,7 @@ mod tests {
             new_str: None,
             insert_line: None,
             diff: None,
+            ..Default::default()
         });
 
         let result = server.text_editor(write_params).await;
@@ -3365,6 +5385,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "sleep 30".to_string(),
+                            ..Default::default()
                         }),
                         context,
                     )
@@ -3453,6 +5474,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "bash -c 'sleep 60 & wait'".to_string(),
+                            ..Default::default()
                         }),
                         context,
                     )
@@ -3550,6 +5572,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "echo 'Hello, World!'".to_string(),
+                        ..Default::default()
                     }),
                     context,
                 )
@@ -3571,4 +5594,205 @@ mod tests {
             cleanup_test_service(running_service, peer);
         });
     }
+
+    /// Answers every elicitation the way a user would in the client, counting the prompts.
+    #[derive(Clone)]
+    struct ElicitationClient {
+        /// `None` declines the request
+        approve: Option<bool>,
+        asked: Arc<AtomicUsize>,
+    }
+
+    impl rmcp::ClientHandler for ElicitationClient {
+        async fn create_elicitation(
+            &self,
+            _request: CreateElicitationRequestParam,
+            _context: RequestContext<rmcp::RoleClient>,
+        ) -> Result<rmcp::model::CreateElicitationResult, ErrorData> {
+            self.asked.fetch_add(1, Ordering::SeqCst);
+            Ok(match self.approve {
+                Some(approve) => rmcp::model::CreateElicitationResult {
+                    action: ElicitationAction::Accept,
+                    content: Some(serde_json::json!({ "approve": approve })),
+                },
+                None => rmcp::model::CreateElicitationResult {
+                    action: ElicitationAction::Decline,
+                    content: None,
+                },
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changelist_text_editor_asks_before_writing_outside() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let listed = temp_dir.path().join("listed.txt");
+        let server =
+            create_test_server().changelist(Some(Changelist::new(["listed.txt"], temp_dir.path())));
+        let write = |path: &Path| {
+            Parameters(TextEditorParams {
+                path: path.to_str().unwrap().to_string(),
+                command: "write".to_string(),
+                view_range: None,
+                file_text: Some("changed\n".to_string()),
+                old_str: None,
+                new_str: None,
+                insert_line: None,
+                diff: None,
+                ..Default::default()
+            })
+        };
+
+        for (approve, name) in [
+            (None, "inside.txt"),
+            (Some(true), "accepted.txt"),
+            (Some(false), "refused.txt"),
+            (None, "declined.txt"),
+        ] {
+            let (client_io, server_io) = tokio::io::duplex(4096);
+            let asked = Arc::new(AtomicUsize::new(0));
+            let client = serve_directly(
+                ElicitationClient {
+                    approve,
+                    asked: asked.clone(),
+                },
+                client_io,
+                None,
+            );
+            let running_service = serve_directly(server.clone(), server_io, None);
+            let peer = running_service.peer().clone();
+
+            let path = if name == "inside.txt" {
+                listed.clone()
+            } else {
+                temp_dir.path().join(name)
+            };
+            let result = server
+                .changelist_text_editor(write(&path), peer.clone())
+                .await;
+
+            match name {
+                "inside.txt" => {
+                    assert!(result.is_ok(), "{:?}", result);
+                    assert_eq!(asked.load(Ordering::SeqCst), 0);
+                }
+                "accepted.txt" => {
+                    assert!(result.is_ok(), "{:?}", result);
+                    assert_eq!(asked.load(Ordering::SeqCst), 1);
+                }
+                _ => {
+                    let err = result.unwrap_err();
+                    assert!(
+                        err.message.contains("outside the changelist"),
+                        "{}",
+                        err.message
+                    );
+                    assert_eq!(asked.load(Ordering::SeqCst), 1);
+                    assert!(!path.exists());
+                }
+            }
+
+            client.cancellation_token().cancel();
+            cleanup_test_service(running_service, peer);
+        }
+        assert_eq!(fs::read_to_string(&listed).unwrap(), "changed\n");
+    }
+
+    #[tokio::test]
+    async fn test_changelist_checks_every_file_in_a_diff() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let listed = temp_dir.path().join("listed.txt");
+        let unlisted = temp_dir.path().join("unlisted.txt");
+        fs::write(&listed, "old\n").unwrap();
+        fs::write(&unlisted, "old\n").unwrap();
+        let server =
+            create_test_server().changelist(Some(Changelist::new(["listed.txt"], temp_dir.path())));
+
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let asked = Arc::new(AtomicUsize::new(0));
+        let client = serve_directly(
+            ElicitationClient {
+                approve: Some(false),
+                asked: asked.clone(),
+            },
+            client_io,
+            None,
+        );
+        let running_service = serve_directly(server.clone(), server_io, None);
+        let peer = running_service.peer().clone();
+
+        let diff = "--- a/listed.txt\n+++ b/listed.txt\n@@ -1 +1 @@\n-old\n+new\n\
+                    --- a/unlisted.txt\n+++ b/unlisted.txt\n@@ -1 +1 @@\n-old\n+new\n";
+        let result = server
+            .changelist_text_editor(
+                Parameters(TextEditorParams {
+                    path: listed.to_str().unwrap().to_string(),
+                    command: "str_replace".to_string(),
+                    view_range: None,
+                    file_text: None,
+                    old_str: None,
+                    new_str: None,
+                    insert_line: None,
+                    diff: Some(diff.to_string()),
+                    ..Default::default()
+                }),
+                peer.clone(),
+            )
+            .await;
+
+        let err = result.unwrap_err();
+        assert!(err.message.contains("unlisted.txt"), "{}", err.message);
+        assert!(!err.message.contains("/listed.txt"), "{}", err.message);
+        assert_eq!(asked.load(Ordering::SeqCst), 1);
+        assert_eq!(fs::read_to_string(&listed).unwrap(), "old\n");
+        assert_eq!(fs::read_to_string(&unlisted).unwrap(), "old\n");
+
+        client.cancellation_token().cancel();
+        cleanup_test_service(running_service, peer);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_allow_ignored_asks_the_user() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        fs::write(".gooseignore", "secret.txt").unwrap();
+        let secret_path = temp_dir.path().join("secret.txt");
+        let server = create_test_server();
+
+        for (approve, written) in [(Some(false), false), (None, false), (Some(true), true)] {
+            let (client_io, server_io) = tokio::io::duplex(4096);
+            let asked = Arc::new(AtomicUsize::new(0));
+            let client = serve_directly(
+                ElicitationClient {
+                    approve,
+                    asked: asked.clone(),
+                },
+                client_io,
+                None,
+            );
+            let running_service = serve_directly(server.clone(), server_io, None);
+            let peer = running_service.peer().clone();
+
+            let result = server
+                .changelist_text_editor(
+                    Parameters(TextEditorParams {
+                        path: secret_path.to_str().unwrap().to_string(),
+                        command: "write".to_string(),
+                        file_text: Some("test content".to_string()),
+                        allow_ignored: Some(true),
+                        ..Default::default()
+                    }),
+                    peer.clone(),
+                )
+                .await;
+
+            assert_eq!(asked.load(Ordering::SeqCst), 1);
+            assert_eq!(result.is_ok(), written, "{:?}", result);
+            assert_eq!(secret_path.exists(), written);
+
+            client.cancellation_token().cancel();
+            cleanup_test_service(running_service, peer);
+        }
+    }
 }
@@ -0,0 +1,238 @@
+use std::path::Path;
+
+use ignore::gitignore::Gitignore;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::RegexBuilder;
+use rmcp::model::{CallToolResult, ErrorCode, ErrorData};
+use rmcp::schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Matches returned before the search stops early
+pub const MAX_SEARCH_MATCHES: usize = 500;
+
+/// Matched lines longer than this are cut short
+const MAX_LINE_CHARS: usize = 300;
+
+/// Files larger than this are skipped
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Parameters for the search tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchParams {
+    /// Regular expression to search for (Rust regex syntax)
+    pub pattern: String,
+
+    /// Optional: file or directory to search. Defaults to the current working directory.
+    pub path: Option<String>,
+
+    /// Optional: globs limiting which files are searched, e.g. ["*.rs", "!*_test.rs"]
+    pub glob: Option<Vec<String>>,
+
+    /// Optional: match case-insensitively. Defaults to false.
+    pub case_insensitive: Option<bool>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SearchMatch {
+    pub file: String,
+    pub line_number: usize,
+    pub column: usize,
+    pub matched_line: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    pub files_searched: usize,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+/// Searches files under `root` line by line, skipping anything matched by `.gitignore`
+/// or `ignore_patterns` as well as binary and very large files.
+pub fn search_files(
+    root: &Path,
+    params: &SearchParams,
+    ignore_patterns: &Gitignore,
+) -> Result<SearchResults, ErrorData> {
+    let regex = RegexBuilder::new(&params.pattern)
+        .case_insensitive(params.case_insensitive.unwrap_or(false))
+        .build()
+        .map_err(|e| invalid_params(format!("Invalid search pattern: {}", e)))?;
+
+    let mut walker = WalkBuilder::new(root);
+    walker.sort_by_file_name(|a, b| a.cmp(b));
+    if let Some(globs) = params.glob.as_ref().filter(|globs| !globs.is_empty()) {
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in globs {
+            overrides
+                .add(glob)
+                .map_err(|e| invalid_params(format!("Invalid glob '{}': {}", glob, e)))?;
+        }
+        walker.overrides(
+            overrides
+                .build()
+                .map_err(|e| invalid_params(format!("Invalid globs: {}", e)))?,
+        );
+    }
+    let ignore_patterns = ignore_patterns.clone();
+    walker.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        !ignore_patterns.matched(entry.path(), is_dir).is_ignore()
+    });
+
+    let mut matches = Vec::new();
+    let mut files_searched = 0;
+    let mut truncated = false;
+    'files: for entry in walker.build().flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if entry.metadata().map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        // Non-UTF-8 files are treated as binary and skipped
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        files_searched += 1;
+
+        let file = entry
+            .path()
+            .strip_prefix(root)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .to_string();
+        for (index, line) in content.lines().enumerate() {
+            let Some(found) = regex.find(line) else {
+                continue;
+            };
+            if matches.len() == MAX_SEARCH_MATCHES {
+                truncated = true;
+                break 'files;
+            }
+            matches.push(SearchMatch {
+                file: file.clone(),
+                line_number: index + 1,
+                column: line.split_at(found.start()).0.chars().count() + 1,
+                matched_line: truncate_line(line),
+            });
+        }
+    }
+
+    let note = truncated.then(|| {
+        format!(
+            "Stopped after {} matches. Narrow the search with a more specific pattern, path or glob.",
+            MAX_SEARCH_MATCHES
+        )
+    });
+    Ok(SearchResults {
+        matches,
+        files_searched,
+        truncated,
+        note,
+    })
+}
+
+fn truncate_line(line: &str) -> String {
+    let line = line.trim_end();
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}...", line.split_at(end).0),
+        None => line.to_string(),
+    }
+}
+
+impl SearchResults {
+    pub fn into_tool_result(self) -> Result<CallToolResult, ErrorData> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        Ok(CallToolResult::structured(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ignore::gitignore::GitignoreBuilder;
+
+    fn params(pattern: &str, glob: Option<Vec<&str>>) -> SearchParams {
+        SearchParams {
+            pattern: pattern.to_string(),
+            path: None,
+            glob: glob.map(|globs| globs.into_iter().map(str::to_string).collect()),
+            case_insensitive: None,
+        }
+    }
+
+    #[test]
+    fn test_search_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    run();\n}\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn run() {}\n").unwrap();
+        std::fs::write(root.join("notes.md"), "call run() first\n").unwrap();
+        std::fs::write(root.join("secret.env"), "run=1\n").unwrap();
+
+        let mut builder = GitignoreBuilder::new(root);
+        builder.add_line(None, "*.env").unwrap();
+        let ignore_patterns = builder.build().unwrap();
+
+        let results = search_files(root, &params(r"run\(", None), &ignore_patterns).unwrap();
+        assert_eq!(
+            results.matches,
+            vec![
+                SearchMatch {
+                    file: "notes.md".to_string(),
+                    line_number: 1,
+                    column: 6,
+                    matched_line: "call run() first".to_string(),
+                },
+                SearchMatch {
+                    file: "src/lib.rs".to_string(),
+                    line_number: 1,
+                    column: 8,
+                    matched_line: "pub fn run() {}".to_string(),
+                },
+                SearchMatch {
+                    file: "src/main.rs".to_string(),
+                    line_number: 2,
+                    column: 5,
+                    matched_line: "    run();".to_string(),
+                },
+            ]
+        );
+        assert!(!results.truncated);
+
+        let results =
+            search_files(root, &params("run", Some(vec!["*.rs"])), &ignore_patterns).unwrap();
+        assert_eq!(results.matches.len(), 2);
+        assert!(results.matches.iter().all(|m| m.file.ends_with(".rs")));
+
+        assert!(search_files(root, &params("(", None), &ignore_patterns).is_err());
+    }
+
+    #[test]
+    fn test_search_files_truncates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("many.txt"),
+            "match\n".repeat(MAX_SEARCH_MATCHES + 10),
+        )
+        .unwrap();
+
+        let results =
+            search_files(dir.path(), &params("match", None), &Gitignore::empty()).unwrap();
+        assert_eq!(results.matches.len(), MAX_SEARCH_MATCHES);
+        assert!(results.truncated);
+        assert!(results.note.is_some());
+    }
+}
@@ -1,4 +1,9 @@
-use std::{env, ffi::OsString, process::Stdio};
+use std::{borrow::Cow, env, ffi::OsString, path::PathBuf, process::Stdio, time::Duration};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::env_flag::env_flag;
 
 #[cfg(unix)]
 #[allow(unused_imports)] // False positive: trait is used for process_group method
@@ -65,6 +70,71 @@ impl ShellConfig {
             }
         }
     }
+
+    /// Adds the environment variables that force colored output.
+    pub fn force_color(&mut self) {
+        self.envs.extend(
+            FORCE_COLOR_ENVS
+                .iter()
+                .map(|(key, value)| (OsString::from(key), OsString::from(value))),
+        );
+    }
+}
+
+/// Makes commands emit color and streams it to the user with ANSI codes intact.
+/// The output stored in the conversation is always stripped of them.
+pub const GOOSE_SHELL_COLOR: &str = "GOOSE_SHELL_COLOR";
+
+/// Variables that ask common tools to keep coloring output written to a pipe
+const FORCE_COLOR_ENVS: &[(&str, &str)] = &[
+    ("CLICOLOR_FORCE", "1"),
+    ("FORCE_COLOR", "1"),
+    ("CARGO_TERM_COLOR", "always"),
+    ("PY_COLORS", "1"),
+];
+
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-_]").unwrap()
+});
+
+pub fn shell_color_enabled() -> bool {
+    env_flag(GOOSE_SHELL_COLOR, false)
+}
+
+/// Seconds a shell command may run before it is killed; unset or 0 means no limit.
+pub const GOOSE_SHELL_TIMEOUT: &str = "GOOSE_SHELL_TIMEOUT";
+
+pub fn shell_timeout_from_env() -> Option<Duration> {
+    env::var(GOOSE_SHELL_TIMEOUT)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+pub fn strip_ansi_codes(text: &str) -> Cow<'_, str> {
+    ANSI_ESCAPE.replace_all(text, "")
+}
+
+/// How much shell output is returned before it is truncated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputLimits {
+    /// Lines kept from the start of truncated output
+    pub head_lines: usize,
+    /// Lines kept from the end of truncated output
+    pub tail_lines: usize,
+    /// Output longer than this is rejected rather than truncated
+    pub max_chars: usize,
+}
+
+impl Default for OutputLimits {
+    fn default() -> Self {
+        Self {
+            head_lines: 0,
+            tail_lines: 100,
+            max_chars: 400_000, // 400KB
+        }
+    }
 }
 
 pub fn expand_path(path_str: &str) -> String {
@@ -92,6 +162,17 @@ pub fn is_absolute_path(path_str: &str) -> bool {
     }
 }
 
+/// The session's working directory: `GOOSE_WORKING_DIR` when it names a directory, otherwise
+/// the process's current directory. Relative paths, ignore patterns and shell commands all
+/// resolve against this so they agree with each other.
+pub fn working_dir() -> PathBuf {
+    env::var_os("GOOSE_WORKING_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
 pub fn normalize_line_endings(text: &str) -> String {
     if cfg!(windows) {
         // Ensure CRLF line endings on Windows
@@ -180,3 +261,21 @@ pub async fn kill_process_group(
         child.kill().await.map_err(|e| e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi_codes() {
+        assert_eq!(
+            strip_ansi_codes("\x1b[1m\x1b[32m   Compiling\x1b[0m goose v1.0.0"),
+            "   Compiling goose v1.0.0"
+        );
+        assert_eq!(
+            strip_ansi_codes("\x1b]8;;https://example.com\x07link\x1b]8;;\x07 done"),
+            "link done"
+        );
+        assert_eq!(strip_ansi_codes("plain text"), "plain text");
+    }
+}
@@ -243,6 +243,8 @@ new file mode 100644
             "", // old_str (ignored when diff is provided)
             "", // new_str (ignored when diff is provided)
             Some(diff),
+            false,
+            false,
             &None, // editor_model
             &history,
         )
@@ -254,6 +256,39 @@ new file mode 100644
         assert!(!content.contains("fn old_name()"));
     }
 
+    #[tokio::test]
+    async fn test_dry_run_diff_reports_hunks_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        let original = "fn one() {}\nfn two() {}\nfn three() {}\n";
+        std::fs::write(&file_path, original).unwrap();
+
+        let diff = r#"--- a/lib.rs
++++ b/lib.rs
+@@ -2,1 +2,1 @@
+-fn two() {}
++fn second() {}
+@@ -40,2 +40,2 @@
+ completely unrelated
+-context that is not there
++replacement
+"#;
+
+        let history = Arc::new(Mutex::new(HashMap::new()));
+        let result =
+            text_editor_replace(&file_path, "", "", Some(diff), false, true, &None, &history)
+                .await
+                .unwrap();
+
+        let report = &result[0].as_text().unwrap().text;
+        assert!(report.contains("1 of 2 hunk(s) would fail"));
+        assert!(report.contains("✓ hunk 1 at line 2 applies"));
+        assert!(report.contains("✗ hunk 2 at line 40 fails"));
+        assert!(report.contains("No files were changed."));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), original);
+        assert!(history.lock().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_empty_file_handling() {
         let temp_dir = TempDir::new().unwrap();
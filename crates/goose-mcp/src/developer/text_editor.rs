@@ -1,4 +1,5 @@
 use anyhow::Result;
+use ignore::gitignore::Gitignore;
 use indoc::formatdoc;
 use mpatch::{apply_patch, parse_diffs, PatchError};
 use std::{
@@ -13,12 +14,15 @@ use rmcp::model::{Content, ErrorCode, ErrorData, Role};
 
 use super::editor_models::EditorModel;
 use super::lang;
+use super::redact::{SecretRedactor, REDACTED};
 use super::shell::normalize_line_endings;
 
 // Constants
 pub const LINE_READ_LIMIT: usize = 2000;
 pub const MAX_DIFF_SIZE: usize = 1024 * 1024; // 1MB max diff size
 pub const MAX_FILES_IN_DIFF: usize = 100; // Maximum files in a multi-file diff
+pub const DEFAULT_VIEW_DEPTH: usize = 2; // How deep `view` descends into directories
+const MAX_DIRECTORY_ITEMS: usize = 50; // Maximum entries shown when viewing a directory
 
 /// Validates paths to prevent directory traversal attacks
 fn validate_path_safety(base_dir: &Path, target_path: &Path) -> Result<(), ErrorData> {
@@ -205,29 +209,8 @@ fn adjust_base_dir_for_overlap(base_dir: &Path, file_path: &Path) -> PathBuf {
     }
 }
 
-/// Applies a single patch and updates results
-fn apply_single_patch(
-    patch: &mpatch::Patch,
-    base_dir: &Path,
-    file_history: &std::sync::Arc<std::sync::Mutex<HashMap<PathBuf, Vec<String>>>>,
-    results: &mut DiffResults,
-    failed_hunks: &mut Vec<String>,
-) -> Result<(), ErrorData> {
-    let adjusted_base_dir = adjust_base_dir_for_overlap(base_dir, &patch.file_path);
-
-    let file_path = adjusted_base_dir.join(&patch.file_path);
-
-    // Validate path safety
-    validate_path_safety(&adjusted_base_dir, &file_path)?;
-
-    // Save history before modifying
-    let file_existed = file_path.exists();
-    if file_existed {
-        save_file_history(&file_path, file_history)?;
-    }
-
-    // Apply patch with fuzzy matching (70% similarity threshold)
-    let success = apply_patch(patch, &adjusted_base_dir, false, 0.7).map_err(|e| match e {
+fn patch_error(e: PatchError) -> ErrorData {
+    match e {
         PatchError::Io { path, source } => ErrorData::new(
             ErrorCode::INTERNAL_ERROR,
             format!("Failed to process '{}': {}", path.display(), source),
@@ -254,7 +237,32 @@ fn apply_single_patch(
             "Invalid patch format".to_string(),
             None,
         ),
-    })?;
+    }
+}
+
+/// Applies a single patch and updates results
+fn apply_single_patch(
+    patch: &mpatch::Patch,
+    base_dir: &Path,
+    file_history: &std::sync::Arc<std::sync::Mutex<HashMap<PathBuf, Vec<String>>>>,
+    results: &mut DiffResults,
+    failed_hunks: &mut Vec<String>,
+) -> Result<(), ErrorData> {
+    let adjusted_base_dir = adjust_base_dir_for_overlap(base_dir, &patch.file_path);
+
+    let file_path = adjusted_base_dir.join(&patch.file_path);
+
+    // Validate path safety
+    validate_path_safety(&adjusted_base_dir, &file_path)?;
+
+    // Save history before modifying
+    let file_existed = file_path.exists();
+    if file_existed {
+        save_file_history(&file_path, file_history)?;
+    }
+
+    // Apply patch with fuzzy matching (70% similarity threshold)
+    let success = apply_patch(patch, &adjusted_base_dir, false, 0.7).map_err(patch_error)?;
 
     if !success {
         // Collect information about failed hunks for better error reporting
@@ -369,12 +377,11 @@ fn report_partial_failures(failed_hunks: &[String]) {
     }
 }
 
-/// Applies any diff (single or multi-file) using mpatch for fuzzy matching
-pub async fn apply_diff(
+/// Validates and parses a diff, returning its patches and the directory they apply to
+fn prepare_diff(
     base_path: &Path,
     diff_content: &str,
-    file_history: &std::sync::Arc<std::sync::Mutex<HashMap<PathBuf, Vec<String>>>>,
-) -> Result<Vec<Content>, ErrorData> {
+) -> Result<(Vec<mpatch::Patch>, PathBuf), ErrorData> {
     validate_diff_size(diff_content)?;
     let patches = parse_diff_content(diff_content)?;
 
@@ -396,6 +403,159 @@ pub async fn apply_diff(
         base_path.to_path_buf()
     };
 
+    Ok((patches, base_dir))
+}
+
+/// The files a diff creates or modifies, resolved the same way `apply_diff` resolves them
+pub fn diff_target_paths(base_path: &Path, diff_content: &str) -> Result<Vec<PathBuf>, ErrorData> {
+    let (patches, base_dir) = prepare_diff(base_path, diff_content)?;
+    Ok(patches
+        .iter()
+        .map(|patch| {
+            adjust_base_dir_for_overlap(&base_dir, &patch.file_path).join(&patch.file_path)
+        })
+        .collect())
+}
+
+/// Old-file start line from each `@@` header, grouped per file in diff order
+fn hunk_header_lines(diff_content: &str) -> Vec<Vec<usize>> {
+    let mut files: Vec<Vec<usize>> = Vec::new();
+    for line in diff_content.lines() {
+        if line.starts_with("+++ ") {
+            files.push(Vec::new());
+        } else if let Some(range) = line.strip_prefix("@@ -") {
+            let start = range.split([',', ' ']).next().and_then(|n| n.parse().ok());
+            if let (Some(hunks), Some(start)) = (files.last_mut(), start) {
+                hunks.push(start);
+            }
+        }
+    }
+    files
+}
+
+/// 1-based line where a hunk's context and removed lines appear verbatim in `content`
+fn find_hunk_line(content: &str, hunk: &mpatch::Hunk) -> Option<usize> {
+    let expected: Vec<String> = hunk
+        .get_match_block()
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    if expected.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .windows(expected.len())
+        .position(|window| {
+            window
+                .iter()
+                .zip(&expected)
+                .all(|(actual, expected)| actual.trim_end() == expected.trim_end())
+        })
+        .map(|idx| idx + 1)
+}
+
+/// Checks every hunk of a diff against the files on disk without writing anything,
+/// reporting which hunks would apply and which would fail.
+pub fn dry_run_diff(base_path: &Path, diff_content: &str) -> Result<Vec<Content>, ErrorData> {
+    let (patches, base_dir) = prepare_diff(base_path, diff_content)?;
+    let header_lines = hunk_header_lines(diff_content);
+
+    let mut report = Vec::new();
+    let mut total_hunks = 0;
+    let mut failed_hunks = 0;
+
+    for (patch_idx, patch) in patches.iter().enumerate() {
+        let adjusted_base_dir = adjust_base_dir_for_overlap(&base_dir, &patch.file_path);
+        let file_path = adjusted_base_dir.join(&patch.file_path);
+        validate_path_safety(&adjusted_base_dir, &file_path)?;
+
+        let existing = std::fs::read_to_string(&file_path).ok();
+        let action = if existing.is_some() {
+            "modified"
+        } else {
+            "created"
+        };
+        report.push(format!(
+            "{} ({}, {} hunk(s))",
+            patch.file_path.display(),
+            action,
+            patch.hunks.len()
+        ));
+
+        for (hunk_idx, hunk) in patch.hunks.iter().enumerate() {
+            total_hunks += 1;
+            let mut single = patch.clone();
+            single.hunks = vec![hunk.clone()];
+            let applies =
+                apply_patch(&single, &adjusted_base_dir, true, 0.7).map_err(patch_error)?;
+
+            let header_line = header_lines
+                .get(patch_idx)
+                .and_then(|lines| lines.get(hunk_idx))
+                .copied();
+            let line = existing
+                .as_deref()
+                .and_then(|content| find_hunk_line(content, hunk))
+                .or(header_line);
+            let location = line.map(|l| format!(" at line {}", l)).unwrap_or_default();
+
+            if applies {
+                report.push(format!("  ✓ hunk {}{} applies", hunk_idx + 1, location));
+            } else {
+                failed_hunks += 1;
+                let first_expected = hunk
+                    .get_match_block()
+                    .first()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "(empty context)".to_string());
+                report.push(format!(
+                    "  ✗ hunk {}{} fails: context not found. First expected line: '{}'",
+                    hunk_idx + 1,
+                    location,
+                    first_expected
+                ));
+            }
+        }
+    }
+
+    let (lines_added, lines_removed) = count_line_changes(diff_content);
+    let verdict = if failed_hunks == 0 {
+        format!(
+            "Dry run: diff would apply cleanly to {} file(s), {} hunk(s), {} line(s) added, {} line(s) removed.",
+            patches.len(),
+            total_hunks,
+            lines_added,
+            lines_removed
+        )
+    } else {
+        format!(
+            "Dry run: {} of {} hunk(s) would fail to apply. Fix their context before applying the diff.",
+            failed_hunks, total_hunks
+        )
+    };
+    let summary = format!(
+        "{}\n\n{}\n\nNo files were changed.",
+        verdict,
+        report.join("\n")
+    );
+
+    Ok(vec![
+        Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+        Content::text(summary)
+            .with_audience(vec![Role::User])
+            .with_priority(0.2),
+    ])
+}
+
+/// Applies any diff (single or multi-file) using mpatch for fuzzy matching
+pub async fn apply_diff(
+    base_path: &Path,
+    diff_content: &str,
+    file_history: &std::sync::Arc<std::sync::Mutex<HashMap<PathBuf, Vec<String>>>>,
+) -> Result<Vec<Content>, ErrorData> {
+    let (patches, base_dir) = prepare_diff(base_path, diff_content)?;
+
     let mut results = DiffResults::default();
     let mut failed_hunks = Vec::new();
 
@@ -519,12 +679,60 @@ pub fn recommend_read_range(path: &Path, total_lines: usize) -> Result<Vec<Conte
     ), None))
 }
 
-/// Lists the contents of a directory with a maximum number of items
-fn list_directory_contents(path: &Path) -> Result<Vec<Content>, ErrorData> {
-    const MAX_ITEMS: usize = 50; // Maximum number of items to display
+/// Renders a directory as a tree down to `max_depth` levels, with file sizes. Entries
+/// matched by `ignore_patterns` are left out and symlinked directories are not followed.
+fn list_directory_tree(
+    path: &Path,
+    max_depth: usize,
+    ignore_patterns: &Gitignore,
+) -> Result<Vec<Content>, ErrorData> {
+    let mut lines = Vec::new();
+    let mut total_count = 0;
+    walk_directory(
+        path,
+        1,
+        max_depth,
+        ignore_patterns,
+        &mut lines,
+        &mut total_count,
+    )?;
+
+    let mut output = format!(
+        "'{}' is a directory. Contents (depth {}):\n\n",
+        path.display(),
+        max_depth
+    );
+
+    if lines.is_empty() {
+        output.push_str("  (empty directory)\n");
+    }
+    for line in &lines {
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    // If we hit the limit, indicate there are more items. Subdirectories past the limit
+    // aren't walked, so their contents are missing from the count
+    if total_count > MAX_DIRECTORY_ITEMS {
+        output.push_str(&format!(
+            "\n... and at least {} more items (showing first {} items)\n",
+            total_count - MAX_DIRECTORY_ITEMS,
+            MAX_DIRECTORY_ITEMS
+        ));
+    }
 
-    // List files in the directory (similar to ls output)
-    let entries = std::fs::read_dir(path).map_err(|e| {
+    Ok(vec![Content::text(output)])
+}
+
+fn walk_directory(
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    ignore_patterns: &Gitignore,
+    lines: &mut Vec<String>,
+    total_count: &mut usize,
+) -> Result<(), ErrorData> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
         ErrorData::new(
             ErrorCode::INTERNAL_ERROR,
             format!("Failed to read directory: {}", e),
@@ -532,10 +740,7 @@ fn list_directory_contents(path: &Path) -> Result<Vec<Content>, ErrorData> {
         )
     })?;
 
-    let mut files = Vec::new();
-    let mut dirs = Vec::new();
-    let mut total_count = 0;
-
+    let mut children = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| {
             ErrorData::new(
@@ -544,73 +749,96 @@ fn list_directory_contents(path: &Path) -> Result<Vec<Content>, ErrorData> {
                 None,
             )
         })?;
-
-        total_count += 1;
-
-        // Only process up to MAX_ITEMS entries
-        if dirs.len() + files.len() < MAX_ITEMS {
-            let metadata = entry.metadata().map_err(|e| {
-                ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to read metadata: {}", e),
-                    None,
-                )
-            })?;
-
-            let name = entry.file_name().to_string_lossy().to_string();
-
-            if metadata.is_dir() {
-                dirs.push(format!("{}/", name));
-            } else {
-                files.push(name);
-            }
-        }
+        // symlink_metadata so that a symlinked directory is reported as a link, not walked
+        let metadata = entry.path().symlink_metadata().map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to read metadata: {}", e),
+                None,
+            )
+        })?;
+        children.push((
+            entry.file_name().to_string_lossy().to_string(),
+            entry.path(),
+            metadata,
+        ));
     }
 
-    // Sort for consistent output
-    dirs.sort();
-    files.sort();
-
-    let mut output = format!("'{}' is a directory. Contents:\n\n", path.display());
-
-    if !dirs.is_empty() {
-        output.push_str("Directories:\n");
-        for dir in &dirs {
-            output.push_str(&format!("  {}\n", dir));
+    // Directories first, then files, each sorted by name for consistent output
+    children.sort_by(|(a_name, _, a_meta), (b_name, _, b_meta)| {
+        b_meta
+            .is_dir()
+            .cmp(&a_meta.is_dir())
+            .then_with(|| a_name.cmp(b_name))
+    });
+
+    let indent = "  ".repeat(depth);
+    for (name, child_path, metadata) in children {
+        if ignore_patterns
+            .matched(&child_path, metadata.is_dir())
+            .is_ignore()
+        {
+            continue;
         }
-        output.push('\n');
-    }
 
-    if !files.is_empty() {
-        output.push_str("Files:\n");
-        for file in &files {
-            output.push_str(&format!("  {}\n", file));
+        *total_count += 1;
+        let show = lines.len() < MAX_DIRECTORY_ITEMS;
+
+        if metadata.file_type().is_symlink() {
+            if show {
+                lines.push(format!("{}{}@", indent, name));
+            }
+        } else if metadata.is_dir() {
+            if show {
+                lines.push(format!("{}{}/", indent, name));
+            }
+            // Once the listing is full, only count the remaining entries at this level
+            // rather than walking further subtrees that will never be shown
+            if show && depth < max_depth {
+                walk_directory(
+                    &child_path,
+                    depth + 1,
+                    max_depth,
+                    ignore_patterns,
+                    lines,
+                    total_count,
+                )?;
+            }
+        } else if show {
+            lines.push(format!(
+                "{}{} ({})",
+                indent,
+                name,
+                format_file_size(metadata.len())
+            ));
         }
     }
 
-    if dirs.is_empty() && files.is_empty() {
-        output.push_str("  (empty directory)\n");
-    }
+    Ok(())
+}
 
-    // If we hit the limit, indicate there are more items
-    if total_count > MAX_ITEMS {
-        output.push_str(&format!(
-            "\n... and {} more items (showing first {} items)\n",
-            total_count - MAX_ITEMS,
-            MAX_ITEMS
-        ));
+fn format_file_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    let size = bytes as f64;
+    if size >= MB {
+        format!("{:.1}MB", size / MB)
+    } else if size >= KB {
+        format!("{:.1}KB", size / KB)
+    } else {
+        format!("{}B", bytes)
     }
-
-    Ok(vec![Content::text(output)])
 }
 
 pub async fn text_editor_view(
     path: &PathBuf,
     view_range: Option<(usize, i64)>,
+    max_depth: usize,
+    ignore_patterns: &Gitignore,
 ) -> Result<Vec<Content>, ErrorData> {
     // Check if path is a directory
     if path.is_dir() {
-        return list_directory_contents(path);
+        return list_directory_tree(path, max_depth.max(1), ignore_patterns);
     }
 
     if !path.is_file() {
@@ -679,6 +907,15 @@ pub async fn text_editor_view(
         )
     })?;
 
+    let redactions = match SecretRedactor::from_env() {
+        Some(redactor) => {
+            let (redacted, count) = redactor.redact(&content);
+            content = redacted;
+            count
+        }
+        None => 0,
+    };
+
     let lines: Vec<&str> = content.lines().collect();
     let total_lines = lines.len();
 
@@ -693,12 +930,24 @@ pub async fn text_editor_view(
 
     // The LLM gets just a quick update as we expect the file to view in the status
     // but we send a low priority message for the human
-    Ok(vec![
+    let mut result = vec![
         Content::embedded_text(uri, content).with_audience(vec![Role::Assistant]),
         Content::text(formatted)
             .with_audience(vec![Role::User])
             .with_priority(0.0),
-    ])
+    ];
+    if redactions > 0 {
+        result.push(
+            Content::text(format!(
+                "Note: {} secret value(s) in this file were replaced with {}. The file still \
+                 contains the real values, so never write {} back into it; edit around those \
+                 lines with str_replace instead.",
+                redactions, REDACTED, REDACTED
+            ))
+            .with_audience(vec![Role::Assistant]),
+        );
+    }
+    Ok(result)
 }
 
 pub async fn text_editor_write(path: &PathBuf, file_text: &str) -> Result<Vec<Content>, ErrorData> {
@@ -750,6 +999,8 @@ pub async fn text_editor_replace(
     old_str: &str,
     new_str: &str,
     diff: Option<&str>,
+    replace_all: bool,
+    dry_run: bool,
     editor_model: &Option<EditorModel>,
     file_history: &std::sync::Arc<
         std::sync::Mutex<std::collections::HashMap<PathBuf, Vec<String>>>,
@@ -766,8 +1017,19 @@ pub async fn text_editor_replace(
             ));
         }
 
+        if dry_run {
+            return dry_run_diff(path, diff_content);
+        }
         return apply_diff(path, diff_content, file_history).await;
     }
+
+    if dry_run {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "'dry_run' is only supported together with the 'diff' parameter".to_string(),
+            None,
+        ));
+    }
     // Check if file exists and is active
     if !path.exists() {
         return Err(ErrorData::new(
@@ -789,6 +1051,10 @@ pub async fn text_editor_replace(
         )
     })?;
 
+    if replace_all {
+        return replace_all_occurrences(path, &content, old_str, new_str, file_history);
+    }
+
     // Check if Editor API is configured and use it as the primary path
     if let Some(ref editor) = editor_model {
         // Editor API path - save history then call API directly
@@ -916,6 +1182,62 @@ pub async fn text_editor_replace(
     ])
 }
 
+/// Replaces every occurrence of `old_str`, reporting how many were changed.
+fn replace_all_occurrences(
+    path: &PathBuf,
+    content: &str,
+    old_str: &str,
+    new_str: &str,
+    file_history: &std::sync::Arc<
+        std::sync::Mutex<std::collections::HashMap<PathBuf, Vec<String>>>,
+    >,
+) -> Result<Vec<Content>, ErrorData> {
+    if old_str.is_empty() {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "'old_str' must not be empty when 'replace_all' is set".to_string(),
+            None,
+        ));
+    }
+    let count = content.matches(old_str).count();
+    if count == 0 {
+        return Err(ErrorData::new(ErrorCode::INVALID_PARAMS, "'old_str' does not appear in the file. Make sure the string exactly matches existing file content, including whitespace!".to_string(), None));
+    }
+
+    save_file_history(path, file_history)?;
+
+    let mut normalized_content = normalize_line_endings(&content.replace(old_str, new_str));
+    if !normalized_content.ends_with('\n') {
+        normalized_content.push('\n');
+    }
+    std::fs::write(path, &normalized_content).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to write file: {}", e),
+            None,
+        )
+    })?;
+
+    let plural = if count == 1 { "" } else { "s" };
+    Ok(vec![
+        Content::text(format!(
+            "The file {} has been edited: replaced {} occurrence{} of 'old_str'. View the file to review the changes if necessary.",
+            path.display(),
+            count,
+            plural
+        ))
+        .with_audience(vec![Role::Assistant]),
+        Content::text(format!(
+            "Replaced {} occurrence{} in {}",
+            count,
+            plural,
+            path.display()
+        ))
+        .with_audience(vec![Role::User])
+        .with_priority(0.2),
+    ])
+}
+
 pub async fn text_editor_insert(
     path: &PathBuf,
     insert_line_spec: i64,
@@ -50,6 +50,15 @@ pub async fn run() -> Result<()> {
     let listener = tokio::net::TcpListener::bind(settings.socket_addr()).await?;
     info!("listening on {}", listener.local_addr()?);
 
+    tokio::spawn(async {
+        if let Err(e) = goose::session::SessionManager::instance()
+            .enforce_retention_policy()
+            .await
+        {
+            tracing::warn!("Failed to apply session retention policy: {}", e);
+        }
+    });
+
     let tunnel_manager = app_state.tunnel_manager.clone();
     tokio::spawn(async move {
         tunnel_manager.check_auto_start().await;
@@ -327,6 +327,7 @@ derive_utoipa!(Icon as IconSchema);
 #[openapi(
     paths(
         super::routes::status::status,
+        super::routes::status::ready,
         super::routes::status::system_info,
         super::routes::status::diagnostics,
         super::routes::mcp_ui_proxy::mcp_ui_proxy,
@@ -360,6 +361,7 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::agent::start_agent,
         super::routes::agent::resume_agent,
         super::routes::agent::stop_agent,
+        super::routes::agent::cancel_subagent,
         super::routes::agent::restart_agent,
         super::routes::agent::update_working_dir,
         super::routes::agent::get_tools,
@@ -376,6 +378,7 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::session::get_session,
         super::routes::session::get_session_insights,
         super::routes::session::update_session_name,
+        super::routes::session::update_session_metadata,
         super::routes::session::delete_session,
         super::routes::session::export_session,
         super::routes::session::import_session,
@@ -440,6 +443,7 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::session::ImportSessionRequest,
         super::routes::session::SessionListResponse,
         super::routes::session::UpdateSessionNameRequest,
+        super::routes::session::UpdateSessionMetadataRequest,
         super::routes::session::UpdateSessionUserRecipeValuesRequest,
         super::routes::session::UpdateSessionUserRecipeValuesResponse,
         super::routes::session::EditType,
@@ -548,6 +552,7 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::agent::StartAgentRequest,
         super::routes::agent::ResumeAgentRequest,
         super::routes::agent::StopAgentRequest,
+        super::routes::agent::CancelSubagentRequest,
         super::routes::agent::RestartAgentRequest,
         super::routes::agent::UpdateWorkingDirRequest,
         super::routes::agent::UpdateFromSessionRequest,
@@ -70,6 +70,8 @@ pub struct StartAgentRequest {
     recipe_deeplink: Option<String>,
     #[serde(default)]
     extension_overrides: Option<Vec<ExtensionConfig>>,
+    #[serde(default)]
+    metadata: Option<HashMap<String, String>>,
 }
 
 #[derive(Deserialize, utoipa::ToSchema)]
@@ -77,6 +79,12 @@ pub struct StopAgentRequest {
     session_id: String,
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct CancelSubagentRequest {
+    session_id: String,
+    subagent_id: String,
+}
+
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct RestartAgentRequest {
     session_id: String,
@@ -177,6 +185,7 @@ async fn start_agent(
         recipe_id,
         recipe_deeplink,
         extension_overrides,
+        metadata,
     } = payload;
 
     let original_recipe = if let Some(deeplink) = recipe_deeplink {
@@ -265,6 +274,21 @@ async fn start_agent(
             })?;
     }
 
+    if let Some(metadata) = metadata {
+        manager
+            .update(&session.id)
+            .metadata(metadata)
+            .apply()
+            .await
+            .map_err(|err| {
+                error!("Failed to update session metadata: {}", err);
+                ErrorResponse {
+                    message: format!("Failed to update session metadata: {}", err),
+                    status: StatusCode::INTERNAL_SERVER_ERROR,
+                }
+            })?;
+    }
+
     // Refetch session to get all updates
     session = manager
         .get_session(&session.id, false)
@@ -664,6 +688,39 @@ async fn stop_agent(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/agent/cancel_subagent",
+    request_body = CancelSubagentRequest,
+    responses(
+        (status = 200, description = "Subagent cancelled"),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 404, description = "No running subagent with that id"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn cancel_subagent(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CancelSubagentRequest>,
+) -> Result<StatusCode, ErrorResponse> {
+    let agent = state
+        .get_agent_for_route(payload.session_id.clone())
+        .await
+        .map_err(|code| ErrorResponse {
+            message: format!("Failed to get agent for session {}", payload.session_id),
+            status: code,
+        })?;
+
+    if agent.cancel_subagent(&payload.subagent_id) {
+        Ok(StatusCode::OK)
+    } else {
+        Err(ErrorResponse {
+            message: format!("No running subagent with id {}", payload.subagent_id),
+            status: StatusCode::NOT_FOUND,
+        })
+    }
+}
+
 async fn restart_agent_internal(
     state: &Arc<AppState>,
     session_id: &str,
@@ -1040,5 +1097,6 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/agent/add_extension", post(agent_add_extension))
         .route("/agent/remove_extension", post(agent_remove_extension))
         .route("/agent/stop", post(stop_agent))
+        .route("/agent/cancel_subagent", post(cancel_subagent))
         .with_state(state)
 }
@@ -41,6 +41,8 @@ pub struct ExtensionQuery {
     pub name: String,
     pub config: ExtensionConfig,
     pub enabled: bool,
+    #[serde(default)]
+    pub required: bool,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -286,6 +288,7 @@ pub async fn add_extension(
 
     goose::config::set_extension(ExtensionEntry {
         enabled: extension_query.enabled,
+        required: extension_query.required,
         config: extension_query.config,
     });
 
@@ -7,6 +7,7 @@ use axum::extract::rejection::JsonRejection;
 use axum::routing::get;
 use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
 use goose::recipe::local_recipes;
+use goose::recipe::parameter_extraction::extract_recipe_parameters;
 use goose::recipe::validate_recipe::validate_recipe_template_from_content;
 use goose::recipe::Recipe;
 use goose::{recipe_deeplink, slash_commands};
@@ -48,6 +49,9 @@ pub struct CreateRecipeRequest {
     session_id: String,
     #[serde(default)]
     author: Option<AuthorRequest>,
+    /// Turn URLs, paths and recurring names from the session into recipe parameters
+    #[serde(default)]
+    extract_parameters: bool,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -193,10 +197,14 @@ async fn create_recipe(
 
     let agent = state.get_agent_for_route(request.session_id).await?;
 
-    let recipe_result = agent.create_recipe(conversation).await;
+    let recipe_result = agent.create_recipe(conversation.clone()).await;
 
     match recipe_result {
         Ok(mut recipe) => {
+            if request.extract_parameters {
+                extract_recipe_parameters(&mut recipe, &conversation);
+            }
+
             if let Some(author_req) = request.author {
                 recipe.author = Some(goose::recipe::Author {
                     contact: author_req.contact,
@@ -14,7 +14,7 @@ use goose::prompt_template::render_template;
 use goose::recipe::build_recipe::{build_recipe_from_template, RecipeError};
 use goose::recipe::local_recipes::{get_recipe_library_dir, list_local_recipes};
 use goose::recipe::validate_recipe::validate_recipe_template_from_content;
-use goose::recipe::Recipe;
+use goose::recipe::{Recipe, RecipeParameter};
 use serde::Serialize;
 use serde_json::Value;
 use tracing::error;
@@ -147,7 +147,7 @@ pub async fn build_recipe_with_parameter_values(
         recipe_content,
         &recipe_dir,
         params,
-        None::<fn(&str, &str) -> Result<String, anyhow::Error>>,
+        None::<fn(&RecipeParameter) -> Result<String, anyhow::Error>>,
     ) {
         Ok(recipe) => Some(recipe),
         Err(RecipeError::MissingParams { .. }) => None,
@@ -11,6 +11,7 @@ use futures::{stream::StreamExt, Stream};
 use goose::agents::{AgentEvent, SessionConfig};
 use goose::conversation::message::{Message, MessageContent, TokenState};
 use goose::conversation::Conversation;
+use goose::mcp_utils::categorize_tool_error;
 use goose::session::SessionManager;
 use rmcp::model::ServerNotification;
 use serde::{Deserialize, Serialize};
@@ -59,13 +60,16 @@ fn track_tool_telemetry(content: &MessageContent, all_messages: &[Message]) {
                 })
                 .unwrap_or_else(|| "unknown".to_string().into());
 
-            let success = tool_response.tool_result.is_ok();
-            let result_status = if success { "success" } else { "error" };
+            let (result_status, error_category) = match &tool_response.tool_result {
+                Ok(_) => ("success", "none"),
+                Err(error) => ("error", categorize_tool_error(error).as_str()),
+            };
 
             tracing::info!(
                 counter.goose.tool_completions = 1,
                 tool_name = %tool_name,
                 result = %result_status,
+                error_category = %error_category,
                 "Tool call completed"
             );
         }
@@ -362,6 +366,8 @@ pub async fn reply(
                                 message: n,
                             }, &tx, &cancel_token).await;
                         }
+                        // The agent has already streamed a message explaining why the turn stopped
+                        Ok(Some(Ok(AgentEvent::ContextLengthExceeded))) => {}
 
                         Ok(Some(Err(e))) => {
                             tracing::error!("Error processing message: {}", e);
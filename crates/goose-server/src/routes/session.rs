@@ -40,6 +40,13 @@ pub struct UpdateSessionUserRecipeValuesRequest {
     user_recipe_values: HashMap<String, String>,
 }
 
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSessionMetadataRequest {
+    /// Metadata to store on the session; replaces any existing metadata
+    metadata: HashMap<String, String>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct UpdateSessionUserRecipeValuesResponse {
     recipe: Recipe,
@@ -199,6 +206,50 @@ async fn update_session_name(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    put,
+    path = "/sessions/{session_id}/metadata",
+    request_body = UpdateSessionMetadataRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Session metadata updated successfully", body = Session),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn update_session_metadata(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<UpdateSessionMetadataRequest>,
+) -> Result<Json<Session>, StatusCode> {
+    let manager = state.session_manager();
+    manager
+        .get_session(&session_id, false)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    manager
+        .update(&session_id)
+        .metadata(request.metadata)
+        .apply()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let session = manager
+        .get_session(&session_id, false)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(session))
+}
+
 #[utoipa::path(
     put,
     path = "/sessions/{session_id}/user_recipe_values",
@@ -483,6 +534,10 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/sessions/import", post(import_session))
         .route("/sessions/insights", get(get_session_insights))
         .route("/sessions/{session_id}/name", put(update_session_name))
+        .route(
+            "/sessions/{session_id}/metadata",
+            put(update_session_metadata),
+        )
         .route(
             "/sessions/{session_id}/user_recipe_values",
             put(update_session_user_recipe_values),
@@ -3,6 +3,7 @@ use axum::extract::State;
 use axum::http::HeaderValue;
 use axum::response::IntoResponse;
 use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+use goose::providers::ping_configured_provider;
 use goose::session::{generate_diagnostics, get_system_info, SystemInfo};
 use std::sync::Arc;
 
@@ -17,6 +18,22 @@ async fn status() -> String {
     "ok".to_string()
 }
 
+#[utoipa::path(get, path = "/ready",
+    responses(
+        (status = 200, description = "The configured provider is reachable", body = String),
+        (status = 503, description = "No provider is configured or it can't be reached", body = String),
+    )
+)]
+async fn ready() -> (StatusCode, String) {
+    match ping_configured_provider().await {
+        Ok(()) => (StatusCode::OK, "ready".to_string()),
+        Err(e) => {
+            tracing::warn!("Readiness check failed: {}", e);
+            (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+        }
+    }
+}
+
 #[utoipa::path(get, path = "/system_info",
     responses(
         (status = 200, description = "System information", body = SystemInfo),
@@ -58,6 +75,7 @@ async fn diagnostics(
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/status", get(status))
+        .route("/ready", get(ready))
         .route("/system_info", get(system_info))
         .route("/diagnostics/{session_id}", get(diagnostics))
         .with_state(state)
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
@@ -19,16 +20,20 @@ use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_
 use crate::agents::platform_tools::PLATFORM_MANAGE_SCHEDULE_TOOL_NAME;
 use crate::agents::prompt_manager::PromptManager;
 use crate::agents::retry::{RetryManager, RetryResult};
-use crate::agents::subagent_task_config::TaskConfig;
+use crate::agents::subagent_handler::SubagentRegistry;
+use crate::agents::subagent_task_config::{max_subagent_depth, TaskConfig};
 use crate::agents::subagent_tool::{
-    create_subagent_tool, handle_subagent_tool, SUBAGENT_TOOL_NAME,
+    create_subagent_tool, handle_subagent_tool, subagent_depth_exceeded, SUBAGENT_TOOL_NAME,
 };
-use crate::agents::types::SessionConfig;
+use crate::agents::tool_result_cache::ToolResultCache;
 use crate::agents::types::{FrontendTool, SharedProvider, ToolResultReceiver};
+use crate::agents::types::{SessionConfig, TurnKind, TurnUsage};
 use crate::config::permission::PermissionManager;
 use crate::config::{get_enabled_extensions, Config, GooseMode};
 use crate::context_mgmt::{
-    check_if_compaction_needed, compact_messages, DEFAULT_COMPACTION_THRESHOLD,
+    check_if_compaction_needed, compact_messages, drop_oldest_turns, estimate_messages_bytes,
+    estimate_request_bytes, fit_request_size, CompactionStrategy, DEFAULT_MAX_REQUEST_BYTES,
+    GOOSE_MAX_CONVERSATION_MESSAGES, GOOSE_MAX_REQUEST_BYTES,
 };
 use crate::conversation::message::{
     ActionRequiredData, Message, MessageContent, ProviderMetadata, SystemNotificationType,
@@ -46,6 +51,7 @@ use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::security::security_inspector::SecurityInspector;
 use crate::session::extension_data::{EnabledExtensionsState, ExtensionState};
+use crate::session::session_manager::GOOSE_SESSION_BATCH_WRITES;
 use crate::session::{Session, SessionManager, SessionType};
 use crate::tool_inspection::ToolInspectionManager;
 use crate::tool_monitor::RepetitionInspector;
@@ -58,9 +64,11 @@ use rmcp::model::{
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
+/// Whether turns re-run by the retry logic count against `max_turns` (default: true)
+pub const GOOSE_RETRIES_COUNT_TOWARD_MAX_TURNS: &str = "GOOSE_RETRIES_COUNT_TOWARD_MAX_TURNS";
 const COMPACTION_THINKING_TEXT: &str = "goose is compacting the conversation...";
 
 /// Context needed for the reply function
@@ -129,6 +137,10 @@ pub struct Agent {
 
     pub(super) retry_manager: RetryManager,
     pub(super) tool_inspection_manager: ToolInspectionManager,
+    pub(super) subagents: SubagentRegistry,
+    pub(super) tool_result_cache: ToolResultCache,
+    pub(super) tools_disabled: AtomicBool,
+    pub(super) subagent_depth: AtomicUsize,
 }
 
 #[derive(Clone, Debug)]
@@ -137,6 +149,7 @@ pub enum AgentEvent {
     McpNotification((String, ServerNotification)),
     ModelChange { model: String, mode: String },
     HistoryReplaced(Conversation),
+    ContextLengthExceeded,
 }
 
 impl Default for Agent {
@@ -213,6 +226,10 @@ impl Agent {
             tool_result_rx: Arc::new(Mutex::new(tool_rx)),
             retry_manager: RetryManager::new(),
             tool_inspection_manager: Self::create_tool_inspection_manager(permission_manager),
+            subagents: SubagentRegistry::default(),
+            tool_result_cache: ToolResultCache::default(),
+            tools_disabled: AtomicBool::new(false),
+            subagent_depth: AtomicUsize::new(0),
         }
     }
 
@@ -461,16 +478,13 @@ impl Agent {
         cancellation_token: Option<CancellationToken>,
         session: &Session,
     ) -> (String, Result<ToolCallResult, ErrorData>) {
-        // Prevent subagents from creating other subagents
-        if session.session_type == SessionType::SubAgent && tool_call.name == SUBAGENT_TOOL_NAME {
-            return (
-                request_id,
-                Err(ErrorData::new(
-                    ErrorCode::INVALID_REQUEST,
-                    "Subagents cannot create other subagents".to_string(),
-                    None,
-                )),
-            );
+        // Stop subagents from nesting past the configured depth
+        let depth = self.session_subagent_depth(session);
+        if tool_call.name == SUBAGENT_TOOL_NAME {
+            let max_depth = max_subagent_depth();
+            if depth >= max_depth {
+                return (request_id, Ok(subagent_depth_exceeded(depth, max_depth)));
+            }
         }
 
         if tool_call.name == PLATFORM_MANAGE_SCHEDULE_TOOL_NAME {
@@ -507,7 +521,33 @@ impl Agent {
         }
 
         debug!("WAITING_TOOL_START: {}", tool_call.name);
-        let result: ToolCallResult = if tool_call.name == SUBAGENT_TOOL_NAME {
+        // Every call goes through the cache lookup, so calls that may write can
+        // invalidate what they touch even when they aren't cacheable themselves
+        let use_cache = ToolResultCache::enabled();
+        let cached = if use_cache {
+            let read_only = self
+                .extension_manager
+                .get_prefixed_tools(None)
+                .await
+                .is_ok_and(|tools| {
+                    tools.iter().any(|tool| {
+                        tool.name == tool_call.name
+                            && tool
+                                .annotations
+                                .as_ref()
+                                .and_then(|annotations| annotations.read_only_hint)
+                                == Some(true)
+                    })
+                });
+            self.tool_result_cache
+                .lookup(&session.id, &session.working_dir, &tool_call, read_only)
+        } else {
+            None
+        };
+        let result: ToolCallResult = if let Some(cached) = cached {
+            debug!("Serving {} from the tool result cache", tool_call.name);
+            ToolCallResult::from(Ok(cached))
+        } else if tool_call.name == SUBAGENT_TOOL_NAME {
             let provider = match self.provider().await {
                 Ok(p) => p,
                 Err(_) => {
@@ -523,8 +563,13 @@ impl Agent {
             };
 
             let extensions = self.get_extension_configs().await;
-            let task_config =
-                TaskConfig::new(provider, &session.id, &session.working_dir, extensions);
+            let task_config = TaskConfig::new(
+                provider,
+                &session.id,
+                &session.working_dir,
+                extensions,
+                depth + 1,
+            );
             let sub_recipes = self.sub_recipes.lock().await.clone();
 
             let arguments = tool_call
@@ -540,6 +585,7 @@ impl Agent {
                 sub_recipes,
                 session.working_dir.clone(),
                 cancellation_token,
+                self.subagents.clone(),
             )
         } else if self.is_frontend_tool(&tool_call.name).await {
             // For frontend tools, return an error indicating we need frontend execution
@@ -558,6 +604,26 @@ impl Agent {
                     cancellation_token.unwrap_or_default(),
                 )
                 .await;
+            let result = match result {
+                Ok(result) if use_cache && ToolResultCache::is_cacheable(&tool_call) => {
+                    let cache = self.tool_result_cache.clone();
+                    let session_id = session.id.clone();
+                    let working_dir = session.working_dir.clone();
+                    let cached_call = tool_call.clone();
+                    let future = result.result;
+                    Ok(ToolCallResult {
+                        notification_stream: result.notification_stream,
+                        result: Box::new(Box::pin(async move {
+                            let output = future.await;
+                            if let Ok(call_result) = &output {
+                                cache.store(&session_id, &working_dir, &cached_call, call_result);
+                            }
+                            output
+                        })),
+                    })
+                }
+                other => other,
+            };
             result.unwrap_or_else(|e| {
                 crate::posthog::emit_error(
                     "tool_execution_failed",
@@ -573,6 +639,12 @@ impl Agent {
 
         debug!("WAITING_TOOL_END: {}", tool_call.name);
 
+        // The tool runs when the returned future is polled, so time it there
+        let execution_span = tracing::info_span!(
+            "tool_execution",
+            tool.name = %tool_call.name,
+            tool.request_id = %request_id
+        );
         (
             request_id,
             Ok(ToolCallResult {
@@ -580,7 +652,8 @@ impl Agent {
                 result: Box::new(
                     result
                         .result
-                        .map(super::large_response_handler::process_tool_response),
+                        .map(super::large_response_handler::process_tool_response)
+                        .instrument(execution_span),
                 ),
             }),
         )
@@ -734,7 +807,7 @@ impl Agent {
         Ok(())
     }
 
-    pub async fn subagents_enabled(&self, session_id: &str) -> bool {
+    pub async fn subagents_enabled(&self) -> bool {
         if self.config.goose_mode != GooseMode::Auto {
             return false;
         }
@@ -746,16 +819,7 @@ impl Agent {
         {
             return false;
         }
-        let context = self.extension_manager.get_context();
-        if matches!(
-            context
-                .session_manager
-                .get_session(session_id, false)
-                .await
-                .ok()
-                .map(|session| session.session_type),
-            Some(SessionType::SubAgent)
-        ) {
+        if self.subagent_depth() >= max_subagent_depth() {
             return false;
         }
         !self
@@ -773,7 +837,7 @@ impl Agent {
             .await
             .unwrap_or_default();
 
-        let subagents_enabled = self.subagents_enabled(session_id).await;
+        let subagents_enabled = self.subagents_enabled().await;
         if (extension_name.is_none() || extension_name.as_deref() == Some("platform"))
             && self.config.scheduler_service.is_some()
         {
@@ -939,12 +1003,28 @@ impl Agent {
         let session = session_manager
             .get_session(&session_config.id, true)
             .await?;
-        let conversation = session
+        let mut conversation = session
             .conversation
             .clone()
             .ok_or_else(|| anyhow::anyhow!("Session {} has no conversation", session_config.id))?;
 
-        let needs_auto_compact = check_if_compaction_needed(
+        // A hard cap on message count, applied before and independently of token-based compaction
+        let max_messages = Config::global()
+            .get_param::<usize>(GOOSE_MAX_CONVERSATION_MESSAGES)
+            .ok()
+            .filter(|max| *max > 0);
+        let mut dropped_messages = None;
+        if let Some((trimmed, dropped)) =
+            max_messages.and_then(|max| drop_oldest_turns(&conversation, max))
+        {
+            session_manager
+                .replace_conversation(&session_config.id, &trimmed)
+                .await?;
+            conversation = trimmed;
+            dropped_messages = Some(dropped);
+        }
+
+        let auto_compaction = check_if_compaction_needed(
             self.provider().await?.as_ref(),
             &conversation,
             None,
@@ -955,18 +1035,24 @@ impl Agent {
         let conversation_to_compact = conversation.clone();
 
         Ok(Box::pin(async_stream::try_stream! {
-            let final_conversation = if !needs_auto_compact {
-                conversation
-            } else {
-                let config = Config::global();
-                let threshold = config
-                    .get_param::<f64>("GOOSE_AUTO_COMPACT_THRESHOLD")
-                    .unwrap_or(DEFAULT_COMPACTION_THRESHOLD);
-                let threshold_percentage = (threshold * 100.0) as u32;
+            if let Some(dropped) = dropped_messages {
+                yield AgentEvent::HistoryReplaced(conversation.clone());
+                yield AgentEvent::Message(
+                    Message::assistant().with_system_notification(
+                        SystemNotificationType::InlineMessage,
+                        format!(
+                            "Conversation exceeded {} messages. Dropped the oldest {} message(s) from context.",
+                            max_messages.unwrap_or_default(),
+                            dropped
+                        ),
+                    )
+                );
+            }
 
+            let final_conversation = if let Some((trigger, strategy)) = auto_compaction {
                 let inline_msg = format!(
-                    "Exceeded auto-compact threshold of {}%. Performing auto-compaction...",
-                    threshold_percentage
+                    "{}. Performing auto-compaction...",
+                    trigger.describe()
                 );
 
                 yield AgentEvent::Message(
@@ -983,8 +1069,8 @@ impl Agent {
                     )
                 );
 
-                match compact_messages(self.provider().await?.as_ref(), &conversation_to_compact, false).await {
-                    Ok((compacted_conversation, summarization_usage)) => {
+                match compact_messages(self.provider().await?.as_ref(), &conversation_to_compact, false, strategy).await {
+                    Ok((compacted_conversation, summarization_usage, stats)) => {
                         session_manager.replace_conversation(&session_config.id, &compacted_conversation).await?;
                         self.update_session_metrics(&session_config, &summarization_usage, true).await?;
 
@@ -993,7 +1079,7 @@ impl Agent {
                         yield AgentEvent::Message(
                             Message::assistant().with_system_notification(
                                 SystemNotificationType::InlineMessage,
-                                "Compaction complete",
+                                format!("Compaction complete: {}", stats),
                             )
                         );
 
@@ -1008,12 +1094,22 @@ impl Agent {
                         return;
                     }
                 }
+            } else {
+                conversation
             };
 
+            let session_id = session_config.id.clone();
             let mut reply_stream = self.reply_internal(final_conversation, session_config, session, cancel_token).await?;
-            while let Some(event) = reply_stream.next().await {
-                yield event?;
-            }
+            let result = loop {
+                match reply_stream.next().await {
+                    Some(Ok(event)) => yield event,
+                    Some(Err(e)) => break Err(e),
+                    None => break Ok(()),
+                }
+            };
+            // Messages queued during the turn are written when it ends, including when it fails
+            session_manager.flush_messages(&session_id).await?;
+            result?;
         }))
     }
 
@@ -1054,9 +1150,23 @@ impl Agent {
         let working_dir = session.working_dir.clone();
         Ok(Box::pin(async_stream::try_stream! {
             let _ = reply_span.enter();
-            let mut turns_taken = 0u32;
+            let mut turn_usage = TurnUsage::default();
+            let mut next_turn_kind = TurnKind::Prompt;
             let max_turns = session_config.max_turns.unwrap_or(DEFAULT_MAX_TURNS);
+            let count_retries = Config::global()
+                .get_param::<bool>(GOOSE_RETRIES_COUNT_TOWARD_MAX_TURNS)
+                .unwrap_or(true);
+            let batch_writes = Config::global()
+                .get_param::<bool>(GOOSE_SESSION_BATCH_WRITES)
+                .unwrap_or(false);
             let mut compaction_attempts = 0;
+            let max_request_bytes = Config::global()
+                .get_param::<usize>(GOOSE_MAX_REQUEST_BYTES)
+                .unwrap_or(DEFAULT_MAX_REQUEST_BYTES);
+            // Kept up to date as messages are added, and re-estimated only when the conversation,
+            // tools or system prompt are replaced
+            let mut request_bytes = None;
+            let mut request_trimmed = false;
 
             loop {
                 if is_token_cancelled(&cancel_token) {
@@ -1073,16 +1183,56 @@ impl Agent {
                     }
                 }
 
-                turns_taken += 1;
-                if turns_taken > max_turns {
+                let in_fallback = self
+                    .provider()
+                    .await?
+                    .as_lead_worker()
+                    .is_some_and(|lead_worker| lead_worker.fallback_active());
+                let turn_kind = match next_turn_kind {
+                    TurnKind::Prompt if in_fallback => TurnKind::Fallback,
+                    kind => kind,
+                };
+                next_turn_kind = TurnKind::Prompt;
+
+                turn_usage.record(turn_kind);
+                if turn_usage.counted(count_retries) > max_turns {
                     yield AgentEvent::Message(
-                        Message::assistant().with_text(
-                            "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
-                        )
+                        Message::assistant().with_text(format!(
+                            "I've reached the maximum number of actions I can do without user input \
+                            (max_turns = {}; used {}). Would you like me to continue?",
+                            max_turns,
+                            turn_usage.summary(count_retries)
+                        ))
                     );
                     break;
                 }
 
+                let estimated_bytes = *request_bytes.get_or_insert_with(|| {
+                    estimate_request_bytes(&system_prompt, conversation.messages(), &tools)
+                });
+                if let Some(trim) = fit_request_size(
+                    estimated_bytes,
+                    conversation.messages(),
+                    max_request_bytes,
+                ) {
+                    // Persisted once the reply finishes, rather than on every turn that trims
+                    conversation = Conversation::new_unvalidated(trim.messages);
+                    request_bytes = Some(trim.trimmed_bytes);
+                    request_trimmed = true;
+                    yield AgentEvent::Message(
+                        Message::assistant().with_system_notification(
+                            SystemNotificationType::InlineMessage,
+                            format!(
+                                "Request was {} bytes, over the {} byte limit. Truncated {} large tool response(s) to bring it down to {} bytes.",
+                                trim.original_bytes,
+                                max_request_bytes,
+                                trim.truncated_responses,
+                                trim.trimmed_bytes
+                            ),
+                        )
+                    );
+                }
+
                 let conversation_with_moim = super::moim::inject_moim(
                     &session_config.id,
                     conversation.clone(),
@@ -1176,8 +1326,8 @@ impl Agent {
                                         yield AgentEvent::Message(msg);
                                     }
                                 }
-                                if goose_mode == GooseMode::Chat {
-                                    // Skip all remaining tool calls in chat mode
+                                if goose_mode == GooseMode::Chat || self.tools_disabled() {
+                                    // Skip all remaining tool calls in chat mode or with tools withheld
                                     for request in remaining_requests.iter() {
                                         if let Some(response_msg) = request_to_response_map.get(&request.id) {
                                             let mut response = response_msg.lock().await;
@@ -1300,6 +1450,15 @@ impl Agent {
                                         yield AgentEvent::Message(msg);
                                     }
 
+                                    for notice in self.extension_manager.take_restart_notices().await {
+                                        yield AgentEvent::Message(
+                                            Message::assistant().with_system_notification(
+                                                SystemNotificationType::InlineMessage,
+                                                notice,
+                                            )
+                                        );
+                                    }
+
                                     if all_install_successful && !enable_extension_request_ids.is_empty() {
                                         if let Err(e) = self.save_extension_state(&session_config).await {
                                             warn!("Failed to save extension state after runtime changes: {}", e);
@@ -1356,6 +1515,7 @@ impl Agent {
                                         "Unable to continue: Context limit still exceeded after compaction. Try using a shorter message, a model with a larger context window, or start a new session."
                                     )
                                 );
+                                yield AgentEvent::ContextLengthExceeded;
                                 break;
                             }
 
@@ -1372,11 +1532,14 @@ impl Agent {
                                 )
                             );
 
-                            match compact_messages(self.provider().await?.as_ref(), &conversation, false).await {
-                                Ok((compacted_conversation, usage)) => {
+                            // The request already overflowed, so summarize everything rather than keep recent messages
+                            match compact_messages(self.provider().await?.as_ref(), &conversation, false, CompactionStrategy::FullSummary).await {
+                                Ok((compacted_conversation, usage, _stats)) => {
                                     session_manager.replace_conversation(&session_config.id, &compacted_conversation).await?;
                                     self.update_session_metrics(&session_config, &usage, true).await?;
                                     conversation = compacted_conversation;
+                                    request_bytes = None;
+                                    request_trimmed = false;
                                     did_recovery_compact_this_iteration = true;
                                     yield AgentEvent::HistoryReplaced(conversation.clone());
                                     break;
@@ -1403,6 +1566,7 @@ impl Agent {
                 if tools_updated {
                     (tools, toolshim_tools, system_prompt) =
                         self.prepare_tools_and_prompt(&session_config.id, &session.working_dir).await?;
+                    request_bytes = None;
                 }
                 let mut exit_chat = false;
                 if no_tools_called {
@@ -1425,6 +1589,7 @@ impl Agent {
                             Ok(should_retry) => {
                                 if should_retry {
                                     info!("Retry logic triggered, restarting agent loop");
+                                    next_turn_kind = TurnKind::Retry;
                                 } else {
                                     exit_chat = true;
                                 }
@@ -1443,7 +1608,14 @@ impl Agent {
                 }
 
                 for msg in &messages_to_add {
-                    session_manager.add_message(&session_config.id, msg).await?;
+                    if batch_writes {
+                        session_manager.queue_message(&session_config.id, msg).await;
+                    } else {
+                        session_manager.add_message(&session_config.id, msg).await?;
+                    }
+                }
+                if let Some(bytes) = request_bytes.as_mut() {
+                    *bytes += estimate_messages_bytes(&messages_to_add);
                 }
                 conversation.extend(messages_to_add);
                 if exit_chat {
@@ -1452,6 +1624,11 @@ impl Agent {
 
                 tokio::task::yield_now().await;
             }
+
+            if request_trimmed {
+                session_manager.replace_conversation(&session_config.id, &conversation).await?;
+                yield AgentEvent::HistoryReplaced(conversation.clone());
+            }
         }))
     }
 
@@ -1460,6 +1637,58 @@ impl Agent {
         prompt_manager.add_system_prompt_extra(instruction);
     }
 
+    /// Withholds every tool definition from the provider so replies are plain text. Takes
+    /// effect from the next reply and can be switched back at any time.
+    pub fn set_tools_disabled(&self, disabled: bool) {
+        self.tools_disabled.store(disabled, Ordering::Relaxed);
+    }
+
+    pub fn tools_disabled(&self) -> bool {
+        self.tools_disabled.load(Ordering::Relaxed)
+    }
+
+    /// How many subagents deep this agent runs; 0 for an agent the user talks to directly.
+    pub fn set_subagent_depth(&self, depth: usize) {
+        self.subagent_depth.store(depth, Ordering::Relaxed);
+    }
+
+    pub fn subagent_depth(&self) -> usize {
+        self.subagent_depth.load(Ordering::Relaxed)
+    }
+
+    /// A subagent session is at least one level deep, even when its agent was never told its depth.
+    fn session_subagent_depth(&self, session: &Session) -> usize {
+        let depth = self.subagent_depth();
+        if session.session_type == SessionType::SubAgent {
+            depth.max(1)
+        } else {
+            depth
+        }
+    }
+
+    /// Ids of the subagents currently running under this agent.
+    pub fn running_subagents(&self) -> Vec<String> {
+        self.subagents.running()
+    }
+
+    /// Cancels a single running subagent, leaving the parent turn running. Returns
+    /// false if no subagent with that id is running.
+    pub fn cancel_subagent(&self, subagent_id: &str) -> bool {
+        self.subagents.cancel(subagent_id)
+    }
+
+    /// Append text to the very end of the system prompt for this agent only
+    pub async fn append_system_prompt(&self, text: String) {
+        let mut prompt_manager = self.prompt_manager.lock().await;
+        prompt_manager.add_system_prompt_append(text);
+    }
+
+    /// Put text at the very start of the system prompt for this agent only
+    pub async fn prepend_system_prompt(&self, text: String) {
+        let mut prompt_manager = self.prompt_manager.lock().await;
+        prompt_manager.add_system_prompt_prepend(text);
+    }
+
     pub async fn update_provider(
         &self,
         provider: Arc<dyn Provider>,
@@ -10,7 +10,7 @@ use indoc::indoc;
 use regex::Regex;
 use rmcp::model::{
     CallToolRequestParam, CallToolResult, Content, Implementation, InitializeResult, JsonObject,
-    ListToolsResult, ProtocolVersion, RawContent, ServerCapabilities, Tool as McpTool,
+    ListToolsResult, ProtocolVersion, RawContent, Role, ServerCapabilities, Tool as McpTool,
     ToolAnnotations, ToolsCapability,
 };
 use schemars::{schema_for, JsonSchema};
@@ -677,19 +677,7 @@ impl CodeExecutionClient {
                         .await
                     {
                         Ok(dispatch_result) => match dispatch_result.result.await {
-                            Ok(result) => Ok(if let Some(sc) = &result.structured_content {
-                                serde_json::to_string(sc).unwrap_or_default()
-                            } else {
-                                result
-                                    .content
-                                    .iter()
-                                    .filter_map(|c| match &c.raw {
-                                        RawContent::Text(t) => Some(t.text.clone()),
-                                        _ => None,
-                                    })
-                                    .collect::<Vec<_>>()
-                                    .join("\n")
-                            }),
+                            Ok(result) => Ok(tool_result_text(&result)),
                             Err(e) => Err(format!("Tool error: {}", e.message)),
                         },
                         Err(e) => Err(format!("Dispatch error: {e}")),
@@ -702,6 +690,27 @@ impl CodeExecutionClient {
     }
 }
 
+/// The text a tool result gives the model, falling back to its structured content when it
+/// has no text. Structured results built with `CallToolResult::structured` carry the same
+/// JSON as text, so scripts can still read their properties.
+fn tool_result_text(result: &CallToolResult) -> String {
+    let text: Vec<String> = result
+        .content
+        .iter()
+        .filter(|c| c.audience().is_none_or(|a| a.contains(&Role::Assistant)))
+        .filter_map(|c| match &c.raw {
+            RawContent::Text(t) => Some(t.text.clone()),
+            _ => None,
+        })
+        .collect();
+    match &result.structured_content {
+        Some(structured) if text.is_empty() => {
+            serde_json::to_string(structured).unwrap_or_default()
+        }
+        _ => text.join("\n"),
+    }
+}
+
 #[async_trait]
 impl McpClientTrait for CodeExecutionClient {
     #[allow(clippy::too_many_lines)]
@@ -1233,6 +1242,78 @@ mod tests {
         assert_eq!(eval_with_tools(code, tools), expected);
     }
 
+    struct ShellLikeClient;
+
+    #[async_trait]
+    impl McpClientTrait for ShellLikeClient {
+        fn get_info(&self) -> Option<&InitializeResult> {
+            None
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<ListToolsResult, Error> {
+            Ok(ListToolsResult::default())
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            _arguments: Option<JsonObject>,
+            _meta: McpMeta,
+            _cancellation_token: CancellationToken,
+        ) -> Result<CallToolResult, Error> {
+            let mut result = CallToolResult::success(vec![
+                Content::text("listing\n\nCommand exited with code 3")
+                    .with_audience(vec![Role::Assistant]),
+                Content::text("listing").with_audience(vec![Role::User]),
+            ]);
+            result.structured_content = Some(serde_json::json!({ "exit_code": 3 }));
+            Ok(result)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_handler_keeps_text_of_structured_results() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(crate::agents::ExtensionManager::new_without_provider(
+            temp_dir.path().to_path_buf(),
+        ));
+        manager
+            .add_client(
+                "developer".to_string(),
+                crate::agents::extension::ExtensionConfig::default(),
+                Arc::new(tokio::sync::Mutex::new(Box::new(ShellLikeClient))),
+                None,
+                None,
+            )
+            .await;
+
+        let (call_tx, call_rx) = mpsc::unbounded_channel();
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        call_tx
+            .send((
+                "developer__shell".to_string(),
+                r#"{"command": "ls"}"#.to_string(),
+                response_tx,
+            ))
+            .unwrap();
+        drop(call_tx);
+        CodeExecutionClient::run_tool_handler(
+            "test-session-id".to_string(),
+            call_rx,
+            Some(Arc::downgrade(&manager)),
+        )
+        .await;
+
+        assert_eq!(
+            response_rx.await.unwrap(),
+            Ok("listing\n\nCommand exited with code 3".to_string())
+        );
+    }
+
     #[test]
     fn test_namespace_import_with_synthetic_module() {
         let tools = vec![ToolInfo {
@@ -2,9 +2,10 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
 
-use crate::context_mgmt::compact_messages;
+use crate::context_mgmt::{compact_messages, CompactionStrategy};
 use crate::conversation::message::{Message, SystemNotificationType};
 use crate::recipe::build_recipe::build_recipe_from_template_with_positional_params;
+use crate::recipe::RecipeParameter;
 
 use super::Agent;
 
@@ -86,10 +87,11 @@ impl Agent {
             .conversation
             .ok_or_else(|| anyhow!("Session has no conversation"))?;
 
-        let (compacted_conversation, _usage) = compact_messages(
+        let (compacted_conversation, _usage, stats) = compact_messages(
             self.provider().await?.as_ref(),
             &conversation,
             true, // is_manual_compact
+            CompactionStrategy::FullSummary,
         )
         .await?;
 
@@ -99,7 +101,7 @@ impl Agent {
 
         Ok(Some(Message::assistant().with_system_notification(
             SystemNotificationType::InlineMessage,
-            "Compaction complete",
+            format!("Compaction complete: {}", stats),
         )))
     }
 
@@ -357,10 +359,10 @@ impl Agent {
             recipe_content,
             recipe_dir,
             param_values,
-            None::<fn(&str, &str) -> Result<String>>,
+            None::<fn(&RecipeParameter) -> Result<String>>,
         ) {
             Ok(recipe) => recipe,
-            Err(crate::recipe::build_recipe::RecipeError::MissingParams { parameters }) => {
+            Err(crate::recipe::build_recipe::RecipeError::MissingParams { parameters, .. }) => {
                 return Ok(Some(Message::assistant().with_text(format!(
                     "Recipe requires {} parameter(s): {}. Provided: {}",
                     parameters.len(),
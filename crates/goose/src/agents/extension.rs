@@ -266,6 +266,12 @@ pub enum ExtensionConfig {
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Respawn the process if it exits while the session is running
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        auto_restart: bool,
+        /// How many times to respawn before giving up (default 3)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_restarts: Option<u32>,
     },
     /// Built-in extension that is part of the bundled goose MCP server
     #[serde(rename = "builtin")]
@@ -405,6 +411,8 @@ impl ExtensionConfig {
             timeout: Some(timeout.into()),
             bundled: None,
             available_tools: Vec::new(),
+            auto_restart: false,
+            max_restarts: None,
         }
     }
 
@@ -439,6 +447,8 @@ impl ExtensionConfig {
                 description,
                 bundled,
                 available_tools,
+                auto_restart,
+                max_restarts,
                 ..
             } => Self::Stdio {
                 name,
@@ -450,6 +460,8 @@ impl ExtensionConfig {
                 timeout,
                 bundled,
                 available_tools,
+                auto_restart,
+                max_restarts,
             },
             other => other,
         }
@@ -646,4 +658,33 @@ available_tools: []
             panic!("unexpected result of deserialization: {}", config)
         }
     }
+
+    #[test]
+    fn test_deserialize_stdio_auto_restart() {
+        let config: ExtensionConfig = serde_yaml::from_str(
+            "type: stdio
+name: github
+cmd: github-mcp-server
+args: [stdio]
+auto_restart: true
+max_restarts: 5",
+        )
+        .unwrap();
+        match &config {
+            ExtensionConfig::Stdio {
+                auto_restart,
+                max_restarts,
+                ..
+            } => {
+                assert!(*auto_restart);
+                assert_eq!(*max_restarts, Some(5));
+            }
+            _ => panic!("unexpected result of deserialization: {}", config),
+        }
+
+        let plain = ExtensionConfig::stdio("plain", "plain-server", "", 300u64);
+        let yaml = serde_yaml::to_string(&plain).unwrap();
+        assert!(!yaml.contains("auto_restart"));
+        assert!(!yaml.contains("max_restarts"));
+    }
 }
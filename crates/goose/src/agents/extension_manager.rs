@@ -14,7 +14,7 @@ use rmcp::transport::{
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tempfile::{tempdir, TempDir};
@@ -49,12 +49,19 @@ use serde_json::Value;
 
 type McpClientBox = Arc<Mutex<Box<dyn McpClientTrait>>>;
 
+const DEFAULT_MAX_RESTARTS: u32 = 3;
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
 struct Extension {
     pub config: ExtensionConfig,
 
     client: McpClientBox,
     server_info: Option<ServerInfo>,
     _temp_dir: Option<tempfile::TempDir>,
+    /// How often an auto-restarting extension has been respawned this session
+    restarts: Arc<AtomicU32>,
+    /// Set while a restart is waiting out its backoff or spawning the new process
+    restarting: Arc<AtomicBool>,
 }
 
 impl Extension {
@@ -69,6 +76,8 @@ impl Extension {
             config,
             server_info,
             _temp_dir: temp_dir,
+            restarts: Arc::new(AtomicU32::new(0)),
+            restarting: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -97,6 +106,8 @@ pub struct ExtensionManager {
     provider: SharedProvider,
     tools_cache: Mutex<Option<Arc<Vec<Tool>>>>,
     tools_cache_version: AtomicU64,
+    /// Restart events waiting to be shown to the user
+    restart_notices: Arc<Mutex<Vec<String>>>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -210,6 +221,170 @@ pub fn get_parameter_names(tool: &Tool) -> Vec<String> {
     names
 }
 
+/// Spawns the process for a stdio extension and connects to it.
+async fn stdio_client(
+    config: &ExtensionConfig,
+    key: &str,
+    provider: SharedProvider,
+    working_dir: Option<&PathBuf>,
+) -> ExtensionResult<McpClient> {
+    let ExtensionConfig::Stdio {
+        cmd,
+        args,
+        envs,
+        env_keys,
+        timeout,
+        ..
+    } = config
+    else {
+        return Err(ExtensionError::ConfigError(format!(
+            "Extension '{}' is not a stdio extension",
+            config.name()
+        )));
+    };
+
+    let all_envs = merge_environments(envs, env_keys, key).await?;
+
+    // Check for malicious packages before launching the process
+    extension_malware_check::deny_if_malicious_cmd_args(cmd, args).await?;
+
+    let cmd = resolve_command(cmd);
+
+    let command = Command::new(cmd).configure(|command| {
+        command.args(args).envs(all_envs);
+    });
+
+    child_process_client(command, timeout, provider, working_dir).await
+}
+
+/// Exponential backoff before the nth restart: 1s, 2s, 4s, ... capped at 30s.
+fn restart_backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(16)).min(MAX_RESTART_BACKOFF)
+}
+
+/// Respawns a stdio extension whose process died, replacing the client in place so
+/// callers holding the shared client handle pick up the new process. The restart runs in
+/// the background; calls made meanwhile fail fast rather than wait out the backoff.
+#[derive(Clone)]
+struct StdioRestarter {
+    name: String,
+    config: ExtensionConfig,
+    provider: SharedProvider,
+    working_dir: PathBuf,
+    max_restarts: u32,
+    restarts: Arc<AtomicU32>,
+    restarting: Arc<AtomicBool>,
+    notices: Arc<Mutex<Vec<String>>>,
+}
+
+impl StdioRestarter {
+    fn for_extension(
+        name: &str,
+        extension: &Extension,
+        provider: SharedProvider,
+        working_dir: PathBuf,
+        notices: Arc<Mutex<Vec<String>>>,
+    ) -> Option<Self> {
+        match &extension.config {
+            ExtensionConfig::Stdio {
+                auto_restart: true,
+                max_restarts,
+                ..
+            } => Some(Self {
+                name: name.to_string(),
+                config: extension.config.clone(),
+                provider,
+                working_dir,
+                max_restarts: max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS),
+                restarts: extension.restarts.clone(),
+                restarting: extension.restarting.clone(),
+                notices,
+            }),
+            _ => None,
+        }
+    }
+
+    fn is_restarting(&self) -> bool {
+        self.restarting.load(Ordering::SeqCst)
+    }
+
+    fn restarting_error(&self) -> ErrorData {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!(
+                "Extension '{}' stopped unexpectedly and is being restarted. Try the call again shortly.",
+                self.name
+            ),
+            None,
+        )
+    }
+
+    /// Starts replacing a dead client in the background, unless the restart budget is spent
+    /// or a restart is already underway.
+    async fn start_restart(&self, client: &McpClientBox) {
+        if self
+            .restarting
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let attempt = self.restarts.fetch_add(1, Ordering::SeqCst) + 1;
+        if attempt > self.max_restarts {
+            self.restarting.store(false, Ordering::SeqCst);
+            if attempt == self.max_restarts + 1 {
+                self.notify(format!(
+                    "Extension '{}' stopped again and will not be restarted after {} attempts",
+                    self.name, self.max_restarts
+                ))
+                .await;
+            }
+            return;
+        }
+
+        let restarter = self.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            restarter.restart(attempt, &client).await;
+            restarter.restarting.store(false, Ordering::SeqCst);
+        });
+    }
+
+    async fn restart(&self, attempt: u32, client: &McpClientBox) {
+        tokio::time::sleep(restart_backoff(attempt)).await;
+        match stdio_client(
+            &self.config,
+            &self.name,
+            self.provider.clone(),
+            Some(&self.working_dir),
+        )
+        .await
+        {
+            Ok(new_client) => {
+                *client.lock().await = Box::new(new_client);
+                self.notify(format!(
+                    "Extension '{}' stopped unexpectedly and was restarted ({}/{})",
+                    self.name, attempt, self.max_restarts
+                ))
+                .await;
+            }
+            Err(e) => {
+                self.notify(format!(
+                    "Extension '{}' stopped unexpectedly and could not be restarted: {}",
+                    self.name, e
+                ))
+                .await;
+            }
+        }
+    }
+
+    async fn notify(&self, notice: String) {
+        warn!("{}", notice);
+        self.notices.lock().await.push(notice);
+    }
+}
+
 async fn child_process_client(
     mut command: Command,
     timeout: &Option<u64>,
@@ -453,6 +628,7 @@ impl ExtensionManager {
             provider,
             tools_cache: Mutex::new(None),
             tools_cache_version: AtomicU64::new(0),
+            restart_notices: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -520,34 +696,15 @@ impl ExtensionManager {
                 )
                 .await?
             }
-            ExtensionConfig::Stdio {
-                cmd,
-                args,
-                envs,
-                env_keys,
-                timeout,
-                ..
-            } => {
-                let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
-
-                // Check for malicious packages before launching the process
-                extension_malware_check::deny_if_malicious_cmd_args(cmd, args).await?;
-
-                let cmd = resolve_command(cmd);
-
-                let command = Command::new(cmd).configure(|command| {
-                    command.args(args).envs(all_envs);
-                });
-
-                let client = child_process_client(
-                    command,
-                    timeout,
+            ExtensionConfig::Stdio { .. } => Box::new(
+                stdio_client(
+                    &config,
+                    &sanitized_name,
                     self.provider.clone(),
                     Some(&effective_working_dir),
                 )
-                .await?;
-                Box::new(client)
-            }
+                .await?,
+            ),
             ExtensionConfig::Builtin { name, timeout, .. } => {
                 let timeout_duration = Duration::from_secs(timeout.unwrap_or(300));
                 let def = goose_mcp::BUILTIN_EXTENSIONS
@@ -673,6 +830,11 @@ impl ExtensionManager {
         Ok(())
     }
 
+    /// Takes the extension restart events recorded since the last call
+    pub async fn take_restart_notices(&self) -> Vec<String> {
+        std::mem::take(&mut *self.restart_notices.lock().await)
+    }
+
     pub async fn get_extension_and_tool_counts(&self) -> (usize, usize) {
         let enabled_extensions_count = self.extensions.lock().await.len();
 
@@ -693,6 +855,27 @@ impl ExtensionManager {
         self.extensions.lock().await.contains_key(name)
     }
 
+    /// Name and reported server version of each loaded extension, sorted by name. Extensions
+    /// that didn't report a version have an empty one.
+    pub async fn get_extension_versions(&self) -> Vec<(String, String)> {
+        let mut versions: Vec<(String, String)> = self
+            .extensions
+            .lock()
+            .await
+            .iter()
+            .map(|(name, ext)| {
+                let version = ext
+                    .server_info
+                    .as_ref()
+                    .map(|info| info.server_info.version.clone())
+                    .unwrap_or_default();
+                (name.clone(), version)
+            })
+            .collect();
+        versions.sort();
+        versions
+    }
+
     pub async fn get_extension_configs(&self) -> Vec<ExtensionConfig> {
         self.extensions
             .lock()
@@ -1152,6 +1335,7 @@ impl ExtensionManager {
             })?
             .to_string();
 
+        let mut restarter = None;
         if let Some(extension) = self.extensions.lock().await.get(&client_name) {
             if !extension.config.is_tool_available(&tool_name) {
                 return Err(ErrorData::new(
@@ -1164,6 +1348,25 @@ impl ExtensionManager {
                 )
                 .into());
             }
+            restarter = StdioRestarter::for_extension(
+                &client_name,
+                extension,
+                self.provider.clone(),
+                self.resolve_working_dir().await,
+                self.restart_notices.clone(),
+            );
+        }
+
+        // Only restart before sending: a call that failed mid-flight may already have run on
+        // the server, so it is reported rather than replayed.
+        if let Some(restarter) = &restarter {
+            let closed = client.lock().await.is_closed().await;
+            if closed {
+                restarter.start_restart(&client).await;
+            }
+            if restarter.is_restarting() {
+                return Err(restarter.restarting_error().into());
+            }
         }
 
         let arguments = tool_call.arguments.clone();
@@ -2057,4 +2260,60 @@ mod tests {
         assert!(tool_names.iter().any(|n| n.starts_with("ext_a__")));
         assert!(!tool_names.iter().any(|n| n.starts_with("ext_b__")));
     }
+
+    #[test]
+    fn test_restart_backoff() {
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(3), Duration::from_secs(4));
+        assert_eq!(restart_backoff(10), MAX_RESTART_BACKOFF);
+        assert_eq!(restart_backoff(u32::MAX), MAX_RESTART_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_restart_runs_in_the_background() {
+        let mut config = ExtensionConfig::stdio(
+            "flaky",
+            "/nonexistent/goose-flaky-extension",
+            "an extension that cannot start",
+            5u64,
+        );
+        if let ExtensionConfig::Stdio {
+            auto_restart,
+            max_restarts,
+            ..
+        } = &mut config
+        {
+            *auto_restart = true;
+            *max_restarts = Some(1);
+        }
+        let client: McpClientBox = Arc::new(Mutex::new(Box::new(MockClient {})));
+        let extension = Extension::new(config, client.clone(), None, None);
+        let notices = Arc::new(Mutex::new(Vec::new()));
+        let restarter = StdioRestarter::for_extension(
+            "flaky",
+            &extension,
+            Arc::new(Mutex::new(None)),
+            std::env::temp_dir(),
+            notices.clone(),
+        )
+        .unwrap();
+
+        // Returns straight away and leaves the backoff to the background task
+        restarter.start_restart(&client).await;
+        assert!(restarter.is_restarting());
+        assert!(notices.lock().await.is_empty());
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while restarter.is_restarting() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(notices.lock().await[0].contains("could not be restarted"));
+
+        restarter.start_restart(&client).await;
+        assert!(!restarter.is_restarting());
+        assert!(notices.lock().await[1].contains("will not be restarted"));
+    }
 }
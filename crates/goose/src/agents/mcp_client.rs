@@ -112,6 +112,11 @@ pub trait McpClientTrait: Send + Sync {
     async fn get_moim(&self, _session_id: &str) -> Option<String> {
         None
     }
+
+    /// Whether the connection is known to be gone, so a request could not reach the server.
+    async fn is_closed(&self) -> bool {
+        false
+    }
 }
 
 pub struct GooseClient {
@@ -397,6 +402,10 @@ impl McpClientTrait for McpClient {
         self.server_info.as_ref()
     }
 
+    async fn is_closed(&self) -> bool {
+        self.client.lock().await.is_transport_closed()
+    }
+
     async fn list_resources(
         &self,
         cursor: Option<String>,
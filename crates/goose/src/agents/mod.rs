@@ -22,12 +22,16 @@ mod subagent_task_config;
 pub mod subagent_tool;
 pub(crate) mod todo_extension;
 mod tool_execution;
+pub mod tool_result_cache;
 pub mod types;
 
-pub use agent::{Agent, AgentConfig, AgentEvent, ExtensionLoadResult};
+pub use agent::{
+    Agent, AgentConfig, AgentEvent, ExtensionLoadResult, GOOSE_RETRIES_COUNT_TOWARD_MAX_TURNS,
+};
 pub use execute_commands::COMPACT_TRIGGERS;
 pub use extension::ExtensionConfig;
 pub use extension_manager::{normalize, ExtensionManager};
 pub use prompt_manager::PromptManager;
 pub use subagent_task_config::TaskConfig;
+pub use tool_execution::GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS;
 pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
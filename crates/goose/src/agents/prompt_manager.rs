@@ -20,6 +20,8 @@ const MAX_TOOLS: usize = 50;
 pub struct PromptManager {
     system_prompt_override: Option<String>,
     system_prompt_extras: Vec<String>,
+    system_prompt_appends: Vec<String>,
+    system_prompt_prepends: Vec<String>,
     current_date_timestamp: String,
 }
 
@@ -52,6 +54,7 @@ pub struct SystemPromptBuilder<'a, M> {
     subagents_enabled: bool,
     hints: Option<String>,
     code_execution_mode: bool,
+    tools_disabled: bool,
 }
 
 impl<'a> SystemPromptBuilder<'a, PromptManager> {
@@ -86,6 +89,12 @@ impl<'a> SystemPromptBuilder<'a, PromptManager> {
         self
     }
 
+    /// Tells the model that no tools are available, even outside `GooseMode::Chat`
+    pub fn with_tools_disabled(mut self, disabled: bool) -> Self {
+        self.tools_disabled = disabled;
+        self
+    }
+
     pub fn with_hints(mut self, working_dir: &Path) -> Self {
         let config = Config::global();
         let hints_filenames = config
@@ -176,19 +185,22 @@ impl<'a> SystemPromptBuilder<'a, PromptManager> {
             system_prompt_extras.push(hints);
         }
 
-        if goose_mode == GooseMode::Chat {
+        if goose_mode == GooseMode::Chat || self.tools_disabled {
             system_prompt_extras.push(
                 "Right now you are in the chat only mode, no access to any tool use and system."
                     .to_string(),
             );
         }
 
+        // Per-run appends go last so they compose after extension and project instructions
+        system_prompt_extras.extend(self.manager.system_prompt_appends.iter().cloned());
+
         let sanitized_system_prompt_extras: Vec<String> = system_prompt_extras
             .into_iter()
             .map(|extra| sanitize_unicode_tags(&extra))
             .collect();
 
-        if sanitized_system_prompt_extras.is_empty() {
+        let system_prompt = if sanitized_system_prompt_extras.is_empty() {
             base_prompt
         } else {
             format!(
@@ -196,6 +208,18 @@ impl<'a> SystemPromptBuilder<'a, PromptManager> {
                 base_prompt,
                 sanitized_system_prompt_extras.join("\n\n")
             )
+        };
+
+        if self.manager.system_prompt_prepends.is_empty() {
+            system_prompt
+        } else {
+            let prepends: Vec<String> = self
+                .manager
+                .system_prompt_prepends
+                .iter()
+                .map(|prepend| sanitize_unicode_tags(prepend))
+                .collect();
+            format!("{}\n\n{}", prepends.join("\n\n"), system_prompt)
         }
     }
 }
@@ -205,6 +229,8 @@ impl PromptManager {
         PromptManager {
             system_prompt_override: None,
             system_prompt_extras: Vec::new(),
+            system_prompt_appends: Vec::new(),
+            system_prompt_prepends: Vec::new(),
             // Use the fixed current date time so that prompt cache can be used.
             // Filtering to an hour to balance user time accuracy and multi session prompt cache hits.
             current_date_timestamp: Utc::now().format("%Y-%m-%d %H:00").to_string(),
@@ -216,6 +242,8 @@ impl PromptManager {
         PromptManager {
             system_prompt_override: None,
             system_prompt_extras: Vec::new(),
+            system_prompt_appends: Vec::new(),
+            system_prompt_prepends: Vec::new(),
             current_date_timestamp: dt.format("%Y-%m-%d %H:%M:%S").to_string(),
         }
     }
@@ -225,6 +253,16 @@ impl PromptManager {
         self.system_prompt_extras.push(instruction);
     }
 
+    /// Append text to the end of the assembled system prompt, after hints and extras
+    pub fn add_system_prompt_append(&mut self, text: String) {
+        self.system_prompt_appends.push(text);
+    }
+
+    /// Put text at the very start of the system prompt, ahead of the base prompt
+    pub fn add_system_prompt_prepend(&mut self, text: String) {
+        self.system_prompt_prepends.push(text);
+    }
+
     /// Override the system prompt with custom text
     pub fn set_system_prompt_override(&mut self, template: String) {
         self.system_prompt_override = Some(template);
@@ -240,6 +278,7 @@ impl PromptManager {
             subagents_enabled: false,
             hints: None,
             code_execution_mode: false,
+            tools_disabled: false,
         }
     }
 
@@ -317,6 +356,37 @@ mod tests {
         assert!(result.contains("emojis"));
     }
 
+    #[test]
+    fn test_build_system_prompt_appends_after_extras() {
+        let mut manager = PromptManager::new();
+        manager.add_system_prompt_append("respond in French".to_string());
+        manager.add_system_prompt_extra("Extra instruction".to_string());
+        manager.add_system_prompt_append("be terse\u{E0041}".to_string());
+
+        let result = manager.builder().build();
+
+        let extra_pos = result.find("Extra instruction").unwrap();
+        let french_pos = result.find("respond in French").unwrap();
+        let terse_pos = result.find("be terse").unwrap();
+        assert!(extra_pos < french_pos);
+        assert!(french_pos < terse_pos);
+        assert!(!result.contains('\u{E0041}'));
+    }
+
+    #[test]
+    fn test_build_system_prompt_prepends_before_base_prompt() {
+        let mut manager = PromptManager::new();
+        manager.add_system_prompt_prepend("You work for ACME".to_string());
+        manager.add_system_prompt_prepend("Be careful\u{E0041}".to_string());
+        manager.add_system_prompt_extra("Extra instruction".to_string());
+
+        let result = manager.builder().build();
+
+        assert!(result.starts_with("You work for ACME\n\nBe careful\n\n"));
+        assert!(result.contains("Extra instruction"));
+        assert!(!result.contains('\u{E0041}'));
+    }
+
     #[test]
     fn test_build_system_prompt_sanitizes_extension_instructions() {
         let manager = PromptManager::new();
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use async_stream::try_stream;
 use futures::stream::StreamExt;
 use serde_json::{json, Value};
-use tracing::debug;
+use tracing::{debug, Instrument};
 
 use super::super::agents::Agent;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
@@ -123,6 +123,11 @@ impl Agent {
             tools.push(frontend_tool.tool.clone());
         }
 
+        let tools_disabled = self.tools_disabled();
+        if tools_disabled {
+            tools.clear();
+        }
+
         let code_execution_active = self
             .extension_manager
             .is_extension_enabled(CODE_EXECUTION_EXTENSION)
@@ -154,7 +159,8 @@ impl Agent {
             .with_extension_and_tool_counts(extension_count, tool_count)
             .with_code_execution_mode(code_execution_active)
             .with_hints(working_dir)
-            .with_enable_subagents(self.subagents_enabled(session_id).await)
+            .with_enable_subagents(self.subagents_enabled().await)
+            .with_tools_disabled(tools_disabled)
             .build();
 
         // Handle toolshim if enabled
@@ -194,6 +200,11 @@ impl Agent {
         let tools = tools.to_owned();
         let toolshim_tools = toolshim_tools.to_owned();
         let provider = provider.clone();
+        let provider_span = tracing::info_span!(
+            "provider_call",
+            model = %config.model_name,
+            streaming = provider.supports_streaming()
+        );
 
         // Capture errors during stream creation and return them as part of the stream
         // so they can be handled by the existing error handling logic in the agent
@@ -205,6 +216,7 @@ impl Agent {
                     messages_for_provider.messages(),
                     &tools,
                 )
+                .instrument(provider_span.clone())
                 .await;
             debug!("WAITING_LLM_STREAM_END");
             result
@@ -216,6 +228,7 @@ impl Agent {
                     messages_for_provider.messages(),
                     &tools,
                 )
+                .instrument(provider_span.clone())
                 .await;
             debug!("WAITING_LLM_END");
 
@@ -238,7 +251,8 @@ impl Agent {
         };
 
         Ok(Box::pin(try_stream! {
-            while let Some(result) = stream.next().await {
+            // Chunks are polled inside the span so it covers the whole response
+            while let Some(result) = stream.next().instrument(provider_span.clone()).await {
                 let (mut message, usage) = result?;
 
                 // Store the model information in the global store
@@ -502,6 +516,14 @@ mod tests {
         sorted.sort();
         assert_eq!(names, sorted);
 
+        agent.set_tools_disabled(true);
+        let (tools, toolshim_tools, system_prompt) = agent
+            .prepare_tools_and_prompt(&session.id, &working_dir)
+            .await?;
+        assert!(tools.is_empty());
+        assert!(toolshim_tools.is_empty());
+        assert!(system_prompt.contains("chat only mode"));
+
         Ok(())
     }
 
@@ -1,5 +1,8 @@
 use crate::{
-    agents::{subagent_task_config::TaskConfig, Agent, AgentConfig, AgentEvent, SessionConfig},
+    agents::{
+        subagent_task_config::{max_subagent_depth, TaskConfig},
+        Agent, AgentConfig, AgentEvent, SessionConfig,
+    },
     conversation::{message::Message, Conversation},
     prompt_template::render_template,
     recipe::Recipe,
@@ -8,21 +11,72 @@ use anyhow::{anyhow, Result};
 use futures::StreamExt;
 use rmcp::model::{ErrorCode, ErrorData};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
 #[derive(Serialize)]
 struct SubagentPromptContext {
     max_turns: usize,
+    depth: usize,
+    max_depth: usize,
     subagent_id: String,
     task_instructions: String,
     tool_count: usize,
     available_tools: String,
 }
 
+/// Cancellation tokens of the subagents currently running under an agent, keyed by
+/// subagent id (the subagent's session id). Cancelling one stops only that subagent;
+/// its tool call returns an error and the parent carries on.
+#[derive(Clone, Default)]
+pub struct SubagentRegistry {
+    tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl SubagentRegistry {
+    pub fn register(&self, subagent_id: &str, token: CancellationToken) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(subagent_id.to_string(), token);
+        }
+    }
+
+    pub fn unregister(&self, subagent_id: &str) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.remove(subagent_id);
+        }
+    }
+
+    /// Cancels the subagent with the given id. Returns false if no such subagent is running.
+    pub fn cancel(&self, subagent_id: &str) -> bool {
+        match self
+            .tokens
+            .lock()
+            .ok()
+            .and_then(|tokens| tokens.get(subagent_id).cloned())
+        {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn running(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .tokens
+            .lock()
+            .map(|tokens| tokens.keys().cloned().collect())
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    }
+}
+
 type AgentMessagesFuture =
     Pin<Box<dyn Future<Output = Result<(Conversation, Option<String>)>> + Send>>;
 
@@ -52,8 +106,7 @@ pub async fn run_complete_subagent_task(
 
     let response_text = if return_last_only {
         messages
-            .messages()
-            .last()
+            .last_assistant_message()
             .and_then(|message| {
                 message.content.iter().find_map(|content| match content {
                     crate::conversation::message::MessageContent::Text(text_content) => {
@@ -126,6 +179,7 @@ fn get_agent_messages(
             .unwrap_or_else(|| "Begin.".to_string());
 
         let agent = Arc::new(Agent::with_config(config));
+        agent.set_subagent_depth(task_config.depth);
 
         agent
             .update_provider(task_config.provider, &session_id)
@@ -154,6 +208,8 @@ fn get_agent_messages(
                 max_turns: task_config
                     .max_turns
                     .expect("TaskConfig always sets max_turns"),
+                depth: task_config.depth,
+                max_depth: max_subagent_depth(),
                 subagent_id: session_id.clone(),
                 task_instructions: system_instructions,
                 tool_count: tools.len(),
@@ -192,7 +248,9 @@ fn get_agent_messages(
         while let Some(message_result) = stream.next().await {
             match message_result {
                 Ok(AgentEvent::Message(msg)) => conversation.push(msg),
-                Ok(AgentEvent::McpNotification(_)) | Ok(AgentEvent::ModelChange { .. }) => {}
+                Ok(AgentEvent::McpNotification(_))
+                | Ok(AgentEvent::ModelChange { .. })
+                | Ok(AgentEvent::ContextLengthExceeded) => {}
                 Ok(AgentEvent::HistoryReplaced(updated_conversation)) => {
                     conversation = updated_conversation;
                 }
@@ -217,3 +275,28 @@ fn get_agent_messages(
         Ok((conversation, final_output))
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_cancels_only_the_named_subagent() {
+        let registry = SubagentRegistry::default();
+        let parent = CancellationToken::new();
+        let first = parent.child_token();
+        let second = parent.child_token();
+        registry.register("sub_1", first.clone());
+        registry.register("sub_2", second.clone());
+
+        assert_eq!(registry.running(), vec!["sub_1", "sub_2"]);
+        assert!(registry.cancel("sub_1"));
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        registry.unregister("sub_1");
+        assert!(!registry.cancel("sub_1"));
+        assert_eq!(registry.running(), vec!["sub_2"]);
+    }
+}
@@ -11,6 +11,21 @@ pub const DEFAULT_SUBAGENT_MAX_TURNS: usize = 25;
 /// Environment variable name for configuring max turns
 pub const GOOSE_SUBAGENT_MAX_TURNS_ENV_VAR: &str = "GOOSE_SUBAGENT_MAX_TURNS";
 
+/// Default maximum nesting depth of subagents; the main agent's subagents are at depth 1,
+/// so by default subagents cannot spawn subagents of their own
+pub const DEFAULT_SUBAGENT_MAX_DEPTH: usize = 1;
+
+/// Environment variable name for configuring the maximum subagent depth
+pub const GOOSE_SUBAGENT_MAX_DEPTH_ENV_VAR: &str = "GOOSE_SUBAGENT_MAX_DEPTH";
+
+/// How deep subagents may nest before spawning another one is refused
+pub fn max_subagent_depth() -> usize {
+    env::var(GOOSE_SUBAGENT_MAX_DEPTH_ENV_VAR)
+        .ok()
+        .and_then(|val| val.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SUBAGENT_MAX_DEPTH)
+}
+
 /// Configuration for task execution with all necessary dependencies
 #[derive(Clone)]
 pub struct TaskConfig {
@@ -19,6 +34,8 @@ pub struct TaskConfig {
     pub parent_working_dir: PathBuf,
     pub extensions: Vec<ExtensionConfig>,
     pub max_turns: Option<usize>,
+    /// Nesting depth of the subagent this config spawns
+    pub depth: usize,
 }
 
 impl fmt::Debug for TaskConfig {
@@ -28,6 +45,7 @@ impl fmt::Debug for TaskConfig {
             .field("parent_session_id", &self.parent_session_id)
             .field("parent_working_dir", &self.parent_working_dir)
             .field("max_turns", &self.max_turns)
+            .field("depth", &self.depth)
             .field("extensions", &self.extensions)
             .finish()
     }
@@ -39,6 +57,7 @@ impl TaskConfig {
         parent_session_id: &str,
         parent_working_dir: &Path,
         extensions: Vec<ExtensionConfig>,
+        depth: usize,
     ) -> Self {
         Self {
             provider,
@@ -51,6 +70,7 @@ impl TaskConfig {
                     .and_then(|val| val.parse::<usize>().ok())
                     .unwrap_or(DEFAULT_SUBAGENT_MAX_TURNS),
             ),
+            depth,
         }
     }
 }
@@ -1,22 +1,26 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use futures::FutureExt;
-use rmcp::model::{Content, ErrorCode, ErrorData, Tool};
+use rmcp::model::{
+    Content, ErrorCode, ErrorData, LoggingLevel, LoggingMessageNotification,
+    LoggingMessageNotificationMethod, LoggingMessageNotificationParam, ServerNotification, Tool,
+};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use tokio_util::sync::CancellationToken;
 
-use crate::agents::subagent_handler::run_complete_subagent_task;
+use crate::agents::subagent_handler::{run_complete_subagent_task, SubagentRegistry};
 use crate::agents::subagent_task_config::TaskConfig;
 use crate::agents::tool_execution::ToolCallResult;
 use crate::agents::AgentConfig;
 use crate::providers;
-use crate::recipe::build_recipe::build_recipe_from_template;
+use crate::recipe::build_recipe::{build_sub_recipe_from_template, RecipeError};
 use crate::recipe::local_recipes::load_local_recipe_file;
-use crate::recipe::{Recipe, SubRecipe};
+use crate::recipe::{Recipe, RecipeParameter, SubRecipe};
 
 pub const SUBAGENT_TOOL_NAME: &str = "subagent";
 
@@ -182,6 +186,7 @@ pub fn handle_subagent_tool(
     sub_recipes: HashMap<String, SubRecipe>,
     working_dir: PathBuf,
     cancellation_token: Option<CancellationToken>,
+    registry: SubagentRegistry,
 ) -> ToolCallResult {
     let parsed_params: SubagentParams = match serde_json::from_value(params) {
         Ok(p) => p,
@@ -221,6 +226,12 @@ pub fn handle_subagent_tool(
         }
     };
 
+    let step_timeout = parsed_params
+        .subrecipe
+        .as_ref()
+        .and_then(|name| sub_recipes.get(name))
+        .and_then(|sub_recipe| sub_recipe.timeout());
+
     let config = config.clone();
     ToolCallResult {
         notification_stream: None,
@@ -232,12 +243,46 @@ pub fn handle_subagent_tool(
                 parsed_params,
                 working_dir,
                 cancellation_token,
+                registry,
+                step_timeout,
             )
             .boxed(),
         ),
     }
 }
 
+/// Refuses a subagent spawn from an agent already `depth` subagents deep, telling the user
+/// through a notification as well as failing the tool call.
+pub fn subagent_depth_exceeded(depth: usize, max_depth: usize) -> ToolCallResult {
+    let message = format!(
+        "Refused to spawn a subagent at depth {}: the maximum subagent depth is {} (set {} to change it)",
+        depth + 1,
+        max_depth,
+        crate::agents::subagent_task_config::GOOSE_SUBAGENT_MAX_DEPTH_ENV_VAR
+    );
+    let notification = ServerNotification::LoggingMessageNotification(LoggingMessageNotification {
+        params: LoggingMessageNotificationParam {
+            level: LoggingLevel::Warning,
+            logger: Some(SUBAGENT_TOOL_NAME.to_string()),
+            data: json!({
+                "type": "subagent_depth_exceeded",
+                "message": message,
+            }),
+        },
+        method: LoggingMessageNotificationMethod,
+        extensions: Default::default(),
+    });
+    ToolCallResult {
+        notification_stream: Some(Box::new(futures::stream::iter(vec![notification]))),
+        result: Box::new(futures::future::ready(Err(ErrorData {
+            code: ErrorCode::INVALID_REQUEST,
+            message: Cow::from(message),
+            data: None,
+        }))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn execute_subagent(
     config: AgentConfig,
     recipe: Recipe,
@@ -245,6 +290,8 @@ async fn execute_subagent(
     params: SubagentParams,
     working_dir: PathBuf,
     cancellation_token: Option<CancellationToken>,
+    registry: SubagentRegistry,
+    step_timeout: Option<Duration>,
 ) -> Result<rmcp::model::CallToolResult, ErrorData> {
     let session = config
         .session_manager
@@ -268,15 +315,57 @@ async fn execute_subagent(
             data: None,
         })?;
 
-    let result = run_complete_subagent_task(
+    // Each subagent gets its own child token so it can be cancelled on its own
+    // without aborting the parent's turn.
+    let subagent_token = cancellation_token
+        .as_ref()
+        .map(|token| token.child_token())
+        .unwrap_or_default();
+    registry.register(&session.id, subagent_token.clone());
+
+    let task = run_complete_subagent_task(
         config,
         recipe,
         task_config,
         params.summary,
-        session.id,
-        cancellation_token,
-    )
-    .await;
+        session.id.clone(),
+        Some(subagent_token.clone()),
+    );
+    let result = match step_timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, task).await {
+            Ok(result) => result,
+            Err(_) => {
+                subagent_token.cancel();
+                registry.unregister(&session.id);
+                return Err(ErrorData {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: Cow::from(format!(
+                        "Recipe step '{}' exceeded its timeout of {}s",
+                        params.subrecipe.as_deref().unwrap_or("subagent"),
+                        timeout.as_secs()
+                    )),
+                    data: None,
+                });
+            }
+        },
+        None => task.await,
+    };
+
+    registry.unregister(&session.id);
+
+    let parent_cancelled = cancellation_token
+        .as_ref()
+        .is_some_and(|token| token.is_cancelled());
+    if subagent_token.is_cancelled() && !parent_cancelled {
+        return Err(ErrorData {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::from(format!(
+                "Subagent {} was cancelled by the user before finishing",
+                session.id
+            )),
+            data: None,
+        });
+    }
 
     match result {
         Ok(text) => Ok(rmcp::model::CallToolResult {
@@ -346,12 +435,19 @@ fn build_subrecipe(
         }
     }
 
-    let mut recipe = build_recipe_from_template(
+    let mut recipe = build_sub_recipe_from_template(
         recipe_file.content,
         &recipe_file.parent_dir,
         param_values,
-        None::<fn(&str, &str) -> Result<String, anyhow::Error>>,
+        None::<fn(&RecipeParameter) -> Result<String, anyhow::Error>>,
     )
+    .map_err(|e| match e {
+        RecipeError::MissingParams { parameters, .. } => RecipeError::MissingParams {
+            parameters,
+            sub_recipe: Some(subrecipe_name.to_string()),
+        },
+        other => other,
+    })
     .map_err(|e| anyhow!("Failed to build subrecipe: {}", e))?;
 
     if let Some(extra) = &params.instructions {
@@ -455,7 +551,9 @@ mod tests {
             name: "test_recipe".to_string(),
             path: "test.yaml".to_string(),
             values: None,
+            inherit: Vec::new(),
             sequential_when_repeated: false,
+            timeout_seconds: None,
             description: Some("A test recipe".to_string()),
         }];
 
@@ -475,14 +573,18 @@ mod tests {
                 name: "parallel_ok".to_string(),
                 path: "test.yaml".to_string(),
                 values: None,
+                inherit: Vec::new(),
                 sequential_when_repeated: false,
+                timeout_seconds: None,
                 description: Some("Can run in parallel".to_string()),
             },
             SubRecipe {
                 name: "sequential_only".to_string(),
                 path: "test.yaml".to_string(),
                 values: None,
+                inherit: Vec::new(),
                 sequential_when_repeated: true,
+                timeout_seconds: None,
                 description: Some("Must run sequentially".to_string()),
             },
         ];
@@ -496,6 +598,24 @@ mod tests {
         assert!(desc.contains("sequential_only [run sequentially, not in parallel]"));
     }
 
+    #[tokio::test]
+    async fn test_subagent_depth_exceeded() {
+        use futures::StreamExt;
+
+        let result = subagent_depth_exceeded(3, 3);
+        let notifications: Vec<_> = result.notification_stream.unwrap().collect().await;
+        assert_eq!(notifications.len(), 1);
+        let ServerNotification::LoggingMessageNotification(notification) = &notifications[0] else {
+            panic!("expected a logging notification");
+        };
+        assert_eq!(notification.params.data["type"], "subagent_depth_exceeded");
+
+        let err = result.result.await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_REQUEST);
+        assert!(err.message.contains("depth 4"));
+        assert!(err.message.contains("maximum subagent depth is 3"));
+    }
+
     #[test]
     fn test_params_deserialization_full() {
         let params: SubagentParams = serde_json::from_value(json!({
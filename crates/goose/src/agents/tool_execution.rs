@@ -1,16 +1,20 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
 use futures::{Stream, StreamExt};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 use crate::config::permission::PermissionLevel;
+use crate::config::{Config, GooseMode};
 use crate::mcp_utils::ToolResult;
-use crate::permission::Permission;
+use crate::permission::permission_confirmation::PrincipalType;
+use crate::permission::{Permission, PermissionConfirmation};
 use rmcp::model::{Content, ServerNotification};
 
 // ToolCallResult combines the result of a tool call with an optional notification stream that
@@ -48,6 +52,60 @@ pub const CHAT_MODE_TOOL_SKIPPED_RESPONSE: &str = "Let the user know the tool ca
                                         2. **Outline Steps** - Break down the steps.\n \
                                         If needed, adjust the explanation based on user preferences or questions.";
 
+/// Seconds to wait for a user to answer a tool confirmation before resolving it automatically.
+/// Unset (or 0) waits indefinitely.
+pub const GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS: &str = "GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS";
+
+fn tool_confirmation_timeout() -> Option<Duration> {
+    Config::global()
+        .get_param::<u64>(GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS)
+        .ok()
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// The decision applied to a confirmation nobody answered: allow in auto mode, deny otherwise.
+fn timed_out_confirmation(goose_mode: GooseMode) -> PermissionConfirmation {
+    let permission = if goose_mode == GooseMode::Auto {
+        Permission::AllowOnce
+    } else {
+        Permission::DenyOnce
+    };
+    PermissionConfirmation {
+        principal_type: PrincipalType::Tool,
+        permission,
+    }
+}
+
+/// Receive the next confirmation, synthesizing one for `request_id` if the deadline passes
+/// so a turn cannot deadlock on a confirmation that never gets answered.
+async fn next_tool_confirmation(
+    rx: &mut mpsc::Receiver<(String, PermissionConfirmation)>,
+    deadline: Option<Instant>,
+    request_id: &str,
+    tool_name: &str,
+) -> Option<(String, PermissionConfirmation)> {
+    let Some(deadline) = deadline else {
+        return rx.recv().await;
+    };
+
+    match tokio::time::timeout_at(deadline, rx.recv()).await {
+        Ok(received) => received,
+        Err(_) => {
+            let goose_mode = Config::global().get_goose_mode().unwrap_or(GooseMode::Auto);
+            let confirmation = timed_out_confirmation(goose_mode);
+            tracing::warn!(
+                tool_name = %tool_name,
+                request_id = %request_id,
+                decision = ?confirmation.permission,
+                "No response to tool confirmation before {} elapsed; resolving automatically",
+                GOOSE_TOOL_CONFIRMATION_TIMEOUT_SECONDS
+            );
+            Some((request_id.to_string(), confirmation))
+        }
+    }
+}
+
 impl Agent {
     pub(crate) fn handle_approval_tool_requests<'a>(
         &'a self,
@@ -71,6 +129,11 @@ impl Agent {
                             None
                         }
                     });
+                // The tool author's warning is shown on its own, since a confirmation prompt
+                // is reserved for security findings and takes away "Always Allow"
+                if let Some(tool_prompt) = request.confirmation_prompt() {
+                    yield Message::assistant().with_text(tool_prompt).user_only();
+                }
 
                 let confirmation = Message::assistant()
                     .with_action_required(
@@ -83,7 +146,10 @@ impl Agent {
                 yield confirmation;
 
                 let mut rx = self.confirmation_rx.lock().await;
-                while let Some((req_id, confirmation)) = rx.recv().await {
+                let deadline = tool_confirmation_timeout().map(|timeout| Instant::now() + timeout);
+                while let Some((req_id, confirmation)) =
+                    next_tool_confirmation(&mut rx, deadline, &request.id, &tool_call.name).await
+                {
                     if req_id == request.id {
                         // Log user decision if this was a security alert
                         if let Some(finding_id) = get_security_finding_id_from_results(&request.id, inspection_results) {
@@ -174,3 +240,52 @@ impl Agent {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timed_out_confirmation_depends_on_mode() {
+        assert_eq!(
+            timed_out_confirmation(GooseMode::Auto).permission,
+            Permission::AllowOnce
+        );
+        assert_eq!(
+            timed_out_confirmation(GooseMode::Approve).permission,
+            Permission::DenyOnce
+        );
+        assert_eq!(
+            timed_out_confirmation(GooseMode::SmartApprove).permission,
+            Permission::DenyOnce
+        );
+    }
+
+    #[tokio::test]
+    async fn test_next_tool_confirmation_times_out() {
+        let (_tx, mut rx) = mpsc::channel(1);
+        let deadline = Some(Instant::now() + Duration::from_millis(10));
+
+        let (id, _confirmation) = next_tool_confirmation(&mut rx, deadline, "req-1", "shell")
+            .await
+            .expect("timeout should synthesize a confirmation");
+
+        assert_eq!(id, "req-1");
+    }
+
+    #[tokio::test]
+    async fn test_next_tool_confirmation_passes_through_responses() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let confirmation = PermissionConfirmation {
+            principal_type: PrincipalType::Tool,
+            permission: Permission::AlwaysAllow,
+        };
+        tx.send(("req-2".to_string(), confirmation.clone()))
+            .await
+            .unwrap();
+
+        let received = next_tool_confirmation(&mut rx, None, "req-2", "shell").await;
+
+        assert_eq!(received, Some(("req-2".to_string(), confirmation)));
+    }
+}
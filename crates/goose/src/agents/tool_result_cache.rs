@@ -0,0 +1,331 @@
+use lru::LruCache;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::Config;
+
+pub const GOOSE_TOOL_RESULT_CACHE: &str = "GOOSE_TOOL_RESULT_CACHE";
+
+const TEXT_EDITOR_WRITE_COMMANDS: &[&str] = &["write", "str_replace", "insert", "undo_edit"];
+
+/// Results kept per session; the least recently used are evicted past this
+const MAX_CACHED_RESULTS: NonZeroUsize = NonZeroUsize::new(64).unwrap();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    tool_name: String,
+    arguments: String,
+}
+
+struct CacheEntry {
+    path: Option<PathBuf>,
+    result: CallToolResult,
+}
+
+/// What a tool call means for the cache.
+#[derive(Debug, PartialEq)]
+enum ToolEffect {
+    /// Idempotent read that may be served from the cache.
+    Cacheable,
+    /// Declared read-only by its tool, so it leaves the cache alone.
+    ReadOnly,
+    /// May have written to this path.
+    Writes(PathBuf),
+    /// May have written anywhere.
+    Unknown,
+}
+
+/// Per-session cache of results from idempotent tools (`text_editor view`, `analyze`).
+/// Opt-in through `GOOSE_TOOL_RESULT_CACHE`. Cached entries are dropped when a later
+/// tool call may have written to the path they read; calls to tools annotated as
+/// read-only never drop anything. Each session keeps a bounded number of results.
+#[derive(Clone, Default)]
+pub struct ToolResultCache {
+    sessions: Arc<Mutex<HashMap<String, LruCache<CacheKey, CacheEntry>>>>,
+}
+
+impl ToolResultCache {
+    pub fn enabled() -> bool {
+        Config::global()
+            .get_param::<bool>(GOOSE_TOOL_RESULT_CACHE)
+            .unwrap_or(false)
+    }
+
+    /// Returns a cached result for a cacheable call, or invalidates whatever the call
+    /// may write to and returns `None`. `read_only` is the tool's `read_only_hint`.
+    pub fn lookup(
+        &self,
+        session_id: &str,
+        working_dir: &Path,
+        tool_call: &CallToolRequestParam,
+        read_only: bool,
+    ) -> Option<CallToolResult> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match classify(tool_call, working_dir, read_only) {
+            ToolEffect::Cacheable => sessions
+                .get_mut(session_id)
+                .and_then(|entries| entries.get(&cache_key(tool_call)))
+                .map(|entry| entry.result.clone()),
+            ToolEffect::ReadOnly => None,
+            ToolEffect::Writes(path) => {
+                if let Some(entries) = sessions.get_mut(session_id) {
+                    let stale: Vec<CacheKey> = entries
+                        .iter()
+                        .filter(|(_, entry)| overlaps(entry.path.as_deref(), &path))
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                    for key in stale {
+                        entries.pop(&key);
+                    }
+                }
+                None
+            }
+            ToolEffect::Unknown => {
+                sessions.remove(session_id);
+                None
+            }
+        }
+    }
+
+    pub fn is_cacheable(tool_call: &CallToolRequestParam) -> bool {
+        classify(tool_call, Path::new(""), false) == ToolEffect::Cacheable
+    }
+
+    pub fn store(
+        &self,
+        session_id: &str,
+        working_dir: &Path,
+        tool_call: &CallToolRequestParam,
+        result: &CallToolResult,
+    ) {
+        if result.is_error == Some(true) || !Self::is_cacheable(tool_call) {
+            return;
+        }
+        self.sessions
+            .lock()
+            .unwrap()
+            .entry(session_id.to_string())
+            .or_insert_with(|| LruCache::new(MAX_CACHED_RESULTS))
+            .put(
+                cache_key(tool_call),
+                CacheEntry {
+                    path: path_argument(tool_call, working_dir),
+                    result: result.clone(),
+                },
+            );
+    }
+}
+
+fn base_tool_name(tool_call: &CallToolRequestParam) -> &str {
+    tool_call
+        .name
+        .rsplit("__")
+        .next()
+        .unwrap_or(&tool_call.name)
+}
+
+fn string_argument<'a>(tool_call: &'a CallToolRequestParam, key: &str) -> Option<&'a str> {
+    tool_call
+        .arguments
+        .as_ref()
+        .and_then(|args| args.get(key))
+        .and_then(Value::as_str)
+}
+
+/// Resolves the call's `path` argument the way the developer tools do, so relative and
+/// absolute references to the same file match.
+fn path_argument(tool_call: &CallToolRequestParam, working_dir: &Path) -> Option<PathBuf> {
+    string_argument(tool_call, "path").map(|path| working_dir.join(path))
+}
+
+fn classify(tool_call: &CallToolRequestParam, working_dir: &Path, read_only: bool) -> ToolEffect {
+    match base_tool_name(tool_call) {
+        "analyze" => ToolEffect::Cacheable,
+        "text_editor" => match string_argument(tool_call, "command") {
+            Some("view") => ToolEffect::Cacheable,
+            Some(command) if !TEXT_EDITOR_WRITE_COMMANDS.contains(&command) => ToolEffect::Unknown,
+            _ => path_argument(tool_call, working_dir)
+                .map(ToolEffect::Writes)
+                .unwrap_or(ToolEffect::Unknown),
+        },
+        _ if read_only => ToolEffect::ReadOnly,
+        _ => path_argument(tool_call, working_dir)
+            .map(ToolEffect::Writes)
+            .unwrap_or(ToolEffect::Unknown),
+    }
+}
+
+/// A write to `written` affects a cached read of `read` when either contains the other,
+/// e.g. editing a file inside a directory that was analyzed.
+fn overlaps(read: Option<&Path>, written: &Path) -> bool {
+    match read {
+        Some(read) => read.starts_with(written) || written.starts_with(read),
+        None => true,
+    }
+}
+
+fn cache_key(tool_call: &CallToolRequestParam) -> CacheKey {
+    let arguments = tool_call
+        .arguments
+        .as_ref()
+        .map(|args| canonical_json(&Value::Object(args.clone())))
+        .unwrap_or_default();
+    CacheKey {
+        tool_name: tool_call.name.to_string(),
+        arguments,
+    }
+}
+
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        Value::String(key.clone()),
+                        canonical_json(&map[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+    use rmcp::object;
+
+    fn call(name: &str, arguments: Value) -> CallToolRequestParam {
+        CallToolRequestParam {
+            task: None,
+            name: name.to_string().into(),
+            arguments: arguments.as_object().cloned(),
+        }
+    }
+
+    fn result(text: &str) -> CallToolResult {
+        CallToolResult {
+            content: vec![Content::text(text)],
+            structured_content: None,
+            is_error: Some(false),
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_caches_reads_and_invalidates_on_write() {
+        let cache = ToolResultCache::default();
+        let repo = Path::new("/repo");
+        let view = call(
+            "developer__text_editor",
+            serde_json::json!({"command": "view", "path": "/repo/src/main.rs"}),
+        );
+        let reordered = call(
+            "developer__text_editor",
+            serde_json::json!({"path": "/repo/src/main.rs", "command": "view"}),
+        );
+        let analyze = call("developer__analyze", serde_json::json!({"path": "src"}));
+        let other_file = call(
+            "developer__text_editor",
+            serde_json::json!({"command": "view", "path": "/repo/README.md"}),
+        );
+
+        assert!(cache.lookup("s1", repo, &view, false).is_none());
+        cache.store("s1", repo, &view, &result("fn main() {}"));
+        cache.store("s1", repo, &analyze, &result("1 file"));
+        cache.store("s1", repo, &other_file, &result("# Readme"));
+
+        assert!(cache.lookup("s1", repo, &reordered, false).is_some());
+        assert!(cache.lookup("s2", repo, &view, false).is_none());
+
+        let write = call(
+            "developer__text_editor",
+            serde_json::json!({"command": "write", "path": "/repo/src/main.rs", "file_text": ""}),
+        );
+        assert!(cache.lookup("s1", repo, &write, false).is_none());
+        assert!(cache.lookup("s1", repo, &view, false).is_none());
+        assert!(cache.lookup("s1", repo, &analyze, false).is_none());
+        assert!(cache.lookup("s1", repo, &other_file, false).is_some());
+
+        let shell = CallToolRequestParam {
+            task: None,
+            name: "developer__shell".into(),
+            arguments: Some(object!({"command": "touch x"})),
+        };
+        assert!(cache.lookup("s1", repo, &shell, false).is_none());
+        assert!(cache.lookup("s1", repo, &other_file, false).is_none());
+    }
+
+    #[test]
+    fn test_error_results_are_not_cached() {
+        let cache = ToolResultCache::default();
+        let repo = Path::new("/repo");
+        let view = call(
+            "developer__text_editor",
+            serde_json::json!({"command": "view", "path": "/missing"}),
+        );
+        let mut error = result("not found");
+        error.is_error = Some(true);
+        cache.store("s1", repo, &view, &error);
+        assert!(cache.lookup("s1", repo, &view, false).is_none());
+    }
+
+    #[test]
+    fn test_read_only_tools_keep_the_cache() {
+        let cache = ToolResultCache::default();
+        let repo = Path::new("/repo");
+        let view = call(
+            "developer__text_editor",
+            serde_json::json!({"command": "view", "path": "/repo/src/main.rs"}),
+        );
+        cache.store("s1", repo, &view, &result("fn main() {}"));
+
+        let todo = call("todo__todo_read", serde_json::json!({}));
+        assert!(cache.lookup("s1", repo, &todo, true).is_none());
+        assert!(cache.lookup("s1", repo, &view, false).is_some());
+
+        assert!(cache.lookup("s1", repo, &todo, false).is_none());
+        assert!(cache.lookup("s1", repo, &view, false).is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_results() {
+        let cache = ToolResultCache::default();
+        let repo = Path::new("/repo");
+        let view = |i: usize| {
+            call(
+                "developer__text_editor",
+                serde_json::json!({"command": "view", "path": format!("/repo/{}.rs", i)}),
+            )
+        };
+        for i in 0..MAX_CACHED_RESULTS.get() {
+            cache.store("s1", repo, &view(i), &result("contents"));
+        }
+        assert!(cache.lookup("s1", repo, &view(0), false).is_some());
+
+        cache.store(
+            "s1",
+            repo,
+            &view(MAX_CACHED_RESULTS.get()),
+            &result("contents"),
+        );
+        assert!(cache.lookup("s1", repo, &view(0), false).is_some());
+        assert!(cache.lookup("s1", repo, &view(1), false).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::mcp_utils::ToolResult;
 use crate::providers::base::Provider;
 use rmcp::model::{CallToolResult, Tool};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
@@ -19,7 +20,7 @@ pub const DEFAULT_RETRY_TIMEOUT_SECONDS: u64 = 300;
 pub const DEFAULT_ON_FAILURE_TIMEOUT_SECONDS: u64 = 600;
 
 /// Configuration for retry logic in recipe execution
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
 pub struct RetryConfig {
     /// Maximum number of retry attempts before giving up
     pub max_retries: u32,
@@ -62,7 +63,7 @@ impl RetryConfig {
 }
 
 /// A single success check to validate recipe completion
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
 #[serde(tag = "type")]
 pub enum SuccessCheck {
     /// Execute a shell command and check its exit status
@@ -93,3 +94,79 @@ pub struct SessionConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_config: Option<RetryConfig>,
 }
+
+/// Why an iteration of the agent loop was started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnKind {
+    /// Responding to the user or continuing after tool calls
+    Prompt,
+    /// Re-running after a failed success check
+    Retry,
+    /// Served by the lead model while a lead/worker provider is in fallback mode
+    Fallback,
+}
+
+/// Breakdown of the turns spent in a single reply, used when enforcing `max_turns`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TurnUsage {
+    pub prompt: u32,
+    pub retry: u32,
+    pub fallback: u32,
+}
+
+impl TurnUsage {
+    pub fn record(&mut self, kind: TurnKind) {
+        match kind {
+            TurnKind::Prompt => self.prompt += 1,
+            TurnKind::Retry => self.retry += 1,
+            TurnKind::Fallback => self.fallback += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.prompt + self.retry + self.fallback
+    }
+
+    /// Turns charged against `max_turns`
+    pub fn counted(&self, count_retries: bool) -> u32 {
+        if count_retries {
+            self.total()
+        } else {
+            self.prompt + self.fallback
+        }
+    }
+
+    pub fn summary(&self, count_retries: bool) -> String {
+        let retries = if count_retries {
+            format!("{} retries", self.retry)
+        } else {
+            format!("{} retries (not counted)", self.retry)
+        };
+        format!(
+            "{} prompt/tool turns, {}, {} provider fallbacks",
+            self.prompt, retries, self.fallback
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_usage_counts_retries_only_when_configured() {
+        let mut usage = TurnUsage::default();
+        usage.record(TurnKind::Prompt);
+        usage.record(TurnKind::Prompt);
+        usage.record(TurnKind::Retry);
+        usage.record(TurnKind::Fallback);
+
+        assert_eq!(usage.total(), 4);
+        assert_eq!(usage.counted(true), 4);
+        assert_eq!(usage.counted(false), 3);
+        assert_eq!(
+            usage.summary(false),
+            "2 prompt/tool turns, 1 retries (not counted), 1 provider fallbacks"
+        );
+    }
+}
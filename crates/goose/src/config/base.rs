@@ -18,6 +18,8 @@ use thiserror::Error;
 const KEYRING_SERVICE: &str = "goose";
 const KEYRING_USERNAME: &str = "secrets";
 pub const CONFIG_YAML_NAME: &str = "config.yaml";
+/// Environment variable pointing at an organization-wide config file layered under the user config
+pub const GOOSE_SYSTEM_CONFIG_PATH: &str = "GOOSE_SYSTEM_CONFIG_PATH";
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -68,6 +70,11 @@ impl From<keyring::Error> for ConfigError {
 /// Configuration values are loaded with the following precedence:
 /// 1. Environment variables (exact key match)
 /// 2. Configuration file (~/.config/goose/config.yaml by default)
+/// 3. System (organization-wide) configuration file, read-only
+///    (/etc/goose/config.yaml by default, or the path in GOOSE_SYSTEM_CONFIG_PATH)
+///
+/// Layers are merged per top-level key: a key set in the user config replaces the
+/// system value for that key entirely.
 ///
 /// Secrets are loaded with the following precedence:
 /// 1. Environment variables (exact key match)
@@ -103,10 +110,44 @@ impl From<keyring::Error> for ConfigError {
 /// For goose-specific configuration, consider prefixing with "goose_" to avoid conflicts.
 pub struct Config {
     config_path: PathBuf,
+    system_config_path: Option<PathBuf>,
     secrets: SecretStorage,
     guard: Mutex<()>,
 }
 
+/// Where the effective value of a configuration key came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Environment,
+    User,
+    System,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Environment => write!(f, "environment"),
+            ConfigSource::User => write!(f, "user"),
+            ConfigSource::System => write!(f, "system"),
+        }
+    }
+}
+
+fn default_system_config_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var(GOOSE_SYSTEM_CONFIG_PATH) {
+        return Some(PathBuf::from(path));
+    }
+
+    if cfg!(windows) {
+        env::var("PROGRAMDATA")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("goose").join(CONFIG_YAML_NAME))
+    } else {
+        Some(PathBuf::from("/etc/goose").join(CONFIG_YAML_NAME))
+    }
+}
+
 enum SecretStorage {
     Keyring { service: String },
     File { path: PathBuf },
@@ -131,6 +172,7 @@ impl Default for Config {
         };
         Config {
             config_path,
+            system_config_path: default_system_config_path(),
             secrets,
             guard: Mutex::new(()),
         }
@@ -232,6 +274,7 @@ impl Config {
     pub fn new<P: AsRef<Path>>(config_path: P, service: &str) -> Result<Self, ConfigError> {
         Ok(Config {
             config_path: config_path.as_ref().to_path_buf(),
+            system_config_path: None,
             secrets: SecretStorage::Keyring {
                 service: service.to_string(),
             },
@@ -249,6 +292,7 @@ impl Config {
     ) -> Result<Self, ConfigError> {
         Ok(Config {
             config_path: config_path.as_ref().to_path_buf(),
+            system_config_path: None,
             secrets: SecretStorage::File {
                 path: secrets_path.as_ref().to_path_buf(),
             },
@@ -256,6 +300,16 @@ impl Config {
         })
     }
 
+    /// Layer a read-only system config file underneath this config
+    pub fn with_system_config<P: AsRef<Path>>(mut self, system_config_path: P) -> Self {
+        self.system_config_path = Some(system_config_path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn system_config_path(&self) -> Option<&Path> {
+        self.system_config_path.as_deref()
+    }
+
     pub fn exists(&self) -> bool {
         self.config_path.exists()
     }
@@ -290,14 +344,55 @@ impl Config {
         }
     }
 
+    /// Load the system config. It is never written to, and a missing or unreadable
+    /// file simply contributes no values.
+    fn load_system(&self) -> Mapping {
+        let Some(path) = self.system_config_path.as_ref().filter(|p| p.exists()) else {
+            return Mapping::new();
+        };
+
+        match std::fs::read_to_string(path)
+            .map_err(ConfigError::from)
+            .and_then(|content| parse_yaml_content(&content))
+        {
+            Ok(values) => values,
+            Err(e) => {
+                tracing::warn!("Ignoring system config {:?}: {}", path, e);
+                Mapping::new()
+            }
+        }
+    }
+
+    /// All file-backed values, with user values layered over system values.
     pub fn all_values(&self) -> Result<HashMap<String, Value>, ConfigError> {
-        self.load().map(|m| {
-            HashMap::from_iter(m.into_iter().filter_map(|(k, v)| {
-                k.as_str()
+        self.all_values_with_source()
+            .map(|values| HashMap::from_iter(values.into_iter().map(|(k, (v, _))| (k, v))))
+    }
+
+    /// Like `all_values`, but also reports which layer each effective value came from.
+    pub fn all_values_with_source(
+        &self,
+    ) -> Result<HashMap<String, (Value, ConfigSource)>, ConfigError> {
+        let user_values = self.load()?;
+        let system_values = self.load_system();
+
+        let layers = [
+            (system_values, ConfigSource::System),
+            (user_values, ConfigSource::User),
+        ];
+        let mut merged = HashMap::new();
+        for (values, source) in layers {
+            for (k, v) in values {
+                if let Some((key, value)) = k
+                    .as_str()
                     .map(|k| k.to_string())
                     .zip(serde_json::to_value(v).ok())
-            }))
-        })
+                {
+                    merged.insert(key, (value, source));
+                }
+            }
+        }
+        Ok(merged)
     }
 
     // Helper method to create and save default config with consistent logging
@@ -623,6 +718,7 @@ impl Config {
     /// This will attempt to get the value from:
     /// 1. Environment variable with the exact key name
     /// 2. Configuration file
+    /// 3. System configuration file
     ///
     /// The value will be deserialized into the requested type. This works with
     /// both simple types (String, i32, etc.) and complex types that implement
@@ -635,17 +731,31 @@ impl Config {
     /// - The value cannot be deserialized into the requested type
     /// - There is an error reading the config file
     pub fn get_param<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<T, ConfigError> {
+        self.get_param_with_source(key).map(|(value, _)| value)
+    }
+
+    /// Get a configuration value (non-secret) together with the layer it was read from.
+    ///
+    /// Lookup order is environment, then the user config file, then the system config file.
+    pub fn get_param_with_source<T: for<'de> Deserialize<'de>>(
+        &self,
+        key: &str,
+    ) -> Result<(T, ConfigSource), ConfigError> {
         let env_key = key.to_uppercase();
         if let Ok(val) = env::var(&env_key) {
             let value = Self::parse_env_value(&val)?;
-            return Ok(serde_json::from_value(value)?);
+            return Ok((serde_json::from_value(value)?, ConfigSource::Environment));
         }
 
         let values = self.load()?;
-        values
+        if let Some(v) = values.get(key) {
+            return Ok((serde_yaml::from_value(v.clone())?, ConfigSource::User));
+        }
+
+        self.load_system()
             .get(key)
             .ok_or_else(|| ConfigError::NotFound(key.to_string()))
-            .and_then(|v| Ok(serde_yaml::from_value(v.clone())?))
+            .and_then(|v| Ok((serde_yaml::from_value(v.clone())?, ConfigSource::System)))
     }
 
     /// Set a configuration value in the config file (non-secret).
@@ -1686,4 +1796,61 @@ mod tests {
         let secrets_file = NamedTempFile::new().unwrap();
         Config::new_with_file_secrets(config_file.path(), secrets_file.path()).unwrap()
     }
+
+    #[test]
+    #[serial]
+    fn test_system_config_layered_under_user() -> Result<(), ConfigError> {
+        let system_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            system_file.path(),
+            "ORG_ONLY_KEY: from_system\nORG_SHARED_KEY: from_system\n",
+        )?;
+        let config = new_test_config().with_system_config(system_file.path());
+
+        config.set_param("ORG_SHARED_KEY", "from_user")?;
+
+        let (value, source) = config.get_param_with_source::<String>("ORG_ONLY_KEY")?;
+        assert_eq!(value, "from_system");
+        assert_eq!(source, ConfigSource::System);
+
+        let (value, source) = config.get_param_with_source::<String>("ORG_SHARED_KEY")?;
+        assert_eq!(value, "from_user");
+        assert_eq!(source, ConfigSource::User);
+
+        let all = config.all_values_with_source()?;
+        assert_eq!(
+            all.get("ORG_ONLY_KEY"),
+            Some(&(Value::String("from_system".into()), ConfigSource::System))
+        );
+        assert_eq!(
+            all.get("ORG_SHARED_KEY"),
+            Some(&(Value::String("from_user".into()), ConfigSource::User))
+        );
+
+        // Writes only ever touch the user config
+        config.delete("ORG_SHARED_KEY")?;
+        let value: String = config.get_param("ORG_SHARED_KEY")?;
+        assert_eq!(value, "from_system");
+        let system_content = std::fs::read_to_string(system_file.path())?;
+        assert!(system_content.contains("ORG_SHARED_KEY: from_system"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_overrides_system_config() -> Result<(), ConfigError> {
+        let system_file = NamedTempFile::new().unwrap();
+        std::fs::write(system_file.path(), "ORG_ENV_KEY: from_system\n")?;
+        let config = new_test_config().with_system_config(system_file.path());
+
+        std::env::set_var("ORG_ENV_KEY", "from_env");
+        let result = config.get_param_with_source::<String>("ORG_ENV_KEY");
+        std::env::remove_var("ORG_ENV_KEY");
+
+        let (value, source) = result?;
+        assert_eq!(value, "from_env");
+        assert_eq!(source, ConfigSource::Environment);
+        Ok(())
+    }
 }
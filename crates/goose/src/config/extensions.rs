@@ -16,6 +16,9 @@ const EXTENSIONS_CONFIG_KEY: &str = "extensions";
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct ExtensionEntry {
     pub enabled: bool,
+    /// Session startup fails instead of continuing when a required extension can't start
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
     #[serde(flatten)]
     pub config: ExtensionConfig,
 }
@@ -68,6 +71,7 @@ fn get_extensions_map() -> IndexMap<String, ExtensionEntry> {
                         available_tools: Vec::new(),
                     },
                     enabled: def.default_enabled,
+                    required: false,
                 },
             );
         }
@@ -135,6 +139,15 @@ pub fn get_enabled_extensions() -> Vec<ExtensionConfig> {
         .collect()
 }
 
+/// Names of enabled extensions marked as required in the config.
+pub fn get_required_extension_names() -> Vec<String> {
+    get_all_extensions()
+        .into_iter()
+        .filter(|ext| ext.enabled && ext.required)
+        .map(|ext| ext.config.name())
+        .collect()
+}
+
 pub fn get_warnings() -> Vec<String> {
     let raw: Mapping = Config::global()
         .get_param(EXTENSIONS_CONFIG_KEY)
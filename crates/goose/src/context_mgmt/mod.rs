@@ -1,16 +1,38 @@
+use crate::config::Config;
 use crate::conversation::message::{ActionRequiredData, MessageMetadata};
 use crate::conversation::message::{Message, MessageContent};
-use crate::conversation::{merge_consecutive_messages, Conversation};
+use crate::conversation::Conversation;
 use crate::prompt_template::render_template;
-use crate::providers::base::{Provider, ProviderUsage};
+use crate::providers::base::{effective_context_limit, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
-use crate::{config::Config, token_counter::create_token_counter};
+use crate::token_counter::{create_token_counter, TokenCounter};
 use anyhow::Result;
-use rmcp::model::Role;
+use rmcp::model::{CallToolResult, Content, ErrorData, Role, Tool};
 use serde::Serialize;
-use tracing::{debug, info};
+use std::collections::HashSet;
+use tracing::{debug, info, warn};
 
 pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 0.8;
+pub const GOOSE_COMPACT_TARGET_TOKENS: &str = "GOOSE_COMPACT_TARGET_TOKENS";
+
+/// Compact once fewer than this many tokens of the context window remain. Takes precedence over
+/// `GOOSE_AUTO_COMPACT_THRESHOLD` when set.
+pub const GOOSE_AUTO_COMPACT_REMAINING_TOKENS: &str = "GOOSE_AUTO_COMPACT_REMAINING_TOKENS";
+
+/// Upper bound on the serialized size of a request before large tool responses are truncated
+pub const GOOSE_MAX_REQUEST_BYTES: &str = "GOOSE_MAX_REQUEST_BYTES";
+pub const DEFAULT_MAX_REQUEST_BYTES: usize = 10 * 1024 * 1024;
+
+/// Fraction of agent-visible tokens that automatic compaction leaves unsummarized. When unset
+/// the whole history is summarized.
+pub const GOOSE_COMPACT_KEEP_RECENT_RATIO: &str = "GOOSE_COMPACT_KEEP_RECENT_RATIO";
+
+/// Cap on agent-visible messages; the oldest turns are dropped, not summarized, to stay under it
+pub const GOOSE_MAX_CONVERSATION_MESSAGES: &str = "GOOSE_MAX_CONVERSATION_MESSAGES";
+
+const TRUNCATED_TOOL_RESPONSE_CHARS: usize = 2_000;
+
+const SUMMARY_TRUNCATED_NOTICE: &str = "\n\n[summary truncated to fit the compaction budget]";
 
 const CONVERSATION_CONTINUATION_TEXT: &str =
     "The previous message contains a summary that was prepared because a context limit was reached.
@@ -22,6 +44,11 @@ const TOOL_LOOP_CONTINUATION_TEXT: &str =
 Do not mention that you read a summary or that conversation summarization occurred.
 Continue calling tools as necessary to complete the task.";
 
+const PARTIAL_COMPACT_CONTINUATION_TEXT: &str =
+    "The text above summarizes the earlier part of the conversation, which was compacted because a context limit was reached.
+Do not mention that you read a summary or that conversation summarization occurred.
+Continue the conversation naturally from the messages that follow.";
+
 const MANUAL_COMPACT_CONTINUATION_TEXT: &str =
     "The previous message contains a summary that was prepared at the user's request.
 Do not mention that you read a summary or that conversation summarization occurred.
@@ -30,6 +57,152 @@ Just continue the conversation naturally based on the summarized context";
 #[derive(Serialize)]
 struct SummarizeContext {
     messages: String,
+    target_tokens: Option<usize>,
+}
+
+/// How much of the conversation a compaction summarizes
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CompactionStrategy {
+    /// Summarize the whole agent-visible history
+    #[default]
+    FullSummary,
+    /// Summarize only the oldest messages, leaving roughly `keep_recent_ratio` of the
+    /// agent-visible tokens untouched at the end of the conversation
+    OldestPortion { keep_recent_ratio: f64 },
+}
+
+impl CompactionStrategy {
+    /// The strategy automatic compaction uses, based on `GOOSE_COMPACT_KEEP_RECENT_RATIO`
+    pub fn from_config() -> Self {
+        Config::global()
+            .get_param::<f64>(GOOSE_COMPACT_KEEP_RECENT_RATIO)
+            .ok()
+            .filter(|ratio| *ratio > 0.0 && *ratio < 1.0)
+            .map(|keep_recent_ratio| Self::OldestPortion { keep_recent_ratio })
+            .unwrap_or_default()
+    }
+}
+
+/// The rule that decides when automatic compaction runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompactionTrigger {
+    /// Compact when fewer than this many tokens of the context window remain
+    RemainingTokens(usize),
+    /// Compact when more than this fraction of the context window is used
+    Threshold(f64),
+}
+
+impl CompactionTrigger {
+    /// `threshold_override` wins, then `GOOSE_AUTO_COMPACT_REMAINING_TOKENS`, then
+    /// `GOOSE_AUTO_COMPACT_THRESHOLD`.
+    pub fn from_config(threshold_override: Option<f64>) -> Self {
+        let config = Config::global();
+        if let Some(threshold) = threshold_override {
+            return Self::Threshold(threshold);
+        }
+        if let Some(tokens) = config
+            .get_param::<usize>(GOOSE_AUTO_COMPACT_REMAINING_TOKENS)
+            .ok()
+            .filter(|tokens| *tokens > 0)
+        {
+            return Self::RemainingTokens(tokens);
+        }
+        Self::threshold_from_config()
+    }
+
+    fn threshold_from_config() -> Self {
+        Self::Threshold(
+            Config::global()
+                .get_param::<f64>("GOOSE_AUTO_COMPACT_THRESHOLD")
+                .unwrap_or(DEFAULT_COMPACTION_THRESHOLD),
+        )
+    }
+
+    /// The trigger to apply to a window of `context_limit` tokens. A remaining-token budget
+    /// larger than half the window would compact on nearly every turn, so it falls back to
+    /// `GOOSE_AUTO_COMPACT_THRESHOLD` instead.
+    pub fn for_context_limit(self, context_limit: usize) -> Self {
+        match self {
+            Self::RemainingTokens(tokens) if tokens > context_limit / 2 => {
+                Self::threshold_from_config()
+            }
+            trigger => trigger,
+        }
+    }
+
+    fn fires(&self, current_tokens: usize, context_limit: usize) -> bool {
+        match *self {
+            Self::RemainingTokens(tokens) => context_limit.saturating_sub(current_tokens) < tokens,
+            // A threshold outside (0, 1) disables auto-compaction
+            Self::Threshold(threshold) if threshold <= 0.0 || threshold >= 1.0 => false,
+            Self::Threshold(threshold) => current_tokens as f64 / context_limit as f64 > threshold,
+        }
+    }
+
+    /// Why compaction ran, for the notice shown to the user
+    pub fn describe(&self) -> String {
+        match self {
+            Self::RemainingTokens(tokens) => {
+                format!("Fewer than {} tokens of context remain", tokens)
+            }
+            Self::Threshold(threshold) => format!(
+                "Exceeded auto-compact threshold of {}%",
+                (threshold * 100.0) as u32
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for CompactionTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RemainingTokens(tokens) => {
+                write!(
+                    f,
+                    "{} = {} tokens",
+                    GOOSE_AUTO_COMPACT_REMAINING_TOKENS, tokens
+                )
+            }
+            Self::Threshold(threshold) => write!(
+                f,
+                "GOOSE_AUTO_COMPACT_THRESHOLD = {:.1}%",
+                threshold * 100.0
+            ),
+        }
+    }
+}
+
+/// What a compaction did to the conversation
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CompactionStats {
+    /// Estimated agent-visible tokens before compacting
+    pub tokens_before: usize,
+    /// Estimated agent-visible tokens after compacting, including the summary
+    pub tokens_after: usize,
+    /// Messages hidden from the agent and replaced by the summary
+    pub messages_compacted: usize,
+    /// Tool responses left out of the summarizer's input to make it fit
+    pub tool_responses_dropped: usize,
+    /// The `filter_tool_responses` attempt that succeeded: the percentage of tool responses removed
+    pub tool_response_removal_percent: u32,
+}
+
+impl std::fmt::Display for CompactionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "compacted {} messages, ~{} -> ~{} tokens",
+            self.messages_compacted, self.tokens_before, self.tokens_after
+        )?;
+        if self.tool_responses_dropped > 0 {
+            write!(
+                f,
+                " ({} tool responses left out of the summary at the {}% removal attempt)",
+                self.tool_responses_dropped, self.tool_response_removal_percent
+            )?;
+        }
+        Ok(())
+    }
 }
 
 /// Compact messages by summarizing them
@@ -42,20 +215,94 @@ struct SummarizeContext {
 /// * `provider` - The provider to use for summarization
 /// * `conversation` - The current conversation history
 /// * `manual_compact` - If true, this is a manual compaction (don't preserve user message)
+/// * `strategy` - Whether to summarize everything or only the oldest messages
 ///
 /// # Returns
 /// * A tuple containing:
 ///   - `Conversation`: The compacted messages
 ///   - `ProviderUsage`: Provider usage from summarization
+///   - `CompactionStats`: How much the compaction saved and what it left out
 pub async fn compact_messages(
     provider: &dyn Provider,
     conversation: &Conversation,
     manual_compact: bool,
-) -> Result<(Conversation, ProviderUsage)> {
+    strategy: CompactionStrategy,
+) -> Result<(Conversation, ProviderUsage, CompactionStats)> {
+    compact_messages_with_guidance(provider, conversation, manual_compact, strategy, None).await
+}
+
+/// Same as [`compact_messages`], but passes extra `guidance` to the summarizer, e.g. to
+/// ask for a better summary after the user rejected the previous one.
+pub async fn compact_messages_with_guidance(
+    provider: &dyn Provider,
+    conversation: &Conversation,
+    manual_compact: bool,
+    strategy: CompactionStrategy,
+    guidance: Option<&str>,
+) -> Result<(Conversation, ProviderUsage, CompactionStats)> {
     info!("Performing message compaction");
 
     let messages = conversation.messages();
+    let token_counter = create_token_counter()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+    let token_counts: Vec<usize> = messages
+        .iter()
+        .map(|msg| agent_visible_tokens(&token_counter, msg))
+        .collect();
+    let mut stats = CompactionStats {
+        tokens_before: token_counts.iter().sum(),
+        ..CompactionStats::default()
+    };
+
+    let split = match strategy {
+        CompactionStrategy::FullSummary => None,
+        CompactionStrategy::OldestPortion { keep_recent_ratio } => {
+            let split = oldest_portion_split(messages, &token_counts, keep_recent_ratio);
+            if split.is_none() {
+                debug!("No safe point to split the conversation, summarizing all of it");
+            }
+            split
+        }
+    };
+    let (compacted, usage) = match split {
+        Some(split) => {
+            compact_oldest_portion(provider, messages, split, guidance, &mut stats).await?
+        }
+        None => summarize_all(provider, messages, manual_compact, guidance, &mut stats).await?,
+    };
+
+    stats.tokens_after = compacted
+        .messages()
+        .iter()
+        .map(|msg| agent_visible_tokens(&token_counter, msg))
+        .sum();
+    stats.messages_compacted = messages
+        .iter()
+        .zip(compacted.messages())
+        .filter(|(before, after)| before.is_agent_visible() && !after.is_agent_visible())
+        .count();
+    info!("Compaction finished: {}", stats);
 
+    Ok((compacted, usage, stats))
+}
+
+fn agent_visible_tokens(token_counter: &TokenCounter, msg: &Message) -> usize {
+    if msg.is_agent_visible() {
+        token_counter.count_chat_tokens("", std::slice::from_ref(msg), &[])
+    } else {
+        0
+    }
+}
+
+/// Replaces the whole agent-visible history with a summary.
+async fn summarize_all(
+    provider: &dyn Provider,
+    messages: &[Message],
+    manual_compact: bool,
+    guidance: Option<&str>,
+    stats: &mut CompactionStats,
+) -> Result<(Conversation, ProviderUsage)> {
     let has_text_only = |msg: &Message| {
         let has_text = msg
             .content
@@ -107,10 +354,11 @@ pub async fn compact_messages(
     } else {
         (None, false)
     };
+    // A pinned message is copied after the summary anyway, so it doesn't need a fresh copy
+    let preserved_user_message = preserved_user_message.filter(|msg| !msg.is_pinned());
 
-    let messages_to_compact = messages.as_slice();
-
-    let (summary_message, summarization_usage) = do_compact(provider, messages_to_compact).await?;
+    let (summary_message, summarization_usage) =
+        do_compact(provider, messages, guidance, stats).await?;
 
     // Create the final message list with updated visibility metadata:
     // 1. Original messages become user_visible but not agent_visible
@@ -118,23 +366,19 @@ pub async fn compact_messages(
     // 3. Assistant messages to continue the conversation are also agent_visible but not user_visible
     let mut final_messages = Vec::new();
 
-    for (idx, msg) in messages_to_compact.iter().enumerate() {
-        let updated_metadata = if is_most_recent
-            && idx == messages_to_compact.len() - 1
-            && preserved_user_message.is_some()
-        {
-            // This is the most recent message and we're preserving it by adding a fresh copy
-            MessageMetadata::invisible()
-        } else {
-            msg.metadata.with_agent_invisible()
-        };
-        let updated_msg = msg.clone().with_metadata(updated_metadata);
+    for (idx, msg) in messages.iter().enumerate() {
+        let updated_msg =
+            if is_most_recent && idx == messages.len() - 1 && preserved_user_message.is_some() {
+                // This is the most recent message and we're preserving it by adding a fresh copy
+                msg.clone().with_metadata(MessageMetadata::invisible())
+            } else {
+                hide_from_agent(msg)
+            };
         final_messages.push(updated_msg);
     }
 
     let summary_msg = summary_message.with_metadata(MessageMetadata::agent_only());
-
-    let mut continuation_messages = vec![summary_msg];
+    final_messages.push(summary_msg);
 
     let continuation_text = if manual_compact {
         MANUAL_COMPACT_CONTINUATION_TEXT
@@ -147,10 +391,8 @@ pub async fn compact_messages(
     let continuation_msg = Message::assistant()
         .with_text(continuation_text)
         .with_metadata(MessageMetadata::agent_only());
-    continuation_messages.push(continuation_msg);
-
-    let (merged_continuation, _issues) = merge_consecutive_messages(continuation_messages);
-    final_messages.extend(merged_continuation);
+    final_messages.push(continuation_msg);
+    final_messages.extend(pinned_copies(messages));
 
     if let Some(user_msg) = preserved_user_message {
         if let Some(text) = extract_text(&user_msg) {
@@ -164,22 +406,168 @@ pub async fn compact_messages(
     ))
 }
 
+/// Hides a summarized message from the agent. Pinned messages are hidden too and come back
+/// through [`pinned_copies`].
+fn hide_from_agent(msg: &Message) -> Message {
+    let metadata = msg.metadata.with_agent_invisible();
+    msg.clone().with_metadata(metadata)
+}
+
+/// Agent-only copies of the pinned messages among `messages` that the agent can see, to follow
+/// the summary. Left where they were, a pinned assistant message could lead the conversation,
+/// and `fix_conversation` drops a leading assistant message.
+fn pinned_copies(messages: &[Message]) -> Vec<Message> {
+    messages
+        .iter()
+        .filter(|msg| msg.is_agent_visible() && msg.is_pinned())
+        .map(|msg| {
+            Message::new(msg.role.clone(), msg.created, msg.content.clone())
+                .with_metadata(MessageMetadata::agent_only().with_pinned(true))
+        })
+        .collect()
+}
+
+/// Finds where to split `messages` so that the messages from the returned index on hold at most
+/// `keep_recent_ratio` of the agent-visible tokens (or just the last exchange, if that alone is
+/// larger). The split always lands on an agent-visible assistant message so no tool response is
+/// separated from its request. Returns `None` when there is nothing older to summarize.
+fn oldest_portion_split(
+    messages: &[Message],
+    token_counts: &[usize],
+    keep_recent_ratio: f64,
+) -> Option<usize> {
+    let total: usize = token_counts.iter().sum();
+    let budget = (total as f64 * keep_recent_ratio) as usize;
+
+    let mut kept = 0;
+    let mut candidate = messages.len().checked_sub(1)?;
+    for idx in (0..messages.len()).rev() {
+        kept += token_counts[idx];
+        if kept > budget {
+            break;
+        }
+        candidate = idx;
+    }
+
+    (1..=candidate)
+        .rev()
+        .find(|&idx| is_safe_split(messages, idx))
+}
+
+fn is_safe_split(messages: &[Message], idx: usize) -> bool {
+    let (older, recent) = messages.split_at(idx);
+    if !matches!(recent[0].role, Role::Assistant)
+        || !recent[0].is_agent_visible()
+        || !older.iter().any(|msg| msg.is_agent_visible())
+    {
+        return false;
+    }
+
+    let older_requests: HashSet<&str> = older
+        .iter()
+        .flat_map(|msg| msg.content.iter())
+        .filter_map(|content| match content {
+            MessageContent::ToolRequest(request) => Some(request.id.as_str()),
+            _ => None,
+        })
+        .collect();
+    !recent
+        .iter()
+        .flat_map(|msg| msg.content.iter())
+        .any(|content| match content {
+            MessageContent::ToolResponse(response) => older_requests.contains(response.id.as_str()),
+            _ => false,
+        })
+}
+
+/// Summarizes the messages before `split` and leaves the rest of the conversation as is.
+async fn compact_oldest_portion(
+    provider: &dyn Provider,
+    messages: &[Message],
+    split: usize,
+    guidance: Option<&str>,
+    stats: &mut CompactionStats,
+) -> Result<(Conversation, ProviderUsage)> {
+    let (older, recent) = messages.split_at(split);
+    info!(
+        "Summarizing the oldest {} of {} messages",
+        older.len(),
+        messages.len()
+    );
+
+    let (summary_message, summarization_usage) =
+        do_compact(provider, older, guidance, stats).await?;
+
+    let mut final_messages: Vec<Message> = older.iter().map(hide_from_agent).collect();
+    // The summary stands in for the older messages, so it is a user message followed by the
+    // assistant message the recent portion starts with.
+    final_messages.push(
+        summary_message
+            .with_text(PARTIAL_COMPACT_CONTINUATION_TEXT)
+            .with_metadata(MessageMetadata::agent_only()),
+    );
+    final_messages.extend(pinned_copies(older));
+    final_messages.extend_from_slice(recent);
+
+    Ok((
+        Conversation::new_unvalidated(final_messages),
+        summarization_usage,
+    ))
+}
+
+/// Hides the oldest whole turns from the agent until at most `max_messages` agent-visible
+/// messages remain. Unlike compaction nothing is summarized: the dropped turns stay visible
+/// to the user but the agent no longer sees them. Splitting at turn boundaries keeps tool
+/// requests with their responses, and the latest turn is always kept.
+///
+/// Returns the trimmed conversation and the number of messages dropped, or `None` when the
+/// conversation is already within the limit.
+pub fn drop_oldest_turns(
+    conversation: &Conversation,
+    max_messages: usize,
+) -> Option<(Conversation, usize)> {
+    let visible_count = |turn: &[Message]| turn.iter().filter(|m| m.is_agent_visible()).count();
+
+    let turns = conversation.turns();
+    let mut remaining: usize = turns.iter().map(|turn| visible_count(turn)).sum();
+    if remaining <= max_messages {
+        return None;
+    }
+
+    let mut dropped = 0;
+    let mut messages = Vec::with_capacity(conversation.len());
+    let last_turn = turns.len().saturating_sub(1);
+    for (idx, turn) in turns.into_iter().enumerate() {
+        if remaining > max_messages && idx < last_turn {
+            let count = visible_count(turn);
+            remaining -= count;
+            dropped += count;
+            messages.extend(turn.iter().map(|msg| {
+                let metadata = msg.metadata.with_agent_invisible();
+                msg.clone().with_metadata(metadata)
+            }));
+        } else {
+            messages.extend_from_slice(turn);
+        }
+    }
+
+    (dropped > 0).then(|| (Conversation::new_unvalidated(messages), dropped))
+}
+
 /// Check if messages exceed the auto-compaction threshold
+///
+/// Returns the trigger that fired and the strategy to compact with, or `None` when no
+/// compaction is needed.
 pub async fn check_if_compaction_needed(
     provider: &dyn Provider,
     conversation: &Conversation,
     threshold_override: Option<f64>,
     session: &crate::session::Session,
-) -> Result<bool> {
+) -> Result<Option<(CompactionTrigger, CompactionStrategy)>> {
     let messages = conversation.messages();
-    let config = Config::global();
-    let threshold = threshold_override.unwrap_or_else(|| {
-        config
-            .get_param::<f64>("GOOSE_AUTO_COMPACT_THRESHOLD")
-            .unwrap_or(DEFAULT_COMPACTION_THRESHOLD)
-    });
-
-    let context_limit = provider.get_model_config().context_limit();
+    let context_limit = effective_context_limit(provider).await;
+    let trigger =
+        CompactionTrigger::from_config(threshold_override).for_context_limit(context_limit);
 
     let (current_tokens, token_source) = match session.total_tokens {
         Some(tokens) => (tokens as usize, "session metadata"),
@@ -199,24 +587,19 @@ pub async fn check_if_compaction_needed(
     };
 
     let usage_ratio = current_tokens as f64 / context_limit as f64;
-
-    let needs_compaction = if threshold <= 0.0 || threshold >= 1.0 {
-        false // Auto-compact is disabled.
-    } else {
-        usage_ratio > threshold
-    };
+    let needs_compaction = trigger.fires(current_tokens, context_limit);
 
     debug!(
-        "Compaction check: {} / {} tokens ({:.1}%), threshold: {:.1}%, needs compaction: {}, source: {}",
+        "Compaction check: {} / {} tokens ({:.1}%), rule: {}, needs compaction: {}, source: {}",
         current_tokens,
         context_limit,
         usage_ratio * 100.0,
-        threshold * 100.0,
+        trigger,
         needs_compaction,
         token_source
     );
 
-    Ok(needs_compaction)
+    Ok(needs_compaction.then(|| (trigger, CompactionStrategy::from_config())))
 }
 
 fn filter_tool_responses<'a>(messages: &[&'a Message], remove_percent: u32) -> Vec<&'a Message> {
@@ -269,15 +652,27 @@ fn filter_tool_responses<'a>(messages: &[&'a Message], remove_percent: u32) -> V
         .collect()
 }
 
+#[tracing::instrument(name = "compact", skip_all, fields(message_count = messages.len()))]
 async fn do_compact(
     provider: &dyn Provider,
     messages: &[Message],
+    guidance: Option<&str>,
+    stats: &mut CompactionStats,
 ) -> Result<(Message, ProviderUsage), anyhow::Error> {
+    // Pinned messages are kept verbatim, so they are left out of the summary
     let agent_visible_messages: Vec<&Message> = messages
         .iter()
-        .filter(|msg| msg.is_agent_visible())
+        .filter(|msg| msg.is_agent_visible() && !msg.is_pinned())
         .collect();
 
+    let target_tokens = Config::global()
+        .get_param::<usize>(GOOSE_COMPACT_TARGET_TOKENS)
+        .ok()
+        .filter(|tokens| *tokens > 0);
+    let token_counter = create_token_counter()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+
     // Try progressively removing more tool response messages from the middle to reduce context length
     let removal_percentages = [0, 10, 20, 50, 100];
 
@@ -290,29 +685,47 @@ async fn do_compact(
             .collect::<Vec<_>>()
             .join("\n");
 
-        let context = SummarizeContext {
-            messages: messages_text,
-        };
+        match summarize(provider, &messages_text, target_tokens, guidance).await {
+            Ok((response, provider_usage)) => {
+                stats.tool_responses_dropped =
+                    agent_visible_messages.len() - filtered_messages.len();
+                stats.tool_response_removal_percent = remove_percent;
 
-        let system_prompt = render_template("compaction.md", &context)?;
-
-        let user_message = Message::user()
-            .with_text("Please summarize the conversation history provided in the system prompt.");
-        let summarization_request = vec![user_message];
-
-        match provider
-            .complete_fast(&system_prompt, &summarization_request, &[])
-            .await
-        {
-            Ok((mut response, mut provider_usage)) => {
-                response.role = Role::User;
-
-                provider_usage
-                    .ensure_tokens(&system_prompt, &summarization_request, &response, &[])
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to ensure usage tokens: {}", e))?;
+                let input_tokens = token_counter.count_tokens(&messages_text);
+                let summary_tokens = token_counter.count_tokens(&response.as_concat_text());
+                if summary_tokens < input_tokens {
+                    return Ok((response, provider_usage));
+                }
 
-                return Ok((response, provider_usage));
+                // A summary that isn't smaller than its input saves nothing, so ask once
+                // more for a tighter one and fall back to truncating it.
+                let budget = target_tokens
+                    .unwrap_or(input_tokens)
+                    .min(input_tokens / 2)
+                    .max(1);
+                warn!(
+                    "Compaction summary ({} tokens) is not smaller than its input ({} tokens), retrying with a {} token target",
+                    summary_tokens, input_tokens, budget
+                );
+
+                let (response, provider_usage) =
+                    match summarize(provider, &messages_text, Some(budget), guidance).await {
+                        Ok((retry, retry_usage)) => {
+                            (retry, provider_usage.combine_with(&retry_usage))
+                        }
+                        Err(e) => {
+                            debug!("Compaction retry failed: {}", e);
+                            (response, provider_usage)
+                        }
+                    };
+
+                if token_counter.count_tokens(&response.as_concat_text()) < input_tokens {
+                    return Ok((response, provider_usage));
+                }
+                return Ok((
+                    truncate_summary(&token_counter, response, budget),
+                    provider_usage,
+                ));
             }
             Err(e) => {
                 if matches!(e, ProviderError::ContextLengthExceeded(_)) {
@@ -334,6 +747,191 @@ async fn do_compact(
     ))
 }
 
+async fn summarize(
+    provider: &dyn Provider,
+    messages_text: &str,
+    target_tokens: Option<usize>,
+    guidance: Option<&str>,
+) -> Result<(Message, ProviderUsage), ProviderError> {
+    let context = SummarizeContext {
+        messages: messages_text.to_string(),
+        target_tokens,
+    };
+
+    let system_prompt = render_template("compaction.md", &context)
+        .map_err(|e| ProviderError::ExecutionError(e.to_string()))?;
+
+    let user_message = Message::user().with_text(summarize_request_text(guidance));
+    let summarization_request = vec![user_message];
+
+    let (mut response, mut provider_usage) = provider
+        .complete_fast(&system_prompt, &summarization_request, &[])
+        .await?;
+    response.role = Role::User;
+
+    provider_usage
+        .ensure_tokens(&system_prompt, &summarization_request, &response, &[])
+        .await
+        .map_err(|e| {
+            ProviderError::ExecutionError(format!("Failed to ensure usage tokens: {}", e))
+        })?;
+
+    Ok((response, provider_usage))
+}
+
+fn summarize_request_text(guidance: Option<&str>) -> String {
+    let request = "Please summarize the conversation history provided in the system prompt.";
+    match guidance.map(str::trim).filter(|g| !g.is_empty()) {
+        Some(guidance) => format!("{}\n\n{}", request, guidance),
+        None => request.to_string(),
+    }
+}
+
+/// Cuts the summary text down until it fits within `max_tokens`.
+fn truncate_summary(token_counter: &TokenCounter, summary: Message, max_tokens: usize) -> Message {
+    let text = summary.as_concat_text();
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        let candidate: String = chars.iter().collect();
+        let tokens = token_counter.count_tokens(&candidate);
+        if tokens <= max_tokens {
+            break;
+        }
+        let keep = (chars.len() * max_tokens / tokens).min(chars.len() * 9 / 10);
+        chars.truncate(keep);
+    }
+
+    let truncated: String = chars.into_iter().collect();
+    Message::new(
+        summary.role,
+        summary.created,
+        vec![MessageContent::text(format!(
+            "{}{}",
+            truncated, SUMMARY_TRUNCATED_NOTICE
+        ))],
+    )
+}
+
+/// Result of shrinking a conversation that exceeded the request size limit
+#[derive(Debug)]
+pub struct RequestTrim {
+    pub messages: Vec<Message>,
+    pub original_bytes: usize,
+    pub trimmed_bytes: usize,
+    pub truncated_responses: usize,
+}
+
+fn serialized_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    serde_json::to_string(value).map_or(0, |s| s.len())
+}
+
+/// Estimated size of the request body a provider would receive
+pub fn estimate_request_bytes(system_prompt: &str, messages: &[Message], tools: &[Tool]) -> usize {
+    system_prompt.len() + estimate_messages_bytes(messages) + serialized_len(tools)
+}
+
+/// Estimated bytes `messages` add to a request, for keeping a running estimate up to date
+pub fn estimate_messages_bytes(messages: &[Message]) -> usize {
+    serialized_len(messages)
+}
+
+/// Truncates the largest tool responses, biggest first, until the request fits in `max_bytes`.
+/// `request_bytes` is the caller's [`estimate_request_bytes`] for `messages`, so it isn't
+/// recomputed here. Returns `None` when the request already fits or there is nothing left to
+/// truncate.
+pub fn fit_request_size(
+    request_bytes: usize,
+    messages: &[Message],
+    max_bytes: usize,
+) -> Option<RequestTrim> {
+    let original_bytes = request_bytes;
+    if max_bytes == 0 || original_bytes <= max_bytes {
+        return None;
+    }
+
+    let mut responses: Vec<(usize, usize, usize)> = messages
+        .iter()
+        .enumerate()
+        .flat_map(|(msg_idx, msg)| {
+            msg.content
+                .iter()
+                .enumerate()
+                .filter(|(_, content)| matches!(content, MessageContent::ToolResponse(_)))
+                .map(move |(content_idx, content)| (msg_idx, content_idx, serialized_len(content)))
+        })
+        .collect();
+    responses.sort_by(|a, b| b.2.cmp(&a.2));
+
+    let mut messages = messages.to_vec();
+    let mut size = original_bytes;
+    let mut truncated_responses = 0;
+    for (msg_idx, content_idx, bytes) in responses {
+        if size <= max_bytes {
+            break;
+        }
+        let shortened = truncate_tool_response(&messages[msg_idx], content_idx, bytes);
+        let new_bytes = serialized_len(&shortened);
+        if new_bytes >= bytes {
+            continue;
+        }
+        messages[msg_idx].content[content_idx] = shortened;
+        size = size - bytes + new_bytes;
+        truncated_responses += 1;
+    }
+
+    (truncated_responses > 0).then_some(RequestTrim {
+        messages,
+        original_bytes,
+        trimmed_bytes: size,
+        truncated_responses,
+    })
+}
+
+fn truncate_tool_response(
+    msg: &Message,
+    content_idx: usize,
+    original_bytes: usize,
+) -> MessageContent {
+    let content = msg.content[content_idx].clone();
+    let MessageContent::ToolResponse(response) = &content else {
+        return content;
+    };
+    let truncate = |rendered: &str| {
+        format!(
+            "{}\n\n[tool response truncated from {} bytes to fit the request size limit]",
+            crate::utils::safe_truncate(rendered, TRUNCATED_TOOL_RESPONSE_CHARS),
+            original_bytes
+        )
+    };
+    // Keep failures failing, so the model still sees that the call didn't work
+    let tool_result = match &response.tool_result {
+        Ok(result) => {
+            let rendered = format_message_for_compacting(&Message::new(
+                msg.role.clone(),
+                msg.created,
+                vec![content.clone()],
+            ));
+            let rendered = rendered
+                .split_once("tool_response: ")
+                .map_or(rendered.as_str(), |(_, rest)| rest);
+            Ok(CallToolResult {
+                is_error: result.is_error,
+                ..CallToolResult::success(vec![Content::text(truncate(rendered))])
+            })
+        }
+        Err(error) => Err(ErrorData {
+            message: truncate(&error.message).into(),
+            data: None,
+            ..error.clone()
+        }),
+    };
+    MessageContent::tool_response_with_metadata(
+        response.id.clone(),
+        tool_result,
+        response.metadata.as_ref(),
+    )
+}
+
 fn format_message_for_compacting(msg: &Message) -> String {
     let content_parts: Vec<String> = msg
         .content
@@ -445,6 +1043,7 @@ mod tests {
                     toolshim_model: None,
                     fast_model: None,
                     request_params: None,
+                    max_response_tokens: None,
                 },
                 max_tool_responses: None,
             }
@@ -529,9 +1128,14 @@ mod tests {
         ];
 
         let conversation = Conversation::new_unvalidated(basic_conversation);
-        let (compacted_conversation, _usage) = compact_messages(&provider, &conversation, false)
-            .await
-            .unwrap();
+        let (compacted_conversation, _usage, _stats) = compact_messages(
+            &provider,
+            &conversation,
+            false,
+            CompactionStrategy::FullSummary,
+        )
+        .await
+        .unwrap();
 
         let agent_conversation = compacted_conversation.agent_visible_messages();
 
@@ -568,7 +1172,13 @@ mod tests {
         }
 
         let conversation = Conversation::new_unvalidated(messages);
-        let result = compact_messages(&provider, &conversation, false).await;
+        let result = compact_messages(
+            &provider,
+            &conversation,
+            false,
+            CompactionStrategy::FullSummary,
+        )
+        .await;
 
         // Should succeed after progressive removal
         assert!(
@@ -576,5 +1186,375 @@ mod tests {
             "Should succeed with progressive removal: {:?}",
             result.err()
         );
+        let (_compacted, _usage, stats) = result.unwrap();
+        assert_eq!(stats.tool_responses_dropped, 10);
+        assert_eq!(stats.tool_response_removal_percent, 100);
+        assert_eq!(stats.messages_compacted, 21);
+        assert!(stats.to_string().contains("10 tool responses left out"));
+    }
+
+    #[tokio::test]
+    async fn test_compaction_keeps_pinned_messages() {
+        let provider = MockProvider::new(Message::assistant().with_text("<mock summary>"), 1000);
+        let pinned = Message::user()
+            .with_text("always use tabs, never commit to main")
+            .with_metadata(MessageMetadata::default().with_pinned(true));
+        let conversation = Conversation::new_unvalidated(vec![
+            pinned.clone(),
+            Message::assistant().with_text("understood"),
+            Message::user().with_text("now fix the parser"),
+            Message::assistant().with_text("done"),
+        ]);
+
+        let strategies = [
+            (CompactionStrategy::FullSummary, 3),
+            (
+                CompactionStrategy::OldestPortion {
+                    keep_recent_ratio: 0.1,
+                },
+                2,
+            ),
+        ];
+        for (strategy, compacted_count) in strategies {
+            let (compacted, _usage, stats) =
+                compact_messages(&provider, &conversation, true, strategy)
+                    .await
+                    .unwrap();
+            let agent_messages = compacted.agent_visible_messages();
+            assert!(agent_messages[0]
+                .as_concat_text()
+                .contains("<mock summary>"));
+            let copy = agent_messages
+                .iter()
+                .find(|m| m.is_pinned())
+                .expect("pinned message is kept");
+            assert_eq!(copy.as_concat_text(), pinned.as_concat_text());
+            assert_eq!(stats.messages_compacted, compacted_count, "{:?}", strategy);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compaction_keeps_pinned_assistant_message() {
+        let provider = MockProvider::new(Message::assistant().with_text("<mock summary>"), 1000);
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("what port does the server use?"),
+            Message::assistant()
+                .with_text("it listens on 8443")
+                .with_metadata(MessageMetadata::default().with_pinned(true)),
+            Message::user().with_text("thanks"),
+            Message::assistant().with_text("anytime"),
+        ]);
+
+        let (mut compacted, _usage, _stats) = compact_messages(
+            &provider,
+            &conversation,
+            true,
+            CompactionStrategy::FullSummary,
+        )
+        .await
+        .unwrap();
+
+        // The agent sees the conversation with the next prompt, after it has been fixed up
+        compacted.push(Message::user().with_text("and the admin port?"));
+        let (fixed, _issues) = crate::conversation::fix_conversation(compacted);
+        let agent_messages = fixed.agent_visible_messages();
+        assert_eq!(agent_messages[0].role, Role::User);
+        assert!(agent_messages
+            .iter()
+            .any(|m| m.as_concat_text().contains("it listens on 8443")));
+    }
+
+    #[tokio::test]
+    async fn test_summary_larger_than_input_is_truncated() {
+        let long_summary = "detail ".repeat(500);
+        let response_message = Message::assistant().with_text(&long_summary);
+        let provider = MockProvider::new(response_message, 1000);
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("hello"),
+            Message::assistant().with_text("hi there"),
+        ]);
+
+        let (compacted_conversation, _usage, _stats) = compact_messages(
+            &provider,
+            &conversation,
+            true,
+            CompactionStrategy::FullSummary,
+        )
+        .await
+        .unwrap();
+
+        let summary = compacted_conversation
+            .agent_visible_messages()
+            .into_iter()
+            .map(|m| m.as_concat_text())
+            .find(|text| text.contains("detail"))
+            .expect("summary should be present");
+        assert!(summary.ends_with(SUMMARY_TRUNCATED_NOTICE));
+        assert!(summary.len() < long_summary.len());
+    }
+
+    fn tool_exchange(id: &str, output: &str) -> Vec<Message> {
+        vec![
+            Message::assistant().with_tool_request(
+                id,
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "read_file".into(),
+                    arguments: None,
+                }),
+            ),
+            Message::user().with_tool_response(
+                id,
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![RawContent::text(output).no_annotation()],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_oldest_portion_split() {
+        let mut messages = vec![Message::user().with_text("refactor the parser")];
+        for i in 0..4 {
+            messages.extend(tool_exchange(&format!("tool_{}", i), "output"));
+        }
+        let counts = vec![10; messages.len()];
+
+        // 40% of 90 tokens keeps the last 3 messages, but the split moves back so the
+        // first kept message is the assistant's tool request rather than a response
+        assert_eq!(oldest_portion_split(&messages, &counts, 0.4), Some(5));
+        assert_eq!(oldest_portion_split(&messages, &counts, 0.5), Some(5));
+        // Even a tiny ratio keeps the last exchange intact
+        assert_eq!(oldest_portion_split(&messages, &counts, 0.01), Some(7));
+        // Keeping everything leaves nothing to summarize
+        assert_eq!(oldest_portion_split(&messages, &counts, 1.0), None);
+        assert_eq!(
+            oldest_portion_split(&messages[..1], &counts[..1], 0.5),
+            None
+        );
+
+        // A response never stays behind once its request is summarized
+        let mut interrupted = tool_exchange("tool_a", "output");
+        interrupted.insert(1, Message::assistant().with_text("still working"));
+        interrupted.insert(0, Message::user().with_text("read it"));
+        assert!(!is_safe_split(&interrupted, 2));
+        assert!(is_safe_split(&interrupted, 1));
+    }
+
+    #[tokio::test]
+    async fn test_compact_oldest_portion_keeps_recent_messages() {
+        let provider = MockProvider::new(Message::assistant().with_text("<mock summary>"), 1000);
+        let mut messages = vec![Message::user().with_text("refactor the parser")];
+        for i in 0..6 {
+            messages.extend(tool_exchange(
+                &format!("tool_{}", i),
+                &format!("output {}", i).repeat(20),
+            ));
+        }
+        let conversation = Conversation::new_unvalidated(messages.clone());
+
+        let (compacted, _usage, stats) = compact_messages(
+            &provider,
+            &conversation,
+            false,
+            CompactionStrategy::OldestPortion {
+                keep_recent_ratio: 0.5,
+            },
+        )
+        .await
+        .unwrap();
+
+        let agent_messages = compacted.agent_visible_messages();
+        assert!(agent_messages[0]
+            .as_concat_text()
+            .contains("<mock summary>"));
+        assert!(matches!(agent_messages[0].role, Role::User));
+        assert!(agent_messages.len() > 2);
+        assert!(agent_messages.len() < messages.len());
+        // The recent messages are kept as they were, still visible to the user
+        assert_eq!(agent_messages.last(), messages.last());
+        assert!(agent_messages[1..].iter().all(|m| m.is_user_visible()));
+        assert_eq!(
+            compacted.messages().len(),
+            messages.len() + 1,
+            "only the summary is added"
+        );
+        assert_eq!(
+            stats.messages_compacted,
+            messages.len() + 1 - agent_messages.len()
+        );
+        assert!(stats.tokens_after < stats.tokens_before);
+        assert_eq!(stats.tool_responses_dropped, 0);
+        Conversation::new(agent_messages).expect("partial compaction keeps tool pairs together");
+    }
+
+    #[test]
+    fn test_compaction_trigger() {
+        let remaining = CompactionTrigger::RemainingTokens(20_000);
+        assert!(!remaining.fires(70_000, 100_000));
+        assert!(remaining.fires(80_001, 100_000));
+        assert!(!remaining.fires(800_000, 1_000_000));
+        assert!(remaining.fires(200_000, 200_000));
+
+        // A budget that leaves less than half the window usable falls back to the ratio
+        let budget = CompactionTrigger::RemainingTokens(8_000);
+        assert_eq!(budget.for_context_limit(20_000), budget);
+        assert!(matches!(
+            budget.for_context_limit(8_000),
+            CompactionTrigger::Threshold(_)
+        ));
+
+        let threshold = CompactionTrigger::Threshold(0.8);
+        assert!(!threshold.fires(80_000, 100_000));
+        assert!(threshold.fires(80_001, 100_000));
+        assert!(!CompactionTrigger::Threshold(0.0).fires(99_999, 100_000));
+        assert!(!CompactionTrigger::Threshold(1.0).fires(99_999, 100_000));
+
+        assert_eq!(
+            CompactionTrigger::from_config(Some(0.5)),
+            CompactionTrigger::Threshold(0.5)
+        );
+        assert_eq!(
+            remaining.describe(),
+            "Fewer than 20000 tokens of context remain"
+        );
+        assert_eq!(
+            threshold.describe(),
+            "Exceeded auto-compact threshold of 80%"
+        );
+    }
+
+    #[test]
+    fn test_drop_oldest_turns() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("first"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: None,
+                }),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![RawContent::text("ok").no_annotation()],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+            Message::assistant().with_text("done"),
+            Message::user().with_text("second"),
+            Message::assistant().with_text("reply"),
+        ]);
+
+        assert!(drop_oldest_turns(&conversation, 6).is_none());
+
+        // The whole first turn goes, including its tool request and response
+        let (trimmed, dropped) = drop_oldest_turns(&conversation, 3).unwrap();
+        assert_eq!(dropped, 4);
+        assert_eq!(trimmed.len(), conversation.len());
+        let visible: Vec<String> = trimmed
+            .agent_visible_messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect();
+        assert_eq!(visible, vec!["second", "reply"]);
+        assert!(trimmed.messages()[0].metadata.user_visible);
+
+        // The latest turn is kept even when it alone exceeds the limit
+        let (trimmed, dropped) = drop_oldest_turns(&conversation, 1).unwrap();
+        assert_eq!(dropped, 4);
+        assert_eq!(trimmed.agent_visible_messages().len(), 2);
+    }
+
+    #[test]
+    fn test_summarize_request_text_includes_guidance() {
+        let plain = summarize_request_text(None);
+        assert_eq!(summarize_request_text(Some("  ")), plain);
+
+        let nudged = summarize_request_text(Some("Keep the file paths."));
+        assert!(nudged.starts_with(&plain));
+        assert!(nudged.ends_with("\n\nKeep the file paths."));
+    }
+
+    #[test]
+    fn test_fit_request_size_truncates_largest_tool_response() {
+        let tool_response = |id: &str, text: String| {
+            Message::user().with_tool_response(
+                id,
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![RawContent::text(text).no_annotation()],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            )
+        };
+        let messages = vec![
+            Message::user().with_text("start"),
+            tool_response("small", "small output".to_string()),
+            tool_response("large", "x".repeat(50_000)),
+        ];
+        let request_bytes = estimate_request_bytes("system", &messages, &[]);
+
+        assert!(fit_request_size(request_bytes, &messages, 100_000).is_none());
+
+        let trim = fit_request_size(request_bytes, &messages, 20_000).unwrap();
+        assert_eq!(trim.truncated_responses, 1);
+        assert!(trim.trimmed_bytes <= 20_000);
+        assert!(trim.original_bytes > 50_000);
+        assert_eq!(
+            trim.trimmed_bytes,
+            estimate_request_bytes("system", &trim.messages, &[])
+        );
+        assert_eq!(trim.messages[1], messages[1]);
+        let truncated = format_message_for_compacting(&trim.messages[2]);
+        assert!(truncated.contains("[tool response truncated from"));
+    }
+
+    #[test]
+    fn test_truncated_tool_response_keeps_errors() {
+        let large = "x".repeat(50_000);
+        let messages = vec![
+            Message::user().with_text("start"),
+            Message::user().with_tool_response(
+                "failed",
+                Ok(rmcp::model::CallToolResult::error(vec![Content::text(
+                    large.clone(),
+                )])),
+            ),
+            Message::user().with_tool_response(
+                "raised",
+                Err(ErrorData::new(
+                    rmcp::model::ErrorCode::INTERNAL_ERROR,
+                    large,
+                    None,
+                )),
+            ),
+        ];
+        let request_bytes = estimate_request_bytes("system", &messages, &[]);
+
+        let trim = fit_request_size(request_bytes, &messages, 20_000).unwrap();
+        assert_eq!(trim.truncated_responses, 2);
+        let result = |message: &Message| match &message.content[0] {
+            MessageContent::ToolResponse(response) => response.tool_result.clone(),
+            other => panic!("expected a tool response, got {:?}", other),
+        };
+
+        let failed = result(&trim.messages[1]).unwrap();
+        assert_eq!(failed.is_error, Some(true));
+        let text = &failed.content[0].as_text().unwrap().text;
+        assert!(text.ends_with("to fit the request size limit]"));
+
+        let raised = result(&trim.messages[2]).unwrap_err();
+        assert_eq!(raised.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+        assert!(raised.message.len() < 20_000);
+        assert!(raised.message.contains("[tool response truncated from"));
     }
 }
@@ -87,8 +87,23 @@ impl ToolRequest {
             Err(e) => format!("Invalid tool call: {}", e),
         }
     }
+
+    /// Warning the tool's author wants shown when asking the user to approve a call,
+    /// declared as `confirmation_prompt` in the tool's `_meta`.
+    pub fn confirmation_prompt(&self) -> Option<String> {
+        self.tool_meta
+            .as_ref()?
+            .get(TOOL_CONFIRMATION_PROMPT_META_KEY)?
+            .as_str()
+            .map(str::trim)
+            .filter(|prompt| !prompt.is_empty())
+            .map(str::to_string)
+    }
 }
 
+/// Key in a tool's `_meta` holding a custom message for its approval prompt
+pub const TOOL_CONFIRMATION_PROMPT_META_KEY: &str = "confirmation_prompt";
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[derive(ToSchema)]
@@ -511,6 +526,9 @@ pub struct MessageMetadata {
     pub user_visible: bool,
     /// Whether the message should be included in the agent's context window
     pub agent_visible: bool,
+    /// Pinned messages are kept verbatim and agent-visible when the conversation is compacted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl Default for MessageMetadata {
@@ -518,6 +536,7 @@ impl Default for MessageMetadata {
         MessageMetadata {
             user_visible: true,
             agent_visible: true,
+            pinned: false,
         }
     }
 }
@@ -528,6 +547,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: false,
             agent_visible: true,
+            pinned: false,
         }
     }
 
@@ -536,6 +556,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: true,
             agent_visible: false,
+            pinned: false,
         }
     }
 
@@ -544,6 +565,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: false,
             agent_visible: false,
+            pinned: false,
         }
     }
 
@@ -578,6 +600,11 @@ impl MessageMetadata {
             ..self
         }
     }
+
+    /// Return a copy with pinned set to `pinned`
+    pub fn with_pinned(self, pinned: bool) -> Self {
+        Self { pinned, ..self }
+    }
 }
 
 #[derive(ToSchema, Clone, PartialEq, Serialize, Deserialize, Debug)]
@@ -852,6 +879,11 @@ impl Message {
     pub fn is_agent_visible(&self) -> bool {
         self.metadata.agent_visible
     }
+
+    /// Check if the message is pinned, so compaction keeps it as is
+    pub fn is_pinned(&self) -> bool {
+        self.metadata.pinned
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
@@ -867,7 +899,7 @@ pub struct TokenState {
 
 #[cfg(test)]
 mod tests {
-    use crate::conversation::message::{Message, MessageContent, MessageMetadata};
+    use crate::conversation::message::{Message, MessageContent, MessageMetadata, ToolRequest};
     use crate::conversation::*;
     use rmcp::model::{
         AnnotateAble, CallToolRequestParam, PromptMessage, PromptMessageContent, PromptMessageRole,
@@ -1268,6 +1300,14 @@ mod tests {
 
         assert_eq!(value["metadata"]["userVisible"], false);
         assert_eq!(value["metadata"]["agentVisible"], true);
+        // Unpinned messages serialize as they did before pinning existed
+        assert!(value["metadata"].get("pinned").is_none());
+
+        let pinned = message.with_metadata(MessageMetadata::default().with_pinned(true));
+        let value: Value = serde_json::to_value(&pinned).unwrap();
+        assert_eq!(value["metadata"]["pinned"], true);
+        let round_trip: Message = serde_json::from_value(value).unwrap();
+        assert!(round_trip.is_pinned());
     }
 
     #[test]
@@ -1523,4 +1563,30 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_tool_request_confirmation_prompt() {
+        let mut request = ToolRequest {
+            id: "1".to_string(),
+            tool_call: Ok(CallToolRequestParam {
+                task: None,
+                name: "drop_database".into(),
+                arguments: None,
+            }),
+            metadata: None,
+            tool_meta: None,
+        };
+        assert_eq!(request.confirmation_prompt(), None);
+
+        request.tool_meta = Some(serde_json::json!({
+            "confirmation_prompt": "This permanently deletes the production database."
+        }));
+        assert_eq!(
+            request.confirmation_prompt().as_deref(),
+            Some("This permanently deletes the production database.")
+        );
+
+        request.tool_meta = Some(serde_json::json!({ "confirmation_prompt": "  " }));
+        assert_eq!(request.confirmation_prompt(), None);
+    }
 }
@@ -66,6 +66,22 @@ impl Conversation {
         self.0.last()
     }
 
+    /// The most recent message written by the user. Tool responses are carried in
+    /// user-role messages but don't count.
+    pub fn last_user_message(&self) -> Option<&Message> {
+        self.0
+            .iter()
+            .rev()
+            .find(|message| effective_role(message) == "user")
+    }
+
+    pub fn last_assistant_message(&self) -> Option<&Message> {
+        self.0
+            .iter()
+            .rev()
+            .find(|message| message.role == Role::Assistant)
+    }
+
     pub fn first(&self) -> Option<&Message> {
         self.0.first()
     }
@@ -122,8 +138,37 @@ impl Conversation {
         self.filtered_messages(|meta| meta.user_visible)
     }
 
-    fn validate(self) -> Result<Self, InvalidConversation> {
+    /// Splits the conversation into turns. A turn starts at each message whose
+    /// effective role is "user" and runs until the next one; any messages before
+    /// the first user message form their own leading turn.
+    pub fn turns(&self) -> Vec<&[Message]> {
+        let mut turns = Vec::new();
+        let mut start = 0;
+        for (idx, message) in self.0.iter().enumerate() {
+            if idx > start && effective_role(message) == "user" {
+                turns.push(&self.0[start..idx]);
+                start = idx;
+            }
+        }
+        if start < self.0.len() {
+            turns.push(&self.0[start..]);
+        }
+        turns
+    }
+
+    /// Reports the structural problems `fix_conversation` would repair, without
+    /// consuming or modifying the conversation.
+    pub fn validation_issues(&self) -> Vec<String> {
         let (_messages, issues) = fix_messages(self.0.clone());
+        issues
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validation_issues().is_empty()
+    }
+
+    fn validate(self) -> Result<Self, InvalidConversation> {
+        let issues = self.validation_issues();
         if !issues.is_empty() {
             let reason = issues.join("\n");
             Err(InvalidConversation {
@@ -162,6 +207,36 @@ impl<'a> IntoIterator for &'a Conversation {
 /// Fix a conversation that we're about to send to an LLM. So the last and first
 /// messages should always be from the user.
 pub fn fix_conversation(conversation: Conversation) -> (Conversation, Vec<String>) {
+    fix_agent_visible_messages(conversation, fix_messages)
+}
+
+/// Like [`fix_conversation`], but keeps a leading or trailing assistant message, for
+/// conversations that are saved rather than sent, such as a merged session.
+pub fn fix_saved_conversation(conversation: Conversation) -> (Conversation, Vec<String>) {
+    fix_agent_visible_messages(conversation, |messages| {
+        apply_fixes(
+            messages,
+            &[
+                merge_text_content_items,
+                trim_assistant_text_whitespace,
+                remove_empty_messages,
+                fix_tool_calling,
+                merge_consecutive_messages,
+            ],
+        )
+    })
+}
+
+/// Removes tool requests without a response and responses without a request, leaving the
+/// rest of the conversation as it is.
+pub fn remove_orphaned_tool_calls(conversation: Conversation) -> (Conversation, Vec<String>) {
+    fix_agent_visible_messages(conversation, fix_tool_calling)
+}
+
+fn fix_agent_visible_messages(
+    conversation: Conversation,
+    fix: impl FnOnce(Vec<Message>) -> (Vec<Message>, Vec<String>),
+) -> (Conversation, Vec<String>) {
     let all_messages = conversation.messages();
 
     // Create a shadow map: track each message as either Visible or NonVisible with its index
@@ -185,7 +260,7 @@ pub fn fix_conversation(conversation: Conversation) -> (Conversation, Vec<String
         .collect();
 
     // Fix only the agent-visible messages
-    let (fixed_visible, issues) = fix_messages(agent_visible_messages);
+    let (fixed_visible, issues) = fix(agent_visible_messages);
 
     // Reconstruct using shadow map: replace Visible slots with fixed messages
     let final_messages: Vec<Message> = shadow_map
@@ -199,18 +274,25 @@ pub fn fix_conversation(conversation: Conversation) -> (Conversation, Vec<String
     (Conversation::new_unvalidated(final_messages), issues)
 }
 
+type MessageFix = fn(Vec<Message>) -> (Vec<Message>, Vec<String>);
+
 fn fix_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
-    [
-        merge_text_content_items,
-        trim_assistant_text_whitespace,
-        remove_empty_messages,
-        fix_tool_calling,
-        merge_consecutive_messages,
-        fix_lead_trail,
-        populate_if_empty,
-    ]
-    .into_iter()
-    .fold(
+    apply_fixes(
+        messages,
+        &[
+            merge_text_content_items,
+            trim_assistant_text_whitespace,
+            remove_empty_messages,
+            fix_tool_calling,
+            merge_consecutive_messages,
+            fix_lead_trail,
+            populate_if_empty,
+        ],
+    )
+}
+
+fn apply_fixes(messages: Vec<Message>, fixes: &[MessageFix]) -> (Vec<Message>, Vec<String>) {
+    fixes.iter().fold(
         (messages, Vec::new()),
         |(msgs, mut all_issues), processor| {
             let (new_msgs, issues) = processor(msgs);
@@ -502,7 +584,9 @@ pub fn debug_conversation_fix(
 #[cfg(test)]
 mod tests {
     use crate::conversation::message::Message;
-    use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
+    use crate::conversation::{
+        debug_conversation_fix, fix_conversation, remove_orphaned_tool_calls, Conversation,
+    };
     use rmcp::model::{CallToolRequestParam, Role};
     use rmcp::object;
 
@@ -594,6 +678,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_remove_orphaned_tool_calls_keeps_trailing_reply() {
+        let request = |id: &str| {
+            Message::assistant().with_tool_request(
+                id,
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: None,
+                }),
+            )
+        };
+        let response = Message::user()
+            .with_tool_response("answered", Ok(rmcp::model::CallToolResult::success(vec![])));
+        let messages = vec![
+            Message::user().with_text("first"),
+            request("answered"),
+            response,
+            Message::assistant().with_text("first reply"),
+            Message::user().with_text("second"),
+            request("unanswered"),
+        ];
+
+        let (fixed, issues) = remove_orphaned_tool_calls(Conversation::new_unvalidated(messages));
+
+        assert_eq!(issues, vec!["Removed orphaned tool request 'unanswered'"]);
+        assert_eq!(fixed.len(), 5);
+        assert_eq!(fixed.messages()[3].as_concat_text(), "first reply");
+        assert_eq!(fixed.messages()[4].as_concat_text(), "second");
+    }
+
     #[test]
     fn test_role_alternation_and_content_placement_issues() {
         let messages = vec![
@@ -1175,4 +1290,96 @@ mod tests {
         assert_eq!(fixed_messages[5].as_concat_text(), "Non-vis C");
         assert!(!fixed_messages[5].metadata.agent_visible);
     }
+
+    #[test]
+    fn test_validation_issues_does_not_consume() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::assistant().with_text("Leading assistant"),
+            Message::user().with_text("Hello"),
+        ]);
+
+        assert!(!conversation.is_valid());
+        assert_eq!(
+            conversation.validation_issues(),
+            vec!["Removed leading assistant message".to_string()]
+        );
+        assert_eq!(conversation.len(), 2);
+
+        let valid = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi"),
+            Message::user().with_text("Bye"),
+        ]);
+        assert!(valid.is_valid());
+        assert!(valid.validation_issues().is_empty());
+    }
+
+    #[test]
+    fn test_turns_split_on_user_messages() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("First"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: Some(object!({"command": "ls"})),
+                }),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+            Message::assistant().with_text("Done"),
+            Message::user().with_text("Second"),
+            Message::assistant().with_text("Ok"),
+        ]);
+
+        let turns = conversation.turns();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].len(), 4);
+        assert_eq!(turns[1].len(), 2);
+        assert_eq!(turns[1][0].as_concat_text(), "Second");
+        assert!(Conversation::empty().turns().is_empty());
+    }
+
+    #[test]
+    fn test_last_user_and_assistant_message() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("List the files"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: Some(object!({"command": "ls"})),
+                }),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(rmcp::model::CallToolResult {
+                    content: vec![],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+        ]);
+
+        assert_eq!(
+            conversation.last_user_message().unwrap().as_concat_text(),
+            "List the files"
+        );
+        assert!(conversation
+            .last_assistant_message()
+            .unwrap()
+            .is_tool_call());
+        assert!(Conversation::empty().last_user_message().is_none());
+        assert!(Conversation::empty().last_assistant_message().is_none());
+    }
 }
@@ -19,6 +19,50 @@ pub struct WindowProps {
     pub width: u32,
     pub height: u32,
     pub resizable: bool,
+    /// When set, the app host keeps width / height at this ratio while resizing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f32>,
+}
+
+impl Default for WindowProps {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            resizable: true,
+            aspect_ratio: None,
+        }
+    }
+}
+
+impl WindowProps {
+    /// Builds window props from the app's resource metadata, honouring a declared
+    /// aspect ratio. Non-positive or non-finite ratios are ignored.
+    pub fn from_metadata(meta: Option<&ResourceMetadata>) -> Self {
+        let aspect_ratio = meta
+            .and_then(|meta| meta.ui.as_ref())
+            .and_then(|ui| ui.aspect_ratio)
+            .filter(|ratio| ratio.is_finite() && *ratio > 0.0);
+
+        let mut props = Self {
+            aspect_ratio,
+            ..Self::default()
+        };
+        props.height = props.constrain(props.width, props.height).1;
+        props
+    }
+
+    /// Adjusts a requested size so it respects the aspect ratio, keeping the width
+    /// fixed and deriving the height from it.
+    pub fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+        match self.aspect_ratio {
+            Some(ratio) => {
+                let height = (width as f32 / ratio).round().max(1.0) as u32;
+                (width, height)
+            }
+            None => (width, height),
+        }
+    }
 }
 
 /// A Goose App combining MCP resource data with Goose-specific metadata
@@ -33,6 +77,15 @@ pub struct GooseApp {
     pub window_props: Option<WindowProps>,
 }
 
+/// A portable snapshot of the app cache, used to back up apps or move them between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppBundle {
+    pub version: u32,
+    pub apps: Vec<GooseApp>,
+}
+
+pub const APP_BUNDLE_VERSION: u32 = 1;
+
 pub struct McpAppCache {
     cache_dir: PathBuf,
 }
@@ -44,6 +97,10 @@ impl McpAppCache {
         Ok(Self { cache_dir })
     }
 
+    pub fn with_cache_dir(cache_dir: PathBuf) -> Self {
+        Self { cache_dir }
+    }
+
     fn cache_key(extension_name: &str, resource_uri: &str) -> String {
         let input = format!("{}::{}", extension_name, resource_uri);
         let hash = Sha256::digest(input.as_bytes());
@@ -101,6 +158,46 @@ impl McpAppCache {
             .and_then(|content| serde_json::from_str::<GooseApp>(&content).ok())
     }
 
+    pub fn export_bundle(&self) -> Result<AppBundle, std::io::Error> {
+        let mut apps = self.list_apps()?;
+        apps.sort_by(|a, b| {
+            (a.mcp_server.as_deref(), a.resource.uri.as_str())
+                .cmp(&(b.mcp_server.as_deref(), b.resource.uri.as_str()))
+        });
+        Ok(AppBundle {
+            version: APP_BUNDLE_VERSION,
+            apps,
+        })
+    }
+
+    /// Stores every app in the bundle, overwriting cached copies of the same app.
+    /// Returns the number of apps imported; apps without an owning server are skipped.
+    pub fn import_bundle(&self, bundle: &AppBundle) -> Result<usize, std::io::Error> {
+        if bundle.version > APP_BUNDLE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "App bundle version {} is newer than supported version {}",
+                    bundle.version, APP_BUNDLE_VERSION
+                ),
+            ));
+        }
+
+        let mut imported = 0;
+        for app in &bundle.apps {
+            if app.mcp_server.is_none() {
+                warn!(
+                    "Skipping app {} without an MCP server in bundle",
+                    app.resource.uri
+                );
+                continue;
+            }
+            self.store_app(app)?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
     pub fn delete_extension_apps(&self, extension_name: &str) -> Result<usize, std::io::Error> {
         let mut deleted_count = 0;
 
@@ -143,38 +240,48 @@ pub async fn fetch_mcp_apps(
         {
             Ok(read_result) => {
                 let mut html = String::new();
+                let mut meta = None;
                 for content in read_result.contents {
-                    if let rmcp::model::ResourceContents::TextResourceContents { text, .. } =
-                        content
+                    if let rmcp::model::ResourceContents::TextResourceContents {
+                        text,
+                        meta: content_meta,
+                        ..
+                    } = content
                     {
                         html = text;
+                        meta = content_meta
+                            .and_then(|m| serde_json::to_value(m).ok())
+                            .and_then(|v| serde_json::from_value::<ResourceMetadata>(v).ok());
                         break;
                     }
                 }
 
-                if !html.is_empty() {
-                    let mcp_resource = McpAppResource {
-                        uri: resource.uri.clone(),
-                        name: format_resource_name(resource.name.clone()),
-                        description: resource.description.clone(),
-                        mime_type: "text/html;profile=mcp-app".to_string(),
-                        text: Some(html),
-                        blob: None,
-                        meta: None,
-                    };
-
-                    let app = GooseApp {
-                        resource: mcp_resource,
-                        mcp_server: Some(extension_name),
-                        window_props: Some(WindowProps {
-                            width: 800,
-                            height: 600,
-                            resizable: true,
-                        }),
-                    };
-
-                    apps.push(app);
+                if let Err(reason) = validate_app_html(&html) {
+                    warn!(
+                        "Skipping app {} from {}: {}",
+                        resource.uri, extension_name, reason
+                    );
+                    continue;
                 }
+
+                let mcp_resource = McpAppResource {
+                    uri: resource.uri.clone(),
+                    name: format_resource_name(resource.name.clone()),
+                    description: resource.description.clone(),
+                    mime_type: "text/html;profile=mcp-app".to_string(),
+                    text: Some(html),
+                    blob: None,
+                    meta,
+                };
+
+                let window_props = WindowProps::from_metadata(mcp_resource.meta.as_ref());
+                let app = GooseApp {
+                    resource: mcp_resource,
+                    mcp_server: Some(extension_name),
+                    window_props: Some(window_props),
+                };
+
+                apps.push(app);
             }
             Err(e) => {
                 warn!(
@@ -188,6 +295,34 @@ pub async fn fetch_mcp_apps(
     Ok(apps)
 }
 
+/// Light sanity check so that error strings or truncated output don't get cached
+/// as apps and then render blank.
+fn validate_app_html(html: &str) -> Result<(), String> {
+    let trimmed = html.trim();
+    if trimmed.is_empty() {
+        return Err("HTML is empty".to_string());
+    }
+
+    let lower = trimmed.to_ascii_lowercase();
+    if lower.contains("<html") || lower.contains("<body") {
+        return Ok(());
+    }
+
+    // Fragments are fine as long as they are rooted in an element
+    let starts_with_element = trimmed
+        .strip_prefix('<')
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '!');
+    if starts_with_element && trimmed.ends_with('>') {
+        Ok(())
+    } else {
+        Err(format!(
+            "content does not look like HTML: {}",
+            crate::utils::safe_truncate(trimmed, 80)
+        ))
+    }
+}
+
 fn format_resource_name(name: String) -> String {
     name.replace('_', " ")
         .split_whitespace()
@@ -201,3 +336,80 @@ fn format_resource_name(name: String) -> String {
         .collect::<Vec<_>>()
         .join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn app(server: Option<&str>, uri: &str) -> GooseApp {
+        GooseApp {
+            resource: McpAppResource {
+                uri: uri.to_string(),
+                name: "App".to_string(),
+                description: None,
+                mime_type: "text/html;profile=mcp-app".to_string(),
+                text: Some("<html><body>hi</body></html>".to_string()),
+                blob: None,
+                meta: None,
+            },
+            mcp_server: server.map(str::to_string),
+            window_props: None,
+        }
+    }
+
+    #[test]
+    fn test_window_props_aspect_ratio_from_metadata() {
+        let meta: ResourceMetadata =
+            serde_json::from_value(serde_json::json!({ "ui": { "aspectRatio": 1.6 } })).unwrap();
+        let props = WindowProps::from_metadata(Some(&meta));
+        assert_eq!(props.aspect_ratio, Some(1.6));
+        assert_eq!((props.width, props.height), (800, 500));
+        assert_eq!(props.constrain(400, 900), (400, 250));
+
+        let invalid: ResourceMetadata =
+            serde_json::from_value(serde_json::json!({ "ui": { "aspectRatio": 0.0 } })).unwrap();
+        let props = WindowProps::from_metadata(Some(&invalid));
+        assert_eq!(props.aspect_ratio, None);
+        assert_eq!((props.width, props.height), (800, 600));
+        assert_eq!(props.constrain(400, 900), (400, 900));
+    }
+
+    #[test]
+    fn test_validate_app_html() {
+        assert!(validate_app_html("<!DOCTYPE html><html><body></body></html>").is_ok());
+        assert!(validate_app_html("  <body><div id=\"root\"></div></body>").is_ok());
+        assert!(validate_app_html("<div id=\"root\"></div>\n").is_ok());
+
+        assert!(validate_app_html("   ").is_err());
+        assert!(validate_app_html("Error: resource not found").is_err());
+        assert!(validate_app_html("<div>truncated").is_err());
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let source_dir = TempDir::new().unwrap();
+        let source = McpAppCache::with_cache_dir(source_dir.path().to_path_buf());
+        source.store_app(&app(Some("ext_b"), "ui://b")).unwrap();
+        source.store_app(&app(Some("ext_a"), "ui://a")).unwrap();
+
+        let bundle = source.export_bundle().unwrap();
+        assert_eq!(bundle.version, APP_BUNDLE_VERSION);
+        let uris: Vec<_> = bundle
+            .apps
+            .iter()
+            .map(|a| a.resource.uri.as_str())
+            .collect();
+        assert_eq!(uris, vec!["ui://a", "ui://b"]);
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let mut parsed: AppBundle = serde_json::from_str(&json).unwrap();
+        parsed.apps.push(app(None, "ui://orphan"));
+
+        let target_dir = TempDir::new().unwrap();
+        let target = McpAppCache::with_cache_dir(target_dir.path().to_path_buf());
+        assert_eq!(target.import_bundle(&parsed).unwrap(), 2);
+        assert!(target.get_app("ext_a", "ui://a").is_some());
+        assert!(target.get_app("ext_b", "ui://b").is_some());
+    }
+}
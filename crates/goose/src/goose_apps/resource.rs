@@ -15,7 +15,7 @@ pub struct CspMetadata {
 }
 
 /// UI-specific metadata for MCP resources
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMetadata {
     /// Content Security Policy configuration
@@ -27,10 +27,13 @@ pub struct UiMetadata {
     /// Whether the app prefers to have a border around it
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefers_border: Option<bool>,
+    /// Width-to-height ratio the app window should keep while resizing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f32>,
 }
 
 /// Resource metadata containing UI configuration
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceMetadata {
     /// UI-specific configuration
@@ -89,6 +92,7 @@ impl McpAppResource {
                     csp: Some(csp),
                     domain: None,
                     prefers_border: None,
+                    aspect_ratio: None,
                 }),
             }),
         }
@@ -1,4 +1,112 @@
+use rmcp::model::ErrorCode;
 pub use rmcp::model::ErrorData;
 
 /// Type alias for tool results
 pub type ToolResult<T> = Result<T, ErrorData>;
+
+/// Coarse classification of why a tool call failed, used as a metrics label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolErrorCategory {
+    Timeout,
+    NotFound,
+    PermissionDenied,
+    Cancelled,
+    Other,
+}
+
+impl ToolErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolErrorCategory::Timeout => "timeout",
+            ToolErrorCategory::NotFound => "not_found",
+            ToolErrorCategory::PermissionDenied => "permission_denied",
+            ToolErrorCategory::Cancelled => "cancelled",
+            ToolErrorCategory::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ToolErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Infers the failure category from the error code and, failing that, the message text.
+pub fn categorize_tool_error(error: &ErrorData) -> ToolErrorCategory {
+    if error.code == ErrorCode::RESOURCE_NOT_FOUND || error.code == ErrorCode::METHOD_NOT_FOUND {
+        return ToolErrorCategory::NotFound;
+    }
+
+    let message = error.message.to_lowercase();
+    if message.contains("timed out") || message.contains("timeout") {
+        ToolErrorCategory::Timeout
+    } else if message.contains("cancelled") || message.contains("canceled") {
+        ToolErrorCategory::Cancelled
+    } else if message.contains("permission denied")
+        || message.contains("not permitted")
+        || message.contains("declined")
+        || message.contains("forbidden")
+    {
+        ToolErrorCategory::PermissionDenied
+    } else if message.contains("not found") || message.contains("no such file") {
+        ToolErrorCategory::NotFound
+    } else {
+        ToolErrorCategory::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: ErrorCode, message: &str) -> ErrorData {
+        ErrorData::new(code, message.to_string(), None)
+    }
+
+    #[test]
+    fn test_categorize_tool_error() {
+        let cases = [
+            (
+                ErrorCode::RESOURCE_NOT_FOUND,
+                "missing",
+                ToolErrorCategory::NotFound,
+            ),
+            (
+                ErrorCode::METHOD_NOT_FOUND,
+                "no tool",
+                ToolErrorCategory::NotFound,
+            ),
+            (
+                ErrorCode::INTERNAL_ERROR,
+                "Command timed out after 300s",
+                ToolErrorCategory::Timeout,
+            ),
+            (
+                ErrorCode::INTERNAL_ERROR,
+                "Request cancelled",
+                ToolErrorCategory::Cancelled,
+            ),
+            (
+                ErrorCode::INVALID_REQUEST,
+                "Permission denied (os error 13)",
+                ToolErrorCategory::PermissionDenied,
+            ),
+            (
+                ErrorCode::INVALID_PARAMS,
+                "No such file or directory",
+                ToolErrorCategory::NotFound,
+            ),
+            (ErrorCode::INTERNAL_ERROR, "boom", ToolErrorCategory::Other),
+        ];
+
+        for (code, message, expected) in cases {
+            assert_eq!(
+                categorize_tool_error(&error(code, message)),
+                expected,
+                "{}",
+                message
+            );
+        }
+    }
+}
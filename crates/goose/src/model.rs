@@ -112,6 +112,10 @@ pub struct ModelConfig {
     /// Provider-specific request parameters (e.g., anthropic_beta headers)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub request_params: Option<HashMap<String, Value>>,
+    /// Per-run cap on response length, applied on top of `max_tokens` for the main
+    /// model only. Background calls made through `use_fast_model` ignore it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_response_tokens: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,6 +167,7 @@ impl ModelConfig {
             toolshim_model,
             fast_model: None,
             request_params,
+            max_response_tokens: None,
         })
     }
 
@@ -322,6 +327,20 @@ impl ModelConfig {
         self
     }
 
+    pub fn with_max_response_tokens(mut self, tokens: Option<i32>) -> Self {
+        self.max_response_tokens = tokens;
+        self
+    }
+
+    /// The output token limit to send to the provider: `max_tokens`, lowered to the
+    /// per-run response cap when one is set.
+    pub fn max_output_tokens(&self) -> Option<i32> {
+        match (self.max_tokens, self.max_response_tokens) {
+            (Some(max), Some(cap)) => Some(max.min(cap)),
+            (max, cap) => cap.or(max),
+        }
+    }
+
     pub fn with_toolshim(mut self, toolshim: bool) -> Self {
         self.toolshim = toolshim;
         self
@@ -343,13 +362,12 @@ impl ModelConfig {
     }
 
     pub fn use_fast_model(&self) -> Self {
+        let mut config = self.clone();
+        config.max_response_tokens = None;
         if let Some(fast_model) = &self.fast_model {
-            let mut config = self.clone();
             config.model_name = fast_model.clone();
-            config
-        } else {
-            self.clone()
         }
+        config
     }
 
     pub fn context_limit(&self) -> usize {
@@ -445,4 +463,17 @@ mod tests {
         let config = ModelConfig::new("test-model").unwrap();
         assert_eq!(config.max_tokens, None);
     }
+
+    #[test]
+    fn test_max_response_tokens_caps_main_model_only() {
+        let config = ModelConfig::new_or_fail("test-model")
+            .with_max_tokens(Some(8192))
+            .with_max_response_tokens(Some(500));
+        assert_eq!(config.max_output_tokens(), Some(500));
+        assert_eq!(config.use_fast_model().max_output_tokens(), Some(8192));
+
+        let uncapped = config.clone().with_max_tokens(None);
+        assert_eq!(uncapped.max_output_tokens(), Some(500));
+        assert_eq!(uncapped.use_fast_model().max_output_tokens(), None);
+    }
 }
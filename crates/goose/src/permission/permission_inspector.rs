@@ -1,14 +1,60 @@
 use crate::agents::extension_manager_extension::MANAGE_EXTENSIONS_TOOL_NAME_COMPLETE;
 use crate::config::permission::PermissionLevel;
-use crate::config::{GooseMode, PermissionManager};
+use crate::config::{Config, GooseMode, PermissionManager};
 use crate::conversation::message::{Message, ToolRequest};
 use crate::permission::permission_judge::PermissionCheckResult;
 use crate::tool_inspection::{InspectionAction, InspectionResult, ToolInspector};
 use anyhow::Result;
 use async_trait::async_trait;
+use goose_mcp::developer::changelist::{Changelist, GOOSE_CHANGELIST};
+use rmcp::model::CallToolRequestParam;
 use std::collections::HashSet;
 use std::sync::Arc;
 
+/// Tools that run without a prompt in approve modes. Entries are full tool names including
+/// the extension prefix (`developer__shell`), optionally followed by `:<command>` to only
+/// allow one command of the tool (`developer__text_editor:view`).
+pub const GOOSE_APPROVE_SAFE_TOOLS: &str = "GOOSE_APPROVE_SAFE_TOOLS";
+
+/// With a changelist set, the developer extension asks before editing files outside it,
+/// so its text editor doesn't need a second prompt here.
+const CHANGELIST_TEXT_EDITOR_TOOL: &str = "developer__text_editor";
+
+/// Reads the safe tool list, accepting either a YAML list or a comma-separated string.
+pub fn configured_safe_tools() -> Vec<String> {
+    let config = Config::global();
+    let entries = config
+        .get_param::<Vec<String>>(GOOSE_APPROVE_SAFE_TOOLS)
+        .or_else(|_| {
+            config
+                .get_param::<String>(GOOSE_APPROVE_SAFE_TOOLS)
+                .map(|raw| raw.split(',').map(str::to_string).collect())
+        })
+        .unwrap_or_default();
+    entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+fn is_safe_tool(safe_tools: &[String], tool_call: &CallToolRequestParam) -> bool {
+    let name = tool_call.name.as_ref();
+    let command = tool_call
+        .arguments
+        .as_ref()
+        .and_then(|args| args.get("command"))
+        .and_then(|command| command.as_str());
+
+    safe_tools.iter().any(|entry| {
+        let (tool, required_command) = match entry.split_once(':') {
+            Some((tool, required_command)) => (tool, Some(required_command)),
+            None => (entry.as_str(), None),
+        };
+        tool == name && required_command.is_none_or(|required| command == Some(required))
+    })
+}
+
 /// Permission Inspector that handles tool permission checking
 pub struct PermissionInspector {
     readonly_tools: HashSet<String>,
@@ -111,10 +157,19 @@ impl ToolInspector for PermissionInspector {
     ) -> Result<Vec<InspectionResult>> {
         let mut results = Vec::new();
         let permission_manager = &self.permission_manager;
+        let safe_tools = match goose_mode {
+            GooseMode::Approve | GooseMode::SmartApprove => configured_safe_tools(),
+            _ => Vec::new(),
+        };
+        let changelist_active = matches!(goose_mode, GooseMode::Approve | GooseMode::SmartApprove)
+            && Changelist::from_env().is_some();
 
         for request in tool_requests {
             if let Ok(tool_call) = &request.tool_call {
                 let tool_name = &tool_call.name;
+                let is_safe = is_safe_tool(&safe_tools, tool_call);
+                let is_changelist_edit =
+                    changelist_active && tool_name == CHANGELIST_TEXT_EDITOR_TOOL;
 
                 let action = match goose_mode {
                     GooseMode::Chat => continue,
@@ -130,7 +185,11 @@ impl ToolInspector for PermissionInspector {
                                 }
                             }
                         }
-                        // 2. Check if it's a readonly or regular tool (both pre-approved)
+                        // 2. Tools the user configured as safe to run without a prompt
+                        else if is_safe || is_changelist_edit {
+                            InspectionAction::Allow
+                        }
+                        // 3. Check if it's a readonly or regular tool (both pre-approved)
                         else if self.readonly_tools.contains(tool_name.as_ref())
                             || self.regular_tools.contains(tool_name.as_ref())
                         {
@@ -153,6 +212,17 @@ impl ToolInspector for PermissionInspector {
                     InspectionAction::Allow => {
                         if goose_mode == GooseMode::Auto {
                             "Auto mode - all tools approved".to_string()
+                        } else if permission_manager.get_user_permission(tool_name).is_none()
+                            && is_safe
+                        {
+                            format!("Tool listed in {}", GOOSE_APPROVE_SAFE_TOOLS)
+                        } else if permission_manager.get_user_permission(tool_name).is_none()
+                            && is_changelist_edit
+                        {
+                            format!(
+                                "Edits outside {} are confirmed by the developer extension",
+                                GOOSE_CHANGELIST
+                            )
                         } else if self.readonly_tools.contains(tool_name.as_ref()) {
                             "Tool marked as read-only".to_string()
                         } else if self.regular_tools.contains(tool_name.as_ref()) {
@@ -185,3 +255,111 @@ impl ToolInspector for PermissionInspector {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arguments: serde_json::Value) -> CallToolRequestParam {
+        CallToolRequestParam {
+            task: None,
+            name: name.to_string().into(),
+            arguments: arguments.as_object().cloned(),
+        }
+    }
+
+    #[test]
+    fn test_is_safe_tool() {
+        let safe_tools = vec![
+            "developer__text_editor:view".to_string(),
+            "computercontroller__list_windows".to_string(),
+            "shell".to_string(),
+        ];
+
+        assert!(is_safe_tool(
+            &safe_tools,
+            &call(
+                "developer__text_editor",
+                serde_json::json!({"command": "view"})
+            )
+        ));
+        assert!(!is_safe_tool(
+            &safe_tools,
+            &call(
+                "developer__text_editor",
+                serde_json::json!({"command": "write"})
+            )
+        ));
+        assert!(is_safe_tool(
+            &safe_tools,
+            &call("computercontroller__list_windows", serde_json::json!({}))
+        ));
+        assert!(!is_safe_tool(
+            &safe_tools,
+            &call("developer__shell", serde_json::json!({"command": "view"}))
+        ));
+        assert!(!is_safe_tool(
+            &safe_tools,
+            &call(
+                "untrusted__text_editor",
+                serde_json::json!({"command": "view"})
+            )
+        ));
+        assert!(!is_safe_tool(
+            &[],
+            &call("developer__text_editor", serde_json::json!({}))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_changelist_allows_text_editor_without_prompt() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let inspector = PermissionInspector::new(
+            HashSet::new(),
+            HashSet::new(),
+            Arc::new(PermissionManager::new(config_dir.path().to_path_buf())),
+        );
+        let request = |id: &str, name: &str| ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(call(name, serde_json::json!({"command": "write"}))),
+            metadata: None,
+            tool_meta: None,
+        };
+        let requests = vec![
+            request("edit", CHANGELIST_TEXT_EDITOR_TOOL),
+            request("shell", "developer__shell"),
+        ];
+        let actions = |results: Vec<InspectionResult>| -> Vec<InspectionAction> {
+            results.into_iter().map(|result| result.action).collect()
+        };
+
+        {
+            let _guard = env_lock::lock_env([
+                (GOOSE_CHANGELIST, Some("src/lib.rs")),
+                (GOOSE_APPROVE_SAFE_TOOLS, None),
+            ]);
+            let results = inspector
+                .inspect(&requests, &[], GooseMode::Approve)
+                .await
+                .unwrap();
+            assert_eq!(
+                actions(results),
+                vec![
+                    InspectionAction::Allow,
+                    InspectionAction::RequireApproval(None)
+                ]
+            );
+        }
+
+        // Without a changelist the extension doesn't ask, so the edit needs approval here
+        let _guard = env_lock::lock_env([
+            (GOOSE_CHANGELIST, None::<&str>),
+            (GOOSE_APPROVE_SAFE_TOOLS, None),
+        ]);
+        let results = inspector
+            .inspect(&requests, &[], GooseMode::Approve)
+            .await
+            .unwrap();
+        assert_eq!(actions(results)[0], InspectionAction::RequireApproval(None));
+    }
+}
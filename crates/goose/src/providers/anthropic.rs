@@ -41,6 +41,8 @@ const ANTHROPIC_KNOWN_MODELS: &[&str] = &[
 
 const ANTHROPIC_DOC_URL: &str = "https://docs.anthropic.com/en/docs/about-claude/models";
 const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+/// Request fields accepted by the `v1/messages/count_tokens` endpoint
+const COUNT_TOKENS_FIELDS: &[&str] = &["model", "messages", "system", "tools", "thinking"];
 
 #[derive(serde::Serialize)]
 pub struct AnthropicProvider {
@@ -227,6 +229,38 @@ impl Provider for AnthropicProvider {
         Ok((message, provider_usage))
     }
 
+    async fn ping(&self) -> Result<(), ProviderError> {
+        self.fetch_supported_models().await.map(|_| ())
+    }
+
+    async fn count_tokens(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Option<usize>, ProviderError> {
+        let request = create_request(model_config, system, messages, tools)?;
+        // The counting endpoint rejects sampling parameters such as max_tokens
+        let payload: serde_json::Map<String, Value> = request
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| COUNT_TOKENS_FIELDS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let response = self
+            .api_client
+            .api_post("v1/messages/count_tokens", &Value::Object(payload))
+            .await?;
+        let json = Self::anthropic_api_call_result(response)?;
+        Ok(json
+            .get("input_tokens")
+            .and_then(|tokens| tokens.as_u64())
+            .map(|tokens| tokens as usize))
+    }
+
     async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         let response = self.api_client.api_get("v1/models").await?;
 
@@ -297,3 +331,58 @@ impl Provider for AnthropicProvider {
         self.supports_streaming
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_count_tokens_sends_the_system_prompt() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 42})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let model = ModelConfig::new_or_fail(ANTHROPIC_DEFAULT_MODEL);
+        let provider = AnthropicProvider {
+            api_client: ApiClient::new(
+                server.uri(),
+                AuthMethod::ApiKey {
+                    header_name: "x-api-key".to_string(),
+                    key: "test-key".to_string(),
+                },
+            )
+            .unwrap(),
+            model: model.clone(),
+            supports_streaming: true,
+            name: AnthropicProvider::metadata().name,
+        };
+
+        let count = provider
+            .count_tokens(
+                &model,
+                "You are a helpful assistant.",
+                &[Message::user().with_text("hello")],
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(count, Some(42));
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(body["system"]
+            .to_string()
+            .contains("You are a helpful assistant."));
+        assert!(body.get("max_tokens").is_none());
+    }
+}
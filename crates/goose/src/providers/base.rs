@@ -343,6 +343,35 @@ impl Usage {
 
 use async_trait::async_trait;
 
+const PING_SYSTEM_PROMPT: &str = "Reply with the single word: pong";
+const PING_MAX_TOKENS: i32 = 16;
+
+/// Pings a provider by requesting a completion capped at a handful of tokens.
+pub async fn ping_with_completion<P: Provider + ?Sized>(provider: &P) -> Result<(), ProviderError> {
+    let model_config = provider
+        .get_model_config()
+        .with_max_response_tokens(Some(PING_MAX_TOKENS));
+    let messages = [Message::user().with_text("ping")];
+    provider
+        .complete_with_model(&model_config, PING_SYSTEM_PROMPT, &messages, &[])
+        .await
+        .map(|_| ())
+}
+
+/// The context limit to account against: an explicit `GOOSE_CONTEXT_LIMIT` first, then the
+/// backend's reported limit when the provider can query it, otherwise the configured
+/// `ModelConfig::context_limit()`.
+pub async fn effective_context_limit<P: Provider + ?Sized>(provider: &P) -> usize {
+    let configured = provider.get_model_config().context_limit();
+    if std::env::var("GOOSE_CONTEXT_LIMIT").is_ok() {
+        return configured;
+    }
+    match provider.actual_context_limit().await {
+        Some(limit) if limit > 0 => limit,
+        _ => configured,
+    }
+}
+
 /// Trait for LeadWorkerProvider-specific functionality
 pub trait LeadWorkerProviderTrait {
     /// Get information about the lead and worker models for logging
@@ -353,6 +382,11 @@ pub trait LeadWorkerProviderTrait {
 
     /// Get (lead_turns, failure_threshold, fallback_turns)
     fn get_settings(&self) -> (usize, usize, usize);
+
+    /// Whether the provider has switched back to the lead model after worker failures
+    fn fallback_active(&self) -> bool {
+        false
+    }
 }
 
 /// Base trait for AI providers (OpenAI, Anthropic, etc)
@@ -411,7 +445,8 @@ pub trait Provider: Send + Sync {
                         e,
                         model_config.model_name
                     );
-                    self.complete_with_model(&model_config, system, messages, tools)
+                    let fallback_config = model_config.clone().with_max_response_tokens(None);
+                    self.complete_with_model(&fallback_config, system, messages, tools)
                         .await
                 } else {
                     Err(e)
@@ -431,6 +466,31 @@ pub trait Provider: Send + Sync {
         Ok(None)
     }
 
+    /// The current model's context limit as reported by the provider's backend. Providers
+    /// that can query it should override this; `None` falls back to the configured limit.
+    async fn actual_context_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Check that the provider is reachable and accepts our credentials. The default sends
+    /// a tiny completion; providers with a cheaper authenticated endpoint should override it.
+    async fn ping(&self) -> Result<(), ProviderError> {
+        ping_with_completion(self).await
+    }
+
+    /// Count the input tokens a request would use with the model's own tokenizer. Returns
+    /// `None` when the provider has no counting endpoint so callers can fall back to the
+    /// local estimate from `token_counter`.
+    async fn count_tokens(
+        &self,
+        _model_config: &ModelConfig,
+        _system: &str,
+        _messages: &[Message],
+        _tools: &[Tool],
+    ) -> Result<Option<usize>, ProviderError> {
+        Ok(None)
+    }
+
     /// Fetch models filtered by canonical registry and usability
     async fn fetch_recommended_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         let all_models = match self.fetch_supported_models().await? {
@@ -725,6 +785,108 @@ mod tests {
         assert_ne!(info, info3);
     }
 
+    #[tokio::test]
+    async fn test_default_ping_sends_capped_completion() {
+        struct RecordingProvider {
+            seen_max_tokens: Mutex<Option<i32>>,
+        }
+
+        #[async_trait]
+        impl Provider for RecordingProvider {
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata::empty()
+            }
+
+            fn get_name(&self) -> &str {
+                "recording"
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new_or_fail("test-model")
+            }
+
+            async fn complete_with_model(
+                &self,
+                model_config: &ModelConfig,
+                _system: &str,
+                _messages: &[Message],
+                _tools: &[Tool],
+            ) -> Result<(Message, ProviderUsage), ProviderError> {
+                *self.seen_max_tokens.lock().unwrap() = model_config.max_output_tokens();
+                Ok((
+                    Message::assistant().with_text("pong"),
+                    ProviderUsage::new("test-model".to_string(), Usage::default()),
+                ))
+            }
+        }
+
+        let provider = RecordingProvider {
+            seen_max_tokens: Mutex::new(None),
+        };
+        provider.ping().await.unwrap();
+        assert_eq!(
+            *provider.seen_max_tokens.lock().unwrap(),
+            Some(PING_MAX_TOKENS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_effective_context_limit_prefers_explicit_then_backend_limit() {
+        struct LimitProvider {
+            reported: Option<usize>,
+        }
+
+        #[async_trait]
+        impl Provider for LimitProvider {
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata::empty()
+            }
+
+            fn get_name(&self) -> &str {
+                "limit"
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                // Reads GOOSE_CONTEXT_LIMIT like a real provider's config
+                ModelConfig::new_or_fail("test-model")
+            }
+
+            async fn complete_with_model(
+                &self,
+                _model_config: &ModelConfig,
+                _system: &str,
+                _messages: &[Message],
+                _tools: &[Tool],
+            ) -> Result<(Message, ProviderUsage), ProviderError> {
+                Ok((
+                    Message::assistant().with_text("ok"),
+                    ProviderUsage::new("test-model".to_string(), Usage::default()),
+                ))
+            }
+
+            async fn actual_context_limit(&self) -> Option<usize> {
+                self.reported
+            }
+        }
+
+        let reported = LimitProvider {
+            reported: Some(200_000),
+        };
+        let unknown = LimitProvider { reported: None };
+        {
+            let _guard = env_lock::lock_env([("GOOSE_CONTEXT_LIMIT", None::<&str>)]);
+            assert_eq!(effective_context_limit(&reported).await, 200_000);
+            assert_eq!(
+                effective_context_limit(&unknown).await,
+                unknown.get_model_config().context_limit()
+            );
+        }
+
+        // An explicit limit wins over what the backend reports
+        let _guard = env_lock::lock_env([("GOOSE_CONTEXT_LIMIT", Some("16000"))]);
+        assert_eq!(effective_context_limit(&reported).await, 16_000);
+    }
+
     #[test]
     fn test_model_info_with_cost() {
         let info = ModelInfo::with_cost("gpt-4o", 128000, 0.0000025, 0.00001);
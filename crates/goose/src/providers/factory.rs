@@ -152,6 +152,23 @@ pub async fn create_with_named_model(
     create(provider_name, config).await
 }
 
+/// Creates the provider and model from the global config and pings it, so readiness
+/// checks can tell whether completions would work without paying for one.
+pub async fn ping_configured_provider() -> Result<()> {
+    let config = crate::config::Config::global();
+    let provider_name = config
+        .get_goose_provider()
+        .map_err(|_| anyhow::anyhow!("No provider configured. Run 'goose configure' first"))?;
+    let model_name = config
+        .get_goose_model()
+        .map_err(|_| anyhow::anyhow!("No model configured. Run 'goose configure' first"))?;
+    let provider = create_with_named_model(&provider_name, &model_name).await?;
+    provider
+        .ping()
+        .await
+        .map_err(|e| anyhow::anyhow!("Provider '{}' is not reachable: {}", provider_name, e))
+}
+
 async fn create_lead_worker_from_env(
     default_provider_name: &str,
     default_model: &ModelConfig,
@@ -397,7 +397,7 @@ pub fn create_request(
 
     // https://platform.claude.com/docs/en/about-claude/models/overview
     // 64k output tokens works for most claude models, but not old opus:
-    let max_tokens = model_config.max_tokens.unwrap_or_else(|| {
+    let max_tokens = model_config.max_output_tokens().unwrap_or_else(|| {
         let name = &model_config.model_name;
         if name.contains("claude-3-haiku") {
             4096
@@ -594,7 +594,7 @@ pub fn create_request(
 
         // For Claude models with thinking enabled, we need to add max_tokens + budget_tokens
         // Default to 8192 (Claude max output) + budget if not specified
-        let max_completion_tokens = model_config.max_tokens.unwrap_or(8192);
+        let max_completion_tokens = model_config.max_output_tokens().unwrap_or(8192);
         payload.as_object_mut().unwrap().insert(
             "max_tokens".to_string(),
             json!(max_completion_tokens + budget_tokens),
@@ -624,7 +624,7 @@ pub fn create_request(
         }
 
         // open ai reasoning models use max_completion_tokens instead of max_tokens
-        if let Some(tokens) = model_config.max_tokens {
+        if let Some(tokens) = model_config.max_output_tokens() {
             let key = if is_openai_reasoning_model {
                 "max_completion_tokens"
             } else {
@@ -1023,6 +1023,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1055,6 +1056,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         assert_eq!(request["reasoning_effort"], "high");
@@ -1372,6 +1374,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
 
         let messages = vec![
@@ -1424,6 +1427,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
 
         let messages = vec![Message::user().with_text("Hello")];
@@ -663,10 +663,10 @@ pub fn create_request(
     };
 
     let generation_config =
-        if model_config.temperature.is_some() || model_config.max_tokens.is_some() {
+        if model_config.temperature.is_some() || model_config.max_output_tokens().is_some() {
             Some(GenerationConfig {
                 temperature: model_config.temperature.map(|t| t as f64),
-                max_output_tokens: model_config.max_tokens,
+                max_output_tokens: model_config.max_output_tokens(),
             })
         } else {
             None
@@ -700,7 +700,7 @@ pub fn create_request(
     }
 
     // o1 models use max_completion_tokens instead of max_tokens
-    if let Some(tokens) = model_config.max_tokens {
+    if let Some(tokens) = model_config.max_output_tokens() {
         let key = if is_ox_model {
             "max_completion_tokens"
         } else {
@@ -1296,6 +1296,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let request = create_request(
             &model_config,
@@ -1336,6 +1337,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let request = create_request(
             &model_config,
@@ -1377,6 +1379,7 @@ mod tests {
             toolshim_model: None,
             fast_model: None,
             request_params: None,
+            max_response_tokens: None,
         };
         let request = create_request(
             &model_config,
@@ -408,7 +408,7 @@ pub fn create_responses_request(
             .insert("temperature".to_string(), json!(temp));
     }
 
-    if let Some(tokens) = model_config.max_tokens {
+    if let Some(tokens) = model_config.max_output_tokens() {
         payload
             .as_object_mut()
             .unwrap()
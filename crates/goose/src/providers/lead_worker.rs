@@ -312,6 +312,13 @@ impl LeadWorkerProviderTrait for LeadWorkerProvider {
             self.fallback_turns,
         )
     }
+
+    fn fallback_active(&self) -> bool {
+        self.in_fallback_mode
+            .try_lock()
+            .map(|in_fallback| *in_fallback)
+            .unwrap_or(false)
+    }
 }
 
 #[async_trait]
@@ -340,6 +347,10 @@ impl Provider for LeadWorkerProvider {
         self.lead_provider.get_model_config()
     }
 
+    async fn actual_context_limit(&self) -> Option<usize> {
+        self.lead_provider.actual_context_limit().await
+    }
+
     async fn complete_with_model(
         &self,
         _model_config: &ModelConfig,
@@ -424,6 +435,12 @@ impl Provider for LeadWorkerProvider {
         final_result
     }
 
+    async fn ping(&self) -> Result<(), ProviderError> {
+        // Ping both directly so the check doesn't count as a lead/worker turn
+        self.lead_provider.ping().await?;
+        self.worker_provider.ping().await
+    }
+
     async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         // Combine models from both providers
         let lead_models = self.lead_provider.fetch_supported_models().await?;
@@ -25,6 +25,8 @@ pub struct LiteLLMProvider {
     model: ModelConfig,
     #[serde(skip)]
     name: String,
+    #[serde(skip)]
+    context_limit: tokio::sync::OnceCell<Option<usize>>,
 }
 
 impl LiteLLMProvider {
@@ -70,10 +72,11 @@ impl LiteLLMProvider {
             base_path,
             model,
             name: Self::metadata().name,
+            context_limit: tokio::sync::OnceCell::new(),
         })
     }
 
-    async fn fetch_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+    async fn fetch_model_info(&self) -> Result<Vec<Value>, ProviderError> {
         let response = self.api_client.response_get("model/info").await?;
 
         if !response.status().is_success() {
@@ -90,9 +93,14 @@ impl LiteLLMProvider {
         let models_data = response_json["data"].as_array().ok_or_else(|| {
             ProviderError::RequestFailed("Missing data field in models response".to_string())
         })?;
+        Ok(models_data.clone())
+    }
+
+    async fn fetch_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        let models_data = self.fetch_model_info().await?;
 
         let mut models = Vec::new();
-        for model_data in models_data {
+        for model_data in &models_data {
             if let Some(model_name) = model_data["model_name"].as_str() {
                 if model_name.contains("/*") {
                     continue;
@@ -215,6 +223,27 @@ impl Provider for LiteLLMProvider {
         self.model.model_name.to_lowercase().contains("claude")
     }
 
+    async fn actual_context_limit(&self) -> Option<usize> {
+        // Looked up once; the proxy's model configuration doesn't change during a session
+        *self
+            .context_limit
+            .get_or_init(|| async {
+                let models = match self.fetch_model_info().await {
+                    Ok(models) => models,
+                    Err(e) => {
+                        tracing::debug!("Failed to fetch LiteLLM context limit: {}", e);
+                        return None;
+                    }
+                };
+                models
+                    .iter()
+                    .find(|model| model["model_name"].as_str() == Some(&self.model.model_name))
+                    .and_then(|model| model["model_info"]["max_input_tokens"].as_u64())
+                    .map(|limit| limit as usize)
+            })
+            .await
+    }
+
     async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         match self.fetch_models().await {
             Ok(models) => {
@@ -39,6 +39,7 @@ pub mod venice;
 pub mod xai;
 
 pub use factory::{
-    create, create_with_default_model, create_with_named_model, providers, refresh_custom_providers,
+    create, create_with_default_model, create_with_named_model, ping_configured_provider,
+    providers, refresh_custom_providers,
 };
 pub use retry::{retry_operation, RetryConfig};
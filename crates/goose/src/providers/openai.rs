@@ -1,5 +1,7 @@
 use super::api_client::{ApiClient, AuthMethod};
-use super::base::{ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage};
+use super::base::{
+    ping_with_completion, ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
 use super::embedding::{EmbeddingCapable, EmbeddingRequest, EmbeddingResponse};
 use super::errors::ProviderError;
 use super::formats::openai::{create_request, get_usage, response_to_message};
@@ -318,6 +320,16 @@ impl Provider for OpenAiProvider {
         }
     }
 
+    async fn ping(&self) -> Result<(), ProviderError> {
+        // OpenAI-compatible servers don't all implement the models endpoint, so only an
+        // auth failure there is conclusive
+        match self.fetch_supported_models().await {
+            Ok(_) => Ok(()),
+            Err(e @ ProviderError::Authentication(_)) => Err(e),
+            Err(_) => ping_with_completion(self).await,
+        }
+    }
+
     async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         let models_path = self.base_path.replace("v1/chat/completions", "v1/models");
         let response = self.api_client.response_get(&models_path).await?;
@@ -1,8 +1,10 @@
 use crate::recipe::read_recipe_file_content::read_parameter_file_content;
-use crate::recipe::template_recipe::render_recipe_content_with_params;
+use crate::recipe::template_recipe::{
+    expand_default_env_references, expand_env_references, render_recipe_content_with_params,
+};
 use crate::recipe::validate_recipe::validate_recipe_template_from_content;
 use crate::recipe::{
-    Recipe, RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement,
+    Recipe, RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement, SubRecipe,
     BUILT_IN_RECIPE_DIR_PARAM,
 };
 use anyhow::Result;
@@ -11,8 +13,14 @@ use std::path::Path;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RecipeError {
-    #[error("Missing required parameters: {parameters:?}")]
-    MissingParams { parameters: Vec<String> },
+    #[error(
+        "Missing required parameters{}: {parameters:?}",
+        .sub_recipe.as_ref().map(|name| format!(" for sub-recipe '{}'", name)).unwrap_or_default()
+    )]
+    MissingParams {
+        parameters: Vec<String>,
+        sub_recipe: Option<String>,
+    },
     #[error("Template rendering failed: {source}")]
     TemplateRendering { source: anyhow::Error },
     #[error("Recipe parsing failed: {source}")]
@@ -24,9 +32,10 @@ fn render_recipe_template<F>(
     recipe_dir: &Path,
     params: Vec<(String, String)>,
     user_prompt_fn: Option<F>,
-) -> Result<(String, Vec<String>)>
+    expand_value: fn(&str) -> String,
+) -> Result<(String, HashMap<String, String>, Vec<String>)>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
     let recipe_dir_str = recipe_dir.display().to_string();
 
@@ -34,8 +43,13 @@ where
         validate_recipe_template_from_content(&recipe_content, Some(recipe_dir_str.clone()))?
             .parameters;
 
-    let (params_for_template, missing_params) =
-        apply_values_to_parameters(&params, recipe_parameters, &recipe_dir_str, user_prompt_fn)?;
+    let (params_for_template, missing_params) = apply_values(
+        &params,
+        recipe_parameters,
+        &recipe_dir_str,
+        user_prompt_fn,
+        expand_value,
+    )?;
 
     let rendered_content = if missing_params.is_empty() {
         render_recipe_content_with_params(&recipe_content, &params_for_template)?
@@ -43,9 +57,11 @@ where
         String::new()
     };
 
-    Ok((rendered_content, missing_params))
+    Ok((rendered_content, params_for_template, missing_params))
 }
 
+/// Builds a recipe from parameter values the user typed, on the command line or in the UI.
+/// Their `${env:VAR}` references may read any variable.
 pub fn build_recipe_from_template<F>(
     recipe_content: String,
     recipe_dir: &Path,
@@ -53,15 +69,60 @@ pub fn build_recipe_from_template<F>(
     user_prompt_fn: Option<F>,
 ) -> Result<Recipe, RecipeError>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
+{
+    build_recipe(
+        recipe_content,
+        recipe_dir,
+        params,
+        user_prompt_fn,
+        expand_env_references,
+    )
+}
+
+/// Builds a sub-recipe from values set by the parent recipe or chosen by the model. Neither
+/// comes from the user, so their `${env:VAR}` references are limited like defaults are.
+pub fn build_sub_recipe_from_template<F>(
+    recipe_content: String,
+    recipe_dir: &Path,
+    params: Vec<(String, String)>,
+    user_prompt_fn: Option<F>,
+) -> Result<Recipe, RecipeError>
+where
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
-    let (rendered_content, missing_params) =
-        render_recipe_template(recipe_content, recipe_dir, params.clone(), user_prompt_fn)
-            .map_err(|source| RecipeError::TemplateRendering { source })?;
+    build_recipe(
+        recipe_content,
+        recipe_dir,
+        params,
+        user_prompt_fn,
+        expand_default_env_references,
+    )
+}
+
+fn build_recipe<F>(
+    recipe_content: String,
+    recipe_dir: &Path,
+    params: Vec<(String, String)>,
+    user_prompt_fn: Option<F>,
+    expand_value: fn(&str) -> String,
+) -> Result<Recipe, RecipeError>
+where
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
+{
+    let (rendered_content, params_for_template, missing_params) = render_recipe_template(
+        recipe_content,
+        recipe_dir,
+        params.clone(),
+        user_prompt_fn,
+        expand_value,
+    )
+    .map_err(|source| RecipeError::TemplateRendering { source })?;
 
     if !missing_params.is_empty() {
         return Err(RecipeError::MissingParams {
             parameters: missing_params,
+            sub_recipe: None,
         });
     }
 
@@ -69,8 +130,19 @@ where
         .map_err(|source| RecipeError::RecipeParsing { source })?;
 
     if let Some(ref mut sub_recipes) = recipe.sub_recipes {
+        // File parameters hold the file contents once rendered; sub-recipes read the file
+        // themselves, so they inherit the path the user gave instead.
+        let mut inherited_values = params_for_template;
+        for param in recipe.parameters.iter().flatten() {
+            if matches!(param.input_type, RecipeParameterInputType::File) {
+                if let Some((_, path)) = params.iter().find(|(key, _)| key == &param.key) {
+                    inherited_values.insert(param.key.clone(), expand_value(path));
+                }
+            }
+        }
         for sub_recipe in sub_recipes {
             sub_recipe.path = resolve_sub_recipe_path(&sub_recipe.path, recipe_dir)?;
+            inherit_parent_values(sub_recipe, &inherited_values);
         }
     }
 
@@ -84,7 +156,7 @@ pub fn build_recipe_from_template_with_positional_params<F>(
     user_prompt_fn: Option<F>,
 ) -> Result<Recipe, RecipeError>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
     let recipe_dir_str = recipe_dir.display().to_string();
 
@@ -103,6 +175,7 @@ where
                 .collect();
             return Err(RecipeError::MissingParams {
                 parameters: required_keys,
+                sub_recipe: None,
             });
         }
         recipe_params
@@ -124,9 +197,31 @@ pub fn apply_values_to_parameters<F>(
     user_prompt_fn: Option<F>,
 ) -> Result<(HashMap<String, String>, Vec<String>)>
 where
-    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
+{
+    apply_values(
+        user_params,
+        recipe_parameters,
+        recipe_dir,
+        user_prompt_fn,
+        expand_env_references,
+    )
+}
+
+fn apply_values<F>(
+    supplied_params: &[(String, String)],
+    recipe_parameters: Option<Vec<RecipeParameter>>,
+    recipe_dir: &str,
+    user_prompt_fn: Option<F>,
+    expand_value: fn(&str) -> String,
+) -> Result<(HashMap<String, String>, Vec<String>)>
+where
+    F: Fn(&RecipeParameter) -> Result<String, anyhow::Error>,
 {
-    let mut param_map: HashMap<String, String> = user_params.iter().cloned().collect();
+    let mut param_map: HashMap<String, String> = supplied_params
+        .iter()
+        .map(|(key, value)| (key.clone(), expand_value(value)))
+        .collect();
     param_map.insert(
         BUILT_IN_RECIPE_DIR_PARAM.to_string(),
         recipe_dir.to_string(),
@@ -135,10 +230,14 @@ where
     for param in recipe_parameters.unwrap_or_default() {
         if !param_map.contains_key(&param.key) {
             match (&param.default, &param.requirement) {
-                (Some(default), _) => param_map.insert(param.key.clone(), default.clone()),
+                (Some(default), _) => {
+                    let value = expand_default_env_references(default);
+                    validate_select_value(&param, &value)?;
+                    param_map.insert(param.key.clone(), value)
+                }
                 (None, RecipeParameterRequirement::UserPrompt) if user_prompt_fn.is_some() => {
-                    let input_value =
-                        user_prompt_fn.as_ref().unwrap()(&param.key, &param.description)?;
+                    let input_value = user_prompt_fn.as_ref().unwrap()(&param)?;
+                    validate_select_value(&param, &input_value)?;
                     param_map.insert(param.key.clone(), input_value)
                 }
                 _ => {
@@ -150,11 +249,45 @@ where
             let file_path = param_map.get(&param.key).unwrap();
             let file_content = read_parameter_file_content(file_path)?;
             param_map.insert(param.key.clone(), file_content);
+        } else {
+            validate_select_value(&param, &param_map[&param.key])?;
         }
     }
     Ok((param_map, missing_params))
 }
 
+/// Checks that a value given for a `select` parameter is one of its options.
+fn validate_select_value(param: &RecipeParameter, value: &str) -> Result<()> {
+    if !matches!(param.input_type, RecipeParameterInputType::Select) {
+        return Ok(());
+    }
+    let options = param.options.as_deref().unwrap_or_default();
+    if options.iter().any(|option| option == value) {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Invalid value '{}' for parameter '{}': expected one of {}",
+            value,
+            param.key,
+            options.join(", ")
+        ))
+    }
+}
+
+/// Forwards the parent's values for the parameters a sub-recipe inherits. Values set on the
+/// sub-recipe itself take precedence.
+fn inherit_parent_values(sub_recipe: &mut SubRecipe, parent_values: &HashMap<String, String>) {
+    if sub_recipe.inherit.is_empty() {
+        return;
+    }
+    let values = sub_recipe.values.get_or_insert_with(HashMap::new);
+    for key in &sub_recipe.inherit {
+        if let Some(value) = parent_values.get(key) {
+            values.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
 fn resolve_sub_recipe_path(
     sub_recipe_path: &str,
     parent_recipe_dir: &Path,
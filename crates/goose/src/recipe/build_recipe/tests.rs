@@ -1,13 +1,14 @@
 use crate::recipe::build_recipe::{
-    build_recipe_from_template, resolve_sub_recipe_path, RecipeError,
+    build_recipe_from_template, build_sub_recipe_from_template, resolve_sub_recipe_path,
+    RecipeError,
 };
 use crate::recipe::read_recipe_file_content::RecipeFile;
-use crate::recipe::{RecipeParameterInputType, RecipeParameterRequirement};
+use crate::recipe::{RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
 #[allow(clippy::type_complexity)]
-const NO_USER_PROMPT: Option<fn(&str, &str) -> Result<String, anyhow::Error>> = None;
+const NO_USER_PROMPT: Option<fn(&RecipeParameter) -> Result<String, anyhow::Error>> = None;
 
 fn setup_recipe_file(instructions_and_parameters: &str) -> (TempDir, String, PathBuf) {
     let recipe_content = format!(
@@ -638,3 +639,242 @@ parameters:
         }
     }
 }
+
+mod select_parameter_tests {
+    use super::*;
+
+    const SELECT_RECIPE: &str = r#"instructions: "Deploy to {{ mode }}"
+parameters:
+  - key: mode
+    input_type: select
+    requirement: required
+    description: Target environment
+    options:
+      - dev
+      - staging
+      - prod"#;
+
+    #[test]
+    fn test_build_recipe_select_parameter_valid_option() {
+        let (_temp_dir, recipe_file) = setup_yaml_recipe_file(SELECT_RECIPE);
+
+        let params = vec![("mode".to_string(), "staging".to_string())];
+        let recipe = build_recipe_from_template(
+            recipe_file.content,
+            &recipe_file.parent_dir,
+            params,
+            NO_USER_PROMPT,
+        )
+        .unwrap();
+
+        assert_eq!(recipe.instructions.unwrap(), "Deploy to staging");
+    }
+
+    #[test]
+    fn test_build_recipe_select_parameter_invalid_option() {
+        let (_temp_dir, recipe_file) = setup_yaml_recipe_file(SELECT_RECIPE);
+
+        let params = vec![("mode".to_string(), "qa".to_string())];
+        let result = build_recipe_from_template(
+            recipe_file.content,
+            &recipe_file.parent_dir,
+            params,
+            NO_USER_PROMPT,
+        );
+
+        if let Err(RecipeError::TemplateRendering { source }) = result {
+            let message = source.to_string();
+            assert!(message.contains("'mode'"));
+            assert!(message.contains("dev, staging, prod"));
+        } else {
+            panic!("Expected TemplateRendering error for invalid select value");
+        }
+    }
+
+    #[test]
+    fn test_build_recipe_select_parameter_default_must_be_option() {
+        let instructions_and_parameters =
+            format!("{}\n    default: qa", SELECT_RECIPE).replace("required", "optional");
+        let (_temp_dir, recipe_file) = setup_yaml_recipe_file(&instructions_and_parameters);
+
+        let result = build_recipe_from_template(
+            recipe_file.content,
+            &recipe_file.parent_dir,
+            vec![],
+            NO_USER_PROMPT,
+        );
+
+        if let Err(RecipeError::TemplateRendering { source }) = result {
+            assert!(source
+                .to_string()
+                .contains("expected one of dev, staging, prod"));
+        } else {
+            panic!("Expected TemplateRendering error for select default outside options");
+        }
+    }
+
+    #[test]
+    fn test_build_recipe_select_parameter_expanded_default_must_be_option() {
+        let _guard = env_lock::lock_env([("USER", Some("tester"))]);
+        let instructions_and_parameters = format!("{}\n    default: ${{env:USER}}", SELECT_RECIPE)
+            .replace("required", "optional");
+        let (_temp_dir, recipe_file) = setup_yaml_recipe_file(&instructions_and_parameters);
+
+        let result = build_recipe_from_template(
+            recipe_file.content,
+            &recipe_file.parent_dir,
+            vec![],
+            NO_USER_PROMPT,
+        );
+
+        if let Err(RecipeError::TemplateRendering { source }) = result {
+            assert!(source.to_string().contains("Invalid value 'tester'"));
+        } else {
+            panic!("Expected TemplateRendering error for expanded select default outside options");
+        }
+    }
+}
+
+mod sub_recipe_parameter_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_build_recipe_sub_recipe_inherits_parent_values() {
+        let parent_content = r#"version: "1.0.0"
+title: "Parent Recipe"
+description: "Forwards parameters"
+instructions: "Deploy to {{ env }}"
+parameters:
+  - key: env
+    input_type: string
+    requirement: required
+    description: Target environment
+  - key: region
+    input_type: string
+    requirement: optional
+    description: Only used by the child
+    default: us-east-1
+sub_recipes:
+  - name: child
+    path: ./child.yaml
+    inherit:
+      - env
+      - region
+    values:
+      env: staging
+"#;
+        let (_temp_dir, parent_file, _child_file) =
+            setup_yaml_recipe_files(parent_content, "title: unused");
+
+        let params = vec![("env".to_string(), "prod".to_string())];
+        let recipe = build_recipe_from_template(
+            parent_file.content,
+            &parent_file.parent_dir,
+            params,
+            NO_USER_PROMPT,
+        )
+        .unwrap();
+
+        let sub_recipe = &recipe.sub_recipes.unwrap()[0];
+        let expected: HashMap<String, String> = [
+            ("env".to_string(), "staging".to_string()),
+            ("region".to_string(), "us-east-1".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(sub_recipe.values.as_ref(), Some(&expected));
+    }
+
+    #[test]
+    fn test_build_recipe_sub_recipe_inherits_file_parameter_path() {
+        let parent_content = r#"version: "1.0.0"
+title: "Parent Recipe"
+description: "Forwards a file parameter"
+instructions: "Review {{ notes }}"
+parameters:
+  - key: notes
+    input_type: file
+    requirement: required
+    description: Notes file
+sub_recipes:
+  - name: child
+    path: ./child.yaml
+    inherit:
+      - notes
+"#;
+        let (temp_dir, parent_file, _child_file) =
+            setup_yaml_recipe_files(parent_content, "title: unused");
+        let notes_path = setup_test_file(&temp_dir, "notes.txt", "contents of the notes");
+        let notes_path = notes_path.to_string_lossy().to_string();
+
+        let params = vec![("notes".to_string(), notes_path.clone())];
+        let recipe = build_recipe_from_template(
+            parent_file.content,
+            &parent_file.parent_dir,
+            params,
+            NO_USER_PROMPT,
+        )
+        .unwrap();
+
+        assert!(recipe
+            .instructions
+            .as_ref()
+            .unwrap()
+            .contains("contents of the notes"));
+        let sub_recipe = &recipe.sub_recipes.unwrap()[0];
+        assert_eq!(
+            sub_recipe.values.as_ref().unwrap().get("notes"),
+            Some(&notes_path)
+        );
+    }
+
+    #[test]
+    fn test_sub_recipe_values_only_read_allowlisted_env() {
+        let _guard = env_lock::lock_env([
+            ("GOOSE_TEST_API_KEY", Some("sk-secret")),
+            ("GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST", None),
+        ]);
+        let instructions_and_parameters = r#"instructions: "Use {{ key }}"
+parameters:
+  - key: key
+    input_type: string
+    requirement: required
+    description: A value"#;
+        let (_temp_dir, recipe_file) = setup_yaml_recipe_file(instructions_and_parameters);
+        let params = vec![("key".to_string(), "${env:GOOSE_TEST_API_KEY}".to_string())];
+
+        let recipe = build_sub_recipe_from_template(
+            recipe_file.content.clone(),
+            &recipe_file.parent_dir,
+            params.clone(),
+            NO_USER_PROMPT,
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Use ${env:GOOSE_TEST_API_KEY}")
+        );
+
+        let recipe = build_recipe_from_template(
+            recipe_file.content,
+            &recipe_file.parent_dir,
+            params,
+            NO_USER_PROMPT,
+        )
+        .unwrap();
+        assert_eq!(recipe.instructions.as_deref(), Some("Use sk-secret"));
+    }
+
+    #[test]
+    fn test_missing_params_error_names_sub_recipe() {
+        let err = RecipeError::MissingParams {
+            parameters: vec!["env".to_string()],
+            sub_recipe: Some("deploy".to_string()),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Missing required parameters for sub-recipe 'deploy': [\"env\"]"
+        );
+    }
+}
@@ -3,18 +3,23 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
+use std::time::{Duration, SystemTime};
 
 use crate::agents::extension::ExtensionConfig;
 use crate::agents::types::RetryConfig;
+use crate::recipe::parse_error::RecipeParseError;
 use crate::recipe::read_recipe_file_content::read_recipe_file;
 use crate::recipe::yaml_format_utils::reformat_fields_with_multiline_values;
 use crate::utils::contains_unicode_tags;
+use schemars::{schema_for, JsonSchema};
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub mod build_recipe;
 pub mod local_recipes;
+pub mod parameter_extraction;
+pub mod parse_error;
 pub mod read_recipe_file_content;
 mod recipe_extension_adapter;
 pub mod template_recipe;
@@ -28,7 +33,7 @@ fn default_version() -> String {
     "1.0.0".to_string()
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct Recipe {
     // Required fields
     #[serde(default = "default_version")]
@@ -51,8 +56,12 @@ pub struct Recipe {
         default,
         deserialize_with = "recipe_extension_adapter::deserialize_recipe_extensions"
     )]
+    #[schemars(with = "Option<Vec<Value>>")]
     pub extensions: Option<Vec<ExtensionConfig>>, // a list of extensions to enable
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_extensions: Option<Vec<String>>, // extensions the session can't start without
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<Settings>, // settings for the recipe
 
@@ -73,9 +82,15 @@ pub struct Recipe {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>, // deadline for the whole run, including sub-recipes
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Vec<String>>, // paths the run is expected to produce
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct Author {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contact: Option<String>, // creator/contact information of the recipe
@@ -84,7 +99,7 @@ pub struct Author {
     pub metadata: Option<String>, // any additional metadata for the author
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct Settings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub goose_provider: Option<String>,
@@ -96,22 +111,37 @@ pub struct Settings {
     pub temperature: Option<f32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub json_schema: Option<serde_json::Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct SubRecipe {
     pub name: String,
     pub path: String,
     #[serde(default, deserialize_with = "deserialize_value_map_as_string")]
+    #[schemars(with = "Option<HashMap<String, Value>>")]
     pub values: Option<HashMap<String, String>>,
+    /// Parameters whose values are forwarded from the parent recipe unless set in `values`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inherit: Vec<String>,
     #[serde(default)]
     pub sequential_when_repeated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+}
+
+impl SubRecipe {
+    /// How long a single run of this sub-recipe may take before it is cancelled.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_seconds
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
 }
 
 fn deserialize_value_map_as_string<'de, D>(
@@ -139,7 +169,7 @@ where
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RecipeParameterRequirement {
     Required,
@@ -157,7 +187,7 @@ impl fmt::Display for RecipeParameterRequirement {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RecipeParameterInputType {
     String,
@@ -180,7 +210,7 @@ impl fmt::Display for RecipeParameterInputType {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
 pub struct RecipeParameter {
     pub key: String,
     pub input_type: RecipeParameterInputType,
@@ -203,6 +233,7 @@ pub struct RecipeBuilder {
     // Optional fields
     prompt: Option<String>,
     extensions: Option<Vec<ExtensionConfig>>,
+    required_extensions: Option<Vec<String>>,
     settings: Option<Settings>,
     activities: Option<Vec<String>>,
     author: Option<Author>,
@@ -210,6 +241,8 @@ pub struct RecipeBuilder {
     response: Option<Response>,
     sub_recipes: Option<Vec<SubRecipe>>,
     retry: Option<RetryConfig>,
+    timeout_seconds: Option<u64>,
+    artifacts: Option<Vec<String>>,
 }
 
 impl Recipe {
@@ -240,6 +273,38 @@ impl Recipe {
         Ok(formatted_recipe_yaml)
     }
 
+    /// Deadline for the whole run, after which it is cancelled.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_seconds
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Declared artifacts that don't exist once the run is over. Relative paths are
+    /// resolved against `working_dir`. With `since`, files last modified before it were left
+    /// behind by an earlier run and count as missing too.
+    pub fn missing_artifacts(&self, working_dir: &Path, since: Option<SystemTime>) -> Vec<String> {
+        self.artifacts
+            .iter()
+            .flatten()
+            .filter(|artifact| {
+                let Ok(metadata) = std::fs::metadata(working_dir.join(artifact)) else {
+                    return true;
+                };
+                match (since, metadata.modified()) {
+                    (Some(since), Ok(modified)) => modified < since,
+                    _ => false,
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// JSON Schema describing recipe files, for validating them in editors.
+    pub fn json_schema() -> Value {
+        serde_json::to_value(schema_for!(Recipe)).expect("recipe schema is valid JSON")
+    }
+
     pub fn builder() -> RecipeBuilder {
         RecipeBuilder {
             version: default_version(),
@@ -248,6 +313,7 @@ impl Recipe {
             instructions: None,
             prompt: None,
             extensions: None,
+            required_extensions: None,
             settings: None,
             activities: None,
             author: None,
@@ -255,6 +321,8 @@ impl Recipe {
             response: None,
             sub_recipes: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         }
     }
 
@@ -263,20 +331,23 @@ impl Recipe {
         Self::from_content(&file.content)
     }
 
+    /// Parses a recipe from YAML or JSON. Deserialization failures are returned as a
+    /// `RecipeParseError` carrying the line, column and surrounding content.
     pub fn from_content(content: &str) -> Result<Self> {
-        let recipe: Recipe = match serde_yaml::from_str::<serde_yaml::Value>(content) {
-            Ok(yaml_value) => {
-                if let Some(nested_recipe) = yaml_value.get("recipe") {
-                    serde_yaml::from_value(nested_recipe.clone())
-                        .map_err(|e| anyhow::anyhow!("Failed to parse nested recipe: {}", e))?
-                } else {
-                    serde_yaml::from_str(content)
-                        .map_err(|e| anyhow::anyhow!("Failed to parse recipe: {}", e))?
-                }
-            }
-            Err(_) => serde_yaml::from_str(content)
-                .map_err(|e| anyhow::anyhow!("Failed to parse recipe: {}", e))?,
+        #[derive(Deserialize)]
+        struct NestedRecipe {
+            recipe: Recipe,
+        }
+
+        let is_nested = serde_yaml::from_str::<serde_yaml::Value>(content)
+            .is_ok_and(|yaml_value| yaml_value.get("recipe").is_some());
+        // Parse from the text rather than a `Value` so errors keep their position
+        let parsed = if is_nested {
+            serde_yaml::from_str::<NestedRecipe>(content).map(|nested| nested.recipe)
+        } else {
+            serde_yaml::from_str::<Recipe>(content)
         };
+        let recipe = parsed.map_err(|e| RecipeParseError::from_yaml(&e, content))?;
 
         if let Some(ref retry_config) = recipe.retry {
             if let Err(validation_error) = retry_config.validate() {
@@ -322,6 +393,11 @@ impl RecipeBuilder {
         self
     }
 
+    pub fn required_extensions(mut self, required_extensions: Vec<String>) -> Self {
+        self.required_extensions = Some(required_extensions);
+        self
+    }
+
     pub fn settings(mut self, settings: Settings) -> Self {
         self.settings = Some(settings);
         self
@@ -352,6 +428,16 @@ impl RecipeBuilder {
         self
     }
 
+    pub fn timeout_seconds(mut self, timeout_seconds: u64) -> Self {
+        self.timeout_seconds = Some(timeout_seconds);
+        self
+    }
+
+    pub fn artifacts(mut self, artifacts: Vec<String>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
     pub fn retry(mut self, retry: RetryConfig) -> Self {
         self.retry = Some(retry);
         self
@@ -372,6 +458,7 @@ impl RecipeBuilder {
             instructions: self.instructions,
             prompt: self.prompt,
             extensions: self.extensions,
+            required_extensions: self.required_extensions,
             settings: self.settings,
             activities: self.activities,
             author: self.author,
@@ -379,6 +466,8 @@ impl RecipeBuilder {
             response: self.response,
             sub_recipes: self.sub_recipes,
             retry: self.retry,
+            timeout_seconds: self.timeout_seconds,
+            artifacts: self.artifacts,
         })
     }
 }
@@ -577,6 +666,21 @@ sub_recipes:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_from_content_reports_error_position() {
+        let content =
+            "recipe:\n  title: Test\n  description: Test\n  prompt: hi\n  activities: 42\n";
+
+        let err = Recipe::from_content(content).unwrap_err();
+        let parse_error = err.downcast_ref::<RecipeParseError>().unwrap();
+        assert_eq!(parse_error.line, Some(5));
+        assert!(parse_error
+            .snippet
+            .as_deref()
+            .unwrap()
+            .contains("5 |   activities: 42"));
+    }
+
     #[test]
     fn test_from_content_missing_required_fields() {
         let content = r#"{
@@ -588,6 +692,63 @@ sub_recipes:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_from_content_with_timeouts() {
+        let content = r#"
+version: 1.0.0
+title: Nightly
+description: Nightly maintenance
+prompt: Run the checks
+timeout_seconds: 1800
+sub_recipes:
+  - name: lint
+    path: ./lint.yaml
+    timeout_seconds: 300
+  - name: report
+    path: ./report.yaml
+    timeout_seconds: 0
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        assert_eq!(recipe.timeout(), Some(Duration::from_secs(1800)));
+        let sub_recipes = recipe.sub_recipes.unwrap();
+        assert_eq!(sub_recipes[0].timeout(), Some(Duration::from_secs(300)));
+        assert_eq!(sub_recipes[1].timeout(), None);
+    }
+
+    #[test]
+    fn test_missing_artifacts() {
+        let content = r#"
+version: 1.0.0
+title: Report
+description: Writes a report
+prompt: Write the report
+artifacts:
+  - report.md
+  - out/summary.json
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            recipe.missing_artifacts(dir.path(), None),
+            vec!["report.md".to_string(), "out/summary.json".to_string()]
+        );
+
+        std::fs::write(dir.path().join("report.md"), "done").unwrap();
+        assert_eq!(
+            recipe.missing_artifacts(dir.path(), Some(SystemTime::UNIX_EPOCH)),
+            vec!["out/summary.json".to_string()]
+        );
+
+        // A file older than the run was left over from a previous one
+        let run_started = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(
+            recipe.missing_artifacts(dir.path(), Some(run_started)),
+            vec!["report.md".to_string(), "out/summary.json".to_string()]
+        );
+    }
+
     #[test]
     fn test_from_content_with_author() {
         let content = r#"{
@@ -710,6 +871,7 @@ isGlobal: true"#;
             instructions: Some("clean instructions".to_string()),
             prompt: Some("clean prompt".to_string()),
             extensions: None,
+            required_extensions: None,
             settings: None,
             activities: Some(vec!["clean activity 1".to_string()]),
             author: None,
@@ -717,6 +879,8 @@ isGlobal: true"#;
             response: None,
             sub_recipes: None,
             retry: None,
+            timeout_seconds: None,
+            artifacts: None,
         };
 
         assert!(!recipe.check_for_security_warnings());
@@ -772,4 +936,30 @@ isGlobal: true"#;
             panic!("Expected Stdio extension");
         }
     }
+
+    #[test]
+    fn test_json_schema_covers_parameters() {
+        let schema = Recipe::json_schema();
+
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(required.contains(&"title"));
+        assert!(required.contains(&"description"));
+
+        let defs = &schema["$defs"];
+        for name in [
+            "RecipeParameter",
+            "RecipeParameterInputType",
+            "RecipeParameterRequirement",
+        ] {
+            assert!(defs.get(name).is_some(), "missing definition for {}", name);
+        }
+        assert!(defs["RecipeParameter"]["properties"]
+            .get("requirement")
+            .is_some());
+    }
 }
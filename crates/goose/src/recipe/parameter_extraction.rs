@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::conversation::Conversation;
+use crate::recipe::{
+    Recipe, RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement,
+};
+
+/// A backtick-quoted name has to show up at least this often in the conversation before it is
+/// treated as something the user would want to vary between runs.
+const MIN_NAME_OCCURRENCES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CandidateKind {
+    Url,
+    Path,
+    Name,
+}
+
+impl CandidateKind {
+    fn key_prefix(&self) -> &'static str {
+        match self {
+            CandidateKind::Url => "url",
+            CandidateKind::Path => "path",
+            CandidateKind::Name => "name",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            CandidateKind::Url => "URL used in the original session",
+            CandidateKind::Path => "Path used in the original session",
+            CandidateKind::Name => "Name used in the original session",
+        }
+    }
+}
+
+fn find_candidates(text: &str) -> Vec<(String, CandidateKind)> {
+    let url_re = Regex::new(r#"https?://[^\s"'<>()\[\]`]+"#).unwrap();
+    let path_re = Regex::new(r#"(?:^|[\s"'(`])((?:~|\.{1,2})?(?:/[\w.\-]+)+/?)"#).unwrap();
+    let name_re = Regex::new(r"`([A-Za-z][\w.\-]{2,})`").unwrap();
+
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+    let mut push = |value: &str, kind: CandidateKind| {
+        let value = value.trim_end_matches(['.', ',', ';', ':']);
+        if !value.is_empty() && seen.insert(value.to_string()) {
+            candidates.push((value.to_string(), kind));
+        }
+    };
+
+    for m in url_re.find_iter(text) {
+        push(m.as_str(), CandidateKind::Url);
+    }
+    for cap in path_re.captures_iter(text) {
+        push(&cap[1], CandidateKind::Path);
+    }
+    for cap in name_re.captures_iter(text) {
+        let name = &cap[1];
+        if text.matches(name).count() >= MIN_NAME_OCCURRENCES {
+            push(name, CandidateKind::Name);
+        }
+    }
+
+    candidates
+}
+
+/// Turns concrete values from the session (URLs, paths, recurring names) that ended up in the
+/// generated instructions, prompt or activities into recipe parameters. Each value is replaced
+/// by a `{{ key }}` placeholder and declared as an optional parameter defaulting to the value
+/// that was observed, so the recipe reproduces the original run unless overridden.
+pub fn extract_recipe_parameters(recipe: &mut Recipe, conversation: &Conversation) {
+    let conversation_text = conversation
+        .iter()
+        .map(|message| message.as_concat_text())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut candidates = find_candidates(&conversation_text);
+    // Replace longer values first so a path nested inside a URL stays part of the URL.
+    candidates.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    let mut used_keys: HashSet<String> = recipe
+        .parameters
+        .iter()
+        .flatten()
+        .map(|param| param.key.clone())
+        .collect();
+
+    let mut fields: Vec<&mut String> = recipe
+        .instructions
+        .iter_mut()
+        .chain(recipe.prompt.iter_mut())
+        .chain(recipe.activities.iter_mut().flatten())
+        .collect();
+
+    // Substitute sentinels first and placeholders last, so that a later candidate can never
+    // match inside a placeholder that was already inserted.
+    let mut replacements: Vec<(String, String)> = Vec::new();
+    let mut new_parameters = Vec::new();
+    for (value, kind) in candidates {
+        if !fields.iter().any(|field| field.contains(&value)) {
+            continue;
+        }
+
+        let prefix = kind.key_prefix();
+        let key = (1..)
+            .map(|n| {
+                if n == 1 {
+                    prefix.to_string()
+                } else {
+                    format!("{}_{}", prefix, n)
+                }
+            })
+            .find(|key| !used_keys.contains(key))
+            .expect("unbounded key search always finds a key");
+        used_keys.insert(key.clone());
+
+        let sentinel = format!("\u{0}{}\u{0}", replacements.len());
+        for field in fields.iter_mut() {
+            **field = field.replace(&value, &sentinel);
+        }
+        replacements.push((sentinel, format!("{{{{ {} }}}}", key)));
+
+        new_parameters.push(RecipeParameter {
+            key,
+            input_type: RecipeParameterInputType::String,
+            requirement: RecipeParameterRequirement::Optional,
+            description: kind.description().to_string(),
+            default: Some(value),
+            options: None,
+        });
+    }
+
+    for field in fields.iter_mut() {
+        for (sentinel, placeholder) in &replacements {
+            **field = field.replace(sentinel, placeholder);
+        }
+    }
+
+    if !new_parameters.is_empty() {
+        recipe
+            .parameters
+            .get_or_insert_with(Vec::new)
+            .extend(new_parameters);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::message::Message;
+
+    fn recipe_with(instructions: &str, activities: Vec<&str>) -> Recipe {
+        Recipe::builder()
+            .title("test")
+            .description("test")
+            .instructions(instructions)
+            .activities(activities.into_iter().map(String::from).collect())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extracts_urls_paths_and_recurring_names() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text(
+                "Fetch https://example.com/api/v1 and save it to /tmp/out/data.json for `acme-service`",
+            ),
+            Message::assistant().with_text("Done for `acme-service`."),
+        ]);
+        let mut recipe = recipe_with(
+            "Fetch https://example.com/api/v1, write /tmp/out/data.json and report on acme-service.",
+            vec!["Check acme-service"],
+        );
+
+        extract_recipe_parameters(&mut recipe, &conversation);
+
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Fetch {{ url }}, write {{ path }} and report on {{ name }}.")
+        );
+        assert_eq!(recipe.activities.unwrap(), vec!["Check {{ name }}"]);
+
+        let params = recipe.parameters.unwrap();
+        let defaults: Vec<(&str, &str)> = params
+            .iter()
+            .map(|p| (p.key.as_str(), p.default.as_deref().unwrap()))
+            .collect();
+        assert!(defaults.contains(&("url", "https://example.com/api/v1")));
+        assert!(defaults.contains(&("path", "/tmp/out/data.json")));
+        assert!(defaults.contains(&("name", "acme-service")));
+    }
+
+    #[test]
+    fn test_ignores_values_not_in_recipe_and_single_mentions() {
+        let conversation =
+            Conversation::new_unvalidated(vec![Message::user()
+                .with_text("Look at https://example.com/other and the `lonely` flag")]);
+        let mut recipe = recipe_with("Summarize the lonely project", vec![]);
+
+        extract_recipe_parameters(&mut recipe, &conversation);
+
+        assert!(recipe.parameters.is_none());
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Summarize the lonely project")
+        );
+    }
+
+    #[test]
+    fn test_avoids_existing_parameter_keys() {
+        let conversation =
+            Conversation::new_unvalidated(vec![Message::user()
+                .with_text("Open https://a.example.com and https://b.example.com/page")]);
+        let mut recipe = recipe_with(
+            "Compare https://a.example.com with https://b.example.com/page for {{ url }}",
+            vec![],
+        );
+        recipe.parameters = Some(vec![RecipeParameter {
+            key: "url".to_string(),
+            input_type: RecipeParameterInputType::String,
+            requirement: RecipeParameterRequirement::Required,
+            description: "existing".to_string(),
+            default: None,
+            options: None,
+        }]);
+
+        extract_recipe_parameters(&mut recipe, &conversation);
+
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Compare {{ url_3 }} with {{ url_2 }} for {{ url }}")
+        );
+        assert_eq!(recipe.parameters.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+const SNIPPET_CONTEXT_LINES: usize = 2;
+
+/// A recipe that failed to deserialize, with the position reported by `serde_yaml`
+/// and the surrounding lines so the offending spot is easy to find.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeParseError {
+    pub message: String,
+    /// 1-based line of the error, when serde_yaml knows it
+    pub line: Option<usize>,
+    /// 1-based column of the error, when serde_yaml knows it
+    pub column: Option<usize>,
+    pub snippet: Option<String>,
+}
+
+impl RecipeParseError {
+    pub fn from_yaml(error: &serde_yaml::Error, content: &str) -> Self {
+        let message = error.to_string();
+        match error.location() {
+            Some(location) => {
+                // serde_yaml appends the position to its message; we report it separately
+                let suffix = format!(" at line {} column {}", location.line(), location.column());
+                let message = message.replacen(&suffix, "", 1);
+                Self {
+                    message,
+                    line: Some(location.line()),
+                    column: Some(location.column()),
+                    snippet: snippet(content, location.line(), location.column()),
+                }
+            }
+            None => Self {
+                message,
+                line: None,
+                column: None,
+                snippet: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for RecipeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(
+                f,
+                "Failed to parse recipe at line {}, column {}: {}",
+                line, column, self.message
+            )?,
+            _ => write!(f, "Failed to parse recipe: {}", self.message)?,
+        }
+        if let Some(snippet) = &self.snippet {
+            write!(f, "\n{}", snippet)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RecipeParseError {}
+
+/// Renders the lines around `line` with numbers and a caret under `column`.
+fn snippet(content: &str, line: usize, column: usize) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    if line == 0 || line > lines.len() {
+        return None;
+    }
+
+    let first = line.saturating_sub(SNIPPET_CONTEXT_LINES).max(1);
+    let last = (line + SNIPPET_CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+
+    let mut out = Vec::new();
+    for number in first..=last {
+        out.push(format!("{:>width$} | {}", number, lines[number - 1]));
+        if number == line {
+            out.push(format!(
+                "{:>width$} | {}^",
+                "",
+                " ".repeat(column.saturating_sub(1))
+            ));
+        }
+    }
+    Some(out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_points_at_offending_line() {
+        let content = "version: 1.0.0\ntitle: Test\ndescription: [unclosed\nprompt: hi\n";
+        let yaml_error = serde_yaml::from_str::<serde_yaml::Value>(content).unwrap_err();
+        let error = RecipeParseError::from_yaml(&yaml_error, content);
+
+        let line = error.line.expect("serde_yaml reports a location");
+        assert!((3..=4).contains(&line));
+        assert!(!error.message.contains(" at line "));
+        let snippet = error.snippet.as_deref().unwrap();
+        assert!(snippet.contains("3 | description: [unclosed"));
+        assert!(snippet.contains('^'));
+        assert!(error
+            .to_string()
+            .starts_with(&format!("Failed to parse recipe at line {}", line)));
+    }
+
+    #[test]
+    fn test_snippet_clamps_to_content() {
+        let snippet = snippet("a: 1\nb: [", 1, 4).unwrap();
+        assert_eq!(snippet, "1 | a: 1\n  |    ^\n2 | b: [");
+        assert!(super::snippet("a: 1", 5, 1).is_none());
+    }
+}
@@ -23,6 +23,10 @@ enum RecipeExtensionConfigInternal {
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        #[serde(default)]
+        auto_restart: bool,
+        #[serde(default)]
+        max_restarts: Option<u32>,
     },
     #[serde(rename = "builtin")]
     Builtin {
@@ -119,7 +123,9 @@ impl From<RecipeExtensionConfigInternal> for ExtensionConfig {
                 env_keys,
                 timeout,
                 bundled,
-                available_tools
+                available_tools,
+                auto_restart,
+                max_restarts
             },
             Builtin {
                 display_name,
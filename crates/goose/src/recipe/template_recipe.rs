@@ -3,6 +3,7 @@ use std::{
     path::Path,
 };
 
+use crate::config::Config;
 use crate::recipe::{Recipe, BUILT_IN_RECIPE_DIR_PARAM};
 use anyhow::Result;
 use minijinja::{Environment, UndefinedBehavior};
@@ -12,6 +13,12 @@ const CURRENT_TEMPLATE_NAME: &str = "current_template";
 const OPEN_BRACE: &str = "{{";
 const CLOSE_BRACE: &str = "}}";
 
+/// Comma-separated environment variables that parameter defaults, sub-recipe values and
+/// model-chosen parameters may read, on top of `HOME`, `USER` and `PWD`
+pub const GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST: &str = "GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST";
+
+const DEFAULT_ENV_ALLOWLIST: [&str; 3] = ["HOME", "USER", "PWD"];
+
 fn preprocess_template_variables(content: &str) -> Result<String> {
     let all_template_variables = extract_template_variables(content);
     let complex_template_variables = filter_complex_variables(&all_template_variables);
@@ -108,6 +115,49 @@ pub fn render_recipe_content_with_params(
     Ok(rendered_content)
 }
 
+/// Replaces `${env:VAR}` references with the variable's value. References to unset
+/// variables are left as they are.
+pub fn expand_env_references(value: &str) -> String {
+    expand_allowed_env_references(value, |_| true)
+}
+
+/// Like [`expand_env_references`], for values the user didn't type: defaults and sub-recipe
+/// values written by the recipe's author, and parameters chosen by the model. Only
+/// allowlisted variables are expanded, so a shared recipe can't copy a secret such as
+/// `${env:OPENAI_API_KEY}` into its prompt.
+pub fn expand_default_env_references(value: &str) -> String {
+    let extra = Config::global()
+        .get_param::<String>(GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST)
+        .unwrap_or_default();
+    expand_allowed_env_references(value, |name| {
+        DEFAULT_ENV_ALLOWLIST.contains(&name) || extra.split(',').any(|n| n.trim() == name)
+    })
+}
+
+fn expand_allowed_env_references(value: &str, allowed: impl Fn(&str) -> bool) -> String {
+    let env_ref_re = Regex::new(r"\$\{env:([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    env_ref_re
+        .replace_all(value, |caps: &regex::Captures| {
+            if !allowed(&caps[1]) {
+                tracing::warn!(
+                    "Recipe parameter values may not read {}; add it to {} to allow it",
+                    &caps[1],
+                    GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST
+                );
+                return caps[0].to_string();
+            }
+            std::env::var(&caps[1]).unwrap_or_else(|_| {
+                tracing::warn!(
+                    "Environment variable {} is not set, leaving {} in the recipe parameter",
+                    &caps[1],
+                    &caps[0]
+                );
+                caps[0].to_string()
+            })
+        })
+        .into_owned()
+}
+
 fn add_template_in_env(
     content: &str,
     recipe_dir: Option<String>,
@@ -281,4 +331,62 @@ description: "A test recipe"
             assert!(result.contains(r#"name: "Simple Recipe""#));
         }
     }
+
+    mod expand_env_references_tests {
+        use crate::recipe::template_recipe::{
+            expand_default_env_references, expand_env_references,
+        };
+
+        #[test]
+        fn test_expand_env_references() {
+            let _guard = env_lock::lock_env([
+                ("GOOSE_TEST_NOTES_HOME", Some("/home/tester")),
+                ("GOOSE_TEST_UNSET_VAR", None),
+            ]);
+
+            assert_eq!(
+                expand_env_references("${env:GOOSE_TEST_NOTES_HOME}/notes"),
+                "/home/tester/notes"
+            );
+            assert_eq!(
+                expand_env_references("${env:GOOSE_TEST_UNSET_VAR}/notes"),
+                "${env:GOOSE_TEST_UNSET_VAR}/notes"
+            );
+            assert_eq!(
+                expand_env_references("{{ name }} in ${HOME}"),
+                "{{ name }} in ${HOME}"
+            );
+        }
+
+        #[test]
+        fn test_expand_default_env_references_only_reads_allowlist() {
+            let _guard = env_lock::lock_env([
+                ("HOME", Some("/home/tester")),
+                ("GOOSE_TEST_API_KEY", Some("sk-secret")),
+                ("GOOSE_TEST_REGION", Some("us-east-1")),
+                (
+                    "GOOSE_RECIPE_DEFAULT_ENV_ALLOWLIST",
+                    Some("GOOSE_TEST_REGION"),
+                ),
+            ]);
+
+            assert_eq!(
+                expand_default_env_references("${env:HOME}/notes"),
+                "/home/tester/notes"
+            );
+            assert_eq!(
+                expand_default_env_references("key=${env:GOOSE_TEST_API_KEY}"),
+                "key=${env:GOOSE_TEST_API_KEY}"
+            );
+            assert_eq!(
+                expand_default_env_references("${env:GOOSE_TEST_REGION}"),
+                "us-east-1"
+            );
+            // Values the user passes in may read any variable
+            assert_eq!(
+                expand_env_references("key=${env:GOOSE_TEST_API_KEY}"),
+                "key=sk-secret"
+            );
+        }
+    }
 }
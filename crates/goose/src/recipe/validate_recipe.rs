@@ -5,20 +5,66 @@ use crate::recipe::{
     BUILT_IN_RECIPE_DIR_PARAM,
 };
 use anyhow::Result;
+use chrono::NaiveDate;
 use std::collections::HashSet;
 
+/// Declared parameters the template never uses, and placeholders it uses without declaring.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParameterLint {
+    pub unused: Vec<String>,
+    pub undefined: Vec<String>,
+}
+
+impl ParameterLint {
+    pub fn is_clean(&self) -> bool {
+        self.unused.is_empty() && self.undefined.is_empty()
+    }
+}
+
 pub fn parse_and_validate_parameters(
     recipe_file_content: &str,
     recipe_dir_str: Option<String>,
 ) -> Result<Recipe> {
     let (recipe_template, template_variables) =
-        parse_recipe_content(recipe_file_content, recipe_dir_str)?;
+        parse_recipe_with_used_variables(recipe_file_content, recipe_dir_str)?;
     let recipe_parameters = &recipe_template.parameters;
     validate_optional_parameters(recipe_parameters)?;
+    validate_parameter_defaults(recipe_parameters)?;
     validate_parameters_in_template(recipe_parameters, &template_variables)?;
     Ok(recipe_template)
 }
 
+/// Cross-references a recipe's declared parameters with the placeholders in its template.
+pub fn lint_recipe_parameters(
+    recipe_file_content: &str,
+    recipe_dir_str: Option<String>,
+) -> Result<ParameterLint> {
+    let (recipe_template, template_variables) =
+        parse_recipe_with_used_variables(recipe_file_content, recipe_dir_str)?;
+    Ok(find_parameter_mismatches(
+        &recipe_template.parameters,
+        &template_variables,
+    ))
+}
+
+fn parse_recipe_with_used_variables(
+    recipe_file_content: &str,
+    recipe_dir_str: Option<String>,
+) -> Result<(Recipe, HashSet<String>)> {
+    let (recipe_template, mut template_variables) =
+        parse_recipe_content(recipe_file_content, recipe_dir_str)?;
+    // Parameters that are only forwarded to sub-recipes still count as used
+    template_variables.extend(
+        recipe_template
+            .sub_recipes
+            .iter()
+            .flatten()
+            .flat_map(|sub_recipe| sub_recipe.inherit.iter().cloned()),
+    );
+    template_variables.remove(BUILT_IN_RECIPE_DIR_PARAM);
+    Ok((recipe_template, template_variables))
+}
+
 fn validate_json_schema(schema: &serde_json::Value) -> Result<()> {
     match jsonschema::validator_for(schema) {
         Ok(_) => Ok(()),
@@ -74,13 +120,10 @@ fn validate_prompt_or_instructions(recipe: &Recipe) -> Result<()> {
     ))
 }
 
-fn validate_parameters_in_template(
+fn find_parameter_mismatches(
     recipe_parameters: &Option<Vec<RecipeParameter>>,
     template_variables: &HashSet<String>,
-) -> Result<()> {
-    let mut template_variables = template_variables.clone();
-    template_variables.remove(BUILT_IN_RECIPE_DIR_PARAM);
-
+) -> ParameterLint {
     let param_keys: HashSet<String> = recipe_parameters
         .as_ref()
         .unwrap_or(&vec![])
@@ -88,39 +131,40 @@ fn validate_parameters_in_template(
         .map(|p| p.key.clone())
         .collect();
 
-    let missing_keys = template_variables
+    let mut undefined: Vec<String> = template_variables
         .difference(&param_keys)
-        .collect::<Vec<_>>();
+        .cloned()
+        .collect();
+    undefined.sort();
 
-    let extra_keys = param_keys
-        .difference(&template_variables)
-        .collect::<Vec<_>>();
+    let mut unused: Vec<String> = param_keys.difference(template_variables).cloned().collect();
+    unused.sort();
+
+    ParameterLint { unused, undefined }
+}
 
-    if missing_keys.is_empty() && extra_keys.is_empty() {
+fn validate_parameters_in_template(
+    recipe_parameters: &Option<Vec<RecipeParameter>>,
+    template_variables: &HashSet<String>,
+) -> Result<()> {
+    let lint = find_parameter_mismatches(recipe_parameters, template_variables);
+    if lint.is_clean() {
         return Ok(());
     }
 
     let mut message = String::new();
 
-    if !missing_keys.is_empty() {
+    if !lint.undefined.is_empty() {
         message.push_str(&format!(
             "Missing definitions for parameters in the recipe file: {}.",
-            missing_keys
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(", ")
+            lint.undefined.join(", ")
         ));
     }
 
-    if !extra_keys.is_empty() {
+    if !lint.unused.is_empty() {
         message.push_str(&format!(
             "\nUnnecessary parameter definitions: {}.",
-            extra_keys
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(", ")
+            lint.unused.join(", ")
         ));
     }
     Err(anyhow::anyhow!("{}", message.trim_end()))
@@ -140,6 +184,22 @@ fn validate_optional_parameters(parameters: &Option<Vec<RecipeParameter>>) -> Re
         return Err(anyhow::anyhow!("File parameters cannot have default values to avoid importing sensitive user files: {}", file_params_with_defaults.join(", ")));
     }
 
+    let select_params_without_options: Vec<String> = params
+        .iter()
+        .filter(|p| {
+            matches!(p.input_type, RecipeParameterInputType::Select)
+                && p.options.as_ref().is_none_or(|options| options.is_empty())
+        })
+        .map(|p| p.key.clone())
+        .collect();
+
+    if !select_params_without_options.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Select parameters must list their options: {}",
+            select_params_without_options.join(", ")
+        ));
+    }
+
     let optional_params_without_default_values: Vec<String> = params
         .iter()
         .filter(|p| {
@@ -155,6 +215,57 @@ fn validate_optional_parameters(parameters: &Option<Vec<RecipeParameter>>) -> Re
     }
 }
 
+fn validate_parameter_defaults(parameters: &Option<Vec<RecipeParameter>>) -> Result<()> {
+    let empty_params = vec![];
+    let params = parameters.as_ref().unwrap_or(&empty_params);
+
+    let invalid_defaults: Vec<String> = params
+        .iter()
+        .filter_map(|p| {
+            let default = p.default.as_deref()?;
+            // Templated and `${env:VAR}` defaults are only known once rendered
+            if default.contains("{{") || default.contains("{%") || default.contains("${env:") {
+                return None;
+            }
+            let value = default.trim();
+            let valid = match p.input_type {
+                RecipeParameterInputType::Number => {
+                    value.parse::<f64>().is_ok_and(|number| number.is_finite())
+                }
+                RecipeParameterInputType::Boolean => {
+                    value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")
+                }
+                RecipeParameterInputType::Date => {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+                }
+                RecipeParameterInputType::Select => p
+                    .options
+                    .as_ref()
+                    .is_some_and(|options| options.iter().any(|option| option == default)),
+                _ => true,
+            };
+            (!valid).then(|| match p.input_type {
+                RecipeParameterInputType::Select => format!(
+                    "{} (expected one of {}, got '{}')",
+                    p.key,
+                    p.options.as_deref().unwrap_or_default().join(", "),
+                    default
+                ),
+                _ => format!("{} (expected {}, got '{}')", p.key, p.input_type, default),
+            })
+        })
+        .collect();
+
+    if invalid_defaults.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Parameter defaults do not match their input_type: {}",
+            invalid_defaults.join(", ")
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +308,91 @@ parameters:
         assert!(recipe.instructions.is_some());
         println!("Recipe: {:?}", recipe.prompt);
     }
+
+    #[test]
+    fn test_validate_parameter_defaults_against_input_type() {
+        let recipe_with_default = |input_type: &str, default: &str| {
+            format!(
+                r#"
+version: 1.0.0
+title: Test Recipe
+description: A test recipe for validation
+prompt: Use {{{{ value }}}}
+parameters:
+  - key: value
+    input_type: {}
+    requirement: optional
+    description: A test parameter
+    default: "{}"
+"#,
+                input_type, default
+            )
+        };
+
+        for (input_type, default) in [
+            ("number", "42"),
+            ("number", "-1.5"),
+            ("boolean", "true"),
+            ("boolean", "False"),
+            ("date", "2025-01-31"),
+            ("string", "anything"),
+            ("number", "{{ recipe_dir }}"),
+        ] {
+            let content = recipe_with_default(input_type, default);
+            let result = validate_recipe_template_from_content(&content, None);
+            assert!(
+                result.is_ok(),
+                "{} default '{}' should be valid: {:?}",
+                input_type,
+                default,
+                result.err()
+            );
+        }
+
+        for (input_type, default) in [
+            ("number", "abc"),
+            ("boolean", "yes"),
+            ("date", "31/01/2025"),
+        ] {
+            let content = recipe_with_default(input_type, default);
+            let err = validate_recipe_template_from_content(&content, None)
+                .expect_err("mismatched default should be rejected")
+                .to_string();
+            assert!(err.contains("value"), "error should name the key: {}", err);
+            assert!(
+                err.contains(&format!("expected {}", input_type)),
+                "error should name the type: {}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_lint_recipe_parameters() {
+        let recipe_content = r#"
+version: 1.0.0
+title: Test Recipe
+description: A test recipe for linting
+prompt: Review {{ repo }} for {{ reviewer }}
+parameters:
+  - key: repo
+    input_type: string
+    requirement: required
+    description: Repository to review
+  - key: stale
+    input_type: string
+    requirement: required
+    description: No longer used
+"#;
+
+        let lint = lint_recipe_parameters(recipe_content, None).unwrap();
+        assert_eq!(
+            lint,
+            ParameterLint {
+                unused: vec!["stale".to_string()],
+                undefined: vec!["reviewer".to_string()],
+            }
+        );
+        assert!(!lint.is_clean());
+    }
 }
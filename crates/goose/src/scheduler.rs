@@ -794,10 +794,20 @@ async fn execute_job(
         retry_config: None,
     };
 
+    // A child token so hitting the recipe's deadline can be told apart from the job being killed
+    let run_token = cancel_token.child_token();
+    let deadline_timer = recipe.timeout().map(|timeout| {
+        let run_token = run_token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            run_token.cancel();
+        })
+    });
+
     let session_id = session_config.id.clone();
     let stream = crate::session_context::with_session_id(Some(session_id.clone()), async {
         agent
-            .reply(user_message, session_config, Some(cancel_token))
+            .reply(user_message, session_config, Some(run_token.clone()))
             .await
     })
     .await?;
@@ -823,15 +833,38 @@ async fn execute_job(
         }
     }
 
+    if let Some(timer) = deadline_timer {
+        timer.abort();
+    }
+    let deadline_exceeded = run_token.is_cancelled() && !cancel_token.is_cancelled();
+
     agent
         .config
         .session_manager
         .update(&session.id)
         .schedule_id(Some(job.id.clone()))
-        .recipe(Some(recipe))
+        .recipe(Some(recipe.clone()))
         .apply()
         .await?;
 
+    if deadline_exceeded {
+        return Err(anyhow!(
+            "Recipe '{}' exceeded its deadline of {}s",
+            recipe.title,
+            recipe.timeout_seconds.unwrap_or_default()
+        ));
+    }
+
+    let missing_artifacts =
+        recipe.missing_artifacts(&session.working_dir, Some(session.created_at.into()));
+    if !missing_artifacts.is_empty() {
+        return Err(anyhow!(
+            "Recipe '{}' did not produce declared artifact(s): {}",
+            recipe.title,
+            missing_artifacts.join(", ")
+        ));
+    }
+
     let duration_secs = start_time.elapsed().as_secs();
     tokio::spawn(async move {
         let mut props = HashMap::new();
@@ -0,0 +1,295 @@
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::Conversation;
+use crate::session::Session;
+use rmcp::model::Role;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallSummary {
+    pub name: String,
+    pub arguments: String,
+    pub response: Option<String>,
+}
+
+/// The parts of a turn that are compared between sessions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnSummary {
+    pub user_text: String,
+    pub tool_calls: Vec<ToolCallSummary>,
+    pub assistant_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TurnDiff {
+    Identical {
+        index: usize,
+        turn: TurnSummary,
+    },
+    Diverged {
+        index: usize,
+        a: TurnSummary,
+        b: TurnSummary,
+        differences: Vec<String>,
+    },
+    OnlyInA {
+        index: usize,
+        turn: TurnSummary,
+    },
+    OnlyInB {
+        index: usize,
+        turn: TurnSummary,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiff {
+    pub session_a: String,
+    pub session_b: String,
+    pub turns: Vec<TurnDiff>,
+}
+
+impl SessionDiff {
+    pub fn first_divergence(&self) -> Option<usize> {
+        self.turns.iter().position(|turn| !turn.is_identical())
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.first_divergence().is_none()
+    }
+}
+
+impl TurnDiff {
+    pub fn is_identical(&self) -> bool {
+        matches!(self, TurnDiff::Identical { .. })
+    }
+}
+
+pub(crate) fn summarize_turn(messages: &[Message]) -> TurnSummary {
+    let mut user_text = Vec::new();
+    let mut tool_calls: Vec<(String, ToolCallSummary)> = Vec::new();
+    let mut assistant_text = Vec::new();
+
+    for message in messages.iter().filter(|m| m.is_agent_visible()) {
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) => match message.role {
+                    Role::User => user_text.push(text.text.clone()),
+                    Role::Assistant => assistant_text.push(text.text.clone()),
+                },
+                MessageContent::ToolRequest(request) => {
+                    let (name, arguments) = match &request.tool_call {
+                        Ok(call) => (
+                            call.name.to_string(),
+                            call.arguments
+                                .as_ref()
+                                .map(|args| serde_json::Value::Object(args.clone()).to_string())
+                                .unwrap_or_default(),
+                        ),
+                        Err(e) => ("<invalid>".to_string(), e.to_string()),
+                    };
+                    tool_calls.push((
+                        request.id.clone(),
+                        ToolCallSummary {
+                            name,
+                            arguments,
+                            response: None,
+                        },
+                    ));
+                }
+                MessageContent::ToolResponse(response) => {
+                    let text = content.as_tool_response_text().unwrap_or_else(|| {
+                        match &response.tool_result {
+                            Ok(_) => String::new(),
+                            Err(e) => format!("error: {}", e.message),
+                        }
+                    });
+                    if let Some((_, call)) =
+                        tool_calls.iter_mut().find(|(id, _)| *id == response.id)
+                    {
+                        call.response = Some(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Only the assistant's final text counts as the turn's answer; intermediate
+    // narration between tool calls is too noisy to compare.
+    let assistant_text = messages
+        .iter()
+        .rev()
+        .find(|m| {
+            m.role == Role::Assistant && m.is_agent_visible() && !m.as_concat_text().is_empty()
+        })
+        .map(|m| m.as_concat_text())
+        .unwrap_or_else(|| assistant_text.join("\n"));
+
+    TurnSummary {
+        user_text: user_text.join("\n"),
+        tool_calls: tool_calls.into_iter().map(|(_, call)| call).collect(),
+        assistant_text,
+    }
+}
+
+fn describe_differences(a: &TurnSummary, b: &TurnSummary) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    if a.user_text != b.user_text {
+        differences.push("user message differs".to_string());
+    }
+
+    let max_calls = a.tool_calls.len().max(b.tool_calls.len());
+    for i in 0..max_calls {
+        match (a.tool_calls.get(i), b.tool_calls.get(i)) {
+            (Some(call_a), Some(call_b)) => {
+                if call_a.name != call_b.name {
+                    differences.push(format!(
+                        "tool call {} differs: {} vs {}",
+                        i + 1,
+                        call_a.name,
+                        call_b.name
+                    ));
+                } else if call_a.arguments != call_b.arguments {
+                    differences.push(format!(
+                        "tool call {} ({}) has different arguments",
+                        i + 1,
+                        call_a.name
+                    ));
+                } else if call_a.response != call_b.response {
+                    differences.push(format!(
+                        "tool call {} ({}) returned a different response",
+                        i + 1,
+                        call_a.name
+                    ));
+                }
+            }
+            (Some(call), None) => {
+                differences.push(format!("tool call {} ({}) only in A", i + 1, call.name))
+            }
+            (None, Some(call)) => {
+                differences.push(format!("tool call {} ({}) only in B", i + 1, call.name))
+            }
+            (None, None) => {}
+        }
+    }
+
+    if a.assistant_text != b.assistant_text {
+        differences.push("assistant response differs".to_string());
+    }
+
+    differences
+}
+
+/// Aligns two conversations turn by turn and reports where they diverge.
+pub fn diff_conversations(a: &Conversation, b: &Conversation) -> Vec<TurnDiff> {
+    let turns_a: Vec<TurnSummary> = a.turns().into_iter().map(summarize_turn).collect();
+    let turns_b: Vec<TurnSummary> = b.turns().into_iter().map(summarize_turn).collect();
+
+    let max_turns = turns_a.len().max(turns_b.len());
+    let mut turns_a = turns_a.into_iter();
+    let mut turns_b = turns_b.into_iter();
+
+    (0..max_turns)
+        .map(|index| match (turns_a.next(), turns_b.next()) {
+            (Some(a), Some(b)) => {
+                let differences = describe_differences(&a, &b);
+                if differences.is_empty() {
+                    TurnDiff::Identical { index, turn: a }
+                } else {
+                    TurnDiff::Diverged {
+                        index,
+                        a,
+                        b,
+                        differences,
+                    }
+                }
+            }
+            (Some(turn), None) => TurnDiff::OnlyInA { index, turn },
+            (None, Some(turn)) => TurnDiff::OnlyInB { index, turn },
+            (None, None) => unreachable!("index is bounded by the longer conversation"),
+        })
+        .collect()
+}
+
+pub fn diff_sessions(a: &Session, b: &Session) -> SessionDiff {
+    let empty = Conversation::empty();
+    SessionDiff {
+        session_a: a.id.clone(),
+        session_b: b.id.clone(),
+        turns: diff_conversations(
+            a.conversation.as_ref().unwrap_or(&empty),
+            b.conversation.as_ref().unwrap_or(&empty),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{CallToolRequestParam, CallToolResult, Content};
+    use rmcp::object;
+
+    fn shell_turn(prompt: &str, command: &str, output: &str, answer: &str) -> Vec<Message> {
+        vec![
+            Message::user().with_text(prompt),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParam {
+                    task: None,
+                    name: "shell".into(),
+                    arguments: Some(object!({ "command": command })),
+                }),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(CallToolResult {
+                    content: vec![Content::text(output)],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }),
+            ),
+            Message::assistant().with_text(answer),
+        ]
+    }
+
+    #[test]
+    fn test_identical_conversations() {
+        let messages = shell_turn("list files", "ls", "a.txt", "There is one file");
+        let a = Conversation::new_unvalidated(messages.clone());
+        let b = Conversation::new_unvalidated(messages);
+
+        let diff = diff_conversations(&a, &b);
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].is_identical());
+    }
+
+    #[test]
+    fn test_diverging_tool_calls_and_extra_turns() {
+        let mut messages_a = shell_turn("list files", "ls", "a.txt", "There is one file");
+        messages_a.push(Message::user().with_text("thanks"));
+        messages_a.push(Message::assistant().with_text("You're welcome"));
+        let a = Conversation::new_unvalidated(messages_a);
+        let b = Conversation::new_unvalidated(shell_turn(
+            "list files",
+            "ls -la",
+            "a.txt",
+            "There is one file",
+        ));
+
+        let diff = diff_conversations(&a, &b);
+        assert_eq!(diff.len(), 2);
+        match &diff[0] {
+            TurnDiff::Diverged { differences, .. } => {
+                assert_eq!(
+                    differences,
+                    &vec!["tool call 1 (shell) has different arguments".to_string()]
+                );
+            }
+            other => panic!("expected divergence, got {:?}", other),
+        }
+        assert!(matches!(diff[1], TurnDiff::OnlyInA { index: 1, .. }));
+    }
+}
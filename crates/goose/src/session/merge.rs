@@ -0,0 +1,141 @@
+use crate::conversation::message::Message;
+use crate::conversation::{fix_saved_conversation, Conversation};
+use crate::session::diff::summarize_turn;
+use std::str::FromStr;
+
+/// How the turns of two sessions are combined after the history they share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// All of the first session's turns, then all of the second's
+    #[default]
+    Append,
+    /// Alternate between the two sessions one turn at a time
+    Interleave,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "append" => Ok(Self::Append),
+            "interleave" => Ok(Self::Interleave),
+            other => Err(anyhow::anyhow!(
+                "Unknown merge strategy '{}' (expected append or interleave)",
+                other
+            )),
+        }
+    }
+}
+
+/// Combines two conversations into one. Leading turns that are the same in both, such as
+/// the history before a fork, appear once; the rest are combined per `strategy`. The result
+/// is repaired with `fix_saved_conversation`, whose adjustments are returned alongside it;
+/// unlike `fix_conversation` it keeps the final reply.
+pub fn merge_conversations(
+    a: &Conversation,
+    b: &Conversation,
+    strategy: MergeStrategy,
+) -> (Conversation, Vec<String>) {
+    let turns_a = a.turns();
+    let turns_b = b.turns();
+    let shared = turns_a
+        .iter()
+        .zip(&turns_b)
+        .take_while(|(turn_a, turn_b)| summarize_turn(turn_a) == summarize_turn(turn_b))
+        .count();
+
+    let mut merged: Vec<&[Message]> = turns_a[..shared].to_vec();
+    let (rest_a, rest_b) = (&turns_a[shared..], &turns_b[shared..]);
+    match strategy {
+        MergeStrategy::Append => {
+            merged.extend(rest_a);
+            merged.extend(rest_b);
+        }
+        MergeStrategy::Interleave => {
+            for i in 0..rest_a.len().max(rest_b.len()) {
+                merged.extend(rest_a.get(i));
+                merged.extend(rest_b.get(i));
+            }
+        }
+    }
+
+    fix_saved_conversation(Conversation::new_unvalidated(
+        merged.into_iter().flatten().cloned(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(prompt: &str, answer: &str) -> Vec<Message> {
+        vec![
+            Message::user().with_text(prompt),
+            Message::assistant().with_text(answer),
+        ]
+    }
+
+    fn texts(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect()
+    }
+
+    fn forked() -> (Conversation, Conversation) {
+        let shared = turn("plan", "ok");
+        let a = [shared.clone(), turn("try a", "a1"), turn("more a", "a2")].concat();
+        let b = [shared, turn("try b", "b1")].concat();
+        (
+            Conversation::new_unvalidated(a),
+            Conversation::new_unvalidated(b),
+        )
+    }
+
+    #[test]
+    fn test_append_keeps_shared_history_once() {
+        let (a, b) = forked();
+        let (merged, issues) = merge_conversations(&a, &b, MergeStrategy::Append);
+        assert!(issues.is_empty());
+        assert_eq!(
+            texts(&merged),
+            vec!["plan", "ok", "try a", "a1", "more a", "a2", "try b", "b1"]
+        );
+    }
+
+    #[test]
+    fn test_interleave_alternates_turns() {
+        let (a, b) = forked();
+        let (merged, issues) = merge_conversations(&a, &b, MergeStrategy::Interleave);
+        assert!(issues.is_empty());
+        assert_eq!(
+            texts(&merged),
+            vec!["plan", "ok", "try a", "a1", "try b", "b1", "more a", "a2"]
+        );
+    }
+
+    #[test]
+    fn test_merge_repairs_result() {
+        // A session that ends on an unanswered prompt would leave two user messages in a row
+        let a = Conversation::new_unvalidated(vec![Message::user().with_text("hello")]);
+        let b = Conversation::new_unvalidated(turn("bye", "later"));
+        let (merged, issues) = merge_conversations(&a, &b, MergeStrategy::Append);
+        assert_eq!(issues, vec!["Merged consecutive user messages"]);
+        assert_eq!(texts(&merged), vec!["hello\nbye", "later"]);
+    }
+
+    #[test]
+    fn test_strategy_from_str() {
+        assert_eq!(
+            "Interleave".parse::<MergeStrategy>().unwrap(),
+            MergeStrategy::Interleave
+        );
+        assert_eq!(
+            "append".parse::<MergeStrategy>().unwrap(),
+            MergeStrategy::Append
+        );
+        assert!("zip".parse::<MergeStrategy>().is_err());
+    }
+}
@@ -1,11 +1,17 @@
 mod chat_history_search;
 mod diagnostics;
+pub mod diff;
 pub mod extension_data;
 mod legacy;
+pub mod merge;
+pub mod retention;
 pub mod session_manager;
 
 pub use diagnostics::{generate_diagnostics, get_system_info, SystemInfo};
+pub use diff::{diff_sessions, SessionDiff, TurnDiff};
 pub use extension_data::{EnabledExtensionsState, ExtensionData, ExtensionState, TodoState};
+pub use merge::{merge_conversations, MergeStrategy};
+pub use retention::{PrunedSession, RetentionPolicy};
 pub use session_manager::{
     Session, SessionInsights, SessionManager, SessionType, SessionUpdateBuilder,
 };
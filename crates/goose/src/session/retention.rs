@@ -0,0 +1,196 @@
+use crate::config::Config;
+use crate::session::Session;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+pub const GOOSE_SESSION_RETENTION_MAX_AGE_DAYS: &str = "GOOSE_SESSION_RETENTION_MAX_AGE_DAYS";
+pub const GOOSE_SESSION_RETENTION_MAX_COUNT: &str = "GOOSE_SESSION_RETENTION_MAX_COUNT";
+pub const GOOSE_SESSION_RETENTION_MAX_BYTES: &str = "GOOSE_SESSION_RETENTION_MAX_BYTES";
+
+/// Sessions updated within this many hours may still be open in a running goose, so
+/// they are kept whatever the policy says
+const RECENTLY_ACTIVE_HOURS: i64 = 24;
+
+/// Limits on how many sessions are kept around. Sessions the user explicitly
+/// named are never pruned, but still count towards the byte budget. Recently
+/// active sessions are never pruned either, and count towards every limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_age_days: Option<u32>,
+    pub max_count: Option<usize>,
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedSession {
+    pub id: String,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+impl RetentionPolicy {
+    pub fn from_config() -> Self {
+        let config = Config::global();
+        Self {
+            max_age_days: config
+                .get_param::<u32>(GOOSE_SESSION_RETENTION_MAX_AGE_DAYS)
+                .ok(),
+            max_count: config
+                .get_param::<usize>(GOOSE_SESSION_RETENTION_MAX_COUNT)
+                .ok(),
+            max_bytes: config
+                .get_param::<u64>(GOOSE_SESSION_RETENTION_MAX_BYTES)
+                .ok(),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age_days.is_none() && self.max_count.is_none() && self.max_bytes.is_none()
+    }
+
+    /// Picks the sessions that fall outside the policy. Sessions are considered
+    /// newest first, so when a limit is hit it is always the oldest ones that go.
+    pub fn select_for_pruning(
+        &self,
+        sessions: Vec<(Session, u64)>,
+        now: DateTime<Utc>,
+    ) -> Vec<PrunedSession> {
+        if self.is_unlimited() {
+            return Vec::new();
+        }
+
+        let mut sessions = sessions;
+        sessions.sort_by(|(a, _), (b, _)| b.updated_at.cmp(&a.updated_at));
+
+        let cutoff = self
+            .max_age_days
+            .map(|days| now - Duration::days(i64::from(days)));
+        let active_since = now - Duration::hours(RECENTLY_ACTIVE_HOURS);
+
+        let mut kept_bytes: u64 = sessions
+            .iter()
+            .filter(|(session, _)| session.user_set_name)
+            .map(|(_, size)| *size)
+            .sum();
+        let mut kept_count = 0usize;
+        let mut pruned = Vec::new();
+
+        for (session, size) in sessions {
+            if session.user_set_name {
+                continue;
+            }
+
+            let too_old = cutoff.is_some_and(|cutoff| session.updated_at < cutoff);
+            let too_many = self.max_count.is_some_and(|max| kept_count >= max);
+            let too_big = self
+                .max_bytes
+                .is_some_and(|max| kept_bytes.saturating_add(size) > max);
+
+            let recently_active = session.updated_at > active_since;
+
+            if !recently_active && (too_old || too_many || too_big) {
+                pruned.push(PrunedSession {
+                    id: session.id,
+                    name: session.name,
+                    updated_at: session.updated_at,
+                    size_bytes: size,
+                });
+            } else {
+                kept_count += 1;
+                kept_bytes += size;
+            }
+        }
+
+        pruned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, days_ago: i64, named: bool, now: DateTime<Utc>) -> Session {
+        Session {
+            id: id.to_string(),
+            name: id.to_string(),
+            user_set_name: named,
+            updated_at: now - Duration::days(days_ago),
+            ..Default::default()
+        }
+    }
+
+    fn ids(pruned: &[PrunedSession]) -> Vec<&str> {
+        pruned.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_unlimited_policy_prunes_nothing() {
+        let now = Utc::now();
+        let pruned = RetentionPolicy::default()
+            .select_for_pruning(vec![(session("a", 400, false, now), 10)], now);
+        assert!(pruned.is_empty());
+    }
+
+    #[test]
+    fn test_prunes_oldest_beyond_limits_and_skips_named() {
+        let now = Utc::now();
+        let sessions = vec![
+            (session("newest", 1, false, now), 100),
+            (session("named", 50, true, now), 100),
+            (session("middle", 5, false, now), 100),
+            (session("oldest", 10, false, now), 100),
+            (session("ancient", 90, false, now), 1),
+        ];
+
+        let by_count = RetentionPolicy {
+            max_count: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&by_count.select_for_pruning(sessions.clone(), now)),
+            vec!["oldest", "ancient"]
+        );
+
+        let by_age = RetentionPolicy {
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&by_age.select_for_pruning(sessions.clone(), now)),
+            vec!["ancient"]
+        );
+
+        let by_bytes = RetentionPolicy {
+            max_bytes: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&by_bytes.select_for_pruning(sessions, now)),
+            vec!["middle", "oldest"]
+        );
+    }
+
+    #[test]
+    fn test_keeps_recently_active_sessions() {
+        let now = Utc::now();
+        let recent = |id: &str, hours_ago: i64| Session {
+            id: id.to_string(),
+            name: id.to_string(),
+            updated_at: now - Duration::hours(hours_ago),
+            ..Default::default()
+        };
+        let sessions = vec![
+            (recent("running", 0), 500),
+            (recent("paused", 3), 500),
+            (session("idle", 2, false, now), 1),
+        ];
+
+        let policy = RetentionPolicy {
+            max_age_days: Some(0),
+            max_count: Some(1),
+            max_bytes: Some(100),
+        };
+        assert_eq!(ids(&policy.select_for_pruning(sessions, now)), vec!["idle"]);
+    }
+}
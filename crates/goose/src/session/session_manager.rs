@@ -5,6 +5,7 @@ use crate::model::ModelConfig;
 use crate::providers::base::{Provider, MSG_COUNT_FOR_SESSION_NAME_GENERATION};
 use crate::recipe::Recipe;
 use crate::session::extension_data::ExtensionData;
+use crate::session::retention::{PrunedSession, RetentionPolicy};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rmcp::model::Role;
@@ -18,7 +19,11 @@ use std::sync::{Arc, LazyLock};
 use tracing::{info, warn};
 use utoipa::ToSchema;
 
-pub const CURRENT_SCHEMA_VERSION: i32 = 6;
+pub const CURRENT_SCHEMA_VERSION: i32 = 7;
+
+/// Queue the messages of an agent turn and write them in one transaction when the turn ends,
+/// rather than one write per message
+pub const GOOSE_SESSION_BATCH_WRITES: &str = "GOOSE_SESSION_BATCH_WRITES";
 pub const SESSIONS_FOLDER: &str = "sessions";
 pub const DB_NAME: &str = "sessions.db";
 
@@ -90,6 +95,9 @@ pub struct Session {
     pub message_count: usize,
     pub provider_name: Option<String>,
     pub model_config: Option<ModelConfig>,
+    /// Free-form tags used to correlate the session with external systems
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
 }
 
 pub struct SessionUpdateBuilder<'a> {
@@ -111,6 +119,7 @@ pub struct SessionUpdateBuilder<'a> {
     user_recipe_values: Option<Option<HashMap<String, String>>>,
     provider_name: Option<Option<String>>,
     model_config: Option<Option<ModelConfig>>,
+    metadata: Option<HashMap<String, String>>,
 }
 
 #[derive(Serialize, ToSchema, Debug)]
@@ -141,6 +150,7 @@ impl<'a> SessionUpdateBuilder<'a> {
             user_recipe_values: None,
             provider_name: None,
             model_config: None,
+            metadata: None,
         }
     }
 
@@ -238,6 +248,12 @@ impl<'a> SessionUpdateBuilder<'a> {
         self.model_config = Some(Some(model_config));
         self
     }
+
+    /// Replaces the session's metadata with `metadata`.
+    pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
 }
 
 pub struct SessionManager {
@@ -273,6 +289,7 @@ impl SessionManager {
     }
 
     pub async fn get_session(&self, id: &str, include_messages: bool) -> Result<Session> {
+        self.storage.flush_messages(id).await?;
         self.storage.get_session(id, include_messages).await
     }
 
@@ -284,31 +301,56 @@ impl SessionManager {
         self.storage.apply_update(builder).await
     }
 
+    /// Stores `message` right away, after any messages queued for the session.
     pub async fn add_message(&self, id: &str, message: &Message) -> Result<()> {
-        self.storage.add_message(id, message).await
+        self.storage.write_messages(id, Some(message)).await
+    }
+
+    /// Holds `message` in memory until the session is flushed. Anything that reads or
+    /// rewrites the session's messages flushes first, so queued messages are never skipped.
+    pub async fn queue_message(&self, id: &str, message: &Message) {
+        self.storage.queue_message(id, message).await
+    }
+
+    /// Writes the messages queued for the session in a single transaction.
+    pub async fn flush_messages(&self, id: &str) -> Result<()> {
+        self.storage.flush_messages(id).await
     }
 
     pub async fn replace_conversation(&self, id: &str, conversation: &Conversation) -> Result<()> {
+        self.storage.flush_messages(id).await?;
         self.storage.replace_conversation(id, conversation).await
     }
 
+    /// Messages stored after row `after`, each paired with its row id, so a reader can
+    /// poll for newly appended messages. Pass 0 to read from the start.
+    pub async fn get_messages_after(&self, id: &str, after: i64) -> Result<Vec<(i64, Message)>> {
+        self.storage.flush_messages(id).await?;
+        self.storage.get_messages_after(id, after).await
+    }
+
     pub async fn list_sessions(&self) -> Result<Vec<Session>> {
+        self.storage.flush_all_messages().await?;
         self.storage.list_sessions().await
     }
 
     pub async fn list_sessions_by_types(&self, types: &[SessionType]) -> Result<Vec<Session>> {
+        self.storage.flush_all_messages().await?;
         self.storage.list_sessions_by_types(types).await
     }
 
     pub async fn delete_session(&self, id: &str) -> Result<()> {
+        self.storage.discard_queued_messages(id).await;
         self.storage.delete_session(id).await
     }
 
     pub async fn get_insights(&self) -> Result<SessionInsights> {
+        self.storage.flush_all_messages().await?;
         self.storage.get_insights().await
     }
 
     pub async fn export_session(&self, id: &str) -> Result<String> {
+        self.storage.flush_messages(id).await?;
         self.storage.export_session(id).await
     }
 
@@ -317,15 +359,70 @@ impl SessionManager {
     }
 
     pub async fn copy_session(&self, session_id: &str, new_name: String) -> Result<Session> {
+        self.storage.flush_messages(session_id).await?;
         self.storage.copy_session(self, session_id, new_name).await
     }
 
     pub async fn truncate_conversation(&self, session_id: &str, timestamp: i64) -> Result<()> {
+        self.storage.flush_messages(session_id).await?;
         self.storage
             .truncate_conversation(session_id, timestamp)
             .await
     }
 
+    /// Deletes the sessions that fall outside `policy`, oldest first. With
+    /// `dry_run` set, only reports what would be removed.
+    pub async fn prune_sessions(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<Vec<PrunedSession>> {
+        if policy.is_unlimited() {
+            return Ok(Vec::new());
+        }
+
+        // Queued messages bump updated_at once written, so sessions in use here look active
+        self.storage.flush_all_messages().await?;
+
+        let sessions = self
+            .storage
+            .list_sessions_by_types(&[
+                SessionType::User,
+                SessionType::Scheduled,
+                SessionType::SubAgent,
+                SessionType::Hidden,
+                SessionType::Terminal,
+            ])
+            .await?;
+        let sizes = self.storage.session_sizes().await?;
+        let sessions = sessions
+            .into_iter()
+            .map(|session| {
+                let size = sizes.get(&session.id).copied().unwrap_or(0);
+                (session, size)
+            })
+            .collect();
+
+        let pruned = policy.select_for_pruning(sessions, Utc::now());
+        if !dry_run {
+            for session in &pruned {
+                self.delete_session(&session.id).await?;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Applies the retention policy from config, if one is set.
+    pub async fn enforce_retention_policy(&self) -> Result<Vec<PrunedSession>> {
+        let pruned = self
+            .prune_sessions(&RetentionPolicy::from_config(), false)
+            .await?;
+        if !pruned.is_empty() {
+            info!("Pruned {} session(s) per retention policy", pruned.len());
+        }
+        Ok(pruned)
+    }
+
     pub async fn maybe_update_name(&self, id: &str, provider: Arc<dyn Provider>) -> Result<()> {
         let session = self.get_session(id, true).await?;
 
@@ -359,6 +456,7 @@ impl SessionManager {
         before_date: Option<chrono::DateTime<chrono::Utc>>,
         exclude_session_id: Option<String>,
     ) -> Result<crate::session::chat_history_search::ChatRecallResults> {
+        self.storage.flush_all_messages().await?;
         self.storage
             .search_chat_history(query, limit, after_date, before_date, exclude_session_id)
             .await
@@ -369,6 +467,8 @@ pub struct SessionStorage {
     pool: Pool<Sqlite>,
     initialized: tokio::sync::OnceCell<()>,
     session_dir: PathBuf,
+    /// Messages queued per session until the next flush
+    queued_messages: tokio::sync::Mutex<HashMap<String, Vec<Message>>>,
 }
 
 fn role_to_string(role: &Role) -> &'static str {
@@ -402,6 +502,7 @@ impl Default for Session {
             message_count: 0,
             provider_name: None,
             model_config: None,
+            metadata: HashMap::new(),
         }
     }
 }
@@ -427,6 +528,11 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
         let model_config_json: Option<String> = row.try_get("model_config_json").ok().flatten();
         let model_config = model_config_json.and_then(|json| serde_json::from_str(&json).ok());
 
+        let metadata_json: Option<String> = row.try_get("metadata_json").ok().flatten();
+        let metadata = metadata_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
         let name: String = {
             let name_val: String = row.try_get("name").unwrap_or_default();
             if !name_val.is_empty() {
@@ -466,6 +572,7 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
             message_count: row.try_get("message_count").unwrap_or(0) as usize,
             provider_name: row.try_get("provider_name").ok().flatten(),
             model_config,
+            metadata,
         })
     }
 }
@@ -492,6 +599,7 @@ impl SessionStorage {
             pool: Self::create_pool(&db_path),
             initialized: tokio::sync::OnceCell::new(),
             session_dir,
+            queued_messages: tokio::sync::Mutex::new(HashMap::new()),
         }
     }
 
@@ -564,7 +672,8 @@ impl SessionStorage {
                 recipe_json TEXT,
                 user_recipe_values_json TEXT,
                 provider_name TEXT,
-                model_config_json TEXT
+                model_config_json TEXT,
+                metadata_json TEXT
             )
         "#,
         )
@@ -666,6 +775,8 @@ impl SessionStorage {
             None => None,
         };
 
+        let metadata_json = serde_json::to_string(&session.metadata)?;
+
         sqlx::query(
             r#"
         INSERT INTO sessions (
@@ -673,8 +784,8 @@ impl SessionStorage {
             total_tokens, input_tokens, output_tokens,
             accumulated_total_tokens, accumulated_input_tokens, accumulated_output_tokens,
             schedule_id, recipe_json, user_recipe_values_json,
-            provider_name, model_config_json
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            provider_name, model_config_json, metadata_json
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
         )
         .bind(&session.id)
@@ -696,6 +807,7 @@ impl SessionStorage {
         .bind(user_recipe_values_json)
         .bind(&session.provider_name)
         .bind(model_config_json)
+        .bind(metadata_json)
         .execute(&mut *tx)
         .await?;
 
@@ -839,6 +951,15 @@ impl SessionStorage {
                 .execute(pool)
                 .await?;
             }
+            7 => {
+                sqlx::query(
+                    r#"
+                    ALTER TABLE sessions ADD COLUMN metadata_json TEXT
+                "#,
+                )
+                .execute(pool)
+                .await?;
+            }
             _ => {
                 anyhow::bail!("Unknown migration version: {}", version);
             }
@@ -896,7 +1017,7 @@ impl SessionStorage {
                total_tokens, input_tokens, output_tokens,
                accumulated_total_tokens, accumulated_input_tokens, accumulated_output_tokens,
                schedule_id, recipe_json, user_recipe_values_json,
-               provider_name, model_config_json
+               provider_name, model_config_json, metadata_json
         FROM sessions
         WHERE id = ?
     "#,
@@ -959,6 +1080,7 @@ impl SessionStorage {
         add_update!(builder.user_recipe_values, "user_recipe_values_json");
         add_update!(builder.provider_name, "provider_name");
         add_update!(builder.model_config, "model_config_json");
+        add_update!(builder.metadata, "metadata_json");
 
         if updates.is_empty() {
             return Ok(());
@@ -1024,6 +1146,9 @@ impl SessionStorage {
                 .transpose()?;
             q = q.bind(model_config_json);
         }
+        if let Some(metadata) = builder.metadata {
+            q = q.bind(serde_json::to_string(&metadata)?);
+        }
 
         let pool = self.pool().await?;
         let mut tx = pool.begin().await?;
@@ -1047,45 +1172,115 @@ impl SessionStorage {
         for (idx, (role_str, content_json, created_timestamp, metadata_json)) in
             rows.into_iter().enumerate()
         {
-            let role = match role_str.as_str() {
-                "user" => Role::User,
-                "assistant" => Role::Assistant,
-                _ => continue,
+            let Some(message) =
+                message_from_row(&role_str, &content_json, created_timestamp, metadata_json)?
+            else {
+                continue;
             };
+            messages.push(message.with_id(format!("msg_{}_{}", session_id, idx)));
+        }
+
+        Ok(Conversation::new_unvalidated(messages))
+    }
+
+    async fn get_messages_after(
+        &self,
+        session_id: &str,
+        after: i64,
+    ) -> Result<Vec<(i64, Message)>> {
+        let pool = self.pool().await?;
+        let rows = sqlx::query_as::<_, (i64, String, String, i64, Option<String>)>(
+            "SELECT id, role, content_json, created_timestamp, metadata_json FROM messages WHERE session_id = ? AND id > ? ORDER BY id",
+        )
+            .bind(session_id)
+            .bind(after)
+            .fetch_all(pool)
+            .await?;
 
-            let content = serde_json::from_str(&content_json)?;
-            let metadata = metadata_json
-                .and_then(|json| serde_json::from_str(&json).ok())
-                .unwrap_or_default();
+        let mut messages = Vec::new();
+        for (row_id, role_str, content_json, created_timestamp, metadata_json) in rows {
+            if let Some(message) =
+                message_from_row(&role_str, &content_json, created_timestamp, metadata_json)?
+            {
+                messages.push((row_id, message));
+            }
+        }
+        Ok(messages)
+    }
 
-            let mut message = Message::new(role, created_timestamp, content);
-            message.metadata = metadata;
-            message = message.with_id(format!("msg_{}_{}", session_id, idx));
-            messages.push(message);
+    async fn queue_message(&self, session_id: &str, message: &Message) {
+        self.queued_messages
+            .lock()
+            .await
+            .entry(session_id.to_string())
+            .or_default()
+            .push(message.clone());
+    }
+
+    async fn flush_messages(&self, session_id: &str) -> Result<()> {
+        self.write_messages(session_id, None).await
+    }
+
+    async fn flush_all_messages(&self) -> Result<()> {
+        let session_ids: Vec<String> = self.queued_messages.lock().await.keys().cloned().collect();
+        for session_id in session_ids {
+            self.flush_messages(&session_id).await?;
         }
+        Ok(())
+    }
 
-        Ok(Conversation::new_unvalidated(messages))
+    async fn discard_queued_messages(&self, session_id: &str) {
+        self.queued_messages.lock().await.remove(session_id);
+    }
+
+    /// Writes the messages queued for `session_id`, then `message`, in one transaction. The
+    /// queue is only locked while the session's messages are taken out, so other sessions can
+    /// queue and write meanwhile; if the write fails the queued messages are put back ahead of
+    /// any queued since.
+    async fn write_messages(&self, session_id: &str, message: Option<&Message>) -> Result<()> {
+        let mut messages = self
+            .queued_messages
+            .lock()
+            .await
+            .remove(session_id)
+            .unwrap_or_default();
+        let queued_count = messages.len();
+        messages.extend(message.cloned());
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.insert_messages(session_id, &messages).await {
+            messages.truncate(queued_count);
+            if !messages.is_empty() {
+                let mut queued = self.queued_messages.lock().await;
+                messages.extend(queued.remove(session_id).unwrap_or_default());
+                queued.insert(session_id.to_string(), messages);
+            }
+            return Err(e);
+        }
+        Ok(())
     }
 
-    async fn add_message(&self, session_id: &str, message: &Message) -> Result<()> {
+    async fn insert_messages(&self, session_id: &str, messages: &[Message]) -> Result<()> {
         let pool = self.pool().await?;
         let mut tx = pool.begin().await?;
 
-        let metadata_json = serde_json::to_string(&message.metadata)?;
-
-        sqlx::query(
-            r#"
+        for message in messages {
+            sqlx::query(
+                r#"
             INSERT INTO messages (session_id, role, content_json, created_timestamp, metadata_json)
             VALUES (?, ?, ?, ?, ?)
         "#,
-        )
-        .bind(session_id)
-        .bind(role_to_string(&message.role))
-        .bind(serde_json::to_string(&message.content)?)
-        .bind(message.created)
-        .bind(metadata_json)
-        .execute(&mut *tx)
-        .await?;
+            )
+            .bind(session_id)
+            .bind(role_to_string(&message.role))
+            .bind(serde_json::to_string(&message.content)?)
+            .bind(message.created)
+            .bind(serde_json::to_string(&message.metadata)?)
+            .execute(&mut *tx)
+            .await?;
+        }
 
         sqlx::query("UPDATE sessions SET updated_at = datetime('now') WHERE id = ?")
             .bind(session_id)
@@ -1096,6 +1291,9 @@ impl SessionStorage {
         Ok(())
     }
 
+    /// Stores `conversation` as the session's messages. Rows matching the start of the new
+    /// conversation are kept, so only the changed tail is rewritten and appending to a
+    /// conversation costs one insert per new message rather than a full rewrite.
     async fn replace_conversation_inner(
         pool: &Pool<Sqlite>,
         session_id: &str,
@@ -1103,14 +1301,44 @@ impl SessionStorage {
     ) -> Result<()> {
         let mut tx = pool.begin().await?;
 
-        sqlx::query("DELETE FROM messages WHERE session_id = ?")
-            .bind(session_id)
-            .execute(&mut *tx)
-            .await?;
+        let stored = sqlx::query_as::<_, (i64, String, String, i64, Option<String>)>(
+            "SELECT id, role, content_json, created_timestamp, metadata_json FROM messages WHERE session_id = ? ORDER BY timestamp, id",
+        )
+        .bind(session_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let rows = conversation
+            .messages()
+            .iter()
+            .map(|message| {
+                Ok((
+                    role_to_string(&message.role),
+                    serde_json::to_string(&message.content)?,
+                    message.created,
+                    serde_json::to_string(&message.metadata)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        for message in conversation.messages() {
-            let metadata_json = serde_json::to_string(&message.metadata)?;
+        let unchanged = stored
+            .iter()
+            .zip(&rows)
+            .take_while(|((_, role, content, created, metadata), row)| {
+                (role.as_str(), content, *created, metadata.as_ref())
+                    == (row.0, &row.1, row.2, Some(&row.3))
+            })
+            .count();
 
+        if let Some((first_changed, ..)) = stored.get(unchanged) {
+            sqlx::query("DELETE FROM messages WHERE session_id = ? AND id >= ?")
+                .bind(session_id)
+                .bind(first_changed)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (role, content_json, created, metadata_json) in &rows[unchanged..] {
             sqlx::query(
                 r#"
             INSERT INTO messages (session_id, role, content_json, created_timestamp, metadata_json)
@@ -1118,9 +1346,9 @@ impl SessionStorage {
         "#,
             )
             .bind(session_id)
-            .bind(role_to_string(&message.role))
-            .bind(serde_json::to_string(&message.content)?)
-            .bind(message.created)
+            .bind(role)
+            .bind(content_json)
+            .bind(created)
             .bind(metadata_json)
             .execute(&mut *tx)
             .await?;
@@ -1151,7 +1379,7 @@ impl SessionStorage {
                    s.total_tokens, s.input_tokens, s.output_tokens,
                    s.accumulated_total_tokens, s.accumulated_input_tokens, s.accumulated_output_tokens,
                    s.schedule_id, s.recipe_json, s.user_recipe_values_json,
-                   s.provider_name, s.model_config_json,
+                   s.provider_name, s.model_config_json, s.metadata_json,
                    COUNT(m.id) as message_count
             FROM sessions s
             INNER JOIN messages m ON s.id = m.session_id
@@ -1204,6 +1432,20 @@ impl SessionStorage {
         Ok(())
     }
 
+    async fn session_sizes(&self) -> Result<HashMap<String, u64>> {
+        let pool = self.pool().await?;
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT session_id, COALESCE(SUM(LENGTH(content_json)), 0) FROM messages GROUP BY session_id",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, size)| (id, size.max(0) as u64))
+            .collect())
+    }
+
     async fn get_insights(&self) -> Result<SessionInsights> {
         let pool = self.pool().await?;
         let row = sqlx::query_as::<_, (i64, Option<i64>)>(
@@ -1253,7 +1495,8 @@ impl SessionStorage {
             .accumulated_output_tokens(import.accumulated_output_tokens)
             .schedule_id(import.schedule_id)
             .recipe(import.recipe)
-            .user_recipe_values(import.user_recipe_values);
+            .user_recipe_values(import.user_recipe_values)
+            .metadata(import.metadata);
 
         if import.user_set_name {
             builder = builder.user_provided_name(import.name.clone());
@@ -1291,6 +1534,7 @@ impl SessionStorage {
             .schedule_id(original_session.schedule_id)
             .recipe(original_session.recipe)
             .user_recipe_values(original_session.user_recipe_values)
+            .metadata(original_session.metadata)
             .apply()
             .await?;
 
@@ -1337,6 +1581,28 @@ impl SessionStorage {
     }
 }
 
+fn message_from_row(
+    role: &str,
+    content_json: &str,
+    created_timestamp: i64,
+    metadata_json: Option<String>,
+) -> Result<Option<Message>> {
+    let role = match role {
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        _ => return Ok(None),
+    };
+
+    let content = serde_json::from_str(content_json)?;
+    let metadata = metadata_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let mut message = Message::new(role, created_timestamp, content);
+    message.metadata = metadata;
+    Ok(Some(message))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1526,4 +1792,168 @@ mod tests {
         assert!(imported.user_set_name);
         assert_eq!(imported.working_dir, PathBuf::from("/tmp/test"));
     }
+
+    #[tokio::test]
+    async fn test_session_metadata_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let sm = SessionManager::new(temp_dir.path().to_path_buf());
+
+        let session = sm
+            .create_session(
+                PathBuf::from("/tmp/test"),
+                "Tagged".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        assert!(session.metadata.is_empty());
+
+        let metadata = HashMap::from([
+            ("ticket".to_string(), "ABC-123".to_string()),
+            ("run_id".to_string(), "42".to_string()),
+        ]);
+        sm.update(&session.id)
+            .metadata(metadata.clone())
+            .apply()
+            .await
+            .unwrap();
+
+        let loaded = sm.get_session(&session.id, false).await.unwrap();
+        assert_eq!(loaded.metadata, metadata);
+
+        let exported = sm.export_session(&session.id).await.unwrap();
+        let imported = sm.import_session(&exported).await.unwrap();
+        assert_eq!(imported.metadata, metadata);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_after() {
+        let temp_dir = TempDir::new().unwrap();
+        let sm = SessionManager::new(temp_dir.path().to_path_buf());
+
+        let session = sm
+            .create_session(
+                PathBuf::from("/tmp/test"),
+                "Tail".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        sm.add_message(&session.id, &Message::user().with_text("first"))
+            .await
+            .unwrap();
+        sm.add_message(&session.id, &Message::assistant().with_text("second"))
+            .await
+            .unwrap();
+
+        let all = sm.get_messages_after(&session.id, 0).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].1.as_concat_text(), "second");
+
+        let last_row = all.last().unwrap().0;
+        assert!(sm
+            .get_messages_after(&session.id, last_row)
+            .await
+            .unwrap()
+            .is_empty());
+
+        sm.add_message(&session.id, &Message::user().with_text("third"))
+            .await
+            .unwrap();
+        let new = sm.get_messages_after(&session.id, last_row).await.unwrap();
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].1.as_concat_text(), "third");
+    }
+
+    #[tokio::test]
+    async fn test_queued_messages_are_flushed_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let sm = SessionManager::new(temp_dir.path().to_path_buf());
+
+        let session = sm
+            .create_session(
+                PathBuf::from("/tmp/test"),
+                "Batched".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        sm.queue_message(&session.id, &Message::user().with_text("first"))
+            .await;
+        sm.queue_message(&session.id, &Message::assistant().with_text("second"))
+            .await;
+        let stored = sm.storage.get_messages_after(&session.id, 0).await.unwrap();
+        assert!(stored.is_empty());
+
+        sm.add_message(&session.id, &Message::user().with_text("third"))
+            .await
+            .unwrap();
+        sm.queue_message(&session.id, &Message::assistant().with_text("fourth"))
+            .await;
+
+        let session = sm.get_session(&session.id, true).await.unwrap();
+        let texts: Vec<String> = session
+            .conversation
+            .unwrap()
+            .messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect();
+        assert_eq!(texts, vec!["first", "second", "third", "fourth"]);
+    }
+
+    #[tokio::test]
+    async fn test_replace_conversation_keeps_unchanged_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let sm = SessionManager::new(temp_dir.path().to_path_buf());
+
+        let session = sm
+            .create_session(
+                PathBuf::from("/tmp/test"),
+                "Replace".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        let first = Message::user().with_text("first");
+        let second = Message::assistant().with_text("second");
+        sm.add_message(&session.id, &first).await.unwrap();
+        sm.add_message(&session.id, &second).await.unwrap();
+        let before = sm.get_messages_after(&session.id, 0).await.unwrap();
+
+        let third = Message::user().with_text("third");
+        let appended =
+            Conversation::new_unvalidated(vec![first.clone(), second.clone(), third.clone()]);
+        sm.replace_conversation(&session.id, &appended)
+            .await
+            .unwrap();
+        let after = sm.get_messages_after(&session.id, 0).await.unwrap();
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[0].0, before[0].0);
+        assert_eq!(after[1].0, before[1].0);
+        assert_eq!(after[2].1.as_concat_text(), "third");
+
+        let edited = Conversation::new_unvalidated(vec![
+            first.clone(),
+            Message::assistant().with_text("edited"),
+        ]);
+        sm.replace_conversation(&session.id, &edited).await.unwrap();
+        let after_edit = sm.get_messages_after(&session.id, 0).await.unwrap();
+        assert_eq!(after_edit.len(), 2);
+        assert_eq!(after_edit[0].0, before[0].0);
+        assert_eq!(after_edit[1].1.as_concat_text(), "edited");
+
+        let stored = sm.get_session(&session.id, true).await.unwrap();
+        let texts: Vec<String> = stored
+            .conversation
+            .unwrap()
+            .messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect();
+        assert_eq!(texts, vec!["first", "edited"]);
+    }
 }
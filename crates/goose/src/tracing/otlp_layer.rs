@@ -21,13 +21,18 @@ pub type OtlpResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 pub struct OtlpConfig {
     pub endpoint: String,
     pub timeout: Duration,
+    /// Fraction of traces to export, between 0 and 1
+    pub sample_ratio: f64,
 }
 
+const DEFAULT_SAMPLE_RATIO: f64 = 0.1;
+
 impl Default for OtlpConfig {
     fn default() -> Self {
         Self {
             endpoint: "http://localhost:4318".to_string(),
             timeout: Duration::from_secs(10),
+            sample_ratio: DEFAULT_SAMPLE_RATIO,
         }
     }
 }
@@ -43,7 +48,7 @@ impl OtlpConfig {
 
         let mut otlp_config = Self {
             endpoint,
-            timeout: Duration::from_secs(10),
+            ..Self::default()
         };
 
         // Try to get timeout from config (checks OTEL_EXPORTER_OTLP_TIMEOUT env var first)
@@ -51,6 +56,11 @@ impl OtlpConfig {
             otlp_config.timeout = Duration::from_millis(timeout_ms);
         }
 
+        // Set OTEL_TRACES_SAMPLER_ARG=1 to export every agent turn when profiling
+        if let Ok(ratio) = config.get_param::<f64>("otel_traces_sampler_arg") {
+            otlp_config.sample_ratio = ratio.clamp(0.0, 1.0);
+        }
+
         Some(otlp_config)
     }
 }
@@ -129,7 +139,9 @@ pub fn create_otlp_tracing_layer() -> OtlpResult<OtlpTracingLayer> {
         .with_max_links_per_span(512)
         .with_resource(resource)
         .with_id_generator(RandomIdGenerator::default())
-        .with_sampler(Sampler::TraceIdRatioBased(0.1))
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
         .build();
 
     let tracer = tracer_provider.tracer("goose");
@@ -280,6 +292,7 @@ mod tests {
         let config = OtlpConfig::default();
         assert_eq!(config.endpoint, "http://localhost:4318");
         assert_eq!(config.timeout, Duration::from_secs(10));
+        assert_eq!(config.sample_ratio, 0.1);
     }
 
     #[test]
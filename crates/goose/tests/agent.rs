@@ -455,6 +455,7 @@ mod tests {
                     }
                     Ok(AgentEvent::McpNotification(_)) => {}
                     Ok(AgentEvent::ModelChange { .. }) => {}
+                    Ok(AgentEvent::ContextLengthExceeded) => {}
                     Ok(AgentEvent::HistoryReplaced(_updated_conversation)) => {
                         // We should update the conversation here, but we're not reading it
                     }
@@ -484,6 +485,185 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod context_overflow_tests {
+        use super::*;
+        use async_trait::async_trait;
+        use goose::agents::SessionConfig;
+        use goose::conversation::message::{Message, MessageContent, SystemNotificationType};
+        use goose::model::ModelConfig;
+        use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+        use goose::providers::errors::ProviderError;
+        use goose::session::session_manager::SessionType;
+        use rmcp::model::Tool;
+        use std::path::PathBuf;
+
+        /// Summarizes on request but rejects every regular turn as too long.
+        struct OverflowProvider {}
+
+        #[async_trait]
+        impl Provider for OverflowProvider {
+            async fn complete(
+                &self,
+                system_prompt: &str,
+                _messages: &[Message],
+                _tools: &[Tool],
+            ) -> Result<(Message, ProviderUsage), ProviderError> {
+                if !system_prompt.contains("An llm context limit was reached") {
+                    return Err(ProviderError::ContextLengthExceeded(
+                        "prompt is too long".to_string(),
+                    ));
+                }
+                let usage = ProviderUsage::new(
+                    "mock-model".to_string(),
+                    Usage::new(Some(10), Some(5), Some(15)),
+                );
+                Ok((Message::assistant().with_text("summary"), usage))
+            }
+
+            async fn complete_with_model(
+                &self,
+                _model_config: &ModelConfig,
+                system_prompt: &str,
+                messages: &[Message],
+                tools: &[Tool],
+            ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+                self.complete(system_prompt, messages, tools).await
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new("mock-model").unwrap()
+            }
+
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata {
+                    name: "mock".to_string(),
+                    display_name: "Mock Provider".to_string(),
+                    description: "Mock provider for testing".to_string(),
+                    default_model: "mock-model".to_string(),
+                    known_models: vec![],
+                    model_doc_link: "".to_string(),
+                    config_keys: vec![],
+                    allows_unlisted_models: false,
+                }
+            }
+
+            fn get_name(&self) -> &str {
+                "mock-overflow"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_overflow_after_compaction_emits_event() -> Result<()> {
+            let agent = Agent::new();
+            let session = agent
+                .config
+                .session_manager
+                .create_session(
+                    PathBuf::default(),
+                    "context-overflow-test".to_string(),
+                    SessionType::Hidden,
+                )
+                .await?;
+            agent
+                .update_provider(Arc::new(OverflowProvider {}), &session.id)
+                .await?;
+
+            let session_config = SessionConfig {
+                id: session.id,
+                schedule_id: None,
+                max_turns: None,
+                retry_config: None,
+            };
+            let reply_stream = agent
+                .reply(Message::user().with_text("Hello"), session_config, None)
+                .await?;
+            tokio::pin!(reply_stream);
+
+            let mut history_replaced = false;
+            let mut overflowed = false;
+            let mut notices = Vec::new();
+            while let Some(event) = reply_stream.next().await {
+                match event? {
+                    AgentEvent::Message(message) => {
+                        for content in message.content {
+                            if let MessageContent::SystemNotification(notification) = content {
+                                if notification.notification_type
+                                    == SystemNotificationType::InlineMessage
+                                {
+                                    notices.push(notification.msg);
+                                }
+                            }
+                        }
+                    }
+                    AgentEvent::HistoryReplaced(_) => history_replaced = true,
+                    AgentEvent::ContextLengthExceeded => overflowed = true,
+                    AgentEvent::McpNotification(_) | AgentEvent::ModelChange { .. } => {}
+                }
+            }
+
+            assert!(history_replaced, "the first overflow should be compacted");
+            assert!(overflowed, "the second overflow should end the turn");
+            assert!(notices
+                .last()
+                .is_some_and(|notice| notice.starts_with("Unable to continue")));
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod subagent_depth_tests {
+        use super::*;
+        use goose::agents::subagent_tool::SUBAGENT_TOOL_NAME;
+        use goose::session::session_manager::SessionType;
+        use rmcp::model::CallToolRequestParam;
+        use rmcp::object;
+        use std::path::PathBuf;
+
+        async fn spawn_nested(agent: &Agent, session_type: SessionType) -> Result<String> {
+            let session = agent
+                .config
+                .session_manager
+                .create_session(PathBuf::default(), "nested".to_string(), session_type)
+                .await?;
+            let tool_call = CallToolRequestParam {
+                task: None,
+                name: SUBAGENT_TOOL_NAME.into(),
+                arguments: Some(object!({"instructions": "Spawn another subagent"})),
+            };
+            let (_, result) = agent
+                .dispatch_tool_call(tool_call, "req-1".to_string(), None, &session)
+                .await;
+            let error = result
+                .expect("a refused spawn still returns a tool result")
+                .result
+                .await
+                .expect_err("nested spawn should be refused");
+            Ok(error.message.to_string())
+        }
+
+        #[tokio::test]
+        async fn test_subagent_cannot_spawn_subagent_by_default() -> Result<()> {
+            let agent = Agent::new();
+            agent.set_subagent_depth(1);
+            let message = spawn_nested(&agent, SessionType::Hidden).await?;
+            assert!(
+                message.contains("maximum subagent depth is 1"),
+                "{}",
+                message
+            );
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_subagent_session_counts_as_nested_without_depth() -> Result<()> {
+            let agent = Agent::new();
+            let message = spawn_nested(&agent, SessionType::SubAgent).await?;
+            assert!(message.contains("at depth 2"), "{}", message);
+            Ok(())
+        }
+    }
+
     #[cfg(test)]
     mod extension_manager_tests {
         use super::*;
@@ -501,6 +681,7 @@ mod tests {
             // Set it as disabled initially so tests can enable it
             let todo_extension_entry = ExtensionEntry {
                 enabled: false,
+                required: false,
                 config: ExtensionConfig::Platform {
                     name: "todo".to_string(),
                     description:
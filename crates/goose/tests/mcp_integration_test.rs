@@ -251,6 +251,8 @@ async fn test_replayed_session(
         timeout: Some(30),
         bundled: Some(false),
         available_tools: vec![],
+        auto_restart: false,
+        max_restarts: None,
     };
 
     let provider = Arc::new(tokio::sync::Mutex::new(Some(Arc::new(MockProvider {
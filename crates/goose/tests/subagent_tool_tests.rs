@@ -30,7 +30,9 @@ fn make_subrecipe(path: String, name: &str, values: Option<HashMap<String, Strin
         name: name.to_string(),
         path,
         values,
+        inherit: Vec::new(),
         sequential_when_repeated: false,
+        timeout_seconds: None,
         description: Some(format!("{} description", name)),
     }
 }